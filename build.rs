@@ -0,0 +1,45 @@
+use std::env;
+
+/// Oldest SuperNOVAS release with every function the `novas` module needs.
+const MIN_SUPERNOVAS_VERSION: (u32, u32) = (1, 3);
+
+/// Releases that add functions to the `novas` module, each enabling a `supernovas_<major>_<minor>`
+/// cfg: `novas_day_of_week`, `novas_day_of_year`, the Moon phase functions and the TIRS and ITRS
+/// reference systems of 1.4.
+const GATED_VERSIONS: [(u32, u32); 1] = [(1, 4)];
+
+fn main() {
+    for (major, minor) in GATED_VERSIONS {
+        println!("cargo:rustc-check-cfg=cfg(supernovas_{}_{})", major, minor);
+    }
+
+    if env::var_os("CARGO_FEATURE_NOVAS").is_none() {
+        return;
+    }
+
+    // Published by supernovas-sys from the `novas.h` it builds or links against
+    let found = match env::var("DEP_SUPERNOVAS_VERSION") {
+        Ok(version) => {
+            let mut parts = version.split('.').map(|part| part.parse::<u32>().unwrap_or(0));
+            let found = (parts.next().unwrap_or(0), parts.next().unwrap_or(0));
+            if found < MIN_SUPERNOVAS_VERSION {
+                panic!(
+                    "SuperNOVAS {} is not supported by the `novas` feature, which needs {}.{} or later: select a newer release with SUPERNOVAS_VERSION, or point SUPERNOVAS_DIR to a newer installation",
+                    version, MIN_SUPERNOVAS_VERSION.0, MIN_SUPERNOVAS_VERSION.1
+                );
+            }
+            found
+        }
+        Err(_) => {
+            let newest = GATED_VERSIONS[GATED_VERSIONS.len() - 1];
+            println!("cargo:warning=supernovas-sys reports no SuperNOVAS version, assuming {}.{} or later", newest.0, newest.1);
+            newest
+        }
+    };
+
+    for (major, minor) in GATED_VERSIONS {
+        if found >= (major, minor) {
+            println!("cargo:rustc-cfg=supernovas_{}_{}", major, minor);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Parallel, fingerprinted compilation of large sets of C sources with `cc`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Compiles `files` with the settings of `cfg` into the static library `name`, in `out_dir`.
+///
+/// The sources are compiled in parallel by `cc`, within the jobs cargo's jobserver grants. The
+/// compiler command line and a hash of the sources are recorded next to the library, and
+/// compilation is skipped when the library is still up to date. Compiler wrappers such as
+/// `ccache` or `sccache` are picked up by `cc` from `CC` (e.g. `CC="ccache gcc"`) or
+/// `RUSTC_WRAPPER`.
+pub fn compile(cfg: &cc::Build, files: &[PathBuf], out_dir: &Path, name: &str) {
+    let stamp = out_dir.join(format!("{}.fingerprint", name));
+    let fingerprint = fingerprint(cfg, files);
+    let archive_exists = out_dir.join(format!("lib{}.a", name)).exists() || out_dir.join(format!("{}.lib", name)).exists();
+
+    if archive_exists && fs::read_to_string(&stamp).ok().as_deref() == Some(fingerprint.as_str()) {
+        println!("cargo:rustc-link-search=native={}", out_dir.display());
+        println!("cargo:rustc-link-lib=static={}", name);
+        return;
+    }
+
+    cfg.clone().out_dir(out_dir).files(files).compile(name);
+
+    fs::write(&stamp, fingerprint).expect("Failed to write build fingerprint");
+}
+
+// Hash of the compiler command line and the sources' names and contents.
+fn fingerprint(cfg: &cc::Build, files: &[PathBuf]) -> String {
+    let mut hasher = DefaultHasher::new();
+
+    let compiler = cfg.get_compiler();
+    compiler.path().hash(&mut hasher);
+    compiler.args().hash(&mut hasher);
+
+    for file in files {
+        file.hash(&mut hasher);
+        fs::read(file).ok().hash(&mut hasher);
+    }
+
+    format!("{:016x}", hasher.finish())
+}
@@ -0,0 +1,116 @@
+//! Conversion of doxygen markup in the C header comments to rustdoc markdown.
+
+use bindgen::callbacks::ParseCallbacks;
+
+/// Bindgen callback that rewrites doxygen comments into rustdoc.
+#[derive(Debug)]
+pub struct Doxygen;
+
+impl ParseCallbacks for Doxygen {
+    fn process_comment(&self, comment: &str) -> Option<String> {
+        Some(to_rustdoc(comment))
+    }
+}
+
+#[derive(PartialEq)]
+enum Section {
+    Text,
+    Params,
+    Returns,
+}
+
+/// Converts a (delimiter-stripped) doxygen comment to markdown.
+pub fn to_rustdoc(comment: &str) -> String {
+    let mut text = Vec::new();
+    let mut params = Vec::new();
+    let mut returns = Vec::new();
+    let mut see_also = Vec::new();
+    let mut section = Section::Text;
+
+    for line in comment.lines() {
+        let line = line.trim();
+
+        // Decorative separator lines, e.g. `-------`
+        if !line.is_empty() && line.chars().all(|c| matches!(c, '-' | '=' | '*' | '/')) {
+            continue;
+        }
+
+        let Some((tag, rest)) = split_tag(line) else {
+            let line = escape(line);
+            match section {
+                Section::Params if !line.is_empty() => params.push(format!("  {}", line)),
+                Section::Returns if !line.is_empty() => returns.push(line),
+                _ => {
+                    section = Section::Text;
+                    text.push(line);
+                }
+            }
+            continue;
+        };
+
+        match tag {
+            "internal" => return String::new(),
+            "param" | "param[in]" | "param[out]" | "param[in,out]" | "tparam" => {
+                let (name, desc) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                let dir = match tag {
+                    "param[out]" => " (out)",
+                    "param[in,out]" => " (in/out)",
+                    _ => "",
+                };
+                params.push(format!("* `{}`{} - {}", name, dir, escape(desc.trim())));
+                section = Section::Params;
+            }
+            "return" | "returns" | "retval" => {
+                returns.push(escape(rest));
+                section = Section::Returns;
+            }
+            "sa" | "see" => {
+                see_also.push(format!("`{}`", rest.trim_end_matches("()")));
+                section = Section::Text;
+            }
+            "deprecated" => {
+                text.push(format!("**Deprecated:** {}", escape(rest)));
+                section = Section::Text;
+            }
+            "brief" | "details" | "note" => {
+                text.push(escape(rest));
+                section = Section::Text;
+            }
+            // @since, @author, @version, @file, @ingroup, ...
+            _ => section = Section::Text,
+        }
+    }
+
+    while text.last().is_some_and(|l| l.is_empty()) {
+        text.pop();
+    }
+    let mut doc = text.join("\n");
+    if !params.is_empty() {
+        doc.push_str("\n\n# Arguments\n\n");
+        doc.push_str(&params.join("\n"));
+    }
+    if !returns.is_empty() {
+        doc.push_str("\n\n# Returns\n\n");
+        doc.push_str(&returns.join("\n"));
+    }
+    if !see_also.is_empty() {
+        doc.push_str("\n\nSee also: ");
+        doc.push_str(&see_also.join(", "));
+    }
+    doc.trim().to_string()
+}
+
+// Splits `@tag rest` or `\tag rest` into its parts.
+fn split_tag(line: &str) -> Option<(&str, &str)> {
+    let body = line.strip_prefix('@').or_else(|| line.strip_prefix('\\'))?;
+    let (tag, rest) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+    if tag.is_empty() || !tag.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some((tag, rest.trim()))
+}
+
+// Keeps unit annotations such as `[deg]` from being read as intra-doc links.
+fn escape(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
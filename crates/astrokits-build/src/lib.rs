@@ -0,0 +1,9 @@
+//! Build script helpers shared by `libcspice-sys`, `calceph-sys` and `supernovas-sys`: compilation
+//! of the C sources, their sanitizer flags and local patches, cross-compilation settings for mobile
+//! targets, and conversion of the header docs for bindgen.
+
+pub mod compile;
+pub mod doxygen;
+pub mod mobile;
+pub mod patches;
+pub mod sanitizer;
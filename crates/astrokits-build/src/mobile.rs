@@ -0,0 +1,156 @@
+//! Cross-compilation settings for Android (NDK) and iOS (Xcode) targets.
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Whether the build targets Android.
+pub fn is_android() -> bool {
+    env::var("CARGO_CFG_TARGET_OS").is_ok_and(|os| os == "android")
+}
+
+/// Whether the build targets iOS.
+pub fn is_ios() -> bool {
+    env::var("CARGO_CFG_TARGET_OS").is_ok_and(|os| os == "ios")
+}
+
+/// The Android NDK, from `ANDROID_NDK_HOME`, `ANDROID_NDK_ROOT` or `NDK_HOME`.
+pub fn android_ndk() -> Option<PathBuf> {
+    ["ANDROID_NDK_HOME", "ANDROID_NDK_ROOT", "NDK_HOME"].iter().find_map(|var| {
+        println!("cargo:rerun-if-env-changed={}", var);
+        env::var_os(var).map(PathBuf::from).filter(|dir| dir.exists())
+    })
+}
+
+/// The prebuilt LLVM toolchain of the NDK for the build host.
+pub fn ndk_toolchain() -> Option<PathBuf> {
+    let host = match env::consts::OS {
+        "linux" => "linux-x86_64",
+        // The NDK ships x86_64 binaries only (run through Rosetta on Apple Silicon)
+        "macos" => "darwin-x86_64",
+        "windows" => "windows-x86_64",
+        _ => return None,
+    };
+    Some(android_ndk()?.join("toolchains/llvm/prebuilt").join(host))
+}
+
+/// Path of an NDK tool such as `llvm-ar` (`.exe` or `.cmd` on Windows hosts).
+pub fn ndk_tool(name: &str) -> Option<PathBuf> {
+    let bin = ndk_toolchain()?.join("bin");
+    ["", ".exe", ".cmd"]
+        .iter()
+        .map(|ext| bin.join(format!("{}{}", name, ext)))
+        .find(|path| path.is_file())
+}
+
+/// The NDK clang driver for the target and API level, e.g. `aarch64-linux-android21-clang`.
+pub fn ndk_clang() -> Option<PathBuf> {
+    let triple = match android_abi() {
+        "arm64-v8a" => "aarch64-linux-android",
+        "armeabi-v7a" => "armv7a-linux-androideabi",
+        "x86" => "i686-linux-android",
+        _ => "x86_64-linux-android",
+    };
+    ndk_tool(&format!("{}{}-clang", triple, android_api_level()))
+}
+
+/// Whether the C compiler was chosen explicitly through `CC` or `CC_<target>`.
+pub fn has_user_cc() -> bool {
+    let target = env::var("TARGET").unwrap_or_default();
+    ["CC".to_string(), "TARGET_CC".to_string(), format!("CC_{}", target), format!("CC_{}", target.replace('-', "_"))]
+        .iter()
+        .any(|var| env::var_os(var).is_some())
+}
+
+/// Makes `cfg` use the NDK clang and LLVM archiver, which are not on `PATH` under the names `cc`
+/// looks for. A compiler set through `CC` is kept.
+pub fn configure_ndk(cfg: &mut cc::Build) {
+    if !has_user_cc() {
+        match ndk_clang() {
+            Some(clang) => {
+                cfg.compiler(clang);
+            }
+            None => println!("cargo:warning=Android NDK not found, set ANDROID_NDK_HOME or CC"),
+        }
+    }
+    if let Some(ar) = ndk_tool("llvm-ar") {
+        cfg.archiver(ar);
+    }
+    if let Some(ranlib) = ndk_tool("llvm-ranlib") {
+        cfg.ranlib(ranlib);
+    }
+}
+
+/// CMake toolchain file shipped with the NDK.
+pub fn ndk_cmake_toolchain() -> Option<PathBuf> {
+    Some(android_ndk()?.join("build/cmake/android.toolchain.cmake")).filter(|path| path.exists())
+}
+
+/// Android ABI name for the target architecture.
+pub fn android_abi() -> &'static str {
+    match env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default().as_str() {
+        "aarch64" => "arm64-v8a",
+        "arm" => "armeabi-v7a",
+        "x86" => "x86",
+        _ => "x86_64",
+    }
+}
+
+/// Android API level, from `ANDROID_PLATFORM` (e.g. `android-24` or `24`), 21 by default.
+pub fn android_api_level() -> u32 {
+    println!("cargo:rerun-if-env-changed=ANDROID_PLATFORM");
+    env::var("ANDROID_PLATFORM")
+        .ok()
+        .and_then(|p| p.trim_start_matches("android-").parse().ok())
+        .unwrap_or(21)
+}
+
+/// Xcode SDK name for the iOS target (`iphoneos` or `iphonesimulator`).
+pub fn ios_sdk_name() -> &'static str {
+    let target = env::var("TARGET").unwrap_or_default();
+    if target.ends_with("-sim") || target.starts_with("x86_64") {
+        "iphonesimulator"
+    } else {
+        "iphoneos"
+    }
+}
+
+/// Path of the iOS SDK, from `SDKROOT` or `xcrun`.
+pub fn ios_sdk_path() -> Option<String> {
+    println!("cargo:rerun-if-env-changed=SDKROOT");
+    if let Ok(sdk) = env::var("SDKROOT") {
+        return Some(sdk);
+    }
+    let output = Command::new("xcrun")
+        .args(["--sdk", ios_sdk_name(), "--show-sdk-path"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Extra clang arguments for bindgen, so that the system headers of the target are found.
+/// (bindgen passes `--target` itself.)
+pub fn clang_args() -> Vec<String> {
+    if is_android() {
+        match ndk_toolchain() {
+            Some(toolchain) => vec![format!("--sysroot={}", toolchain.join("sysroot").display())],
+            None => {
+                println!("cargo:warning=Set ANDROID_NDK_HOME to the Android NDK to generate bindings for Android");
+                Vec::new()
+            }
+        }
+    } else if is_ios() {
+        match ios_sdk_path() {
+            Some(sdk) => vec!["-isysroot".to_string(), sdk],
+            None => {
+                println!("cargo:warning=Could not locate the {} SDK, set SDKROOT", ios_sdk_name());
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    }
+}
@@ -0,0 +1,57 @@
+//! Local patches applied to downloaded C sources before they are built.
+
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Patch files (`*.patch`, `*.diff`) from the directory named by the environment variable `var`
+/// (e.g. `SUPERNOVAS_PATCH_DIR`), in the order they are applied.
+pub fn user_patches(var: &str) -> Vec<PathBuf> {
+    println!("cargo:rerun-if-env-changed={}", var);
+
+    let Some(dir) = env::var_os(var).map(PathBuf::from) else {
+        return Vec::new();
+    };
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut patches: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("Failed to read {} ({}): {}", var, dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| matches!(path.extension().and_then(|s| s.to_str()), Some("patch" | "diff")))
+        .collect();
+    patches.sort();
+    patches
+}
+
+/// Identifies a set of patches, so that sources patched with a different set are fetched again.
+pub fn fingerprint(patches: &[PathBuf]) -> String {
+    if patches.is_empty() {
+        return String::new();
+    }
+    let mut hasher = DefaultHasher::new();
+    for patch in patches {
+        patch.file_name().hash(&mut hasher);
+        fs::read(patch).unwrap().hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// Applies the patches with `patch -p1` to the sources in `src_dir`, and records them in a stamp
+/// file (`.patches`) that [`fingerprint`] is compared against.
+pub fn apply(src_dir: &Path, patches: &[PathBuf]) {
+    for patch in patches {
+        let status = Command::new("patch")
+            .current_dir(src_dir)
+            .args(["-p1", "--forward", "--batch", "-i"])
+            .arg(patch)
+            .status()
+            .expect("Failed to run `patch`");
+        if !status.success() {
+            panic!("Failed to apply {} to {}", patch.display(), src_dir.display());
+        }
+    }
+    fs::write(src_dir.join(".patches"), fingerprint(patches)).expect("Failed to write patch stamp");
+}
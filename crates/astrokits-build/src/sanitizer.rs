@@ -0,0 +1,54 @@
+//! Sanitizer flags for the C sources, from `ASTROKITS_SANITIZE`.
+
+use std::env;
+
+const SANITIZE: &str = "ASTROKITS_SANITIZE";
+
+/// Compiler flags for the sanitizers (`address`, `undefined`) listed in `ASTROKITS_SANITIZE`,
+/// for diagnosing memory errors at the FFI boundary. The Rust side must be built with matching
+/// flags, e.g. `RUSTFLAGS="-Zsanitizer=address"` (nightly) or `RUSTFLAGS="-Clink-arg=-fsanitize=address"`.
+pub fn flags(target: &str) -> Vec<String> {
+    println!("cargo:rerun-if-env-changed={}", SANITIZE);
+
+    let Ok(list) = env::var(SANITIZE) else {
+        return Vec::new();
+    };
+    let sanitizers: Vec<&str> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|s| match *s {
+            "address" | "undefined" => true,
+            "" => false,
+            _ => {
+                println!("cargo:warning=Ignoring unsupported sanitizer `{}` in {}", s, SANITIZE);
+                false
+            }
+        })
+        .collect();
+
+    if sanitizers.is_empty() {
+        return Vec::new();
+    }
+
+    if target.contains("msvc") {
+        if sanitizers.contains(&"undefined") {
+            println!("cargo:warning=MSVC supports only the address sanitizer");
+        }
+        if sanitizers.contains(&"address") {
+            return vec!["/fsanitize=address".to_string(), "/Zi".to_string()];
+        }
+        return Vec::new();
+    }
+
+    let rustflags = env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+    if !rustflags.contains("sanitize") {
+        println!("cargo:warning=C sources are built with -fsanitize={}; set matching RUSTFLAGS (e.g. -Zsanitizer=address or -Clink-arg=-fsanitize=address) to link the runtime", sanitizers.join(","));
+    }
+
+    vec![
+        format!("-fsanitize={}", sanitizers.join(",")),
+        "-fno-omit-frame-pointer".to_string(),
+        "-fno-sanitize-recover=all".to_string(),
+        "-g".to_string(),
+    ]
+}
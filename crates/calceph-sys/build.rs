@@ -1,43 +1,60 @@
+#[cfg(feature = "calceph-src")]
 use cmake::Config;
-use std::path::PathBuf;
-use std::{env, fs};
+use std::env;
+#[cfg(feature = "calceph-src")]
+use std::fs;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "calceph-src")]
 use std::process::Command;
 
+use astrokits_build::{doxygen, mobile};
+#[cfg(feature = "calceph-src")]
+use astrokits_build::{patches, sanitizer};
+
 const CALCEPH_DIR: &str = "CALCEPH_DIR";
+#[cfg(feature = "calceph-src")]
+const PATCH_DIR: &str = "CALCEPH_PATCH_DIR";
 
 fn main() {
     println!("cargo:rerun-if-env-changed={}", CALCEPH_DIR);
 
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     let calceph_dir = env::var(CALCEPH_DIR).ok().map(PathBuf::from);
 
-    let calceph_include = if calceph_dir.is_some() {
-        calceph_dir.as_ref().unwrap().join("include")
-    } else {
-        PathBuf::from("vendor/calceph/include")
+    let calceph_include = match calceph_dir.as_ref() {
+        Some(dir) => dir.join("include"),
+        None => PathBuf::from("vendor/calceph/include"),
     };
 
     gen_bindings(&calceph_include);
 
     #[cfg(feature = "calceph-src")]
     let calceph_dir = calceph_dir.or_else(|| {
-        let downloaded = out_path.join("cacleph");
-        if !downloaded.exists() {
+        let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+        let downloaded = out_path.join("calceph");
+        let user_patches = patches::user_patches(PATCH_DIR);
+        let patched = fs::read_to_string(downloaded.join(".patches")).unwrap_or_default();
+        if !downloaded.exists() || patched != patches::fingerprint(&user_patches) {
             download_calceph(&out_path);
+            patches::apply(&downloaded, &user_patches);
         }
         Some(out_path)
     });
 
+    // Dependents (`DEP_CALCEPH_INCLUDE`) can always use the headers, even without a library to link
+    let vendor_include = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("vendor/calceph/include");
+
     let calceph_dir = match calceph_dir {
         Some(dir) => {
             if !dir.exists() {
-                println!("cargo:warning={}", format!("`calceph_dir` does not point to a valid directory: {}", dir.display()));
+                println!("cargo:include={}", vendor_include.display());
+                println!("cargo:warning=`calceph_dir` does not point to a valid directory: {}", dir.display());
                 return;
             }
             dir
         },
         None => {
-            println!("cargo:warning={}", format!("`calceph_dir` does not point to a valid directory. Please set the {} environment variable or use `calceph-src` feature.", CALCEPH_DIR));
+            println!("cargo:include={}", vendor_include.display());
+            println!("cargo:warning=`calceph_dir` does not point to a valid directory. Please set the {} environment variable or use `calceph-src` feature.", CALCEPH_DIR);
             return;
         }
     };
@@ -50,11 +67,13 @@ fn main() {
 
     println!("cargo:rustc-link-search=native={}", calceph_lib.to_str().unwrap());
     println!("cargo:rustc-link-lib=static=calceph");
+    println!("cargo:root={}", calceph_dir.to_str().unwrap());
+    println!("cargo:lib={}", calceph_lib.to_str().unwrap());
     println!("cargo:include={}", calceph_include.to_str().unwrap());
 }
 
 #[cfg(feature = "calceph-src")]
-fn download_calceph(dst: &PathBuf) {
+fn download_calceph(dst: &Path) {
     let calceph_version = "4_0_5";
     let url = format!("https://gitlab.obspm.fr/imcce_calceph/calceph/-/archive/calceph_{}/calceph-calceph_{}.tar.gz", calceph_version, calceph_version);
 
@@ -88,11 +107,22 @@ fn download_calceph(dst: &PathBuf) {
 }
 
 #[cfg(feature = "calceph-src")]
-fn build_calceph(cacleph_dir: &PathBuf) {
+fn build_calceph(cacleph_dir: &Path) {
     let target = env::var("TARGET").unwrap();
     // Build the CMake project using NMake Makefiles generator
     let mut cfg = Config::new(cacleph_dir.join("calceph"));
     cfg.define("ENABLE_FORTRAN", "OFF");
+    for flag in sanitizer::flags(&target) {
+        cfg.cflag(flag);
+    }
+    if let Some(launcher) = compiler_launcher() {
+        cfg.define("CMAKE_C_COMPILER_LAUNCHER", launcher);
+    }
+    if mobile::is_android() {
+        configure_ndk(&mut cfg);
+    } else if mobile::is_ios() {
+        configure_ios(&mut cfg);
+    }
     if target.contains("msvc")
     {
         cfg.generator("NMake Makefiles");
@@ -100,11 +130,63 @@ fn build_calceph(cacleph_dir: &PathBuf) {
     cfg.build();
 }
 
-fn gen_bindings(include_dst: &PathBuf) {
+/// Cross-compile with the toolchain file of the NDK, which sets the compiler, sysroot and ABI.
+#[cfg(feature = "calceph-src")]
+fn configure_ndk(cfg: &mut Config) {
+    let Some(toolchain) = mobile::ndk_cmake_toolchain() else {
+        println!("cargo:warning=Android NDK not found, set ANDROID_NDK_HOME to build CALCEPH for Android");
+        return;
+    };
+    cfg.define("CMAKE_TOOLCHAIN_FILE", toolchain)
+        .define("ANDROID_ABI", mobile::android_abi())
+        .define("ANDROID_PLATFORM", format!("android-{}", mobile::android_api_level()));
+}
+
+/// Point CMake at the iOS SDK. The tools of CALCEPH are built too, but never run on the host.
+#[cfg(feature = "calceph-src")]
+fn configure_ios(cfg: &mut Config) {
+    let arch = match env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default().as_str() {
+        "aarch64" => "arm64",
+        _ => "x86_64",
+    };
+    cfg.define("CMAKE_OSX_SYSROOT", mobile::ios_sdk_path().unwrap_or_else(|| mobile::ios_sdk_name().to_string()))
+        .define("CMAKE_OSX_ARCHITECTURES", arch);
+}
+
+/// Compiler cache wrapper for CMake, taken from `CC` (e.g. `CC="ccache gcc"`) or from an
+/// `sccache`-like `RUSTC_WRAPPER`, the same way `cc` picks it up for the other sys crates.
+#[cfg(feature = "calceph-src")]
+fn compiler_launcher() -> Option<String> {
+    let stem = |path: &str| {
+        Path::new(path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(str::to_ascii_lowercase)
+    };
+
+    if let Ok(cc) = env::var("CC") {
+        let mut parts = cc.split_whitespace();
+        if let (Some(wrapper), Some(_)) = (parts.next(), parts.next())
+            && matches!(stem(wrapper).as_deref(), Some("ccache" | "sccache" | "distcc" | "cachepot" | "buildcache"))
+        {
+            return Some(wrapper.to_string());
+        }
+    }
+
+    env::var("RUSTC_WRAPPER")
+        .ok()
+        .filter(|wrapper| matches!(stem(wrapper).as_deref(), Some("sccache" | "cachepot" | "buildcache")))
+}
+
+fn gen_bindings(include_dst: &Path) {
     let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     // Generate the bindings
     let bindings = bindgen::Builder::default()
         .header(include_dst.join("calceph.h").to_str().unwrap())
+        .clang_args(mobile::clang_args())
+        .generate_comments(true)
+        .clang_arg("-fparse-all-comments")
+        .parse_callbacks(Box::new(doxygen::Doxygen))
         .generate()
         .expect("Unable to generate bindings");
 
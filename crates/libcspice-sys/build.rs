@@ -1,70 +1,86 @@
 use std::env;
+#[cfg(feature = "cspice-src")]
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "cspice-src")]
 use std::process::Command;
 
+#[cfg(feature = "cspice-src")]
+use astrokits_build::{compile, patches, sanitizer};
+use astrokits_build::mobile;
+
 const CSPICE_DIR: &str = "CSPICE_DIR";
+#[cfg(feature = "cspice-src")]
+const PATCH_DIR: &str = "CSPICE_PATCH_DIR";
 
 fn main() {
     println!("cargo:rerun-if-env-changed={}", CSPICE_DIR);
 
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     let cspice_dir = env::var(CSPICE_DIR).ok().map(PathBuf::from);
 
-    let cspice_include = if cspice_dir.is_some() {
-        cspice_dir.as_ref().unwrap().join("include")
-    } else {
-        PathBuf::from("vendor/cspice/include")
+    let cspice_include = match cspice_dir.as_ref() {
+        Some(dir) => dir.join("include"),
+        None => PathBuf::from("vendor/cspice/include"),
     };
 
     gen_bindings(&cspice_include);
 
     #[cfg(feature = "cspice-src")]
     let cspice_dir = cspice_dir.or_else(|| {
+        let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
         let downloaded = out_path.join("cspice");
-        if !downloaded.exists() {
+        let user_patches = patches::user_patches(PATCH_DIR);
+        let patched = fs::read_to_string(downloaded.join(".patches")).unwrap_or_default();
+        if !downloaded.exists() || patched != patches::fingerprint(&user_patches) {
+            if downloaded.exists() {
+                fs::remove_dir_all(&downloaded).expect("Failed to remove existing cspice directory");
+            }
             download_cspice(&out_path);
+            patches::apply(&downloaded, &user_patches);
         }
         Some(out_path)
     });
 
+    // Dependents (`DEP_CSPICE_INCLUDE`) can always use the headers, even without a library to link
+    let vendor_include = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("vendor/cspice/include");
+
     let cspice_dir = match cspice_dir {
         Some(dir) => {
             if !dir.exists() {
-                println!("cargo:warning={}", format!("`cspice_dir` does not point to a valid directory: {}", dir.display()));
+                println!("cargo:include={}", vendor_include.display());
+                println!("cargo:warning=`cspice_dir` does not point to a valid directory: {}", dir.display());
                 return;
             }
             dir
         },
         None => {
-            println!("cargo:warning={}", format!("`cspice_dir` does not point to a valid directory. Please set the {} environment variable or use `cspice-src` feature.", CSPICE_DIR));
+            println!("cargo:include={}", vendor_include.display());
+            println!("cargo:warning=`cspice_dir` does not point to a valid directory. Please set the {} environment variable or use `cspice-src` feature.", CSPICE_DIR);
             return;
         }
     };
 
-    let mut cfg = cc::Build::new();
-
     #[cfg(feature = "cspice-src")]
-    build_cspicelib(&mut cfg, &cspice_dir.join("cspice"));
+    build_cspicelib(&mut cc::Build::new(), &cspice_dir.join("cspice"));
 
     let cspice_lib = cspice_dir.join("lib");
     let cspice_include = cspice_dir.join("include");
 
     println!("cargo:rustc-link-search=native={}", cspice_lib.to_str().unwrap());
     println!("cargo:rustc-link-lib=static=cspice");
+    println!("cargo:root={}", cspice_dir.to_str().unwrap());
+    println!("cargo:lib={}", cspice_lib.to_str().unwrap());
     println!("cargo:include={}", cspice_include.to_str().unwrap());
 }
 
 #[cfg(feature = "cspice-src")]
-fn build_cspicelib(cfg: &mut cc::Build, cspice_dst: &PathBuf) {
+fn build_cspicelib(cfg: &mut cc::Build, cspice_dst: &Path) {
     let dst = PathBuf::from(env::var("OUT_DIR").unwrap());
     let lib = dst.join("lib");
 
     cfg.warnings(false).out_dir(&lib);
 
-    println!("cargo:warning={}", cspice_dst.join("src/cspice").display());
-
-    let src_files: Vec<_> = fs::read_dir(&cspice_dst.join("src/cspice"))
+    let src_files: Vec<_> = fs::read_dir(cspice_dst.join("src/cspice"))
         .unwrap()
         .filter_map(|entry| {
             let entry = entry.unwrap();
@@ -77,10 +93,16 @@ fn build_cspicelib(cfg: &mut cc::Build, cspice_dst: &PathBuf) {
         })
         .collect();
 
-    cfg.files(&src_files);
-
     let target = env::var("TARGET").unwrap();
 
+    for flag in sanitizer::flags(&target) {
+        cfg.flag(&flag);
+    }
+
+    if mobile::is_android() {
+        mobile::configure_ndk(cfg);
+    }
+
     if target.contains("msvc") {
         cfg.flag_if_supported("/c")
             .flag_if_supported("/TC")
@@ -93,21 +115,22 @@ fn build_cspicelib(cfg: &mut cc::Build, cspice_dst: &PathBuf) {
             .define("NON_ANSI_STDIO", None);
     }
 
-    if target.contains("gnu") {
+    if target.contains("gnu") || target.contains("apple") || target.contains("android") {
+        if target.starts_with("x86_64") {
+            cfg.flag_if_supported("-m64");
+        }
         cfg.flag_if_supported("-c")
             .flag_if_supported("-ansi")
-            .flag_if_supported("-m64")
             .flag_if_supported("-O2")
             .flag_if_supported("-fPIC")
             .define("NON_UNIX_STDIO", None);
     }
 
-    cfg.compile("cspice");
+    compile::compile(cfg, &src_files, &lib, "cspice");
 
     fs::create_dir_all(dst.join("include/cspice")).unwrap();
     fs::read_dir(cspice_dst.join("include"))
         .unwrap()
-        .into_iter()
         .for_each(|entry| {
             let entry = entry.unwrap();
             let path = entry.path();
@@ -118,11 +141,12 @@ fn build_cspicelib(cfg: &mut cc::Build, cspice_dst: &PathBuf) {
         });
 }
 
-fn gen_bindings(dst: &PathBuf) {
+fn gen_bindings(dst: &Path) {
     let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     // Generate the bindings
     let bindings = bindgen::Builder::default()
         .header(dst.join("cspice/SpiceUsr.h").to_str().unwrap())
+        .clang_args(mobile::clang_args())
         .generate()
         .expect("Unable to generate bindings");
 
@@ -134,21 +158,36 @@ fn gen_bindings(dst: &PathBuf) {
 
 // Fetch CSPICE source from NAIF servers and extract to `<out_dir>/cspice`
 #[cfg(feature = "cspice-src")]
-fn download_cspice(out_dir: &PathBuf) {
-    // Pick appropriate package to download
-    let (platform, extension) = match env::consts::OS {
-        "linux" => ("PC_Linux_GCC_64bit", "tar.Z"),
-        "macos" => (
-            if cfg!(target_arch = "arm") {
-                "MacM1_OSX_clang_64bit"
-            } else {
-                "MacIntel_OSX_AppleC_64bit"
-            },
-            "tar.Z",
-        ),
-        "windows" => ("PC_Windows_VisualC_64bit", "zip"),
+fn download_cspice(out_dir: &Path) {
+    // Pick appropriate package to download. The library is always compiled from the package
+    // sources, so for targets without a NAIF package the sources of the closest one are used.
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+    let (platform, extension) = match (target_os.as_str(), target_arch.as_str()) {
+        ("linux", "x86_64") => ("PC_Linux_GCC_64bit", "tar.Z"),
+        ("linux", "x86") => ("PC_Linux_GCC_32bit", "tar.Z"),
+        ("macos", "aarch64") => ("MacM1_OSX_clang_64bit", "tar.Z"),
+        ("macos", "x86_64") => ("MacIntel_OSX_AppleC_64bit", "tar.Z"),
+        ("windows", "x86_64") => ("PC_Windows_VisualC_64bit", "zip"),
+        ("windows", "x86") => ("PC_Windows_VisualC_32bit", "zip"),
+        ("linux", "aarch64" | "riscv64" | "powerpc64" | "s390x" | "loongarch64") => {
+            println!("cargo:warning=NAIF ships no CSPICE package for {}-linux, building from the PC_Linux_GCC_64bit sources", target_arch);
+            ("PC_Linux_GCC_64bit", "tar.Z")
+        }
+        ("windows", "aarch64") => {
+            println!("cargo:warning=NAIF ships no CSPICE package for aarch64-windows, building from the PC_Windows_VisualC_64bit sources");
+            ("PC_Windows_VisualC_64bit", "zip")
+        }
+        // Mobile targets are cross-compiled from the sources of the matching desktop package,
+        // none of the prebuilt host binaries in it are used.
+        ("android", "x86_64" | "aarch64") => ("PC_Linux_GCC_64bit", "tar.Z"),
+        ("android", "x86" | "arm") => ("PC_Linux_GCC_32bit", "tar.Z"),
+        ("ios", _) => ("MacM1_OSX_clang_64bit", "tar.Z"),
         _ => {
-            unimplemented!("Cannot fetch CSPICE source for this platform, please download manually")
+            panic!(
+                "unsupported target {}-{} for the CSPICE toolkit: NAIF ships no package to build it from, install CSPICE manually and set {}",
+                target_arch, target_os, CSPICE_DIR
+            )
         }
     };
 
@@ -165,9 +204,9 @@ fn download_cspice(out_dir: &PathBuf) {
         .unwrap();
     std::fs::write(download_target, body).expect("Failed to write archive file");
 
-    // Extract package based on platform
-    match (env::consts::OS, extension) {
-        ("linux" | "macos", "tar.Z") => {
+    // Extract package based on its format
+    match extension {
+        "tar.Z" => {
             Command::new("gzip")
                 .current_dir(out_dir)
                 .args(["-d", "cspice.tar.Z"])
@@ -179,13 +218,13 @@ fn download_cspice(out_dir: &PathBuf) {
                 .status()
                 .expect("Failed to extract with tar");
 
-            fs::rename(
-                out_dir.join("cspice/lib/cspice.a"),
-                out_dir.join("cspice/lib/libcspice.a"),
-            )
-            .unwrap();
+            // The prebuilt library is not linked, but keep the conventional name for manual use
+            let prebuilt = out_dir.join("cspice/lib/cspice.a");
+            if prebuilt.exists() {
+                fs::rename(prebuilt, out_dir.join("cspice/lib/libcspice.a")).unwrap();
+            }
         }
-        ("windows", "zip") => {
+        "zip" => {
             Command::new("tar")
                 .current_dir(out_dir)
                 .args(["xf", "cspice.zip"])
@@ -1,49 +1,81 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{env, fs};
+#[cfg(feature = "novas-src")]
 use std::process::Command;
+#[cfg(feature = "novas-src")]
 use cc::Build;
 
+#[cfg(feature = "novas-src")]
+use astrokits_build::{compile, patches, sanitizer};
+use astrokits_build::{doxygen, mobile};
+
 const SUPERNOVAS_DIR: &str = "SUPERNOVAS_DIR";
+const SUPERNOVAS_VERSION: &str = "SUPERNOVAS_VERSION";
+#[cfg(feature = "novas-src")]
+const PATCH_DIR: &str = "SUPERNOVAS_PATCH_DIR";
+
+/// The release the vendored sources are patched from.
+#[cfg(feature = "novas-src")]
+const VENDORED_VERSION: &str = "1.4.0";
 
 fn main() {
     println!("cargo:rerun-if-env-changed={}", SUPERNOVAS_DIR);
+    println!("cargo:rerun-if-env-changed={}", SUPERNOVAS_VERSION);
 
     let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     let supernovas_dir = env::var(SUPERNOVAS_DIR).ok().map(PathBuf::from);
 
-    let supernovas_include = if supernovas_dir.is_some() {
-        supernovas_dir.as_ref().unwrap().join("include")
-    } else {
-        PathBuf::from("vendor/SuperNOVAS/include")
-    };
-
-    gen_bindings(&supernovas_include);
+    #[cfg(feature = "novas-src")]
+    let version = supernovas_version();
 
     #[cfg(feature = "novas-src")]
     let supernovas_dir = supernovas_dir.or_else(|| {
         let downloaded = out_path.join("supernovas");
-        if !downloaded.exists() {
-            download_supernovas(&out_path);
+        let user_patches = patches::user_patches(PATCH_DIR);
+        let stamp = fs::read_to_string(downloaded.join(".version")).unwrap_or_default();
+        let patched = fs::read_to_string(downloaded.join(".patches")).unwrap_or_default();
+        if stamp != version || patched != patches::fingerprint(&user_patches) {
+            download_supernovas(&out_path, &version);
+            patches::apply(&downloaded, &user_patches);
         }
-        Some(out_path)
+        Some(out_path.clone())
     });
 
+    // The bindings and the version come from the headers that are built: those of the downloaded
+    // and patched release, or of the installation in SUPERNOVAS_DIR
+    let supernovas_include = match supernovas_dir.as_ref() {
+        Some(dir) if dir == &out_path => out_path.join("supernovas/include"),
+        Some(dir) => dir.join("include"),
+        None => PathBuf::from("vendor/SuperNOVAS/include"),
+    };
+
+    emit_version(&supernovas_include);
+    gen_bindings(&supernovas_include);
+
     let supernovas_dir = match supernovas_dir {
         Some(dir) => {
             if !dir.exists() {
-                println!("cargo:warning={}", format!("`supernovas_dir` does not point to a valid directory: {}", dir.display()));
+                println!("cargo:warning=`supernovas_dir` does not point to a valid directory: {}", dir.display());
                 return;
             }
             dir
         },
         None => {
-            println!("cargo:warning={}", format!("`supernovas_dir` does not point to a valid directory. Please set the {} environment variable or use `novas-src` feature.", SUPERNOVAS_DIR));
+            println!("cargo:warning=`supernovas_dir` does not point to a valid directory. Please set the {} environment variable or use `novas-src` feature.", SUPERNOVAS_DIR);
             return;
         }
     };
 
-    println!("cargo:rustc-link-lib=static=cspice");
-    println!("cargo:rustc-link-lib=static=calceph");
+    // CSPICE and CALCEPH are linked through `libcspice-sys` and `calceph-sys`, which publish the
+    // libraries they found or built as `DEP_CSPICE_LIB` and `DEP_CALCEPH_LIB`.
+    #[cfg(feature = "cspice")]
+    if env::var_os("DEP_CSPICE_LIB").is_none() {
+        println!("cargo:warning=libcspice-sys provides no CSPICE library, the CSPICE plugin will not link");
+    }
+    #[cfg(feature = "calceph")]
+    if env::var_os("DEP_CALCEPH_LIB").is_none() {
+        println!("cargo:warning=calceph-sys provides no CALCEPH library, the CALCEPH plugin will not link");
+    }
 
     #[cfg(feature = "novas-src")]
     build_supernovas(&supernovas_dir);
@@ -56,9 +88,46 @@ fn main() {
     println!("cargo:include={}", supernovas_include.to_str().unwrap());
 }
 
+/// The SuperNOVAS release to download: `SUPERNOVAS_VERSION` if set, otherwise the newest
+/// release selected by a `novas-1-*` feature, or the vendored release by default.
+#[cfg(feature = "novas-src")]
+fn supernovas_version() -> String {
+    if let Ok(version) = env::var(SUPERNOVAS_VERSION) {
+        return version.trim().trim_start_matches('v').to_string();
+    }
+    if cfg!(feature = "novas-1-4") {
+        "1.4.0".to_string()
+    } else if cfg!(feature = "novas-1-3") {
+        "1.3.0".to_string()
+    } else {
+        VENDORED_VERSION.to_string()
+    }
+}
+
+/// Publishes the version in `novas.h` as `DEP_SUPERNOVAS_VERSION`, for dependents to check the
+/// functions they call against.
+fn emit_version(include: &Path) {
+    let header = fs::read_to_string(include.join("novas.h")).unwrap_or_default();
+    let define = |name: &str| -> Option<u32> {
+        header.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some("#define"), Some(n), Some(value)) if n == name => value.parse().ok(),
+                _ => None,
+            }
+        })
+    };
+
+    let (Some(major), Some(minor)) = (define("SUPERNOVAS_MAJOR_VERSION"), define("SUPERNOVAS_MINOR_VERSION")) else {
+        println!("cargo:warning=Could not determine the SuperNOVAS version from {}", include.join("novas.h").display());
+        return;
+    };
+    let patch = define("SUPERNOVAS_PATCHLEVEL").unwrap_or(0);
+    println!("cargo:version={}.{}.{}", major, minor, patch);
+}
+
 #[cfg(feature = "novas-src")]
-fn download_supernovas(dst: &PathBuf) {
-    let supernovas_version = "1.4.0";
+fn download_supernovas(dst: &Path, supernovas_version: &str) {
     let url = format!("https://github.com/Smithsonian/SuperNOVAS/archive/refs/tags/v{}.tar.gz", supernovas_version);
 
     let body = reqwest::blocking::get(url)
@@ -88,6 +157,12 @@ fn download_supernovas(dst: &PathBuf) {
         fs::remove_dir_all(&to).expect("Failed to remove existing supernovas directory");
     }
     fs::rename(&from, &to).expect("Failed to rename extracted directory");
+    fs::write(to.join(".version"), supernovas_version).expect("Failed to write version stamp");
+
+    // The vendored sources carry local fixes for the release they are based on only
+    if supernovas_version != VENDORED_VERSION {
+        return;
+    }
 
     // 将 vendor/SuperNOVAS/src 覆盖到 to.join("src")
     let src_dir = to.join("src");
@@ -106,7 +181,7 @@ fn download_supernovas(dst: &PathBuf) {
 }
 
 #[cfg(feature = "novas-src")]
-fn build_supernovas(supernovas_dir: &PathBuf) {
+fn build_supernovas(supernovas_dir: &Path) {
     let supernovas_dir = supernovas_dir.join("supernovas");
     let dst = PathBuf::from(env::var("OUT_DIR").unwrap());
     let lib = dst.join("lib");
@@ -117,20 +192,36 @@ fn build_supernovas(supernovas_dir: &PathBuf) {
 
     let mut cfg = Build::new();
 
-    if let Some(include) = std::env::var_os("DEP_CSPICE_INCLUDE") {
+    #[cfg(feature = "cspice")]
+    if let Some(include) = env::var_os("DEP_CSPICE_INCLUDE") {
         cfg.include(include);
-    }    if let Some(include) = std::env::var_os("DEP_CALCEPH_INCLUDE") {
+    }
+    #[cfg(feature = "calceph")]
+    if let Some(include) = env::var_os("DEP_CALCEPH_INCLUDE") {
         cfg.include(include);
     }
 
     cfg.warnings(false).out_dir(&lib).include(supernovas_dir.join("include"));
 
+    for flag in sanitizer::flags(&target) {
+        cfg.flag(&flag);
+    }
+
+    if mobile::is_android() {
+        mobile::configure_ndk(&mut cfg);
+    }
+
     let src_files: Vec<_> = fs::read_dir(supernovas_dir.join("src"))
     .unwrap()
     .filter_map(|entry| {
         let entry = entry.unwrap();
         let path = entry.path();
-        if path.extension().and_then(|s| s.to_str()) == Some("c") {
+        let glue = match path.file_name().and_then(|s| s.to_str()) {
+            Some("solsys-cspice.c") => !cfg!(feature = "cspice"),
+            Some("solsys-calceph.c") => !cfg!(feature = "calceph"),
+            _ => false,
+        };
+        if path.extension().and_then(|s| s.to_str()) == Some("c") && !glue {
             Some(path)
         } else {
             None
@@ -138,8 +229,6 @@ fn build_supernovas(supernovas_dir: &PathBuf) {
     })
     .collect();
 
-    cfg.files(&src_files);
-
     if target.contains("windows") {
         // Use appropriate runtime library based on build profile
         let (runtime_lib, runtime_flag) = if is_debug {
@@ -168,7 +257,8 @@ fn build_supernovas(supernovas_dir: &PathBuf) {
         println!("cargo:rustc-link-arg=/DEFAULTLIB:{}.lib", runtime_lib);
     }
 
-    cfg.compile("supernovas");
+    compile::compile(&cfg, &src_files, &lib, "supernovas");
+
     let src_include = supernovas_dir.join("include");
     let dst_include = dst.join("include");
     fs::create_dir_all(&dst_include).unwrap();
@@ -178,42 +268,67 @@ fn build_supernovas(supernovas_dir: &PathBuf) {
     });
 }
 
-fn gen_bindings(include_dst: &PathBuf) {
+fn gen_bindings(include_dst: &Path) {
     let dst = PathBuf::from(env::var("OUT_DIR").unwrap());
-    // Generate the bindings
-    let mut builder = bindgen::Builder::default()
-        .header(include_dst.join("novas-calceph.h").to_str().unwrap())
-        .header(include_dst.join("novas-cspice.h").to_str().unwrap())
+
+    // Core library bindings
+    let builder = bindgen::Builder::default()
         .header(include_dst.join("novas.h").to_str().unwrap())
         .header(include_dst.join("nutation.h").to_str().unwrap())
-        .header(include_dst.join("solarsystem.h").to_str().unwrap());
-
-    builder = builder.clang_arg(format!("-I{}", include_dst.to_string_lossy()));
-
-    if let Some(calceph_include) = env::var_os("DEP_CALCEPH_INCLUDE") {
-        builder = builder.clang_arg(format!("-I{}", calceph_include.to_string_lossy()));
-    } else {
-        builder = builder.clang_arg("-Ivendor/calceph/include");
-    }
-
-    if let Some(cspice_include) = env::var_os("DEP_CSPICE_INCLUDE") {
-        builder = builder.clang_arg(format!("-I{}", cspice_include.to_string_lossy()));
-    }
-
-    builder = builder.blocklist_item("FP_NAN")
+        .header(include_dst.join("solarsystem.h").to_str().unwrap())
+        .blocklist_item("FP_NAN")
         .blocklist_item("FP_INFINITE")
         .blocklist_item("FP_ZERO")
         .blocklist_item("FP_SUBNORMAL")
         .blocklist_item("FP_NORMAL")
         .derive_default(true)
         .derive_debug(true);
-    
-    let bindings_path = dst.join("bindings.rs");
-    
+
+    write_bindings(base_builder(builder, include_dst), &dst.join("bindings.rs"));
+
+    // Glue for the CSPICE plugin. Only the items declared in the glue header are generated, the
+    // types they use come from the core bindings.
+    #[cfg(feature = "cspice")]
+    {
+        let builder = bindgen::Builder::default()
+            .header(include_dst.join("novas-cspice.h").to_str().unwrap())
+            .allowlist_file(".*novas-cspice\\.h")
+            .allowlist_recursively(false);
+
+        write_bindings(base_builder(builder, include_dst), &dst.join("cspice_bindings.rs"));
+    }
+
+    // Glue for the CALCEPH plugin. `t_calcephbin` is taken from `calceph-sys`.
+    #[cfg(feature = "calceph")]
+    {
+        let mut builder = bindgen::Builder::default()
+            .header(include_dst.join("novas-calceph.h").to_str().unwrap())
+            .allowlist_file(".*novas-calceph\\.h")
+            .allowlist_recursively(false);
+
+        if let Some(calceph_include) = env::var_os("DEP_CALCEPH_INCLUDE") {
+            builder = builder.clang_arg(format!("-I{}", calceph_include.to_string_lossy()));
+        }
+
+        write_bindings(base_builder(builder, include_dst), &dst.join("calceph_bindings.rs"));
+    }
+}
+
+// Settings shared by the core and glue bindings.
+fn base_builder(builder: bindgen::Builder, include_dst: &Path) -> bindgen::Builder {
+    builder
+        .clang_arg(format!("-I{}", include_dst.to_string_lossy()))
+        .clang_args(mobile::clang_args())
+        .generate_comments(true)
+        .clang_arg("-fparse-all-comments")
+        .parse_callbacks(Box::new(doxygen::Doxygen))
+}
+
+fn write_bindings(builder: bindgen::Builder, path: &Path) {
     let bindings = builder
         .generate()
         .expect("Unable to generate bindings for SuperNOVAS");
 
-    bindings.write_to_file(&bindings_path)
+    bindings.write_to_file(path)
         .expect("Couldn't write bindings!");
 }
@@ -3,42 +3,267 @@
 #![allow(non_snake_case)]
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
+// The CSPICE plugin calls into CSPICE, whose library `libcspice-sys` links only if the crate is
+// referenced; the CALCEPH glue references `calceph-sys` through `t_calcephbin`.
+#[cfg(feature = "cspice")]
+use libcspice_sys as _;
+
+/// Bindings for the CSPICE ephemeris plugin (`novas-cspice.h`).
+#[cfg(feature = "cspice")]
+pub mod cspice {
+    #[allow(unused_imports)]
+    use super::*;
+    include!(concat!(env!("OUT_DIR"), "/cspice_bindings.rs"));
+}
+
+/// Bindings for the CALCEPH ephemeris plugin (`novas-calceph.h`).
+#[cfg(feature = "calceph")]
+pub mod calceph {
+    use super::*;
+    use calceph_sys::t_calcephbin;
+    include!(concat!(env!("OUT_DIR"), "/calceph_bindings.rs"));
+}
+
+#[cfg(feature = "cspice")]
+pub use cspice::*;
+#[cfg(feature = "calceph")]
+pub use calceph::*;
+
 pub mod utils {
+    use std::str::FromStr;
+
+    /// Hours, minutes and seconds. Negative values carry the sign on every component.
+    ///
+    /// # Output styles
+    ///
+    /// `{}` displays the unit form, `12h 29m 06.70s`; the alternate flag, `{:#}`, displays the
+    /// compact form, `12:29:06.70`, which is also what most catalogs and tools take as input.
+    /// The precision sets the decimals of the seconds (default 2), which are rounded with the
+    /// carry into the minutes and hours, so that they never show as 60. Both forms parse back
+    /// with [`FromStr`].
+    ///
+    /// ```
+    /// use supernovas_sys::utils::HMS;
+    ///
+    /// let ra = HMS::from(12.485194);
+    /// assert_eq!(format!("{}", ra), "12h 29m 06.70s");
+    /// assert_eq!(format!("{:#.1}", ra), "12:29:06.7");
+    /// ```
     pub struct HMS(pub i32, pub i32, pub f64);
 
-    // impl from(h: f64) for HMS
     impl From<f64> for HMS {
-        fn from(hour: f64) -> Self {
-            let h = hour.floor() as i32;
-            let m = ((hour - h as f64) * 60.0).floor() as i32;
-            let s = (hour - h as f64 - m as f64 / 60.0) * 3600.0;
-            HMS(h, m, s)
+        fn from(hours: f64) -> Self {
+            let (negative, h, m, s) = split(hours);
+            let sign = if negative { -1 } else { 1 };
+            HMS(sign * h, sign * m, f64::from(sign) * s)
         }
     }
 
     impl std::fmt::Display for HMS {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let HMS(h, m, s) = self;
-            write!(f, "{:02}h {:02}m {:02.2}s", h, m, s)
+            let HMS(h, m, s) = *self;
+            let units = if f.alternate() { [":", ":", ""] } else { ["h ", "m ", "s"] };
+            write_sexagesimal(f, h, m, s, units)
         }
     }
 
+    /// Degrees, arcminutes and arcseconds. Negative values carry the sign on every component.
+    ///
+    /// # Output styles
+    ///
+    /// `{}` displays the unicode form, `-02° 03′ 08.64″`, with the degree, prime and double
+    /// prime signs; the alternate flag, `{:#}`, displays the compact ASCII form, `-02:03:08.64`.
+    /// The precision sets the decimals of the arcseconds (default 2), which are rounded with the
+    /// carry into the arcminutes and degrees, so that they never show as 60, and the `+` flag
+    /// shows the sign of positive angles too, as usual for declinations. Both forms parse back
+    /// with [`FromStr`].
+    ///
+    /// ```
+    /// use supernovas_sys::utils::DMS;
+    ///
+    /// let dec = DMS::from(-2.0524);
+    /// assert_eq!(format!("{}", dec), "-02° 03′ 08.64″");
+    /// assert_eq!(format!("{:#.0}", dec), "-02:03:09");
+    /// assert_eq!(format!("{}", DMS::from(-0.5)), "-00° 30′ 00.00″");
+    /// assert_eq!(format!("{:+#}", DMS::from(38.7836)), "+38:47:00.96");
+    /// ```
     pub struct DMS(pub i32, pub i32, pub f64);
 
-    // impl from(d: f64) for DMS
     impl From<f64> for DMS {
-        fn from(deg: f64) -> Self {
-            let d = deg.floor() as i32;
-            let m = ((deg - d as f64) * 60.0).floor() as i32;
-            let s = (deg - d as f64 - m as f64 / 60.0) * 3600.0;
-            DMS(d, m, s)
+        fn from(degrees: f64) -> Self {
+            let (negative, d, m, s) = split(degrees);
+            let sign = if negative { -1 } else { 1 };
+            DMS(sign * d, sign * m, f64::from(sign) * s)
         }
     }
 
     impl std::fmt::Display for DMS {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let DMS(d, m, s) = self;
-            write!(f, "{:02}° {:02}′ {:02.2}″", d, m, s)
+            let DMS(d, m, s) = *self;
+            let units = if f.alternate() { [":", ":", ""] } else { ["° ", "′ ", "″"] };
+            write_sexagesimal(f, d, m, s, units)
+        }
+    }
+
+    // Splits a value into its sign and the unsigned whole units, minutes and seconds.
+    fn split(value: f64) -> (bool, i32, i32, f64) {
+        let abs = value.abs();
+        let whole = abs.floor();
+        let minutes = ((abs - whole) * 60.0).floor();
+        let seconds = ((abs - whole) * 3600.0 - minutes * 60.0).max(0.0);
+        (value < 0.0, whole as i32, minutes as i32, seconds)
+    }
+
+    // Writes whole units, minutes and seconds, each followed by its unit, with a single leading
+    // minus sign for negative values, or a plus sign for the others with the `+` flag. The seconds
+    // are rounded to the precision of the formatter.
+    fn write_sexagesimal(
+        f: &mut std::fmt::Formatter<'_>,
+        whole: i32,
+        minutes: i32,
+        seconds: f64,
+        units: [&str; 3],
+    ) -> std::fmt::Result {
+        let negative = whole < 0 || minutes < 0 || seconds < 0.0;
+        let decimals = f.precision().unwrap_or(2);
+        let scale = 10f64.powi(decimals as i32);
+        let total = f64::from(whole.unsigned_abs()) * 3600.0
+            + f64::from(minutes.unsigned_abs()) * 60.0
+            + seconds.abs();
+        let total = (total * scale).round() / scale;
+        let whole = (total / 3600.0).floor();
+        let minutes = ((total - whole * 3600.0) / 60.0).floor();
+        let seconds = total - whole * 3600.0 - minutes * 60.0;
+        let width = if decimals == 0 { 2 } else { decimals + 3 };
+        write!(
+            f,
+            "{}{:02}{}{:02}{}{:0width$.decimals$}{}",
+            if negative { "-" } else if f.sign_plus() { "+" } else { "" },
+            whole,
+            units[0],
+            minutes,
+            units[1],
+            seconds,
+            units[2],
+        )
+    }
+
+    impl HMS {
+        /// The angle in decimal hours.
+        pub fn hours(&self) -> f64 {
+            let HMS(h, m, s) = *self;
+            h as f64 + m as f64 / 60.0 + s / 3600.0
+        }
+    }
+
+    impl DMS {
+        /// The angle in decimal degrees.
+        pub fn degrees(&self) -> f64 {
+            let DMS(d, m, s) = *self;
+            d as f64 + m as f64 / 60.0 + s / 3600.0
+        }
+    }
+
+    impl FromStr for HMS {
+        type Err = ParseAngleError;
+
+        /// Parses hours as decimal hours or as sexagesimal hours, minutes and seconds, e.g. "12.485",
+        /// "12h29m6.7s", "12:29:06.7" or "12 29 06.7". A leading sign is accepted.
+        fn from_str(s: &str) -> Result<Self, ParseAngleError> {
+            let (negative, h, m, s) = parse_sexagesimal(s, &['h', 'm', 's'])?;
+            let sign = if negative { -1 } else { 1 };
+            Ok(HMS(sign * h, sign * m, f64::from(sign) * s))
+        }
+    }
+
+    impl FromStr for DMS {
+        type Err = ParseAngleError;
+
+        /// Parses degrees as decimal degrees or as sexagesimal degrees, arcminutes and arcseconds,
+        /// e.g. "-2.0524", "+2 3 8.6", "2:03:08.6", "2d3m8.6s" or "2°03′08.6″". The sign may be
+        /// given as a leading `+` or `-`, or as a trailing N, E, S or W.
+        fn from_str(s: &str) -> Result<Self, ParseAngleError> {
+            let s = s.trim();
+            let (s, compass) = match s.strip_suffix(['N', 'E', 'S', 'W']) {
+                Some(rest) => (rest, Some(s.ends_with(['S', 'W']))),
+                None => (s, None),
+            };
+            let markers = ['d', '°', 'm', '\'', '′', 's', '"', '″'];
+            let (negative, d, m, sec) = parse_sexagesimal(s, &markers)?;
+            let negative = match compass {
+                Some(_) if s.starts_with(['+', '-']) => {
+                    return Err(ParseAngleError(
+                        "angle has both a sign and a compass direction",
+                    ));
+                }
+                Some(south) => south,
+                None => negative,
+            };
+            let sign = if negative { -1 } else { 1 };
+            Ok(DMS(sign * d, sign * m, f64::from(sign) * sec))
+        }
+    }
+
+    /// Error of parsing a sexagesimal angle.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ParseAngleError(&'static str);
+
+    impl ParseAngleError {
+        /// Description of the error.
+        pub fn message(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    impl std::fmt::Display for ParseAngleError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl std::error::Error for ParseAngleError {}
+
+    // Parses an optional sign and one to three numbers separated by whitespace, colons or unit
+    // markers, into the sign and the unsigned whole units, minutes and seconds. Only the last
+    // number may have a fraction, and minutes and seconds must be under 60. A single number is
+    // split into whole units, minutes and seconds.
+    fn parse_sexagesimal(
+        s: &str,
+        markers: &[char],
+    ) -> Result<(bool, i32, i32, f64), ParseAngleError> {
+        let s = s.trim();
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let fields: Vec<&str> = s
+            .split(|c: char| c.is_whitespace() || c == ':' || markers.contains(&c))
+            .filter(|f| !f.is_empty())
+            .collect();
+        if fields.is_empty() || fields.len() > 3 {
+            return Err(ParseAngleError("expected one to three numbers"));
+        }
+        let mut values = [0.0f64; 3];
+        for (i, field) in fields.iter().enumerate() {
+            let last = i + 1 == fields.len();
+            let digits = field.bytes().all(|b| b.is_ascii_digit() || (last && b == b'.'));
+            if !digits || field.bytes().filter(|b| *b == b'.').count() > 1 {
+                return Err(ParseAngleError("invalid number in angle"));
+            }
+            values[i] = field.parse().map_err(|_| ParseAngleError("invalid number in angle"))?;
+            if i > 0 && values[i] >= 60.0 {
+                return Err(ParseAngleError("minutes or seconds out of range"));
+            }
+        }
+        if fields.len() == 1 {
+            let value = values[0];
+            let whole = value.floor();
+            let minutes = ((value - whole) * 60.0).floor();
+            values = [whole, minutes, (value - whole) * 3600.0 - minutes * 60.0];
+        }
+        if values[0] > i32::MAX as f64 {
+            return Err(ParseAngleError("angle out of range"));
         }
+        Ok((negative, values[0] as i32, values[1] as i32, values[2]))
     }
-}
\ No newline at end of file
+}
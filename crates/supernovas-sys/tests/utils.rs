@@ -0,0 +1,85 @@
+//! Properties of the sexagesimal `HMS` and `DMS` formatting and parsing.
+
+use proptest::prelude::*;
+use supernovas_sys::utils::{DMS, HMS};
+
+// Largest error of a value shown with `decimals` decimals of seconds, in units of the whole part.
+fn rounding(decimals: usize) -> f64 {
+    0.5 * 10f64.powi(-(decimals as i32)) / 3600.0 + 1e-12
+}
+
+// The whole units, minutes and seconds fields of `{:#}` output, without the sign.
+fn fields(text: &str) -> (i32, i32, f64) {
+    let parts: Vec<&str> = text.trim_start_matches('-').split(':').collect();
+    (parts[0].parse().unwrap(), parts[1].parse().unwrap(), parts[2].parse().unwrap())
+}
+
+proptest! {
+    #[test]
+    fn hms_display_parses_back(hours in -48.0f64..48.0, decimals in 0usize..7, alternate in any::<bool>()) {
+        let text = match alternate {
+            true => format!("{:#.*}", decimals, HMS::from(hours)),
+            false => format!("{:.*}", decimals, HMS::from(hours)),
+        };
+        let parsed: HMS = text.parse().unwrap();
+        prop_assert!((parsed.hours() - hours).abs() <= rounding(decimals), "{} parsed as {}", text, parsed.hours());
+    }
+
+    #[test]
+    fn dms_display_parses_back(degrees in -360.0f64..360.0, decimals in 0usize..7, alternate in any::<bool>()) {
+        let text = match alternate {
+            true => format!("{:#.*}", decimals, DMS::from(degrees)),
+            false => format!("{:.*}", decimals, DMS::from(degrees)),
+        };
+        let parsed: DMS = text.parse().unwrap();
+        prop_assert!((parsed.degrees() - degrees).abs() <= rounding(decimals), "{} parsed as {}", text, parsed.degrees());
+    }
+
+    #[test]
+    fn seconds_rounding_to_60_carry_into_minutes_and_degrees(
+        whole in 0i32..360,
+        minutes in 0i32..60,
+        below in 0.0f64..0.4,
+        decimals in 0usize..7,
+        negative in any::<bool>(),
+    ) {
+        // Less than half a unit of the last decimal below a full minute
+        let seconds = 60.0 - below * 10f64.powi(-(decimals as i32));
+        let value = f64::from(whole) + f64::from(minutes) / 60.0 + seconds / 3600.0;
+        let value = if negative { -value } else { value };
+        let text = format!("{:#.*}", decimals, DMS::from(value));
+        let carry = i32::from(minutes == 59);
+        prop_assert_eq!(fields(&text), (whole + carry, (minutes + 1) % 60, 0.0), "{}", text);
+        prop_assert_eq!(text.starts_with('-'), negative, "{}", text);
+    }
+
+    #[test]
+    fn angles_above_minus_one_keep_their_sign(minutes in 0i32..60, seconds in 0.0f64..60.0, decimals in 0usize..4) {
+        prop_assume!(minutes > 0 || seconds >= 1.0);
+        let degrees = -(f64::from(minutes) / 60.0 + seconds / 3600.0);
+        let text = format!("{:.*}", decimals, DMS::from(degrees));
+        prop_assert!(text.starts_with("-00° "), "{}", text);
+        let parsed: DMS = text.parse().unwrap();
+        prop_assert!(parsed.degrees() < 0.0, "{} parsed as {}", text, parsed.degrees());
+        prop_assert!((parsed.degrees() - degrees).abs() <= rounding(decimals), "{} parsed as {}", text, parsed.degrees());
+    }
+}
+
+#[test]
+fn minus_half_a_degree() {
+    assert_eq!(format!("{}", DMS::from(-0.5)), "-00° 30′ 00.00″");
+    assert_eq!(format!("{:#.0}", DMS::from(-0.5)), "-00:30:00");
+    assert_eq!(format!("{}", HMS::from(-0.5)), "-00h 30m 00.00s");
+    for text in ["-0°30′", "-00:30:00", "-0 30", "0°30′S", "0d30mW"] {
+        assert_eq!(text.parse::<DMS>().unwrap().degrees(), -0.5, "{}", text);
+    }
+    assert_eq!("-0:30".parse::<HMS>().unwrap().hours(), -0.5);
+}
+
+#[test]
+fn plus_sign() {
+    assert_eq!(format!("{:+#.1}", DMS::from(38.7836)), "+38:47:01.0");
+    assert_eq!(format!("{:+#.1}", DMS::from(-38.7836)), "-38:47:01.0");
+    assert_eq!(format!("{:+}", HMS::from(0.0)), "+00h 00m 00.00s");
+    assert_eq!("+38:47:01.0".parse::<DMS>().unwrap().degrees(), 38.0 + 47.0 / 60.0 + 1.0 / 3600.0);
+}
@@ -0,0 +1,32 @@
+//! Position of the Sun from Bonn, with CALCEPH as the ephemeris provider.
+//!
+//! ```
+//! EPH_DE440S=/path/to/de440s.bsp cargo run --example calceph
+//! ```
+
+use astrokits::novas::{self, ephemeris, Accuracy, Observer, ObservingFrame, Planet, ReferenceSystem, Refraction, Source, Time};
+
+const LEAP_SECONDS: i32 = 37; // [s] current leap seconds from IERS Bulletin C
+const DUT1: f64 = 0.114; // [s] current UT1 - UTC time difference from IERS Bulletin A
+const POLAR_DX: f64 = 230.0; // [mas] Earth polar offset x
+const POLAR_DY: f64 = -62.0; // [mas] Earth polar offset y
+
+fn main() -> novas::Result<()> {
+    // Open the ephemeris and use CALCEPH as ephemeris provider
+    let path = std::env::var("EPH_DE440S").expect("EPH_DE440S should name an ephemeris file, e.g. de440s.bsp");
+    ephemeris::use_calceph(ephemeris::Calceph::open(path)?)?;
+
+    let source = Source::planet(Planet::Sun)?;
+    let observer = Observer::on_surface(50.7374, 7.0982, 60.0)?;
+    let time = Time::from_unix(1750680744, 238528900, LEAP_SECONDS, DUT1)?;
+    let frame = ObservingFrame::new(Accuracy::Full, &observer, &time, POLAR_DX, POLAR_DY)?;
+
+    // Apparent position (CIRS)
+    let apparent = frame.sky_pos(&source, ReferenceSystem::Cirs)?;
+    println!(" RA = {:.9} h, Dec = {:.9} deg, rad_vel = {:.6} km/s", apparent.ra, apparent.dec, apparent.rv);
+
+    // Azimuth and elevation, with standard optical refraction
+    let hor = frame.to_horizontal(&apparent, ReferenceSystem::Cirs, Refraction::Standard)?;
+    println!(" Az = {:.6} deg, El = {:.6} deg", hor.az, hor.el);
+    Ok(())
+}
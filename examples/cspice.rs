@@ -0,0 +1,38 @@
+//! Position of the Sun from Ürümqi, with CSPICE as the ephemeris provider.
+//!
+//! ```
+//! EPH_DE440S=/path/to/de440s.bsp cargo run --example cspice
+//! ```
+
+use astrokits::novas::{self, ephemeris, Accuracy, Observer, ObservingFrame, Planet, ReferenceSystem, Refraction, Source, Time, Timescale};
+use supernovas_sys::utils::{DMS, HMS};
+
+const LEAP_SECONDS: i32 = 37; // [s] current leap seconds from IERS Bulletin C
+const DUT1: f64 = 0.035044; // [s] current UT1 - UTC time difference from IERS Bulletin A
+const POLAR_DX: f64 = 142.0; // [mas] Earth polar offset x
+const POLAR_DY: f64 = 443.05; // [mas] Earth polar offset y
+
+fn main() -> novas::Result<()> {
+    // Load the ephemeris and use CSPICE as ephemeris provider
+    let kernel = std::env::var("EPH_DE440S").expect("EPH_DE440S should name an SPK file, e.g. de440s.bsp");
+    ephemeris::load_cspice_kernel(kernel)?;
+    ephemeris::use_cspice()?;
+
+    let source = Source::planet(Planet::Sun)?;
+    let observer = Observer::on_surface(43.82441, 87.61390, 0.0)?;
+    let time = Time::parse("2025-06-24T12:29:36Z", Timescale::Utc, LEAP_SECONDS, DUT1)?;
+    let frame = ObservingFrame::new(Accuracy::Full, &observer, &time, POLAR_DX, POLAR_DY)?;
+
+    // Astrometric position (ICRS)
+    let current = frame.sky_pos(&source, ReferenceSystem::Icrs)?;
+    println!(" RA = {} h, Dec = {} deg, rad_vel = {:.6} km/s", HMS::from(current.ra), DMS::from(current.dec), current.rv);
+
+    // Apparent position (CIRS)
+    let apparent = frame.sky_pos(&source, ReferenceSystem::Cirs)?;
+    println!(" RA = {} h, Dec = {} deg, rad_vel = {:.6} km/s", HMS::from(apparent.ra), DMS::from(apparent.dec), apparent.rv);
+
+    // Azimuth and elevation, with standard optical refraction
+    let hor = frame.to_horizontal(&apparent, ReferenceSystem::Cirs, Refraction::Standard)?;
+    println!(" Az = {} deg, El = {} deg", DMS::from(hor.az), DMS::from(hor.el));
+    Ok(())
+}
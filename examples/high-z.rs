@@ -0,0 +1,34 @@
+//! Position of the quasar 3C 273 now, at reduced accuracy, without a planet provider.
+//!
+//! ```
+//! cargo run --example high-z
+//! ```
+
+use astrokits::novas::{self, Accuracy, Degrees, Hours, Observer, ObservingFrame, ReferenceSystem, Redshift, Refraction, Source, Time};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LEAP_SECONDS: i32 = 37; // [s] current leap seconds from IERS Bulletin C
+const DUT1: f64 = 0.114; // [s] current UT1 - UTC time difference from IERS Bulletin A
+const POLAR_DX: f64 = 230.0; // [mas] Earth polar offset x
+const POLAR_DY: f64 = -62.0; // [mas] Earth polar offset y
+
+fn main() -> novas::Result<()> {
+    // 3C 273: 12h29m6.6997s +2d3m8.598s (ICRS), z = 0.158339
+    let ra: Hours = "12h29m6.6997s".parse()?;
+    let dec: Degrees = "+2d3m8.598s".parse()?;
+    let source = Source::redshifted("3c273", ra.0, dec.0, "ICRS", Redshift::new(0.158339)?)?;
+
+    let observer = Observer::on_surface(50.7374, 7.0982, 60.0)?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let time = Time::from_unix(now.as_secs() as i64, now.subsec_nanos() as i32, LEAP_SECONDS, DUT1)?;
+    let frame = ObservingFrame::new(Accuracy::Reduced, &observer, &time, POLAR_DX, POLAR_DY)?;
+
+    // Apparent position (CIRS)
+    let apparent = frame.sky_pos(&source, ReferenceSystem::Cirs)?;
+    println!(" RA = {:.9} h, Dec = {:.9} deg, z_obs = {:.9}", apparent.ra, apparent.dec, apparent.redshift()?.value());
+
+    // Azimuth and elevation, with standard optical refraction
+    let hor = frame.to_horizontal(&apparent, ReferenceSystem::Cirs, Refraction::Standard)?;
+    println!(" Az = {:.6} deg, El = {:.6} deg", hor.az, hor.el);
+    Ok(())
+}
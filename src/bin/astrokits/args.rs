@@ -0,0 +1,143 @@
+use crate::CliResult;
+use std::str::FromStr;
+
+/// Command-line arguments, consumed option by option.
+pub struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    pub fn from_env() -> Self {
+        Args { args: std::env::args().skip(1).collect() }
+    }
+
+    #[cfg(test)]
+    pub fn from_slice(args: &[&str]) -> Self {
+        Args { args: args.iter().map(|arg| arg.to_string()).collect() }
+    }
+
+    /// Removes `name` if present.
+    pub fn flag(&mut self, name: &str) -> bool {
+        match self.args.iter().position(|arg| arg == name) {
+            Some(i) => {
+                self.args.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes `name VALUE` or `name=VALUE` and returns the value.
+    pub fn value(&mut self, name: &str) -> CliResult<Option<String>> {
+        let prefix = format!("{}=", name);
+        for i in 0..self.args.len() {
+            if let Some(value) = self.args[i].strip_prefix(&prefix) {
+                let value = value.to_string();
+                self.args.remove(i);
+                return Ok(Some(value));
+            }
+            if self.args[i] == name {
+                if i + 1 >= self.args.len() {
+                    return Err(format!("missing value for {}", name).into());
+                }
+                let value = self.args.remove(i + 1);
+                self.args.remove(i);
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Removes and parses the value of `name`.
+    pub fn parse<T: FromStr>(&mut self, name: &str) -> CliResult<Option<T>> {
+        match self.value(name)? {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("invalid value for {}: `{}`", name, value).into()),
+            None => Ok(None),
+        }
+    }
+
+    /// Removes the first argument that is not an option. Values of options must have been taken
+    /// before.
+    pub fn positional(&mut self) -> Option<String> {
+        let i = self.args.iter().position(|arg| !arg.starts_with("--") || arg == "--")?;
+        if self.args[i] == "--" {
+            self.args.remove(i);
+            return (i < self.args.len()).then(|| self.args.remove(i));
+        }
+        Some(self.args.remove(i))
+    }
+
+    /// Fails if any argument was not consumed.
+    pub fn finish(self) -> CliResult<()> {
+        match self.args.first() {
+            Some(arg) => Err(format!("unexpected argument `{}`", arg).into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses a duration such as `90`, `30s`, `15min`, `2h` or `1d`, in seconds.
+pub fn parse_duration(s: &str) -> CliResult<f64> {
+    let s = s.trim();
+    let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid duration `{}`", s))?;
+    let scale = match unit {
+        "" | "s" | "sec" => 1.0,
+        "m" | "min" => 60.0,
+        "h" | "hr" => 3600.0,
+        "d" | "day" => 86400.0,
+        _ => return Err(format!("invalid duration unit `{}`", unit).into()),
+    };
+    let seconds = number * scale;
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return Err(format!("duration must be positive: `{}`", s).into());
+    }
+    Ok(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_and_positionals() {
+        let mut args = Args::from_slice(&["riseset", "--utc", "--date=2025-06-24", "--step", "1h", "--", "--vega"]);
+        assert!(args.flag("--utc"));
+        assert!(!args.flag("--utc"));
+        assert_eq!(args.value("--date").unwrap().as_deref(), Some("2025-06-24"));
+        assert_eq!(args.value("--step").unwrap().as_deref(), Some("1h"));
+        assert_eq!(args.value("--step").unwrap(), None);
+        assert_eq!(args.positional().as_deref(), Some("riseset"));
+        // Arguments after `--` are positional even if they look like options
+        assert_eq!(args.positional().as_deref(), Some("--vega"));
+        assert_eq!(args.positional(), None);
+        args.finish().unwrap();
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert_eq!(Args::from_slice(&["--date"]).value("--date").unwrap_err().to_string(), "missing value for --date");
+        let mut args = Args::from_slice(&["--height", "high"]);
+        assert_eq!(args.parse::<f64>("--height").unwrap_err().to_string(), "invalid value for --height: `high`");
+        assert_eq!(Args::from_slice(&["--utc"]).finish().unwrap_err().to_string(), "unexpected argument `--utc`");
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("90").unwrap(), 90.0);
+        assert_eq!(parse_duration("15min").unwrap(), 900.0);
+        assert_eq!(parse_duration(" 1.5 h").unwrap(), 5400.0);
+        assert_eq!(parse_duration("1d").unwrap(), 86400.0);
+        assert!(parse_duration("1w").is_err());
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("-5m").is_err());
+        assert!(parse_duration("soon").is_err());
+    }
+}
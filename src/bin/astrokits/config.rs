@@ -0,0 +1,419 @@
+use crate::args::Args;
+use crate::CliResult;
+use astrokits::novas::eop::{self, EopTable, LeapSeconds, MJD0};
+use astrokits::novas::{self, Accuracy, Degrees, ObservingFrame, Observer, Source, Time, Timescale};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs};
+
+/// Observing site on Earth's surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Site {
+    /// [deg] geodetic latitude
+    pub latitude: f64,
+    /// [deg] geodetic longitude, east positive
+    pub longitude: f64,
+    /// [m] height above sea level
+    pub height: f64,
+}
+
+/// Settings shared by the commands, read from a small TOML file:
+///
+/// ```toml
+/// [site]
+/// latitude = 43.4712
+/// longitude = 87.1781
+/// height = 2080
+///
+/// [eop]
+/// leap_seconds = 37
+/// dut1 = 0.035
+/// polar_dx = 142.0
+/// polar_dy = 443.05
+/// cache = "/var/cache/astrokits/eop"
+///
+/// [ephemeris]
+/// kernels = ["de440s.bsp"]
+/// cache = "/var/cache/astrokits/kernels"
+/// ```
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub site: Option<Site>,
+    /// [s] TAI - UTC, or `None` to take that of the date from the leap-second table: the file of
+    /// `astrokits eop refresh` if cached, or else the compiled-in table.
+    pub leap_seconds: Option<i32>,
+    /// [s] UT1 - UTC, or `None` to take that of the date from the cached finals2000A file of
+    /// `astrokits eop refresh`, if any
+    pub dut1: Option<f64>,
+    /// [mas] polar offsets from IERS Bulletin A, or `None` to take them from the cached
+    /// finals2000A file, as UT1 - UTC
+    pub polar_dx: Option<f64>,
+    pub polar_dy: Option<f64>,
+    /// Earth orientation of the cached finals2000A file
+    pub eop: Option<EopTable>,
+    /// SPICE kernels. Relative paths are resolved against the directory of the file, or else
+    /// against the kernel cache.
+    pub kernels: Vec<PathBuf>,
+    /// Directory of the kernels managed by `astrokits kernels`.
+    pub cache: PathBuf,
+    /// Directory of the IERS files managed by `astrokits eop`.
+    pub eop_cache: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            site: None,
+            leap_seconds: None,
+            dut1: None,
+            polar_dx: None,
+            polar_dy: None,
+            eop: None,
+            kernels: Vec::new(),
+            cache: cache_root().join("kernels"),
+            eop_cache: cache_root().join("eop"),
+        }
+    }
+}
+
+impl Config {
+    /// Reads `path`, or the default configuration file if it exists.
+    pub fn load(path: Option<&Path>) -> CliResult<Config> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match default_path() {
+                Some(path) if path.is_file() => path,
+                _ => {
+                    let mut config = Config::default();
+                    config.load_iers_files();
+                    return Ok(config);
+                }
+            },
+        };
+        let text = fs::read_to_string(&path).map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+        let base = path.parent().unwrap_or(Path::new("."));
+        let mut config = Config::parse(&text, base).map_err(|err| format!("{}: {}", path.display(), err))?;
+        config.load_iers_files();
+        Ok(config)
+    }
+
+    /// Installs the cached leap-second file as the table of the library, and reads the cached
+    /// finals2000A file unless its values are all configured.
+    fn load_iers_files(&mut self) {
+        let read = |file: &str| {
+            let path = self.eop_cache.join(file);
+            fs::read_to_string(&path).ok().map(|text| (path, text))
+        };
+        if let Some((path, text)) = read(crate::eop::LEAP_SECONDS_FILE) {
+            match LeapSeconds::parse(&text) {
+                Ok(table) => eop::set_leap_seconds(table),
+                Err(err) => eprintln!("astrokits: warning: ignoring {}: {}", path.display(), err),
+            }
+        }
+        if self.dut1.is_some() && self.polar_dx.is_some() && self.polar_dy.is_some() {
+            return;
+        }
+        if let Some((path, text)) = read(crate::eop::FINALS_FILE) {
+            match EopTable::parse(&text) {
+                Ok(table) => self.eop = Some(table),
+                Err(err) => eprintln!("astrokits: warning: ignoring {}: {}", path.display(), err),
+            }
+        }
+    }
+
+    fn parse(text: &str, base: &Path) -> CliResult<Config> {
+        let mut config = Config::default();
+        let mut site = (None, None, 0.0);
+        let mut kernels = Vec::new();
+        let mut section = String::new();
+
+        for (n, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_string();
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `key = value`", n + 1))?;
+            let (key, value) = (key.trim(), value.trim());
+            let number = || -> CliResult<f64> {
+                value
+                    .parse()
+                    .map_err(|_| format!("line {}: `{}` is not a number", n + 1, value).into())
+            };
+
+            match (section.as_str(), key) {
+                ("site", "latitude") => site.0 = Some(number()?),
+                ("site", "longitude") => site.1 = Some(number()?),
+                ("site", "height") => site.2 = number()?,
+                ("eop", "leap_seconds") => config.leap_seconds = Some(number()? as i32),
+                ("eop", "dut1") => config.dut1 = Some(number()?),
+                ("eop", "polar_dx") => config.polar_dx = Some(number()?),
+                ("eop", "polar_dy") => config.polar_dy = Some(number()?),
+                ("ephemeris", "kernels") => {
+                    kernels = parse_strings(value).ok_or_else(|| format!("line {}: expected a list of strings", n + 1))?;
+                }
+                ("ephemeris", "cache") => config.cache = base.join(parse_string(value, n)?),
+                ("eop", "cache") => config.eop_cache = base.join(parse_string(value, n)?),
+                _ => return Err(format!("line {}: unknown key `{}` in [{}]", n + 1, key, section).into()),
+            }
+        }
+
+        config.kernels = kernels
+            .into_iter()
+            .map(|kernel| match base.join(&kernel) {
+                path if path.exists() => path,
+                _ => config.cache.join(kernel),
+            })
+            .collect();
+        config.site = match site {
+            (Some(latitude), Some(longitude), height) => Some(Site { latitude, longitude, height }),
+            (None, None, _) => None,
+            _ => return Err("[site] needs both latitude and longitude".into()),
+        };
+        Ok(config)
+    }
+
+    /// Applies the `--lat`, `--lon` and `--height` overrides of a command.
+    pub fn apply_site_args(&mut self, args: &mut Args) -> CliResult<()> {
+        let angle = |value: Option<String>, name: &str| -> CliResult<Option<f64>> {
+            value
+                .map(|v| match v.parse::<Degrees>() {
+                    Ok(angle) => Ok(angle.0),
+                    Err(err) => Err(format!("invalid value for {}: `{}`: {}", name, v, err).into()),
+                })
+                .transpose()
+        };
+        let latitude = angle(args.value("--lat")?, "--lat")?;
+        let longitude = angle(args.value("--lon")?, "--lon")?;
+        let height: Option<f64> = args.parse("--height")?;
+
+        let site = match (self.site, latitude, longitude) {
+            (Some(site), _, _) => Site {
+                latitude: latitude.unwrap_or(site.latitude),
+                longitude: longitude.unwrap_or(site.longitude),
+                height: height.unwrap_or(site.height),
+            },
+            (None, Some(latitude), Some(longitude)) => Site { latitude, longitude, height: height.unwrap_or(0.0) },
+            (None, None, None) if height.is_none() => return Ok(()),
+            _ => return Err("the site needs both --lat and --lon".into()),
+        };
+        self.site = Some(site);
+        Ok(())
+    }
+
+    /// The observer at the configured site, or at the geocenter without one.
+    pub fn observer(&self) -> novas::Result<Observer> {
+        match self.site {
+            Some(site) => Observer::on_surface(site.latitude, site.longitude, site.height),
+            None => Observer::in_space([0.0; 3], [0.0; 3]),
+        }
+    }
+
+    /// Parses a UTC date, or `now`.
+    pub fn time(&self, date: &str) -> CliResult<Time> {
+        self.time_in(date, Timescale::Utc)
+    }
+
+    /// Parses a date in the timescale `scale`, or `now`.
+    pub fn time_in(&self, date: &str, scale: Timescale) -> CliResult<Time> {
+        if date == "now" {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+            return self.make_time(|leap, dut1| Time::from_unix(now.as_secs() as i64, now.subsec_nanos() as i32, leap, dut1));
+        }
+        self.make_time(|leap, dut1| Time::parse(date, scale, leap, dut1))
+            .map_err(|_| format!("invalid date `{}`", date).into())
+    }
+
+    /// Creates a time with `make(leap_seconds, dut1)`, with the configured TAI - UTC and UT1 - UTC
+    /// or else those of its date in the IERS tables. Warns about dates past the expiry of the
+    /// leap-second table.
+    pub fn make_time(&self, make: impl Fn(i32, f64) -> novas::Result<Time>) -> CliResult<Time> {
+        if let (Some(leap), Some(dut1)) = (self.leap_seconds, self.dut1) {
+            return Ok(make(leap, dut1)?);
+        }
+        let table = eop::leap_seconds();
+        // The leap seconds only move UTC by a minute at most, so those of the latest entry find
+        // the date.
+        let latest = table.at(f64::INFINITY).unwrap_or(0);
+        let mjd = make(self.leap_seconds.unwrap_or(latest), 0.0)?.jd(Timescale::Utc) - MJD0;
+        let leap = match self.leap_seconds {
+            Some(leap) => leap,
+            None => {
+                if table.is_stale(mjd) {
+                    eprintln!(
+                        "astrokits: warning: the leap-second table expired on {}, run `astrokits eop refresh`",
+                        table.expires().unwrap_or("an unknown date")
+                    );
+                }
+                table.at(mjd).ok_or("date precedes the leap-second table")?
+            }
+        };
+        let dut1 = self.dut1.or_else(|| Some(self.eop.as_ref()?.at(mjd)?.dut1)).unwrap_or(0.0);
+        Ok(make(leap, dut1)?)
+    }
+
+    /// [mas] Polar offsets at `time`: the configured ones, or else those of the cached
+    /// finals2000A file, or zero outside of it.
+    pub fn polar_offsets(&self, time: &Time) -> (f64, f64) {
+        let eop = self.eop.as_ref().and_then(|table| table.at(time.jd(Timescale::Utc) - MJD0));
+        let dx = self.polar_dx.or(eop.map(|eop| eop.xp * 1e3)).unwrap_or(0.0);
+        let dy = self.polar_dy.or(eop.map(|eop| eop.yp * 1e3)).unwrap_or(0.0);
+        (dx, dy)
+    }
+
+    /// Loads the configured kernels. Returns full accuracy if there were any.
+    pub fn load_ephemeris(&self) -> CliResult<Accuracy> {
+        if self.kernels.is_empty() {
+            return Ok(Accuracy::Reduced);
+        }
+        for kernel in &self.kernels {
+            novas::ephemeris::load_cspice_kernel(kernel)
+                .map_err(|err| format!("cannot load kernel {}: {}", kernel.display(), err))?;
+        }
+        novas::ephemeris::use_cspice()?;
+        Ok(Accuracy::Full)
+    }
+
+    /// Describes a failed position calculation, hinting at missing kernels if none are configured.
+    pub fn position_error(&self, err: novas::Error, source: &Source) -> String {
+        if self.kernels.is_empty() {
+            format!("{} (no ephemeris for {}? add SPICE kernels to the configuration)", err, source.name())
+        } else {
+            err.to_string()
+        }
+    }
+
+    /// An observing frame for the configured observer.
+    pub fn frame(&self, accuracy: Accuracy, time: &Time) -> novas::Result<ObservingFrame> {
+        let (dx, dy) = self.polar_offsets(time);
+        ObservingFrame::new(accuracy, &self.observer()?, time, dx, dy)
+    }
+}
+
+/// `$ASTROKITS_CONFIG`, or `astrokits/config.toml` in the user's configuration directory.
+fn default_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("ASTROKITS_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(dir.join("astrokits").join("config.toml"))
+}
+
+/// `astrokits` in the user's cache directory.
+fn cache_root() -> PathBuf {
+    let dir = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(env::temp_dir);
+    dir.join("astrokits")
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_string(value: &str, line: usize) -> CliResult<String> {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .map(str::to_string)
+        .ok_or_else(|| format!("line {}: expected a string", line + 1).into())
+}
+
+/// Parses `["a", "b"]` or a single `"a"`.
+fn parse_strings(value: &str) -> Option<Vec<String>> {
+    let items = match value.strip_prefix('[') {
+        Some(list) => list.strip_suffix(']')?,
+        None => value,
+    };
+    items
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| Some(item.strip_prefix('"')?.strip_suffix('"')?.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r##"
+# Nanshan station
+[site]
+latitude = 43.4712
+longitude = 87.1781   # east
+height = 2080
+
+[eop]
+leap_seconds = 37
+dut1 = 0.035
+cache = "eop"
+
+[ephemeris]
+kernels = ["de440s.bsp", "#1.tls"]
+cache = "/var/cache/astrokits/kernels"
+"##;
+
+    #[test]
+    fn parses_the_settings() {
+        let config = Config::parse(CONFIG, Path::new("/etc/astrokits")).unwrap();
+        assert_eq!(config.site, Some(Site { latitude: 43.4712, longitude: 87.1781, height: 2080.0 }));
+        assert_eq!((config.leap_seconds, config.dut1, config.polar_dx), (Some(37), Some(0.035), None));
+        assert_eq!(config.eop_cache, Path::new("/etc/astrokits/eop"));
+        // Kernels that are not next to the file are looked up in the cache
+        assert_eq!(config.kernels, [Path::new("/var/cache/astrokits/kernels/de440s.bsp"), Path::new("/var/cache/astrokits/kernels/#1.tls")]);
+        let time = config.time("2025-06-24T12:29:36").unwrap();
+        assert_eq!((time.leap_seconds(), time.dut1()), (37, 0.035));
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        let error = |text: &str| Config::parse(text, Path::new(".")).unwrap_err().to_string();
+        assert_eq!(error("[site]\nlatitude = north\n"), "line 2: `north` is not a number");
+        assert_eq!(error("[site]\nlatitude = 43\n"), "[site] needs both latitude and longitude");
+        assert_eq!(error("[eop]\nleap = 37\n"), "line 2: unknown key `leap` in [eop]");
+        assert_eq!(error("[ephemeris]\nkernels = [de440s.bsp]\n"), "line 2: expected a list of strings");
+        assert_eq!(error("[site]\nlatitude\n"), "line 2: expected `key = value`");
+    }
+
+    #[test]
+    fn site_arguments_override_the_file() {
+        let mut config = Config::parse(CONFIG, Path::new(".")).unwrap();
+        config.apply_site_args(&mut Args::from_slice(&["--lat", "-30:30", "--height=10"])).unwrap();
+        assert_eq!(config.site, Some(Site { latitude: -30.5, longitude: 87.1781, height: 10.0 }));
+
+        let mut config = Config::default();
+        config.apply_site_args(&mut Args::from_slice(&[])).unwrap();
+        assert_eq!(config.site, None);
+        assert!(config.apply_site_args(&mut Args::from_slice(&["--lat", "30"])).is_err());
+        config.apply_site_args(&mut Args::from_slice(&["--lat", "30", "--lon", "-100"])).unwrap();
+        assert_eq!(config.site, Some(Site { latitude: 30.0, longitude: -100.0, height: 0.0 }));
+    }
+
+    #[test]
+    fn comments_and_strings() {
+        assert_eq!(strip_comment(r#"cache = "a#b" # comment"#), r#"cache = "a#b" "#);
+        assert_eq!(parse_strings(r#""de440s.bsp""#), Some(vec!["de440s.bsp".to_string()]));
+        assert_eq!(parse_strings("[]"), Some(vec![]));
+        assert_eq!(parse_strings(r#"["a", b]"#), None);
+        assert!(parse_string("unquoted", 0).is_err());
+    }
+}
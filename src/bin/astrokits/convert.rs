@@ -0,0 +1,284 @@
+use crate::args::Args;
+use crate::config::Config;
+use crate::output::{Format, Output, Value};
+use crate::CliResult;
+use astrokits::novas::{Degrees, Ecliptic, Epoch, Equatorial, Galactic, Horizontal, Hours, ObservingFrame, ReferenceSystem, Source, Time, Timescale};
+use std::io::{self, BufRead, Write};
+
+pub const USAGE: &str = "\
+Usage: astrokits convert time <TIME> [OPTIONS]
+       astrokits convert coords <LON> <LAT> [OPTIONS]
+
+Converts a time between timescales and formats, or coordinates between reference systems.
+The result is printed on one line. With `-` in place of TIME or LON and LAT, the values are
+read from standard input, one per line (LON and LAT separated by a comma or white space), and
+a result is printed for each; lines that fail are reported and skipped.
+
+Options:
+  --format FORMAT   table, json, jsonl or csv (default: table); the field is `time`, or `lon` and `lat`
+
+Time options:
+  --from SCALE      Timescale of TIME: utc, ut1, tai, gps, tt, tdb, tcg, tcb (default: utc)
+  --to SCALE        Timescale of the result (default: --from)
+  --input FORMAT    Format of TIME: iso, jd, mjd, unix (default: jd for numbers, else iso)
+  --output FORMAT   Format of the result: iso, jd, mjd, unix (default: iso)
+
+Coordinate options:
+  --from SYSTEM     System of LON/LAT (default: icrs)
+  --to SYSTEM       System of the result (default: icrs)
+  --date DATE       UTC date for cirs, tod and altaz (default: now)
+  --lat, --lon, --height, --no-refraction
+                    Site for altaz, as for the other commands
+
+Systems: icrs, j2000, b1950, galactic, ecliptic (J2000), cirs, tod (apparent, of date) and
+altaz. LON is in hours for equatorial systems and in degrees otherwise; both may be
+sexagesimal. Apparent and horizontal positions are for a distant source.";
+
+const MJD0: f64 = 2400000.5;
+
+pub fn run(mut args: Args, config: &Config) -> CliResult<()> {
+    match args.positional().as_deref() {
+        Some("time") => time(args, config),
+        Some("coords") => coords(args, config),
+        Some(other) => Err(format!("unknown conversion `{}`, expected `time` or `coords`", other).into()),
+        None => Err("missing `time` or `coords`".into()),
+    }
+}
+
+fn time(mut args: Args, config: &Config) -> CliResult<()> {
+    let from: Timescale = args.parse("--from")?.unwrap_or(Timescale::Utc);
+    let to: Timescale = args.parse("--to")?.unwrap_or(from);
+    let input = args.value("--input")?;
+    let output = args.value("--output")?.unwrap_or_else(|| "iso".to_string());
+    let format = Format::from_args(&mut args)?;
+    let value = args.positional().ok_or("missing TIME")?;
+    args.finish()?;
+
+    let convert = |value: &str| -> CliResult<(Vec<Value>, String)> {
+        let input = input.as_deref().unwrap_or(if value.parse::<f64>().is_ok() { "jd" } else { "iso" });
+        let number = || value.parse::<f64>().map_err(|_| format!("`{}` is not a number", value));
+        let time = match input {
+            "iso" if value == "now" => config.time(value)?,
+            "iso" => config.make_time(|leap, dut1| Time::parse(value, from, leap, dut1)).map_err(|_| format!("invalid date `{}`", value))?,
+            "jd" => {
+                let jd = number()?;
+                config.make_time(|leap, dut1| Time::new(from, jd, leap, dut1))?
+            }
+            "mjd" => {
+                let jd = number()? + MJD0;
+                config.make_time(|leap, dut1| Time::new(from, jd, leap, dut1))?
+            }
+            "unix" if from == Timescale::Utc => {
+                let seconds = number()?;
+                config.make_time(|leap, dut1| Time::from_unix(seconds.floor() as i64, (seconds.fract().abs() * 1e9) as i32, leap, dut1))?
+            }
+            "unix" => return Err("UNIX times are UTC".into()),
+            _ => return Err(format!("unknown time format `{}`", input).into()),
+        };
+
+        let (value, line): (Value, String) = match output.as_str() {
+            "iso" if to == Timescale::Utc => (time.iso_timestamp().into(), time.iso_timestamp()),
+            "iso" => (time.timestamp(to).into(), time.timestamp(to)),
+            "jd" => (time.jd(to).into(), format!("{:.9}", time.jd(to))),
+            "mjd" => ((time.jd(to) - MJD0).into(), format!("{:.9}", time.jd(to) - MJD0)),
+            "unix" if to == Timescale::Utc => {
+                // Microseconds, about the resolution of a Julian date input
+                let (seconds, nanos) = time.unix();
+                let unix = seconds as f64 + nanos as f64 * 1e-9;
+                (unix.into(), format!("{:.6}", unix))
+            }
+            "unix" => return Err("UNIX times are UTC".into()),
+            _ => return Err(format!("unknown time format `{}`", output).into()),
+        };
+        Ok((vec![value], line))
+    };
+
+    let mut out = Output::new(format, &["time"]);
+    if value == "-" {
+        return stream(&mut out, convert);
+    }
+    let (values, line) = convert(&value)?;
+    out.record(values, || line);
+    out.finish();
+    Ok(())
+}
+
+/// Converts each line of the standard input, skipping blank lines and `#` comments. Lines that
+/// fail are reported on the standard error, and make the command fail at the end.
+fn stream(out: &mut Output, convert: impl Fn(&str) -> CliResult<(Vec<Value>, String)>) -> CliResult<()> {
+    let mut failed = 0;
+    for (n, line) in io::stdin().lock().lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match convert(line) {
+            Ok((values, text)) => out.record(values, || text),
+            Err(err) => {
+                eprintln!("astrokits: line {}: {}", n + 1, err);
+                failed += 1;
+            }
+        }
+        // Results are passed on as they come, for use in pipelines
+        io::stdout().flush()?;
+    }
+    out.finish();
+    match failed {
+        0 => Ok(()),
+        n => Err(format!("{} line(s) could not be converted", n).into()),
+    }
+}
+
+/// Coordinate systems known to `convert coords`.
+#[derive(Clone, Copy, PartialEq)]
+enum System {
+    Icrs,
+    J2000,
+    B1950,
+    Galactic,
+    Ecliptic,
+    Cirs,
+    Tod,
+    AltAz,
+}
+
+impl System {
+    fn parse(name: &str) -> CliResult<System> {
+        let system = match name.to_ascii_lowercase().as_str() {
+            "icrs" => System::Icrs,
+            "j2000" | "fk5" => System::J2000,
+            "b1950" | "fk4" => System::B1950,
+            "galactic" | "gal" => System::Galactic,
+            "ecliptic" | "ecl" => System::Ecliptic,
+            "cirs" => System::Cirs,
+            "tod" => System::Tod,
+            "altaz" | "hor" => System::AltAz,
+            _ => return Err(format!("unknown coordinate system `{}`", name).into()),
+        };
+        Ok(system)
+    }
+
+    /// Whether the longitude is a right ascension in hours.
+    fn hours(self) -> bool {
+        !matches!(self, System::Galactic | System::Ecliptic | System::AltAz)
+    }
+
+    fn apparent(self) -> Option<ReferenceSystem> {
+        match self {
+            System::Cirs | System::AltAz => Some(ReferenceSystem::Cirs),
+            System::Tod => Some(ReferenceSystem::Tod),
+            _ => None,
+        }
+    }
+}
+
+fn coords(mut args: Args, config: &Config) -> CliResult<()> {
+    let mut config = config.clone();
+    config.apply_site_args(&mut args)?;
+    let from = System::parse(&args.value("--from")?.unwrap_or_else(|| "icrs".to_string()))?;
+    let to = System::parse(&args.value("--to")?.unwrap_or_else(|| "icrs".to_string()))?;
+    let date = args.value("--date")?.unwrap_or_else(|| "now".to_string());
+    let refraction = !args.flag("--no-refraction");
+    let format = Format::from_args(&mut args)?;
+    let lon = args.positional().ok_or("missing LON")?;
+    let lat = if lon == "-" { None } else { Some(args.positional().ok_or("missing LAT")?) };
+    args.finish()?;
+
+    if (from == System::AltAz || to == System::AltAz) && config.site.is_none() {
+        return Err("altaz needs a site, use --lat and --lon or configure [site]".into());
+    }
+
+    // Apparent systems need an observing frame; the reduced-accuracy models suffice for stars.
+    let frame = if from.apparent().is_some() || to.apparent().is_some() {
+        let time = config.time(&date)?;
+        Some(config.frame(astrokits::novas::Accuracy::Reduced, &time)?)
+    } else {
+        None
+    };
+
+    let convert = |lon: &str, lat: &str| -> CliResult<(Vec<Value>, String)> {
+        let lon = match from.hours() {
+            true => lon.parse::<Hours>().map(|lon| lon.0),
+            false => lon.parse::<Degrees>().map(|lon| lon.0),
+        }
+        .map_err(|err| format!("invalid longitude `{}`: {}", lon, err))?;
+        let lat = lat.parse::<Degrees>().map_err(|err| format!("invalid latitude `{}`: {}", lat, err))?.0;
+        let (lon, lat) = convert_coords(from, to, lon, lat, frame.as_ref(), refraction)?;
+        Ok((vec![lon.into(), lat.into()], format!("{:.9} {:+.9}", lon, lat)))
+    };
+
+    let mut out = Output::new(format, &["lon", "lat"]);
+    let Some(lat) = lat else {
+        return stream(&mut out, |line| {
+            let parts: Vec<&str> = match line.contains(',') {
+                true => line.split(',').map(str::trim).collect(),
+                false => line.split_whitespace().collect(),
+            };
+            match parts[..] {
+                [lon, lat] => convert(lon, lat),
+                _ => Err(format!("expected LON and LAT in `{}`", line).into()),
+            }
+        });
+    };
+    let (values, line) = convert(&lon, &lat)?;
+    out.record(values, || line);
+    out.finish();
+    Ok(())
+}
+
+/// Converts coordinates between systems. `frame` is needed for the apparent systems.
+fn convert_coords(from: System, to: System, lon: f64, lat: f64, frame: Option<&ObservingFrame>, refraction: bool) -> CliResult<(f64, f64)> {
+    // Everything goes through ICRS.
+    let icrs = match from {
+        System::Icrs => Equatorial::new(lon, lat),
+        System::J2000 => Equatorial::new(lon, lat).j2000_to_icrs()?,
+        System::B1950 => Equatorial::new(lon, lat).fk4_to_icrs(Epoch::B1950)?,
+        System::Galactic => Galactic::new(lon, lat).to_equatorial()?,
+        System::Ecliptic => Ecliptic::new(lon, lat).j2000_to_equatorial()?,
+        System::Cirs | System::Tod | System::AltAz => {
+            let frame = frame.ok_or("no observing frame")?;
+            let system = from.apparent().unwrap();
+            let apparent = match from {
+                System::AltAz => frame.from_horizontal(&Horizontal { az: lon, el: lat }, system, refraction)?,
+                _ => Equatorial::new(lon, lat),
+            };
+            frame.apparent_to_icrs(&apparent, system)?
+        }
+    };
+
+    let (lon, lat) = match to {
+        System::Icrs => (icrs.ra, icrs.dec),
+        System::J2000 => {
+            let e = icrs.icrs_to_j2000()?;
+            (e.ra, e.dec)
+        }
+        System::B1950 => {
+            let e = icrs.icrs_to_fk4(Epoch::B1950)?;
+            (e.ra, e.dec)
+        }
+        System::Galactic => {
+            let g = icrs.to_galactic()?;
+            (g.l, g.b)
+        }
+        System::Ecliptic => {
+            let e = icrs.to_ecliptic_j2000()?;
+            (e.lon, e.lat)
+        }
+        System::Cirs | System::Tod | System::AltAz => {
+            let frame = frame.ok_or("no observing frame")?;
+            let system = to.apparent().unwrap();
+            let pos = frame.sky_pos(&Source::star("", icrs.ra, icrs.dec, "ICRS")?, system)?;
+            match to {
+                System::AltAz => {
+                    let hor = frame.to_horizontal(&pos, system, refraction)?;
+                    (hor.az, hor.el)
+                }
+                _ => (pos.ra, pos.dec),
+            }
+        }
+    };
+
+    let lon = if to.hours() { lon.rem_euclid(24.0) } else { lon.rem_euclid(360.0) };
+    Ok((lon, lat))
+}
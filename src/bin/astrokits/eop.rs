@@ -0,0 +1,155 @@
+use crate::args::Args;
+use crate::config::Config;
+use crate::fetch::fetch;
+use crate::output::{Format, Output};
+use crate::CliResult;
+use astrokits::novas::eop::{self, EopTable, LeapSeconds, MJD0};
+use astrokits::novas::{Time, Timescale};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+pub const USAGE: &str = "\
+Usage: astrokits eop <ACTION> [OPTIONS]
+
+Keeps the IERS leap-second and Earth orientation (finals2000A) files in the local cache (see
+`cache` in the [eop] configuration) and reports their values. The cached files give TAI-UTC,
+UT1-UTC and polar motion to the other commands, unless these are configured.
+
+Actions:
+  refresh    Download the files if missing or older than --max-age
+  show       Print TAI-UTC, UT1-UTC, polar motion and celestial pole offsets for DATE
+
+Options:
+  --max-age DAYS        Age after which refresh downloads the files again (default: 7)
+  --force               Download the files even if they are recent
+  --leap-seconds URL    Source of the leap-second file (URL or path)
+  --finals URL          Source of the finals2000A file (URL or path)
+  --date DATE           UTC date for show (default: now)
+  --toml                Print the values of show as an [eop] configuration section
+  --format FORMAT       table, json, jsonl or csv (default: table)";
+
+pub const LEAP_SECONDS_FILE: &str = "Leap_Second.dat";
+const LEAP_SECONDS_URL: &str = "https://hpiers.obspm.fr/iers/bul/bulc/Leap_Second.dat";
+pub const FINALS_FILE: &str = "finals2000A.all";
+const FINALS_URL: &str = "https://maia.usno.navy.mil/ser7/finals2000A.all";
+
+pub fn run(mut args: Args, config: &Config) -> CliResult<()> {
+    let action = args.positional().ok_or("missing ACTION")?;
+    match action.as_str() {
+        "refresh" => {
+            let max_age: f64 = args.parse("--max-age")?.unwrap_or(7.0);
+            let force = args.flag("--force");
+            let leap_url = args.value("--leap-seconds")?.unwrap_or_else(|| LEAP_SECONDS_URL.to_string());
+            let finals_url = args.value("--finals")?.unwrap_or_else(|| FINALS_URL.to_string());
+            let format = Format::from_args(&mut args)?;
+            args.finish()?;
+
+            let max_age = if force { Duration::ZERO } else { Duration::from_secs_f64(max_age.max(0.0) * 86400.0) };
+            let dir = &config.eop_cache;
+            fs::create_dir_all(dir).map_err(|err| format!("cannot create {}: {}", dir.display(), err))?;
+            let mut out = Output::new(format, &["file", "status", "age", "source"]);
+            refresh(&mut out, &dir.join(LEAP_SECONDS_FILE), &leap_url, max_age, |text| Ok(LeapSeconds::parse(text).map(drop)?))?;
+            refresh(&mut out, &dir.join(FINALS_FILE), &finals_url, max_age, |text| Ok(EopTable::parse(text).map(drop)?))?;
+            out.finish();
+            Ok(())
+        }
+        "show" => {
+            let date = args.value("--date")?.unwrap_or_else(|| "now".to_string());
+            let toml = args.flag("--toml");
+            let format = Format::from_args(&mut args)?;
+            args.finish()?;
+            if toml && format != Format::Table {
+                return Err("--toml and --format cannot be combined".into());
+            }
+            show(config, &date, toml, format)
+        }
+        _ => Err(format!("unknown action `{}`", action).into()),
+    }
+}
+
+/// Downloads `url` to `path` unless the file is younger than `max_age`, checking the contents
+/// with `validate` before replacing the cached copy.
+fn refresh(out: &mut Output, path: &Path, url: &str, max_age: Duration, validate: impl Fn(&str) -> CliResult<()>) -> CliResult<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let age = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    if let Some(age) = age
+        && age < max_age
+    {
+        let days = age.as_secs_f64() / 86400.0;
+        let values = vec![name.as_ref().into(), "up to date".into(), days.into(), url.into()];
+        out.record(values, || format!("{:<20}  up to date ({:.1} days old)", name, days));
+        return Ok(());
+    }
+
+    let part = path.with_extension("part");
+    let result = fetch(url, &part).and_then(|()| validate(&fs::read_to_string(&part)?));
+    if let Err(err) = result {
+        let _ = fs::remove_file(&part);
+        return Err(format!("{}: {}", url, err).into());
+    }
+    fs::rename(&part, path)?;
+    let values = vec![name.as_ref().into(), "updated".into(), 0.0.into(), url.into()];
+    out.record(values, || format!("{:<20}  updated from {}", name, url));
+    Ok(())
+}
+
+fn show(config: &Config, date: &str, toml: bool, format: Format) -> CliResult<()> {
+    let read = |file: &str| {
+        let path = config.eop_cache.join(file);
+        fs::read_to_string(&path).map_err(|err| format!("cannot read {} ({}), run `astrokits eop refresh`", path.display(), err))
+    };
+    // The cached leap-second file, installed by the configuration, or else the built-in table
+    let leap = eop::leap_seconds();
+    let finals = EopTable::parse(&read(FINALS_FILE)?)?;
+
+    let time = if date == "now" { config.time(date)? } else { Time::parse(date, Timescale::Utc, 0, 0.0)? };
+    let mjd = time.jd(Timescale::Utc) - MJD0;
+    let leap_seconds = leap.at(mjd).ok_or("date precedes the leap-second table")?;
+    let eop = finals.at(mjd).ok_or("date is not covered by the finals2000A file")?;
+
+    if toml {
+        println!("[eop]");
+        println!("leap_seconds = {}", leap_seconds);
+        println!("dut1 = {:.7}", eop.dut1);
+        println!("polar_dx = {:.3}", eop.xp * 1000.0);
+        println!("polar_dy = {:.3}", eop.yp * 1000.0);
+        return Ok(());
+    }
+
+    let fields = &["time", "mjd", "tai_utc", "ut1_utc", "xp", "yp", "dx", "dy", "predicted", "leap_seconds_expire"];
+    let mut out = Output::new(format, fields);
+    let values = vec![
+        time.iso_timestamp().into(),
+        mjd.into(),
+        i64::from(leap_seconds).into(),
+        eop.dut1.into(),
+        eop.xp.into(),
+        eop.yp.into(),
+        eop.dx.into(),
+        eop.dy.into(),
+        eop.predicted.into(),
+        leap.expires().into(),
+    ];
+    out.record(values, || {
+        let kind = if eop.predicted { "predicted" } else { "IERS" };
+        let mut lines = vec![
+            format!("Date            {} (MJD {:.5})", time.iso_timestamp(), mjd),
+            format!("TAI - UTC       {} s", leap_seconds),
+            format!("UT1 - UTC       {:+.7} s ({})", eop.dut1, kind),
+            format!("Polar motion    x = {:+.6}\", y = {:+.6}\" ({})", eop.xp, eop.yp, kind),
+        ];
+        if let (Some(dx), Some(dy)) = (eop.dx, eop.dy) {
+            lines.push(format!("Pole offsets    dX = {:+.3} mas, dY = {:+.3} mas", dx, dy));
+        }
+        if let Some(expires) = leap.expires() {
+            lines.push(format!("Leap seconds    file expires {}", expires));
+        }
+        lines.join("\n")
+    });
+    out.finish();
+    Ok(())
+}
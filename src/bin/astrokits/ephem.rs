@@ -0,0 +1,153 @@
+use crate::args::{parse_duration, Args};
+use crate::config::Config;
+use crate::format::{dms, hms};
+use crate::output::{Format, Output, Value};
+use crate::{target, CliResult};
+use astrokits::novas::{ReferenceSystem, Source, Time, TimeRange, Timescale};
+
+pub const USAGE: &str = "\
+Usage: astrokits ephem <TARGET> [OPTIONS]
+
+Prints the apparent position of TARGET (see below), or of a catalog source named TARGET at
+--ra and --dec.
+
+Options:
+  --start DATE      First date, e.g. 2025-06-24T12:00:00Z or 2025-06-24 20:00+08:00 (default: now)
+  --stop DATE       Last date (default: one day after --start)
+  --step DURATION   Interval between rows, e.g. 30s, 10m, 1h, 1d (default: 1h)
+  --samples N       Number of rows, evenly spaced from --start to --stop, instead of --step
+  --scale SCALE     Timescale of --start, --stop, --step and of the table: utc, tt, tdb, ...
+                    (default: utc)
+  --ra RA           Right ascension [h], decimal or HH:MM:SS
+  --dec DEC         Declination [deg], decimal or DD:MM:SS
+  --system SYSTEM   Catalog system of --ra/--dec: ICRS, J2000, B1950, ... (default: ICRS)
+  --lat DEG         Site latitude (overrides the configuration)
+  --lon DEG         Site longitude, east positive (overrides the configuration)
+  --height M        Site height above sea level (overrides the configuration)
+  --no-refraction   Do not correct elevations for atmospheric refraction
+  --format FORMAT   table, json, jsonl or csv (default: table)
+  --angles STYLE    RA and Dec in json, jsonl and csv: decimal [h, deg] or sexagesimal, as in
+                    the table (default: decimal)
+
+Positions are true equator and equinox of date. Az/El are printed when a site is configured.
+Planets other than the Sun and the Earth need SPICE kernels in the configuration.";
+
+/// Upper limit on the rows of one table, against typos in --step.
+const MAX_ROWS: usize = 100_000;
+
+pub fn run(mut args: Args, config: &Config) -> CliResult<()> {
+    let mut config = config.clone();
+    config.apply_site_args(&mut args)?;
+    let start = args.value("--start")?.unwrap_or_else(|| "now".to_string());
+    let stop = args.value("--stop")?;
+    let step = args.value("--step")?;
+    let samples: Option<usize> = args.parse("--samples")?;
+    let scale: Timescale = args.parse("--scale")?.unwrap_or(Timescale::Utc);
+    let source = source(&mut args)?;
+    let refraction = !args.flag("--no-refraction");
+    let format = Format::from_args(&mut args)?;
+    let sexagesimal = match args.value("--angles")?.as_deref() {
+        None | Some("decimal") => false,
+        Some("sexagesimal") => true,
+        Some(other) => return Err(format!("unknown angle style `{}`, expected decimal or sexagesimal", other).into()),
+    };
+    args.finish()?;
+
+    let accuracy = config.load_ephemeris()?;
+    let start = config.time_in(&start, scale)?;
+    let stop = match stop {
+        Some(stop) => config.time_in(&stop, scale)?,
+        None => start.offset(86400.0)?,
+    };
+    if stop.diff(&start) < 0.0 {
+        return Err("--stop is before --start".into());
+    }
+    let range = match (step, samples) {
+        (Some(_), Some(_)) => return Err("--step and --samples cannot be combined".into()),
+        (_, Some(samples)) => TimeRange::with_count(&start, &stop, samples)?,
+        (step, None) => {
+            let step = parse_duration(step.as_deref().unwrap_or("1h"))?;
+            TimeRange::with_step(&start, &stop, step, scale)?
+        }
+    };
+    if range.len() > MAX_ROWS {
+        return Err(format!("{} rows requested, use a larger --step (at most {})", range.len(), MAX_ROWS).into());
+    }
+
+    // Timestamps in other scales than UTC end in the scale name
+    let date_width = if scale == Timescale::Utc { 24 } else { 28 };
+    let row = |time: Time| -> CliResult<(Vec<Value>, String)> {
+        let frame = config.frame(accuracy, &time)?;
+        let pos = frame
+            .sky_pos(&source, ReferenceSystem::Tod)
+            .map_err(|err| config.position_error(err, &source))?;
+        let dist = (pos.dist < 1e10).then_some(pos.dist);
+        let hor = match config.site {
+            Some(_) => Some(frame.to_horizontal(&pos, ReferenceSystem::Tod, refraction)?),
+            None => None,
+        };
+
+        let date = match scale {
+            Timescale::Utc => time.iso_timestamp(),
+            scale => time.timestamp(scale),
+        };
+        let mut line = format!(
+            "{:<width$}  {}  {}  {:>14}  {:>10.4}",
+            date,
+            hms(pos.ra, 3),
+            dms(pos.dec, 2),
+            dist.map_or_else(|| "-".to_string(), |dist| format!("{:.9}", dist)),
+            pos.rv,
+            width = date_width
+        );
+        if let Some(hor) = hor {
+            line += &format!("  {:>8.3}  {:>8.3}", hor.az, hor.el);
+        }
+        let (ra, dec) = match sexagesimal {
+            true => (hms(pos.ra, 3).into(), dms(pos.dec, 2).into()),
+            false => (pos.ra.into(), pos.dec.into()),
+        };
+        let values = vec![
+            time.iso_timestamp().into(),
+            ra,
+            dec,
+            dist.into(),
+            pos.rv.into(),
+            hor.map(|hor| hor.az).into(),
+            hor.map(|hor| hor.el).into(),
+        ];
+        Ok((values, line))
+    };
+
+    // The first row is calculated before the header, so that errors are not mixed into the table
+    let mut times = range.iter();
+    let (values, line) = row(times.next().ok_or("no rows")??)?;
+    let mut out = Output::new(format, &["time", "ra", "dec", "dist", "rv", "az", "el"]);
+    let date = format!("Date ({})", format!("{:?}", scale).to_uppercase());
+    let mut header = format!("{:<width$}  {:>12}  {:>12}  {:>14}  {:>10}", date, "RA", "Dec", "Dist [AU]", "RV [km/s]", width = date_width);
+    if config.site.is_some() {
+        header += &format!("  {:>8}  {:>8}", "Az [deg]", "El [deg]");
+    }
+    out.text(header);
+    out.record(values, || line);
+    for time in times {
+        let (values, line) = row(time?)?;
+        out.record(values, || line);
+    }
+    out.finish();
+    Ok(())
+}
+
+/// The source named by the positional argument, a catalog source if coordinates are given.
+fn source(args: &mut Args) -> CliResult<Source> {
+    let ra = args.value("--ra")?;
+    let dec = args.value("--dec")?;
+    let system = args.value("--system")?.unwrap_or_else(|| "ICRS".to_string());
+    let name = args.positional().ok_or("missing TARGET")?;
+
+    match (ra, dec) {
+        (Some(ra), Some(dec)) => target::catalog(&name, &ra, &dec, &system),
+        (None, None) => target::parse(&name),
+        _ => Err("--ra and --dec must be given together".into()),
+    }
+}
@@ -0,0 +1,31 @@
+use crate::CliResult;
+use std::fs;
+#[cfg(feature = "download")]
+use std::fs::File;
+use std::path::Path;
+
+/// Copies `source`, an HTTP(S) or `file://` URL or a local path, to `dst`.
+pub fn fetch(source: &str, dst: &Path) -> CliResult<()> {
+    if let Some(path) = source.strip_prefix("file://") {
+        fs::copy(path, dst)?;
+    } else if source.starts_with("http://") || source.starts_with("https://") {
+        http_get(source, dst)?;
+    } else if source.contains("://") {
+        return Err(format!("unsupported URL `{}`", source).into());
+    } else {
+        fs::copy(source, dst)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "download")]
+fn http_get(url: &str, dst: &Path) -> CliResult<()> {
+    let mut response = reqwest::blocking::get(url)?.error_for_status()?;
+    response.copy_to(&mut File::create(dst)?)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "download"))]
+fn http_get(_url: &str, _dst: &Path) -> CliResult<()> {
+    Err("built without the `download` feature, download the file by hand and give its path".into())
+}
@@ -0,0 +1,11 @@
+use astrokits::novas::{Degrees, Hours};
+
+/// Formats hours as `HH:MM:SS.sss`.
+pub fn hms(hours: f64, decimals: usize) -> String {
+    format!("{:#.*}", decimals, Hours(hours))
+}
+
+/// Formats degrees as `+DD:MM:SS.ss`.
+pub fn dms(degrees: f64, decimals: usize) -> String {
+    format!("{:+#.*}", decimals, Degrees(degrees))
+}
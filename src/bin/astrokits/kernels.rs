@@ -0,0 +1,283 @@
+use crate::args::Args;
+use crate::config::Config;
+use crate::fetch::fetch;
+use crate::output::{Format, Output};
+use crate::{sha256, CliResult};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const USAGE: &str = "\
+Usage: astrokits kernels <ACTION> [OPTIONS]
+
+Manages the SPICE kernel cache (see `cache` in the [ephemeris] configuration). Kernels listed
+in the configuration by file name alone are loaded from the cache.
+
+Actions:
+  download <KERNEL>...  Fetch kernels by well-known name, URL or local path
+  list                  List the cached kernels
+  verify                Check the cached kernels against their recorded SHA-256 sums
+  prune                 Remove cached kernels that the configuration does not use
+
+Options:
+  --sha256 HEX    Expected SHA-256 of the kernel (download, single kernel)
+  --force         Download kernels again even if they are cached
+  --all           Remove all cached kernels (prune)
+  --dry-run       Only print what would be removed (prune)
+  --format FORMAT table, json, jsonl or csv (default: table)
+
+Well-known kernels: de440s.bsp, de440.bsp, de430.bsp, naif0012.tls, pck00011.tpc,
+earth_latest_high_prec.bpc";
+
+const NAIF_GENERIC: &str = "https://naif.jpl.nasa.gov/pub/naif/generic_kernels/";
+
+const KNOWN: [(&str, &str); 6] = [
+    ("de440s.bsp", "spk/planets/de440s.bsp"),
+    ("de440.bsp", "spk/planets/de440.bsp"),
+    ("de430.bsp", "spk/planets/de430.bsp"),
+    ("naif0012.tls", "lsk/naif0012.tls"),
+    ("pck00011.tpc", "pck/pck00011.tpc"),
+    ("earth_latest_high_prec.bpc", "pck/earth_latest_high_prec.bpc"),
+];
+
+const MANIFEST: &str = "manifest.tsv";
+
+/// A cached kernel as recorded in the manifest.
+struct Entry {
+    sha256: String,
+    size: u64,
+    source: String,
+}
+
+pub fn run(mut args: Args, config: &Config) -> CliResult<()> {
+    let action = args.positional().ok_or("missing ACTION")?;
+    let format = Format::from_args(&mut args)?;
+    let cache = Cache::new(&config.cache);
+    match action.as_str() {
+        "download" => {
+            let sha256 = args.value("--sha256")?;
+            let force = args.flag("--force");
+            let specs: Vec<String> = std::iter::from_fn(|| args.positional()).collect();
+            args.finish()?;
+            if specs.is_empty() {
+                return Err("missing KERNEL".into());
+            }
+            if sha256.is_some() && specs.len() > 1 {
+                return Err("--sha256 applies to a single kernel".into());
+            }
+            let mut out = Output::new(format, &["name", "status", "size", "sha256", "source"]);
+            for spec in &specs {
+                cache.download(&mut out, spec, sha256.as_deref(), force)?;
+            }
+            out.finish();
+            Ok(())
+        }
+        "list" => {
+            args.finish()?;
+            cache.list(config, format)
+        }
+        "verify" => {
+            args.finish()?;
+            cache.verify(format)
+        }
+        "prune" => {
+            let all = args.flag("--all");
+            let dry_run = args.flag("--dry-run");
+            args.finish()?;
+            cache.prune(config, all, dry_run, format)
+        }
+        _ => Err(format!("unknown action `{}`", action).into()),
+    }
+}
+
+struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    fn new(dir: &Path) -> Self {
+        Cache { dir: dir.to_path_buf() }
+    }
+
+    fn download(&self, out: &mut Output, spec: &str, expected: Option<&str>, force: bool) -> CliResult<()> {
+        let (name, source) = resolve(spec)?;
+        let path = self.dir.join(&name);
+        let mut manifest = self.manifest()?;
+
+        if !force && path.is_file() && manifest.contains_key(&name) {
+            let entry = &manifest[&name];
+            if let Some(expected) = expected
+                && !entry.sha256.eq_ignore_ascii_case(expected)
+            {
+                return Err(format!("cached {} has SHA-256 {}, use --force to download again", name, entry.sha256).into());
+            }
+            let values = vec![name.as_str().into(), "cached".into(), entry.size.into(), entry.sha256.as_str().into(), entry.source.as_str().into()];
+            out.record(values, || format!("{:<28}  cached", name));
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.dir).map_err(|err| format!("cannot create {}: {}", self.dir.display(), err))?;
+        let part = self.dir.join(format!("{}.part", name));
+        let result = fetch(&source, &part).and_then(|()| check(&part, expected));
+        let (sha256, size) = match result {
+            Ok(checked) => checked,
+            Err(err) => {
+                let _ = fs::remove_file(&part);
+                return Err(format!("{}: {}", name, err).into());
+            }
+        };
+        fs::rename(&part, &path)?;
+
+        let values = vec![name.as_str().into(), "downloaded".into(), size.into(), sha256.as_str().into(), source.as_str().into()];
+        out.record(values, || format!("{:<28}  {:>12}  {}", name, size, sha256));
+        manifest.insert(name, Entry { sha256, size, source });
+        self.write_manifest(&manifest)
+    }
+
+    fn list(&self, config: &Config, format: Format) -> CliResult<()> {
+        let manifest = self.manifest()?;
+        let mut out = Output::new(format, &["name", "size", "sha256", "used"]);
+        out.text(self.dir.display());
+        for name in self.files()? {
+            let size = fs::metadata(self.dir.join(&name))?.len();
+            let sha256 = manifest.get(&name).map(|entry| entry.sha256.as_str());
+            let used = self.in_use(config, &name);
+            out.record(vec![name.as_str().into(), size.into(), sha256.into(), used.into()], || {
+                let sha256 = sha256.map_or("untracked", |sha256| &sha256[..16]);
+                format!("  {:<28}  {:>12}  {:<16}  {}", name, size, sha256, if used { "used" } else { "" })
+            });
+        }
+        out.finish();
+        Ok(())
+    }
+
+    fn verify(&self, format: Format) -> CliResult<()> {
+        let mut out = Output::new(format, &["name", "status"]);
+        let mut failed = 0;
+        for (name, entry) in &self.manifest()? {
+            let path = self.dir.join(name);
+            let status = if !path.is_file() {
+                "missing"
+            } else if fs::metadata(&path)?.len() != entry.size || sha256::hex_digest(File::open(&path)?)? != entry.sha256 {
+                "corrupt"
+            } else {
+                "ok"
+            };
+            if status != "ok" {
+                failed += 1;
+            }
+            out.record(vec![name.as_str().into(), status.into()], || format!("{:<28}  {}", name, status));
+        }
+        out.finish();
+        match failed {
+            0 => Ok(()),
+            n => Err(format!("{} kernel(s) failed verification, download them again with --force", n).into()),
+        }
+    }
+
+    fn prune(&self, config: &Config, all: bool, dry_run: bool, format: Format) -> CliResult<()> {
+        let mut out = Output::new(format, &["name", "action"]);
+        let mut manifest = self.manifest()?;
+        for name in self.files()? {
+            if !all && self.in_use(config, &name) {
+                continue;
+            }
+            let action = if dry_run { "would remove" } else { "removed" };
+            out.record(vec![name.as_str().into(), action.into()], || format!("{} {}", action, name));
+            if !dry_run {
+                fs::remove_file(self.dir.join(&name))?;
+                manifest.remove(&name);
+            }
+        }
+        out.finish();
+        if !dry_run {
+            manifest.retain(|name, _| self.dir.join(name).is_file());
+            self.write_manifest(&manifest)?;
+        }
+        Ok(())
+    }
+
+    fn in_use(&self, config: &Config, name: &str) -> bool {
+        let path = self.dir.join(name);
+        config.kernels.contains(&path)
+    }
+
+    /// Kernel files in the cache, sorted by name.
+    fn files(&self) -> CliResult<Vec<String>> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Ok(Vec::new());
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.file_type()?.is_file() && name != MANIFEST && !name.ends_with(".part") {
+                names.push(name);
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    fn manifest(&self) -> CliResult<BTreeMap<String, Entry>> {
+        let Ok(text) = fs::read_to_string(self.dir.join(MANIFEST)) else {
+            return Ok(BTreeMap::new());
+        };
+        let mut manifest = BTreeMap::new();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [name, sha256, size, source] = fields[..] else {
+                return Err(format!("malformed line in {}: `{}`", MANIFEST, line).into());
+            };
+            let size = size.parse().map_err(|_| format!("malformed size in {}: `{}`", MANIFEST, line))?;
+            manifest.insert(name.to_string(), Entry { sha256: sha256.to_string(), size, source: source.to_string() });
+        }
+        Ok(manifest)
+    }
+
+    fn write_manifest(&self, manifest: &BTreeMap<String, Entry>) -> CliResult<()> {
+        let text: String = manifest
+            .iter()
+            .map(|(name, entry)| format!("{}\t{}\t{}\t{}\n", name, entry.sha256, entry.size, entry.source))
+            .collect();
+        fs::write(self.dir.join(MANIFEST), text)?;
+        Ok(())
+    }
+}
+
+/// File name and source (URL or local path) of a kernel specification.
+fn resolve(spec: &str) -> CliResult<(String, String)> {
+    if spec.contains("://") || Path::new(spec).is_file() {
+        let name = spec
+            .trim_end_matches('/')
+            .rsplit(['/', '\\'])
+            .next()
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| format!("no file name in `{}`", spec))?;
+        return Ok((name.to_string(), spec.to_string()));
+    }
+    KNOWN
+        .iter()
+        .find(|(name, _)| *name == spec)
+        .map(|(name, path)| (name.to_string(), format!("{}{}", NAIF_GENERIC, path)))
+        .ok_or_else(|| format!("unknown kernel `{}`, give a URL or a path", spec).into())
+}
+
+/// Checks that a file looks like a SPICE kernel and has the expected checksum. Returns its
+/// SHA-256 and size.
+fn check(path: &Path, expected: Option<&str>) -> CliResult<(String, u64)> {
+    let mut magic = [0u8; 4];
+    File::open(path)?.read_exact(&mut magic).map_err(|_| "file is too short")?;
+    // Binary kernels (SPK, PCK, CK) are DAF files, text kernels start with KPL/
+    if &magic != b"DAF/" && &magic != b"KPL/" && &magic != b"NAIF" {
+        return Err("not a SPICE kernel".into());
+    }
+    let sha256 = sha256::hex_digest(File::open(path)?)?;
+    if let Some(expected) = expected
+        && !sha256.eq_ignore_ascii_case(expected)
+    {
+        return Err(format!("SHA-256 mismatch, got {}", sha256).into());
+    }
+    Ok((sha256, fs::metadata(path)?.len()))
+}
@@ -0,0 +1,102 @@
+//! The `astrokits` command-line tool.
+
+mod args;
+mod config;
+mod convert;
+mod eop;
+mod ephem;
+mod fetch;
+mod format;
+mod kernels;
+mod output;
+mod plan;
+mod riseset;
+mod sha256;
+mod target;
+mod watch;
+
+use args::Args;
+use config::Config;
+use std::process::ExitCode;
+
+pub type CliResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+const USAGE: &str = "\
+Usage: astrokits [--config FILE] <COMMAND> [OPTIONS]
+
+Commands:
+  convert    Convert times and coordinates
+  eop        Refresh the IERS leap-second and Earth orientation data
+  ephem      Ephemeris table of a solar-system body or catalog source
+  kernels    Download, list, verify and prune cached SPICE kernels
+  plan       Visibility report of a target list for a night
+  riseset    Rise, transit and set times, and twilight
+  watch      Live azimuth, elevation, airmass and hour angle of targets
+
+Options:
+  --config FILE   Configuration file (default: $ASTROKITS_CONFIG or ~/.config/astrokits/config.toml)
+  -h, --help      Print help
+
+Run `astrokits <COMMAND> --help` for the options of a command.";
+
+fn main() -> ExitCode {
+    match run(Args::from_env()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("astrokits: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(mut args: Args) -> CliResult<()> {
+    let config_path = args.value("--config")?;
+    let wants_help = args.flag("--help") || args.flag("-h");
+    let Some(command) = args.positional() else {
+        if !wants_help {
+            return Err(format!("missing command\n\n{}", USAGE).into());
+        }
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
+    if wants_help || command == "help" {
+        let topic = if command == "help" { args.positional() } else { Some(command) };
+        match topic {
+            Some(topic) => println!("{}", help(&topic)?),
+            None => println!("{}", USAGE),
+        }
+        return Ok(());
+    }
+
+    let config = Config::load(config_path.as_deref().map(std::path::Path::new))?;
+    match command.as_str() {
+        "convert" => convert::run(args, &config),
+        "eop" => eop::run(args, &config),
+        "ephem" => ephem::run(args, &config),
+        "kernels" => kernels::run(args, &config),
+        "plan" => plan::run(args, &config),
+        "riseset" => riseset::run(args, &config),
+        "watch" => watch::run(args, &config),
+        _ => Err(format!("unknown command `{}`\n\n{}", command, USAGE).into()),
+    }
+}
+
+fn help(command: &str) -> CliResult<String> {
+    // Whether the command takes targets
+    let (usage, targets) = match command {
+        "convert" => (convert::USAGE, false),
+        "eop" => (eop::USAGE, false),
+        "ephem" => (ephem::USAGE, true),
+        "kernels" => (kernels::USAGE, false),
+        "plan" => (plan::USAGE, false),
+        "riseset" => (riseset::USAGE, true),
+        "watch" => (watch::USAGE, true),
+        _ => return Err(format!("unknown command `{}`", command).into()),
+    };
+    let mut help = usage.to_string();
+    if targets {
+        help = format!("{}\n\n{}", help, target::SYNTAX);
+    }
+    Ok(format!("{}\n\n{}", help, output::FORMATS))
+}
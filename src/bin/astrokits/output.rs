@@ -0,0 +1,266 @@
+use crate::args::Args;
+use crate::CliResult;
+use std::fmt::Write as _;
+
+/// Help text for the `--format` option.
+pub const FORMATS: &str = "\
+Output formats (--format): table (default), json (an array of objects), jsonl (one object per
+line) or csv (with a header row). JSON and CSV use fixed field names, times are ISO 8601 UTC and numbers are in the units of
+the table, unrounded; missing values are null or empty.";
+
+/// Output format of a command, chosen with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+    /// JSON lines: one object per record, without an enclosing array
+    JsonLines,
+    Csv,
+}
+
+impl Format {
+    /// Takes the `--format` option from the arguments.
+    pub fn from_args(args: &mut Args) -> CliResult<Format> {
+        match args.value("--format")?.as_deref() {
+            None | Some("table") => Ok(Format::Table),
+            Some("json") => Ok(Format::Json),
+            Some("jsonl") => Ok(Format::JsonLines),
+            Some("csv") => Ok(Format::Csv),
+            Some(other) => Err(format!("unknown format `{}`, expected table, json, jsonl or csv", other).into()),
+        }
+    }
+}
+
+/// A field of an output record.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    Number(f64),
+    Integer(i64),
+    Bool(bool),
+    List(Vec<Value>),
+    Null,
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Text(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Text(s)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Self {
+        Value::Number(x)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Integer(n)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Value::Integer(n as i64)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Value::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl Value {
+    fn json(&self, out: &mut String) {
+        match self {
+            Value::Text(s) => json_string(s, out),
+            Value::Number(x) if x.is_finite() => write!(out, "{}", x).unwrap(),
+            Value::Integer(n) => write!(out, "{}", n).unwrap(),
+            Value::Bool(b) => write!(out, "{}", b).unwrap(),
+            Value::List(values) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    value.json(out);
+                }
+                out.push(']');
+            }
+            Value::Number(_) | Value::Null => out.push_str("null"),
+        }
+    }
+
+    /// The value as a CSV field, before quoting. List items are separated by spaces.
+    fn text(&self) -> String {
+        match self {
+            Value::Text(s) => s.clone(),
+            Value::Number(x) if x.is_finite() => x.to_string(),
+            Value::Integer(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::List(values) => values.iter().map(Value::text).collect::<Vec<_>>().join(" "),
+            Value::Number(_) | Value::Null => String::new(),
+        }
+    }
+}
+
+fn json_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Prints the records of a command in the chosen format.
+///
+/// The table layout is left to the command; JSON and CSV records use the field names given to
+/// [`Output::new`], which are part of the output schema and should not change.
+pub struct Output {
+    format: Format,
+    fields: &'static [&'static str],
+    /// Records printed since the last [`Output::finish`]
+    records: usize,
+    header: bool,
+}
+
+impl Output {
+    pub fn new(format: Format, fields: &'static [&'static str]) -> Self {
+        Output { format, fields, records: 0, header: false }
+    }
+
+    /// Prints a line of the table format, e.g. a header, and nothing in the other formats.
+    pub fn text(&self, line: impl std::fmt::Display) {
+        if self.format == Format::Table {
+            println!("{}", line);
+        }
+    }
+
+    /// Prints one record: `table` formats it in the table format, `values` are its fields in the
+    /// order of the field names.
+    pub fn record(&mut self, values: Vec<Value>, table: impl FnOnce() -> String) {
+        debug_assert_eq!(values.len(), self.fields.len());
+        let mut line = String::new();
+        match self.format {
+            Format::Table => println!("{}", table()),
+            Format::Json => {
+                // The separator goes before a record, so that finish() can close the array
+                line.push_str(if self.records == 0 { "[\n  " } else { ",\n  " });
+                self.json_object(&values, &mut line);
+                print!("{}", line);
+            }
+            Format::JsonLines => {
+                self.json_object(&values, &mut line);
+                println!("{}", line);
+            }
+            Format::Csv => {
+                if !self.header {
+                    println!("{}", self.fields.join(","));
+                    self.header = true;
+                }
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        line.push(',');
+                    }
+                    let text = value.text();
+                    if text.contains([',', '"', '\n', '\r']) {
+                        write!(line, "\"{}\"", text.replace('"', "\"\"")).unwrap();
+                    } else {
+                        line.push_str(&text);
+                    }
+                }
+                println!("{}", line);
+            }
+        }
+        self.records += 1;
+    }
+
+    fn json_object(&self, values: &[Value], out: &mut String) {
+        out.push('{');
+        for (i, (field, value)) in self.fields.iter().zip(values).enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            json_string(field, out);
+            out.push_str(": ");
+            value.json(out);
+        }
+        out.push('}');
+    }
+
+    /// Ends a batch of records, closing the JSON array. Commands that print several batches
+    /// (e.g. `watch`) call this after each.
+    pub fn finish(&mut self) {
+        if self.format == Format::Json {
+            println!("{}", if self.records == 0 { "[]" } else { "\n]" });
+        }
+        self.records = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json(value: impl Into<Value>) -> String {
+        let mut out = String::new();
+        value.into().json(&mut out);
+        out
+    }
+
+    #[test]
+    fn formats_by_name() {
+        assert_eq!(Format::from_args(&mut Args::from_slice(&[])).unwrap(), Format::Table);
+        assert_eq!(Format::from_args(&mut Args::from_slice(&["--format", "jsonl"])).unwrap(), Format::JsonLines);
+        assert_eq!(Format::from_args(&mut Args::from_slice(&["--format=csv"])).unwrap(), Format::Csv);
+        assert!(Format::from_args(&mut Args::from_slice(&["--format", "xml"])).is_err());
+    }
+
+    #[test]
+    fn json_values() {
+        assert_eq!(json("Vega \"α Lyr\"\n"), r#""Vega \"α Lyr\"\n""#);
+        assert_eq!(json("\u{1}"), r#""\u0001""#);
+        assert_eq!(json(1.5), "1.5");
+        assert_eq!(json(f64::NAN), "null");
+        assert_eq!(json(None::<f64>), "null");
+        assert_eq!(json(vec![1i64, -2]), "[1,-2]");
+        assert_eq!(json(true), "true");
+    }
+
+    #[test]
+    fn csv_fields() {
+        assert_eq!(Value::from(vec!["a", "b"]).text(), "a b");
+        assert_eq!(Value::from(f64::INFINITY).text(), "");
+        assert_eq!(Value::from(42u64).text(), "42");
+        assert_eq!(Value::Null.text(), "");
+    }
+}
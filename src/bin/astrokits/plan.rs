@@ -0,0 +1,183 @@
+use crate::args::{parse_duration, Args};
+use crate::config::Config;
+use crate::output::{Format, Output, Value};
+use crate::CliResult;
+use astrokits::novas::{Plan, PlanTarget, Planet, ReferenceSystem, Source, Time};
+
+pub const USAGE: &str = "\
+Usage: astrokits plan <FILE> [OPTIONS]
+
+Reports when each target of a TOML or YAML target list meets its constraints during a night, and whether
+its requested duration fits in one window. With --schedule, places each target once for its
+duration instead, always taking next the target that can start the earliest.
+
+Options:
+  --date DATE       Plan the first night after DATE (default: now)
+  --start DATE      Start of the planned interval instead of dusk
+  --stop DATE       End of the planned interval instead of dawn
+  --step DURATION   Sampling step of the constraints (default: 5m)
+  --sun-el DEG      Sun elevation at dusk and dawn (default: -12, nautical twilight)
+  --schedule        Report a schedule of the targets, in the order of the list on ties
+  --lat DEG         Site latitude (overrides the configuration)
+  --lon DEG         Site longitude, east positive (overrides the configuration)
+  --height M        Site height above sea level (overrides the configuration)
+  --format FORMAT   table, json, jsonl or csv (default: table)
+
+Target list (YAML if FILE ends in .yaml or .yml, with the same keys and a `targets` sequence):
+  [defaults]                 # constraints of all targets, each can be set per target
+  min_elevation = 30         # [deg]
+  max_airmass = 2.0
+  min_moon_separation = 20   # [deg]
+  max_sun_elevation = -18    # [deg]
+  duration = \"45m\"
+
+  [[target]]
+  name = \"M31\"
+  ra = \"00:42:44.3\"          # omit ra and dec for solar-system bodies
+  dec = \"+41:16:09\"
+  system = \"ICRS\"
+  duration = \"1h\"";
+
+pub fn run(mut args: Args, config: &Config) -> CliResult<()> {
+    let mut config = config.clone();
+    config.apply_site_args(&mut args)?;
+    if config.site.is_none() {
+        return Err("planning needs a site, use --lat and --lon or configure [site]".into());
+    }
+    let date = args.value("--date")?.unwrap_or_else(|| "now".to_string());
+    let start = args.value("--start")?;
+    let stop = args.value("--stop")?;
+    let step = parse_duration(&args.value("--step")?.unwrap_or_else(|| "5m".to_string()))?;
+    let sun_el: f64 = args.parse("--sun-el")?.unwrap_or(-12.0);
+    let schedule = args.flag("--schedule");
+    let format = Format::from_args(&mut args)?;
+    let file = args.positional().ok_or("missing FILE")?;
+    args.finish()?;
+
+    let targets = load_targets(&file)?;
+    if targets.is_empty() {
+        return Err(format!("{}: no [[target]] entries", file).into());
+    }
+
+    let accuracy = config.load_ephemeris()?;
+    let night = config.time(&date)?;
+    let (dx, dy) = config.polar_offsets(&night);
+    let plan = Plan::new(config.observer()?)
+        .with_accuracy(accuracy)
+        .with_polar_offsets(dx, dy)
+        .with_step(step)
+        .with_sun_elevation(sun_el);
+    let (start, stop) = match (start, stop) {
+        (Some(start), Some(stop)) => (config.time(&start)?, config.time(&stop)?),
+        (start, stop) => {
+            let (dusk, dawn) = plan
+                .night(&night)?
+                .ok_or_else(|| format!("the Sun does not set below {} deg within a day", sun_el))?;
+            let start = start.map(|date| config.time(&date)).transpose()?;
+            let stop = stop.map(|date| config.time(&date)).transpose()?;
+            (start.unwrap_or(dusk), stop.unwrap_or(dawn))
+        }
+    };
+    if stop.diff(&start) < 0.0 {
+        return Err("the end of the interval is before its start".into());
+    }
+
+    // Missing ephemeris data is reported against the source that needs it
+    let frame = config.frame(accuracy, &start)?;
+    let moon = Source::planet(Planet::Moon)?;
+    let moon = targets.iter().any(|t| t.constraints.min_moon_separation.is_some()).then_some(&moon);
+    for source in targets.iter().map(|t| &t.source).chain(moon) {
+        frame
+            .sky_pos(source, ReferenceSystem::Tod)
+            .map_err(|err| config.position_error(err, source))?;
+    }
+    if schedule {
+        print_schedule(&plan, &start, &stop, &targets, format)?;
+        return Ok(());
+    }
+    let report = plan.evaluate(&start, &stop, &targets)?;
+
+    let fields = &["target", "max_elevation", "culmination", "usable", "feasible", "windows"];
+    let mut out = Output::new(format, fields);
+    out.text(format!("Interval {} to {}", start.iso_timestamp(), stop.iso_timestamp()));
+    out.text("");
+    out.text(format!("{:<16}  {:>8}  {:<11}  {:>7}  {:<8}  Windows (UTC)", "Target", "Max El", "Culm. (UTC)", "Usable", "Feasible"));
+    for visibility in &report {
+        let usable = visibility.windows.iter().fold(0.0, |sum, (from, to)| sum + to.diff(from));
+        // ISO 8601 intervals
+        let windows: Vec<Value> = visibility
+            .windows
+            .iter()
+            .map(|(from, to)| format!("{}/{}", from.iso_timestamp(), to.iso_timestamp()).into())
+            .collect();
+        let values = vec![
+            visibility.name.as_str().into(),
+            visibility.max_elevation.into(),
+            visibility.culmination.iso_timestamp().into(),
+            (usable / 3600.0).into(),
+            visibility.feasible.into(),
+            Value::List(windows),
+        ];
+        out.record(values, || {
+            let windows: Vec<String> = visibility
+                .windows
+                .iter()
+                .map(|(from, to)| format!("{}-{}", clock(from), clock(to)))
+                .collect();
+            format!(
+                "{:<16}  {:>8.2}  {:<11}  {:>6.2}h  {:<8}  {}",
+                visibility.name,
+                visibility.max_elevation,
+                clock(&visibility.culmination),
+                usable / 3600.0,
+                if visibility.feasible { "yes" } else { "no" },
+                if windows.is_empty() { "-".to_string() } else { windows.join(", ") }
+            )
+        });
+    }
+    out.finish();
+    Ok(())
+}
+
+fn print_schedule(plan: &Plan, start: &Time, stop: &Time, targets: &[PlanTarget], format: Format) -> CliResult<()> {
+    let schedule = plan.schedule(start, stop, targets)?;
+    let mut out = Output::new(format, &["target", "start", "end"]);
+    out.text(format!("Interval {} to {}", start.iso_timestamp(), stop.iso_timestamp()));
+    out.text("");
+    out.text(format!("{:<16}  {:<5}  {:<5}", "Target", "Start", "End"));
+    for observation in &schedule {
+        let values = vec![
+            observation.name.as_str().into(),
+            observation.start.iso_timestamp().into(),
+            observation.end.iso_timestamp().into(),
+        ];
+        out.record(values, || format!("{:<16}  {:<5}  {:<5}", observation.name, clock(&observation.start), clock(&observation.end)));
+    }
+    let unscheduled: Vec<&str> = targets
+        .iter()
+        .filter(|t| !schedule.iter().any(|o| o.name == t.name))
+        .map(|t| t.name.as_str())
+        .collect();
+    if !unscheduled.is_empty() {
+        out.text("");
+        out.text(format!("Not scheduled: {}", unscheduled.join(", ")));
+    }
+    out.finish();
+    Ok(())
+}
+
+/// `HH:MM` of a UTC time.
+fn clock(time: &Time) -> String {
+    time.iso_timestamp().get(11..16).unwrap_or_default().to_string()
+}
+
+/// Loads the target list described in [`USAGE`].
+#[cfg(feature = "serde")]
+fn load_targets(file: &str) -> CliResult<Vec<PlanTarget>> {
+    Ok(astrokits::novas::load_targets(file)?)
+}
+
+#[cfg(not(feature = "serde"))]
+fn load_targets(_file: &str) -> CliResult<Vec<PlanTarget>> {
+    Err("built without the `serde` feature, which reads target lists".into())
+}
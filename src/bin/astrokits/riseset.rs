@@ -0,0 +1,114 @@
+use crate::args::Args;
+use crate::config::Config;
+use crate::output::{Format, Output, Value};
+use crate::{target, CliResult};
+use astrokits::novas::{self, ObservingFrame, Planet, ReferenceSystem, Source, Time, Twilight};
+
+pub const USAGE: &str = "\
+Usage: astrokits riseset <TARGET>... [OPTIONS]
+
+Prints the rise, transit and set times of each TARGET in the 24 hours after DATE.
+
+Options:
+  --date DATE       Start of the search, e.g. 2025-06-24T12:00+08:00 (default: now)
+  --el DEG          Elevation of the horizon (default: 0)
+  --twilight        Also print the civil, nautical and astronomical twilight times
+  --lat DEG         Site latitude (overrides the configuration)
+  --lon DEG         Site longitude, east positive (overrides the configuration)
+  --height M        Site height above sea level (overrides the configuration)
+  --no-refraction   Do not correct for atmospheric refraction
+  --format FORMAT   table, json, jsonl or csv (default: table)
+
+Times are for the center of the source; for the upper limb of the Sun use --el -0.27.";
+
+/// Sun elevations [deg] at the ends of civil, nautical and astronomical twilight.
+const TWILIGHT: [(&str, Twilight); 3] = [
+    ("civil", Twilight::Civil),
+    ("nautical", Twilight::Nautical),
+    ("astronomical", Twilight::Astronomical),
+];
+
+pub fn run(mut args: Args, config: &Config) -> CliResult<()> {
+    let mut config = config.clone();
+    config.apply_site_args(&mut args)?;
+    if config.site.is_none() {
+        return Err("rise and set times need a site, use --lat and --lon or configure [site]".into());
+    }
+    let date = args.value("--date")?.unwrap_or_else(|| "now".to_string());
+    let el: f64 = args.parse("--el")?.unwrap_or(0.0);
+    let twilight = args.flag("--twilight");
+    let refraction = !args.flag("--no-refraction");
+    let format = Format::from_args(&mut args)?;
+    let targets: Vec<(String, Source)> = std::iter::from_fn(|| args.positional())
+        .map(|spec| Ok((spec.split('@').next().unwrap_or(&spec).to_string(), target::parse(&spec)?)))
+        .collect::<CliResult<_>>()?;
+    args.finish()?;
+    if targets.is_empty() && !twilight {
+        return Err("missing TARGET".into());
+    }
+
+    let accuracy = config.load_ephemeris()?;
+    let time = config.time(&date)?;
+    let frame = config.frame(accuracy, &time)?;
+
+    // Twilight records have the dawn as rise and the dusk as set
+    let mut out = Output::new(format, &["name", "rise", "transit", "set", "state"]);
+    out.text(format!("{:<16}  {:<24}  {:<24}  {:<24}", "Target", "Rise (UTC)", "Transit (UTC)", "Set (UTC)"));
+    for (name, source) in &targets {
+        // Also reports missing ephemeris data, which the event searches cannot tell apart from
+        // a source that does not rise or set.
+        let current = elevation(&frame, source, refraction).map_err(|err| config.position_error(err, source))?;
+        let rise = frame.rises_above(el, source, refraction);
+        let set = frame.sets_below(el, source, refraction);
+        let transit = frame.transit_time(source);
+        let state = match (rise, set) {
+            (None, None) if current > el => Some("always up"),
+            (None, None) => Some("never up"),
+            _ => None,
+        };
+        let values = vec![
+            name.as_str().into(),
+            rise.map(|time| time.iso_timestamp()).into(),
+            transit.map(|time| time.iso_timestamp()).into(),
+            set.map(|time| time.iso_timestamp()).into(),
+            state.into(),
+        ];
+        out.record(values, || {
+            let (rise, set) = match state {
+                Some(state) => (state.to_string(), state.to_string()),
+                None => (timestamp(rise), timestamp(set)),
+            };
+            format!("{:<16}  {:<24}  {:<24}  {:<24}", name, rise, timestamp(transit), set)
+        });
+    }
+
+    if twilight {
+        let sun = Source::planet(Planet::Sun)?;
+        out.text("");
+        out.text(format!("{:<16}  {:<24}  {:<24}", "Twilight", "Dusk (UTC)", "Dawn (UTC)"));
+        for (name, twilight) in TWILIGHT {
+            let el = twilight.sun_elevation();
+            let dusk = frame.sets_below(el, &sun, false);
+            let dawn = frame.rises_above(el, &sun, false);
+            let values = vec![
+                format!("{} twilight", name).into(),
+                dawn.map(|time| time.iso_timestamp()).into(),
+                Value::Null,
+                dusk.map(|time| time.iso_timestamp()).into(),
+                Value::Null,
+            ];
+            out.record(values, || format!("{:<16}  {:<24}  {:<24}", name, timestamp(dusk), timestamp(dawn)));
+        }
+    }
+    out.finish();
+    Ok(())
+}
+
+fn timestamp(time: Option<Time>) -> String {
+    time.map_or_else(|| "-".to_string(), |time| time.iso_timestamp())
+}
+
+fn elevation(frame: &ObservingFrame, source: &Source, refraction: bool) -> novas::Result<f64> {
+    let pos = frame.sky_pos(source, ReferenceSystem::Tod)?;
+    Ok(frame.to_horizontal(&pos, ReferenceSystem::Tod, refraction)?.el)
+}
@@ -0,0 +1,54 @@
+use crate::CliResult;
+use astrokits::novas::{Degrees, Hours, Planet, Source};
+
+/// Help text for the target syntax.
+pub const SYNTAX: &str = "\
+A target is a solar-system body (sun, moon, mercury, ..., pluto) or a catalog source given as
+NAME@RA,DEC[,SYSTEM], with RA in hours and DEC in degrees (decimal or sexagesimal) and SYSTEM
+one of ICRS (default), J2000, B1950, ... e.g. Vega@18:36:56.3,+38:47:01";
+
+/// Parses a target specification, see [`SYNTAX`].
+pub fn parse(spec: &str) -> CliResult<Source> {
+    let Some((name, coords)) = spec.split_once('@') else {
+        let planet: Planet = spec.parse().map_err(|_| format!("unknown body `{}`", spec))?;
+        return Ok(Source::planet(planet)?);
+    };
+    let parts: Vec<&str> = coords.split(',').map(str::trim).collect();
+    let (ra, dec, system) = match parts.as_slice() {
+        [ra, dec] => (ra, dec, "ICRS"),
+        [ra, dec, system] => (ra, dec, *system),
+        _ => return Err(format!("expected NAME@RA,DEC[,SYSTEM] in `{}`", spec).into()),
+    };
+    catalog(name, ra, dec, system)
+}
+
+/// A catalog source at sexagesimal or decimal coordinates.
+pub fn catalog(name: &str, ra: &str, dec: &str, system: &str) -> CliResult<Source> {
+    let ra: Hours = ra.parse().map_err(|err| format!("invalid right ascension `{}`: {}", ra, err))?;
+    let dec: Degrees = dec.parse().map_err(|err| format!("invalid declination `{}`: {}", dec, err))?;
+    Ok(Source::star(name, ra.0, dec.0, system)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astrokits::novas::SourceKind;
+
+    #[test]
+    fn bodies_and_catalog_sources() {
+        assert_eq!(parse("jupiter").unwrap().kind(), SourceKind::Planet);
+        let vega = parse("Vega@18:36:56.3,+38:47:01").unwrap();
+        assert_eq!(vega.kind(), SourceKind::Catalog);
+        assert!((vega.as_raw().star.ra - 18.615639).abs() < 1e-6);
+        assert!((vega.as_raw().star.dec - 38.783611).abs() < 1e-6);
+        assert!(parse("Vega@18.6156, 38.7836, B1950").is_ok());
+    }
+
+    #[test]
+    fn invalid_targets() {
+        assert_eq!(parse("vulcan").unwrap_err().to_string(), "unknown body `vulcan`");
+        assert!(parse("Vega@18.6").unwrap_err().to_string().starts_with("expected NAME@RA,DEC"));
+        assert!(parse("Vega@18.6,north").unwrap_err().to_string().starts_with("invalid declination `north`"));
+        assert!(parse("Vega@18.6,38.8,NOWHERE").is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::args::{parse_duration, Args};
+use crate::config::Config;
+use crate::format::{dms, hms};
+use crate::output::{Format, Output, Value};
+use crate::{target, CliResult};
+use astrokits::novas::{Accuracy, ReferenceSystem, Source};
+use std::io::{self, IsTerminal, Write};
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: astrokits watch <TARGET>... [OPTIONS]
+
+Prints the current azimuth, elevation, airmass and hour angle of each TARGET, refreshing the
+table every --interval until interrupted.
+
+Options:
+  --interval DURATION   Time between updates, e.g. 5s, 1m (default: 10s)
+  --once                Print the table once and exit
+  --lat DEG             Site latitude (overrides the configuration)
+  --lon DEG             Site longitude, east positive (overrides the configuration)
+  --height M            Site height above sea level (overrides the configuration)
+  --no-refraction       Do not correct elevations for atmospheric refraction
+  --format FORMAT       table, json, jsonl or csv (default: table)
+
+On a terminal the table is redrawn in place, otherwise each update is appended. In JSON each
+update is a separate array.";
+
+pub fn run(mut args: Args, config: &Config) -> CliResult<()> {
+    let mut config = config.clone();
+    config.apply_site_args(&mut args)?;
+    if config.site.is_none() {
+        return Err("watch needs a site, use --lat and --lon or configure [site]".into());
+    }
+    let interval = parse_duration(&args.value("--interval")?.unwrap_or_else(|| "10s".to_string()))?;
+    let once = args.flag("--once");
+    let refraction = !args.flag("--no-refraction");
+    let format = Format::from_args(&mut args)?;
+    let targets: Vec<(String, Source)> = std::iter::from_fn(|| args.positional())
+        .map(|spec| Ok((spec.split('@').next().unwrap_or(&spec).to_string(), target::parse(&spec)?)))
+        .collect::<CliResult<_>>()?;
+    args.finish()?;
+    if targets.is_empty() {
+        return Err("missing TARGET".into());
+    }
+
+    let accuracy = config.load_ephemeris()?;
+    let redraw = io::stdout().is_terminal() && !once && format == Format::Table;
+    let mut out = Output::new(format, &["time", "target", "az", "el", "airmass", "hour_angle", "lst"]);
+    loop {
+        // Calculated before printing, so that a redraw does not flicker
+        let (header, rows) = update(&config, accuracy, &targets, refraction)?;
+        if redraw {
+            // Home the cursor and clear the screen
+            print!("\x1b[H\x1b[2J");
+        }
+        out.text(header);
+        for (values, line) in rows {
+            out.record(values, || line);
+        }
+        out.finish();
+        if !redraw && !once {
+            out.text("");
+        }
+        io::stdout().flush()?;
+        if once {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_secs_f64(interval));
+    }
+}
+
+/// Fields and table line of a target.
+type Row = (Vec<Value>, String);
+
+/// The table header and the records of the targets at the current time.
+fn update(config: &Config, accuracy: Accuracy, targets: &[(String, Source)], refraction: bool) -> CliResult<(String, Vec<Row>)> {
+    let time = config.time("now")?;
+    let frame = config.frame(accuracy, &time)?;
+    let lst = frame.local_sidereal_time().0;
+
+    let mut header = format!("{}  LST {}\n\n", time.iso_timestamp(), hms(lst, 0));
+    header += &format!("{:<16}  {:>8}  {:>8}  {:>8}  {:>10}", "Target", "Az [deg]", "El [deg]", "Airmass", "HA [h]");
+    let mut rows = Vec::new();
+    for (name, source) in targets {
+        let pos = frame
+            .sky_pos(source, ReferenceSystem::Tod)
+            .map_err(|err| config.position_error(err, source))?;
+        let hor = frame.to_horizontal(&pos, ReferenceSystem::Tod, refraction)?;
+        let ha = (lst - pos.ra + 12.0).rem_euclid(24.0) - 12.0;
+        let airmass = hor.airmass().map_or_else(|| "-".to_string(), |x| format!("{:.3}", x));
+        let line = format!("{:<16}  {:>8.3}  {:>8.3}  {:>8}  {:>10}", name, hor.az, hor.el, airmass, dms(ha, 0));
+        let values = vec![
+            time.iso_timestamp().into(),
+            name.as_str().into(),
+            hor.az.into(),
+            hor.el.into(),
+            hor.airmass().into(),
+            ha.into(),
+            lst.into(),
+        ];
+        rows.push((values, line));
+    }
+    Ok((header, rows))
+}
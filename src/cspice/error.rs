@@ -0,0 +1,186 @@
+use libcspice_sys as cs;
+use std::ffi::CStr;
+use std::fmt;
+use std::os::raw::c_char;
+use std::sync::{Mutex, MutexGuard, Once};
+
+/// Errors reported by the safe CSPICE layer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// A CSPICE routine signalled an error.
+    Spice(SpiceError),
+    /// An argument was rejected before it reached CSPICE.
+    InvalidArgument(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Spice(err) => err.fmt(f),
+            Error::InvalidArgument(what) => write!(f, "invalid argument: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Spice(err) => Some(err),
+            Error::InvalidArgument(_) => None,
+        }
+    }
+}
+
+impl From<SpiceError> for Error {
+    fn from(err: SpiceError) -> Self {
+        Error::Spice(err)
+    }
+}
+
+/// An error signalled by CSPICE, with the messages of `getmsg_c`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpiceError {
+    /// The CSPICE routine called, e.g. "furnsh_c"
+    pub call: &'static str,
+    /// Short message, e.g. "SPICE(NOSUCHFILE)"
+    pub short: String,
+    /// Explanation of the short message, e.g. "The file does not exist."
+    pub explain: String,
+    /// Long message, specific to the failure
+    pub long: String,
+}
+
+impl fmt::Display for SpiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed: {}", self.call, self.short)?;
+        if !self.explain.is_empty() {
+            write!(f, " ({})", self.explain.trim_end_matches('.'))?;
+        }
+        if !self.long.is_empty() {
+            write!(f, ": {}", self.long)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SpiceError {}
+
+/// Longest CSPICE error message, that of `getmsg_c` LONG.
+const MESSAGE_LEN: usize = 1841;
+
+static RETURN_ON_ERROR: Once = Once::new();
+
+/// Serializes the calls of the safe layer, as CSPICE is not thread-safe and its error status is
+/// global.
+static CSPICE: Mutex<()> = Mutex::new(());
+
+pub(crate) fn lock() -> MutexGuard<'static, ()> {
+    CSPICE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Sets CSPICE to return from failed routines, instead of printing the error and aborting the
+/// process, so that [`checked`] can report the errors. Messages are neither printed nor written
+/// to a log file.
+fn return_on_error() {
+    RETURN_ON_ERROR.call_once(|| {
+        let mut action = *b"RETURN\0";
+        let mut report = *b"NONE\0";
+        let mut device = *b"NULL\0";
+        unsafe {
+            cs::erract_c(c"SET".as_ptr(), action.len() as _, action.as_mut_ptr() as *mut c_char);
+            cs::errprt_c(c"SET".as_ptr(), report.len() as _, report.as_mut_ptr() as *mut c_char);
+            cs::errdev_c(c"SET".as_ptr(), device.len() as _, device.as_mut_ptr() as *mut c_char);
+        }
+    });
+}
+
+/// Runs `f`, a call to the raw CSPICE routine `call`, with CSPICE set to return on errors, and
+/// maps the error it signals, if any, to a [`SpiceError`]. The CSPICE error status is reset
+/// before and after the call, so that a failure left by earlier raw calls does not turn `f`
+/// into a no-op.
+///
+/// `f` runs with the lock that serializes the calls of the safe layer held, so it must not use
+/// the safe layer itself. The first call sets CSPICE to return on errors for the whole process,
+/// see the [module docs](super).
+///
+/// ```no_run
+/// use astrokits::cspice::{self, checked};
+///
+/// let mut et = 0.0;
+/// checked("str2et_c", || unsafe { cspice::str2et_c(c"2025-06-24T12:00:00".as_ptr(), &mut et) })?;
+/// # Ok::<(), cspice::Error>(())
+/// ```
+pub fn checked<T>(call: &'static str, f: impl FnOnce() -> T) -> Result<T> {
+    let _lock = lock();
+    checked_locked(call, f)
+}
+
+/// [`checked`], for callers that hold [`lock`].
+pub(crate) fn checked_locked<T>(call: &'static str, f: impl FnOnce() -> T) -> Result<T> {
+    return_on_error();
+    if unsafe { cs::failed_c() } != 0 {
+        unsafe { cs::reset_c() };
+    }
+    let value = f();
+    check(call)?;
+    Ok(value)
+}
+
+/// Maps the CSPICE error status after a call to a `Result`, resetting it.
+fn check(call: &'static str) -> Result<()> {
+    if unsafe { cs::failed_c() } == 0 {
+        return Ok(());
+    }
+    let err = SpiceError { call, short: message(c"SHORT"), explain: message(c"EXPLAIN"), long: message(c"LONG") };
+    unsafe { cs::reset_c() };
+    Err(err.into())
+}
+
+fn message(option: &CStr) -> String {
+    let mut buf = [0 as c_char; MESSAGE_LEN];
+    unsafe {
+        cs::getmsg_c(option.as_ptr(), buf.len() as _, buf.as_mut_ptr());
+        CStr::from_ptr(buf.as_ptr())
+    }
+    .to_string_lossy()
+    .trim()
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages() {
+        let err = SpiceError {
+            call: "furnsh_c",
+            short: "SPICE(NOSUCHFILE)".to_string(),
+            explain: "The file does not exist.".to_string(),
+            long: "The file 'de999.bsp' could not be found.".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "furnsh_c failed: SPICE(NOSUCHFILE) (The file does not exist): The file 'de999.bsp' could not be found."
+        );
+        let terse = SpiceError { explain: String::new(), long: String::new(), ..err.clone() };
+        assert_eq!(terse.to_string(), "furnsh_c failed: SPICE(NOSUCHFILE)");
+        assert_eq!(Error::from(err.clone()), Error::Spice(err));
+        assert_eq!(Error::InvalidArgument("no kernel").to_string(), "invalid argument: no kernel");
+    }
+
+    #[test]
+    fn failures_are_reported_and_reset() {
+        let mut et = 0.0;
+        let err = checked("str2et_c", || unsafe { cs::str2et_c(c"bad".as_ptr(), &mut et) }).unwrap_err();
+        let Error::Spice(err) = err else { panic!("{err:?}") };
+        assert_eq!(err.call, "str2et_c");
+        assert!(err.short.starts_with("SPICE("), "{}", err.short);
+        // The next call is not a no-op
+        let mut total = -1;
+        checked("ktotal_c", || unsafe { cs::ktotal_c(c"ALL".as_ptr(), &mut total) }).unwrap();
+        assert!(total >= 0);
+    }
+}
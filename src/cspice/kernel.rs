@@ -0,0 +1,247 @@
+use super::error::{checked_locked, lock, Error, Result};
+use libcspice_sys as cs;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// Longest file name CSPICE accepts, `FILSIZ` - 1.
+const MAX_PATH_LEN: usize = 254;
+
+/// Longest kernel type or source name returned by `kdata_c`.
+const NAME_LEN: usize = 256;
+
+/// Kernels loaded through [`Kernel`], by path, with their number of handles. CSPICE keeps a
+/// single copy of a file loaded twice, so it is unloaded with its last handle. Taken after the
+/// lock of the CSPICE calls.
+static LOADED: Mutex<Option<HashMap<PathBuf, usize>>> = Mutex::new(None);
+
+fn loaded() -> MutexGuard<'static, Option<HashMap<PathBuf, usize>>> {
+    LOADED.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Kind of kernel, as enumerated by [`KernelPool::loaded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum KernelKind {
+    #[default]
+    All,
+    /// Ephemerides
+    Spk,
+    /// Orientations of spacecraft and instruments
+    Ck,
+    /// Binary planetary constants, e.g. high-precision Earth orientation
+    Pck,
+    /// Digital shape models
+    Dsk,
+    /// Event kernels
+    Ek,
+    /// Text kernels: leap seconds, constants, frames, ...
+    Text,
+    /// Meta-kernels, which load other kernels
+    Meta,
+}
+
+impl KernelKind {
+    fn as_cstr(self) -> &'static CStr {
+        match self {
+            KernelKind::All => c"ALL",
+            KernelKind::Spk => c"SPK",
+            KernelKind::Ck => c"CK",
+            KernelKind::Pck => c"PCK",
+            KernelKind::Dsk => c"DSK",
+            KernelKind::Ek => c"EK",
+            KernelKind::Text => c"TEXT",
+            KernelKind::Meta => c"META",
+        }
+    }
+}
+
+/// A kernel loaded in the CSPICE kernel pool, unloaded when dropped.
+///
+/// Loading the same file again gives another handle on it; it stays loaded until all its
+/// handles are dropped. See [`Kernel::leak`] to keep it loaded for the life of the process.
+#[derive(Debug)]
+pub struct Kernel {
+    path: PathBuf,
+}
+
+impl Kernel {
+    /// Loads a kernel file (SPK, PCK, leap seconds, meta-kernel, ...) with `furnsh_c`. The
+    /// file must exist; its absolute path must fit in the 254 characters CSPICE accepts.
+    ///
+    /// Sets CSPICE to return on errors for the whole process, see the [module docs](super).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(Error::InvalidArgument("kernel file does not exist"));
+        }
+        // Not canonicalized: on Windows that gives `\\?\` paths, which CSPICE cannot open
+        let path = std::path::absolute(path).map_err(|_| Error::InvalidArgument("kernel path cannot be resolved"))?;
+        let name = c_path(&path)?;
+
+        let _lock = lock();
+        let mut loaded = loaded();
+        let loaded = loaded.get_or_insert_with(HashMap::new);
+        if !loaded.contains_key(&path) {
+            checked_locked("furnsh_c", || unsafe { cs::furnsh_c(name.as_ptr()) })?;
+        }
+        *loaded.entry(path.clone()).or_insert(0) += 1;
+        Ok(Kernel { path })
+    }
+
+    /// The absolute path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Keeps the kernel loaded until the end of the process, or until it is unloaded with
+    /// `unload_c` or `kclear_c`.
+    pub fn leak(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for Kernel {
+    fn drop(&mut self) {
+        let _lock = lock();
+        let mut loaded = loaded();
+        let Some(count) = loaded.as_mut().and_then(|loaded| loaded.get_mut(&self.path)) else {
+            return;
+        };
+        *count -= 1;
+        if *count > 0 {
+            return;
+        }
+        loaded.as_mut().map(|loaded| loaded.remove(&self.path));
+        // The path was checked when the kernel was loaded
+        if let Ok(name) = c_path(&self.path) {
+            // Errors cannot be reported from a destructor
+            let _ = checked_locked("unload_c", || unsafe { cs::unload_c(name.as_ptr()) });
+        }
+    }
+}
+
+/// A file in the kernel pool, as reported by `kdata_c`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedKernel {
+    /// Path the file was loaded from
+    pub file: String,
+    /// Kind: SPK, CK, PCK, DSK, EK, TEXT or META
+    pub kind: String,
+    /// The meta-kernel that loaded the file, or empty if loaded directly
+    pub source: String,
+    /// Handle of binary kernels, 0 for text kernels
+    pub handle: i32,
+}
+
+/// The CSPICE kernel pool, shared by the whole process: the kernels loaded through [`Kernel`],
+/// by meta-kernels, and by other users of CSPICE such as the SuperNOVAS CSPICE plugin.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KernelPool;
+
+impl KernelPool {
+    /// Number of loaded files of a kind.
+    pub fn count(kind: KernelKind) -> Result<usize> {
+        let _lock = lock();
+        count(kind)
+    }
+
+    /// The loaded files of a kind, in load order.
+    pub fn loaded(kind: KernelKind) -> Result<Vec<LoadedKernel>> {
+        let _lock = lock();
+        let total = count(kind)?;
+        let mut kernels = Vec::with_capacity(total);
+        for which in 0..total {
+            let mut file = [0 as c_char; MAX_PATH_LEN + 2];
+            let mut filtyp = [0 as c_char; NAME_LEN];
+            let mut source = [0 as c_char; MAX_PATH_LEN + 2];
+            let mut handle: cs::SpiceInt = 0;
+            let mut found: cs::SpiceBoolean = 0;
+            checked_locked("kdata_c", || unsafe {
+                cs::kdata_c(
+                    which as _,
+                    kind.as_cstr().as_ptr(),
+                    file.len() as _,
+                    filtyp.len() as _,
+                    source.len() as _,
+                    file.as_mut_ptr(),
+                    filtyp.as_mut_ptr(),
+                    source.as_mut_ptr(),
+                    &mut handle,
+                    &mut found,
+                )
+            })?;
+            if found == 0 {
+                break;
+            }
+            kernels.push(LoadedKernel {
+                file: string(&file),
+                kind: string(&filtyp),
+                source: string(&source),
+                handle: handle as i32,
+            });
+        }
+        Ok(kernels)
+    }
+
+    /// Whether a file is loaded, by any means.
+    pub fn is_loaded(path: impl AsRef<Path>) -> Result<bool> {
+        let Ok(path) = path.as_ref().canonicalize() else {
+            return Ok(false);
+        };
+        let loaded = KernelPool::loaded(KernelKind::All)?;
+        Ok(loaded.iter().any(|kernel| Path::new(&kernel.file).canonicalize().is_ok_and(|file| file == path)))
+    }
+}
+
+// Number of loaded files of a kind, with the lock held.
+fn count(kind: KernelKind) -> Result<usize> {
+    let mut total: cs::SpiceInt = 0;
+    checked_locked("ktotal_c", || unsafe { cs::ktotal_c(kind.as_cstr().as_ptr(), &mut total) })?;
+    Ok(usize::try_from(total).unwrap_or(0))
+}
+
+fn c_path(path: &Path) -> Result<CString> {
+    let path = path.to_str().ok_or(Error::InvalidArgument("kernel path is not UTF-8"))?;
+    if path.len() > MAX_PATH_LEN {
+        return Err(Error::InvalidArgument("kernel path is longer than 254 characters"));
+    }
+    CString::new(path).map_err(|_| Error::InvalidArgument("kernel path contains NUL"))
+}
+
+fn string(buf: &[c_char]) -> String {
+    unsafe { CStr::from_ptr(buf.as_ptr()) }.to_string_lossy().trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leap_seconds() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("crates/libcspice-sys/tests/data/latest_leapseconds.tls")
+    }
+
+    #[test]
+    fn kernels_stay_loaded_with_their_handles() {
+        let first = Kernel::load(leap_seconds()).unwrap();
+        assert!(first.path().is_absolute());
+        let second = Kernel::load(leap_seconds()).unwrap();
+        let text = KernelPool::loaded(KernelKind::Text).unwrap();
+        assert_eq!(text.iter().filter(|kernel| kernel.file.ends_with("latest_leapseconds.tls")).count(), 1);
+        assert_eq!(text[0].kind, "TEXT");
+        assert!(KernelPool::count(KernelKind::All).unwrap() >= 1);
+        drop(first);
+        assert!(KernelPool::is_loaded(leap_seconds()).unwrap());
+        drop(second);
+        assert!(!KernelPool::is_loaded(leap_seconds()).unwrap());
+    }
+
+    #[test]
+    fn paths_are_checked() {
+        assert_eq!(Kernel::load("no/such/kernel.bsp").unwrap_err(), Error::InvalidArgument("kernel file does not exist"));
+        assert!(c_path(&PathBuf::from("k".repeat(MAX_PATH_LEN + 1))).is_err());
+        assert!(c_path(Path::new("de440s.bsp")).is_ok());
+        assert!(!KernelPool::is_loaded("no/such/kernel.bsp").unwrap());
+    }
+}
@@ -0,0 +1,20 @@
+//! Raw CSPICE bindings, and a safe layer over its kernel pool.
+//!
+//! The safe layer sets CSPICE to return from failed routines rather than abort the process, and
+//! reports their errors as [`SpiceError`]. Raw calls can get the same treatment with [`checked`].
+//!
+//! The error action is global: on its first call, the safe layer sets it to `RETURN`, and the
+//! error output to `NULL`, for the whole process, as the SuperNOVAS CSPICE plugin
+//! (`novas::ephemeris::use_cspice`) does. A failed
+//! routine then leaves the error status set, and later CSPICE calls return without doing
+//! anything until it is reset: code calling CSPICE directly must check `failed_c` after its
+//! calls, or go through [`checked`].
+
+pub use libcspice_sys::*;
+
+mod error;
+mod kernel;
+
+pub(crate) use error::lock;
+pub use error::{checked, Error, Result, SpiceError};
+pub use kernel::{Kernel, KernelKind, KernelPool, LoadedKernel};
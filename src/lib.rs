@@ -1,7 +1,5 @@
 #[cfg(feature = "cspice")]
-pub mod cspice {
-    pub use libcspice_sys::*;
-}
+pub mod cspice;
 
 #[cfg(feature = "calceph")]
 pub mod calceph {
@@ -11,4 +9,7 @@ pub mod calceph {
 #[cfg(feature = "novas")]
 pub mod supernvas {
     pub use supernovas_sys::*;
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "novas")]
+pub mod novas;
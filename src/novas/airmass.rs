@@ -0,0 +1,64 @@
+use super::frame::Horizontal;
+
+/// Formula for the relative airmass at an elevation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AirmassModel {
+    /// sec(z) of a plane-parallel atmosphere, accurate only well above the horizon
+    PlaneParallel,
+    /// Pickering (2002), accurate down to the horizon
+    Pickering,
+    /// Kasten & Young (1989)
+    #[default]
+    KastenYoung,
+}
+
+/// Relative airmass at elevation `el` [deg], or `None` below the horizon.
+pub fn airmass(el: f64, model: AirmassModel) -> Option<f64> {
+    if el <= 0.0 {
+        return None;
+    }
+    Some(match model {
+        AirmassModel::PlaneParallel => 1.0 / el.to_radians().sin(),
+        AirmassModel::Pickering => 1.0 / (el + 244.0 / (165.0 + 47.0 * el.powf(1.1))).to_radians().sin(),
+        AirmassModel::KastenYoung => 1.0 / (el.to_radians().sin() + 0.50572 * (el + 6.07995).powf(-1.6364)),
+    })
+}
+
+/// Magnitude above the atmosphere of a source observed with magnitude `mag` through `airmass`,
+/// with the extinction coefficient `k` [mag/airmass] of the band.
+pub fn mag_above_atmosphere(mag: f64, airmass: f64, k: f64) -> f64 {
+    mag - k * airmass
+}
+
+impl Horizontal {
+    /// Relative airmass with the given formula, or `None` below the horizon.
+    pub fn airmass_with(&self, model: AirmassModel) -> Option<f64> {
+        airmass(self.el, model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn models_agree_high_and_differ_low() {
+        for model in [AirmassModel::PlaneParallel, AirmassModel::Pickering, AirmassModel::KastenYoung] {
+            assert!((airmass(90.0, model).unwrap() - 1.0).abs() < 1e-3);
+            assert!((airmass(30.0, model).unwrap() - 2.0).abs() < 1e-2);
+            assert_eq!(airmass(0.0, model), None);
+            assert_eq!(airmass(-5.0, model), None);
+        }
+        // About 38 airmasses at the horizon, where sec(z) diverges
+        let horizon = airmass(1e-6, AirmassModel::KastenYoung).unwrap();
+        assert!(horizon > 37.0 && horizon < 39.0);
+        assert!(airmass(1e-6, AirmassModel::PlaneParallel).unwrap() > 1e6);
+    }
+
+    #[test]
+    fn extinction() {
+        assert_eq!(mag_above_atmosphere(5.0, 2.0, 0.2), 4.6);
+        let hor = Horizontal { az: 120.0, el: 30.0 };
+        assert_eq!(hor.airmass_with(AirmassModel::Pickering), airmass(30.0, AirmassModel::Pickering));
+    }
+}
@@ -0,0 +1,253 @@
+use super::error::{Error, Result};
+use std::f64::consts::{PI, TAU};
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+use supernovas_sys as sn;
+
+/// An angle in degrees.
+///
+/// Displays in sexagesimal degrees like [`sn::utils::DMS`], e.g. `+38:47:01.00` with `{:+#.2}`.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Degrees(pub f64);
+
+/// An angle in radians.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Radians(pub f64);
+
+/// An angle in hours, e.g. a right ascension or hour angle.
+///
+/// Displays normalized to [0, 24) in sexagesimal hours like [`sn::utils::HMS`], e.g.
+/// `18:36:56.300` with `{:#.3}`.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hours(pub f64);
+
+// Arithmetic between angles of the same unit, and scaling by numbers.
+macro_rules! angle_ops {
+    ($angle:ident) => {
+        impl Add for $angle {
+            type Output = $angle;
+            fn add(self, other: $angle) -> $angle {
+                $angle(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $angle {
+            type Output = $angle;
+            fn sub(self, other: $angle) -> $angle {
+                $angle(self.0 - other.0)
+            }
+        }
+
+        impl Neg for $angle {
+            type Output = $angle;
+            fn neg(self) -> $angle {
+                $angle(-self.0)
+            }
+        }
+
+        impl Mul<f64> for $angle {
+            type Output = $angle;
+            fn mul(self, factor: f64) -> $angle {
+                $angle(self.0 * factor)
+            }
+        }
+
+        impl Div<f64> for $angle {
+            type Output = $angle;
+            fn div(self, divisor: f64) -> $angle {
+                $angle(self.0 / divisor)
+            }
+        }
+    };
+}
+
+angle_ops!(Degrees);
+angle_ops!(Radians);
+angle_ops!(Hours);
+
+impl Degrees {
+    /// The angle in [0, 360).
+    pub fn normalized(self) -> Degrees {
+        Degrees(self.0.rem_euclid(360.0))
+    }
+
+    /// The angle in [-180, 180).
+    pub fn wrapped(self) -> Degrees {
+        Degrees((self.0 + 180.0).rem_euclid(360.0) - 180.0)
+    }
+
+    /// The angle in degrees, arcminutes and arcseconds.
+    pub fn to_dms(self) -> sn::utils::DMS {
+        sn::utils::DMS::from(self.0)
+    }
+}
+
+impl Radians {
+    /// The angle in [0, 2 pi).
+    pub fn normalized(self) -> Radians {
+        Radians(self.0.rem_euclid(TAU))
+    }
+
+    /// The angle in [-pi, pi).
+    pub fn wrapped(self) -> Radians {
+        Radians((self.0 + PI).rem_euclid(TAU) - PI)
+    }
+}
+
+impl Hours {
+    /// The angle in [0, 24).
+    pub fn normalized(self) -> Hours {
+        Hours(self.0.rem_euclid(24.0))
+    }
+
+    /// The angle in [-12, 12).
+    pub fn wrapped(self) -> Hours {
+        Hours((self.0 + 12.0).rem_euclid(24.0) - 12.0)
+    }
+
+    /// The angle, normalized to [0, 24), in hours, minutes and seconds.
+    pub fn to_hms(self) -> sn::utils::HMS {
+        sn::utils::HMS::from(self.normalized().0)
+    }
+}
+
+impl From<Radians> for Degrees {
+    fn from(angle: Radians) -> Self {
+        Degrees(angle.0.to_degrees())
+    }
+}
+
+impl From<Hours> for Degrees {
+    fn from(angle: Hours) -> Self {
+        Degrees(angle.0 * 15.0)
+    }
+}
+
+impl From<Degrees> for Radians {
+    fn from(angle: Degrees) -> Self {
+        Radians(angle.0.to_radians())
+    }
+}
+
+impl From<Hours> for Radians {
+    fn from(angle: Hours) -> Self {
+        Radians((angle.0 * 15.0).to_radians())
+    }
+}
+
+impl From<Degrees> for Hours {
+    fn from(angle: Degrees) -> Self {
+        Hours(angle.0 / 15.0)
+    }
+}
+
+impl From<Radians> for Hours {
+    fn from(angle: Radians) -> Self {
+        Hours(angle.0.to_degrees() / 15.0)
+    }
+}
+
+impl From<Degrees> for sn::utils::DMS {
+    fn from(angle: Degrees) -> Self {
+        angle.to_dms()
+    }
+}
+
+impl From<Hours> for sn::utils::HMS {
+    fn from(angle: Hours) -> Self {
+        angle.to_hms()
+    }
+}
+
+impl fmt::Display for Degrees {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_dms(), f)
+    }
+}
+
+impl fmt::Display for Hours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Rounded to the displayed seconds (2 decimals by default, as for HMS) before normalizing,
+        // so that the hours never show as 24
+        let scale = 3600.0 * 10f64.powi(f.precision().unwrap_or(2) as i32);
+        fmt::Display::fmt(&Hours((self.0 * scale).round() / scale).to_hms(), f)
+    }
+}
+
+impl FromStr for Degrees {
+    type Err = Error;
+
+    /// Parses decimal or sexagesimal degrees, e.g. "-12.5", "+12 30 00", "12:30:00" or
+    /// "12d30m00s S", see [`sn::utils::DMS`].
+    fn from_str(s: &str) -> Result<Self> {
+        let dms: sn::utils::DMS = s.parse().map_err(|err: sn::utils::ParseAngleError| Error::InvalidArgument(err.message()))?;
+        Ok(Degrees(dms.degrees()))
+    }
+}
+
+impl FromStr for Hours {
+    type Err = Error;
+
+    /// Parses decimal or sexagesimal hours, e.g. "18.6", "18:36:56.3" or "18h36m56.3s", see
+    /// [`sn::utils::HMS`].
+    fn from_str(s: &str) -> Result<Self> {
+        let hms: sn::utils::HMS = s.parse().map_err(|err: sn::utils::ParseAngleError| Error::InvalidArgument(err.message()))?;
+        Ok(Hours(hms.hours()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_and_wraps() {
+        assert_eq!(Degrees(-30.0).normalized(), Degrees(330.0));
+        assert_eq!(Degrees(190.0).wrapped(), Degrees(-170.0));
+        assert_eq!(Degrees(180.0).wrapped(), Degrees(-180.0));
+        assert_eq!(Hours(25.5).normalized(), Hours(1.5));
+        assert_eq!(Hours(13.0).wrapped(), Hours(-11.0));
+        assert_eq!(Hours(12.0).wrapped(), Hours(-12.0));
+        assert_eq!(Hours(-12.0).wrapped(), Hours(-12.0));
+        assert_eq!(Hours(25.0).wrapped(), Hours(1.0));
+        assert!((Radians(-PI / 2.0).normalized().0 - 1.5 * PI).abs() < 1e-15);
+        assert!((Radians(1.5 * PI).wrapped().0 + PI / 2.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn converts_between_units() {
+        assert_eq!(Degrees::from(Hours(6.0)), Degrees(90.0));
+        assert_eq!(Hours::from(Degrees(90.0)), Hours(6.0));
+        assert!((Radians::from(Hours(12.0)).0 - PI).abs() < 1e-15);
+        assert!((Degrees::from(Radians(PI)).0 - 180.0).abs() < 1e-12);
+        assert!((Hours::from(Radians(PI / 2.0)).0 - 6.0).abs() < 1e-15);
+        assert_eq!(Degrees(10.0) + Degrees(5.0) * 2.0 - Degrees(4.0) / 2.0, Degrees(18.0));
+        assert_eq!(-Hours(1.0), Hours(-1.0));
+    }
+
+    #[test]
+    fn formats_sexagesimal() {
+        assert_eq!(format!("{:#}", Degrees(-12.5)), "-12:30:00.00");
+        assert_eq!(format!("{:+}", Degrees(38.783611)), "+38° 47′ 01.00″");
+        assert_eq!(format!("{:#.1}", Hours(18.615639)), "18:36:56.3");
+        // Rounded up to the next minute and hour, never to 60 seconds
+        assert_eq!(format!("{:+#.0}", Degrees(1.0 - 1e-7)), "+01:00:00");
+        assert_eq!(format!("{:#.3}", Hours(24.0 - 1e-8)), "00:00:00.000");
+        assert_eq!(Hours(-1.0).to_string(), "23h 00m 00.00s");
+        assert_eq!(Hours(-1.0).to_hms().hours(), 23.0);
+        assert_eq!(sn::utils::DMS::from(Degrees(-0.5)).degrees(), -0.5);
+    }
+
+    #[test]
+    fn parses_decimal_and_sexagesimal() {
+        assert_eq!("-12.5".parse::<Degrees>().unwrap(), Degrees(-12.5));
+        assert!(("12:30:00".parse::<Degrees>().unwrap().0 - 12.5).abs() < 1e-12);
+        assert!(("12d30m00s S".parse::<Degrees>().unwrap().0 + 12.5).abs() < 1e-12);
+        assert!(("18h36m56.3s".parse::<Hours>().unwrap().0 - (18.0 + 36.0 / 60.0 + 56.3 / 3600.0)).abs() < 1e-12);
+        assert!(matches!("north".parse::<Degrees>(), Err(Error::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,168 @@
+use super::coords::{dot, Equatorial};
+use super::error::{Error, Result};
+use super::frame::{Accuracy, ObservingFrame};
+use super::observer::Observer;
+use super::redshift::Redshift;
+use super::rv::{frame_velocity, VelocityFrame};
+use super::source::Planet;
+use super::time::{Time, Timescale};
+use supernovas_sys as sn;
+
+/// Barycentric corrections of an observation, e.g. for radial velocities of exoplanet hosts.
+#[derive(Debug, Clone, Copy)]
+pub struct BarycentricCorrection {
+    /// Barycentric arrival time (BJD_TDB)
+    pub time: Time,
+    /// [m/s] velocity that measured radial velocities compound with, see
+    /// [`Redshift::compound`], to refer them to the Solar-System Barycenter. It includes the
+    /// orbital and rotational motion of the observer and the gravitational redshifts.
+    pub velocity: f64,
+}
+
+/// [s] Terms of the delay of the arrival of light at the Solar-System Barycenter (SSB),
+/// relative to its arrival at an observer, as used to barycenter pulsar times of arrival.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BarycentricDelays {
+    /// Geometric delay along the direction of the source
+    pub roemer: f64,
+    /// TDB - TT at the observer, including the topocentric term
+    pub einstein: f64,
+    /// Gravitational delay of the light passing the Sun and the other deflecting bodies of the
+    /// frame, except the Earth
+    pub shapiro: f64,
+}
+
+impl BarycentricDelays {
+    /// [s] TDB arrival time at the SSB less the TT arrival time at the observer.
+    pub fn total(&self) -> f64 {
+        self.roemer + self.einstein - self.shapiro
+    }
+}
+
+impl ObservingFrame {
+    /// [s] Delay of the arrival of light from the ICRS direction `icrs` at the Solar-System
+    /// Barycenter (SSB) relative to its arrival at the observer of this frame: the Rømer delay
+    /// less the Shapiro delay along the path to the observer, see
+    /// [`ObservingFrame::barycentric_delays`].
+    pub fn barycentric_delay(&self, icrs: &Equatorial) -> f64 {
+        let delays = self.barycentric_delays(icrs);
+        delays.roemer - delays.shapiro
+    }
+
+    /// [s] Rømer, Einstein and Shapiro delays of the arrival of light from the ICRS direction
+    /// `icrs` at the SSB, with the positions of the planet provider. The Shapiro delay is that of
+    /// the deflecting bodies of the frame: by default the Sun, and Jupiter and Saturn in full
+    /// accuracy.
+    pub fn barycentric_delays(&self, icrs: &Equatorial) -> BarycentricDelays {
+        let raw = self.as_raw();
+        let n = icrs.to_vector();
+        let roemer = dot(&raw.obs_pos, &n) * sn::NOVAS_AU / sn::NOVAS_C;
+
+        // Geocentric TDB - TT and the term of the observer's position relative to the geocenter
+        let time = self.time();
+        let geocentric = (time.jd(Timescale::Tdb) - time.jd(Timescale::Tt)) * sn::NOVAS_DAY;
+        let r: [f64; 3] = std::array::from_fn(|i| raw.obs_pos[i] - raw.earth_pos[i]);
+        let einstein = geocentric + dot(&raw.earth_vel, &r) * sn::NOVAS_AU.powi(2) / sn::NOVAS_DAY / sn::NOVAS_C.powi(2);
+
+        // Planet positions are relative to the observer.
+        let shapiro = self.deflecting_bodies().into_iter()
+            .filter(|&body| body != Planet::Earth)
+            .filter_map(|body| {
+                let gm = sn::NOVAS_G_SUN * body.mass()? / Planet::Sun.mass()?;
+                let r = raw.planets.pos[body.to_raw() as usize].map(|x| -x);
+                let cos = dot(&r, &n) / dot(&r, &r).sqrt();
+                Some(-2.0 * gm / sn::NOVAS_C.powi(3) * (1.0 + cos).ln())
+            })
+            .sum();
+
+        BarycentricDelays { roemer, einstein, shapiro }
+    }
+}
+
+impl Observer {
+    /// Barycentric arrival time (e.g. BJD_TDB) of light from the ICRS direction `icrs` that
+    /// reaches this observer at `time`.
+    pub fn barycentric_time(&self, accuracy: Accuracy, time: &Time, icrs: &Equatorial) -> Result<Time> {
+        let frame = ObservingFrame::new(accuracy, self, time, 0.0, 0.0)?;
+        time.offset(frame.barycentric_delay(icrs))
+    }
+
+    /// [s] Terms of the delay of the arrival of light from the ICRS direction `icrs` at the SSB,
+    /// relative to its arrival at this observer at `time`.
+    pub fn barycentric_delays(&self, accuracy: Accuracy, time: &Time, icrs: &Equatorial) -> Result<BarycentricDelays> {
+        Ok(ObservingFrame::new(accuracy, self, time, 0.0, 0.0)?.barycentric_delays(icrs))
+    }
+
+    /// Barycentric arrival time and radial-velocity correction of light from the ICRS direction
+    /// `icrs` that reaches this observer at `time`.
+    pub fn barycentric_correction(&self, accuracy: Accuracy, time: &Time, icrs: &Equatorial) -> Result<BarycentricCorrection> {
+        let frame = ObservingFrame::new(accuracy, self, time, 0.0, 0.0)?;
+        let at_rest = Redshift::from_velocity(frame_velocity(&frame, icrs, VelocityFrame::Barycentric)?)?;
+        Ok(BarycentricCorrection {
+            time: time.offset(frame.barycentric_delay(icrs))?,
+            velocity: at_rest.inverse()?.velocity() * 1e3,
+        })
+    }
+
+    /// Time at which light from the ICRS direction `icrs` that arrives at the Solar-System
+    /// Barycenter at `barycentric` reaches this observer, inverting
+    /// [`Observer::barycentric_time`] to 1 ns.
+    pub fn observed_time(&self, accuracy: Accuracy, barycentric: &Time, icrs: &Equatorial) -> Result<Time> {
+        const MAX_ITERATIONS: usize = 10;
+        let mut time = *barycentric;
+        let mut delay = 0.0;
+        for _ in 0..MAX_ITERATIONS {
+            let frame = ObservingFrame::new(accuracy, self, &time, 0.0, 0.0)?;
+            let next = frame.barycentric_delay(icrs);
+            time = barycentric.offset(-next)?;
+            if (next - delay).abs() < 1e-9 {
+                return Ok(time);
+            }
+            delay = next;
+        }
+        Err(Error::NoConvergence { call: "observed time", iterations: MAX_ITERATIONS })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observer() -> Observer {
+        Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap()
+    }
+
+    fn time() -> Time {
+        Time::new(Timescale::Utc, 2460850.5, 37, 0.035).unwrap()
+    }
+
+    #[test]
+    fn delays() {
+        let vega = Equatorial::new(18.6156, 38.7836);
+        let delays = observer().barycentric_delays(Accuracy::Reduced, &time(), &vega).unwrap();
+        // Up to 8.5 minutes, 1.7 ms and some microseconds away from the Sun
+        assert!(delays.roemer.abs() < 510.0);
+        assert!(delays.einstein.abs() < 2e-3);
+        assert!(delays.shapiro.abs() < 1e-4);
+        assert_eq!(delays.total(), delays.roemer + delays.einstein - delays.shapiro);
+        // Opposite directions have opposite Rømer delays
+        let opposite = Equatorial::new(18.6156 - 12.0, -38.7836);
+        let other = observer().barycentric_delays(Accuracy::Reduced, &time(), &opposite).unwrap();
+        assert!((delays.roemer + other.roemer).abs() < 1e-9);
+        assert_eq!(delays.einstein, other.einstein);
+    }
+
+    #[test]
+    fn barycentric_times_invert() {
+        let vega = Equatorial::new(18.6156, 38.7836);
+        let bjd = observer().barycentric_time(Accuracy::Reduced, &time(), &vega).unwrap();
+        let delays = observer().barycentric_delays(Accuracy::Reduced, &time(), &vega).unwrap();
+        assert!((bjd.diff(&time()) - (delays.roemer - delays.shapiro)).abs() < 1e-6);
+        let back = observer().observed_time(Accuracy::Reduced, &bjd, &vega).unwrap();
+        assert!(back.diff(&time()).abs() < 1e-6);
+        let correction = observer().barycentric_correction(Accuracy::Reduced, &time(), &vega).unwrap();
+        assert!(correction.time.diff(&bjd).abs() < 1e-9);
+        // [m/s] The orbital motion of the Earth
+        assert!(correction.velocity.abs() < 30500.0 && correction.velocity.abs() > 1000.0);
+    }
+}
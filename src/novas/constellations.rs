@@ -0,0 +1,228 @@
+//! Constellation of a sky position, from the IAU constellation boundaries.
+//!
+//! The boundaries are read from the table of Roman (1987, PASP 99, 695), distributed by the CDS
+//! as catalog VI/42 (`data.dat`), in which the boundaries are arcs of constant right ascension
+//! and declination of the B1875 equinox.
+
+use super::coords::{Epoch, Equatorial};
+use super::error::{Error, Result};
+
+/// The 88 IAU constellations: abbreviation and name.
+const CONSTELLATIONS: [(&str, &str); 88] = [
+    ("And", "Andromeda"),
+    ("Ant", "Antlia"),
+    ("Aps", "Apus"),
+    ("Aqr", "Aquarius"),
+    ("Aql", "Aquila"),
+    ("Ara", "Ara"),
+    ("Ari", "Aries"),
+    ("Aur", "Auriga"),
+    ("Boo", "Boötes"),
+    ("Cae", "Caelum"),
+    ("Cam", "Camelopardalis"),
+    ("Cnc", "Cancer"),
+    ("CVn", "Canes Venatici"),
+    ("CMa", "Canis Major"),
+    ("CMi", "Canis Minor"),
+    ("Cap", "Capricornus"),
+    ("Car", "Carina"),
+    ("Cas", "Cassiopeia"),
+    ("Cen", "Centaurus"),
+    ("Cep", "Cepheus"),
+    ("Cet", "Cetus"),
+    ("Cha", "Chamaeleon"),
+    ("Cir", "Circinus"),
+    ("Col", "Columba"),
+    ("Com", "Coma Berenices"),
+    ("CrA", "Corona Australis"),
+    ("CrB", "Corona Borealis"),
+    ("Crv", "Corvus"),
+    ("Crt", "Crater"),
+    ("Cru", "Crux"),
+    ("Cyg", "Cygnus"),
+    ("Del", "Delphinus"),
+    ("Dor", "Dorado"),
+    ("Dra", "Draco"),
+    ("Equ", "Equuleus"),
+    ("Eri", "Eridanus"),
+    ("For", "Fornax"),
+    ("Gem", "Gemini"),
+    ("Gru", "Grus"),
+    ("Her", "Hercules"),
+    ("Hor", "Horologium"),
+    ("Hya", "Hydra"),
+    ("Hyi", "Hydrus"),
+    ("Ind", "Indus"),
+    ("Lac", "Lacerta"),
+    ("Leo", "Leo"),
+    ("LMi", "Leo Minor"),
+    ("Lep", "Lepus"),
+    ("Lib", "Libra"),
+    ("Lup", "Lupus"),
+    ("Lyn", "Lynx"),
+    ("Lyr", "Lyra"),
+    ("Men", "Mensa"),
+    ("Mic", "Microscopium"),
+    ("Mon", "Monoceros"),
+    ("Mus", "Musca"),
+    ("Nor", "Norma"),
+    ("Oct", "Octans"),
+    ("Oph", "Ophiuchus"),
+    ("Ori", "Orion"),
+    ("Pav", "Pavo"),
+    ("Peg", "Pegasus"),
+    ("Per", "Perseus"),
+    ("Phe", "Phoenix"),
+    ("Pic", "Pictor"),
+    ("Psc", "Pisces"),
+    ("PsA", "Piscis Austrinus"),
+    ("Pup", "Puppis"),
+    ("Pyx", "Pyxis"),
+    ("Ret", "Reticulum"),
+    ("Sge", "Sagitta"),
+    ("Sgr", "Sagittarius"),
+    ("Sco", "Scorpius"),
+    ("Scl", "Sculptor"),
+    ("Sct", "Scutum"),
+    ("Ser", "Serpens"),
+    ("Sex", "Sextans"),
+    ("Tau", "Taurus"),
+    ("Tel", "Telescopium"),
+    ("Tri", "Triangulum"),
+    ("TrA", "Triangulum Australe"),
+    ("Tuc", "Tucana"),
+    ("UMa", "Ursa Major"),
+    ("UMi", "Ursa Minor"),
+    ("Vel", "Vela"),
+    ("Vir", "Virgo"),
+    ("Vol", "Volans"),
+    ("Vul", "Vulpecula"),
+];
+
+/// Equinox of the boundaries.
+const B1875: Epoch = Epoch::Besselian(1875.0);
+
+/// An IAU constellation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Constellation {
+    /// IAU abbreviation, e.g. `UMa`
+    pub abbreviation: &'static str,
+    /// Latin name, e.g. `Ursa Major`
+    pub name: &'static str,
+}
+
+impl Constellation {
+    /// All 88 constellations, in alphabetical order of their names.
+    pub fn all() -> impl Iterator<Item = Constellation> {
+        CONSTELLATIONS.iter().map(|&(abbreviation, name)| Constellation { abbreviation, name })
+    }
+
+    /// The constellation with an IAU abbreviation, in any letter case.
+    pub fn from_abbreviation(abbreviation: &str) -> Option<Constellation> {
+        Constellation::all().find(|c| c.abbreviation.eq_ignore_ascii_case(abbreviation))
+    }
+}
+
+// One row of the boundary table: the area north of `dec` [deg] between `ra_low` and `ra_high` [h],
+// at B1875, belongs to the constellation unless an earlier row claims it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Boundary {
+    ra_low: f64,
+    ra_high: f64,
+    dec: f64,
+    constellation: Constellation,
+}
+
+impl Boundary {
+    fn from_fields(ra_low: &str, ra_high: &str, dec: &str, abbreviation: &str) -> Option<Self> {
+        Some(Boundary {
+            ra_low: ra_low.parse().ok()?,
+            ra_high: ra_high.parse().ok()?,
+            dec: dec.parse().ok()?,
+            constellation: Constellation::from_abbreviation(abbreviation)?,
+        })
+    }
+}
+
+/// The IAU constellation boundaries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Boundaries {
+    rows: Vec<Boundary>,
+}
+
+impl Boundaries {
+    /// Parses the boundary table of the CDS catalog VI/42 (`data.dat`): lower and upper right
+    /// ascension [h], lower declination [deg] and constellation abbreviation, by decreasing
+    /// declination.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rows = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() {
+                continue;
+            }
+            let row = match fields[..] {
+                [ra_low, ra_high, dec, abbreviation] => Boundary::from_fields(ra_low, ra_high, dec, abbreviation),
+                _ => None,
+            };
+            rows.push(row.ok_or(Error::Parse { what: "unexpected constellation boundary", line: n + 1 })?);
+        }
+        if rows.is_empty() {
+            return Err(Error::InvalidArgument("no constellation boundaries in file"));
+        }
+        Ok(Boundaries { rows })
+    }
+
+    /// The constellation containing mean coordinates `pos` of the equator and equinox of `epoch`,
+    /// e.g. ICRS coordinates with [`Epoch::J2000`].
+    pub fn constellation(&self, pos: &Equatorial, epoch: Epoch) -> Result<Constellation> {
+        let pos = pos.precess(epoch, B1875)?;
+        let ra = pos.ra.rem_euclid(24.0);
+        self.rows
+            .iter()
+            .find(|row| pos.dec >= row.dec && row.ra_low <= ra && ra < row.ra_high)
+            .map(|row| row.constellation)
+            .ok_or(Error::InvalidArgument("position is outside the constellation boundaries"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A few rows of VI/42: the polar cap, and a band down to the equator
+    const BOUNDARIES: &str = "\
+ 0.0000 24.0000  88.0000 UMi
+ 8.0000 14.5000  86.5000 UMi
+21.0000 23.0000  86.1667 UMi
+ 0.0000 24.0000  -1.0000 Ori
+ 0.0000 24.0000 -90.0000 Oct
+";
+
+    #[test]
+    fn abbreviations() {
+        assert_eq!(Constellation::all().count(), 88);
+        assert_eq!(Constellation::from_abbreviation("uma").unwrap().name, "Ursa Major");
+        assert_eq!(Constellation::from_abbreviation("CVn").unwrap().name, "Canes Venatici");
+        assert_eq!(Constellation::from_abbreviation("Xyz"), None);
+    }
+
+    #[test]
+    fn positions_are_looked_up_at_b1875() {
+        let boundaries = Boundaries::parse(BOUNDARIES).unwrap();
+        let name = |ra, dec| boundaries.constellation(&Equatorial::new(ra, dec), Epoch::J2000).unwrap().abbreviation;
+        // Polaris, which was 1.5 degrees from the pole in 1875
+        assert_eq!(name(2.5303, 89.2641), "UMi");
+        assert_eq!(name(12.0, -60.0), "Oct");
+        assert_eq!(name(5.5, 0.5), "Ori");
+    }
+
+    #[test]
+    fn malformed_tables() {
+        assert!(matches!(Boundaries::parse(" 0.0 24.0 88.0 UMi\n 0.0 24.0 XYZ\n"), Err(Error::Parse { line: 2, .. })));
+        assert!(matches!(Boundaries::parse(" 0.0 24.0 88.0 Xyz\n"), Err(Error::Parse { line: 1, .. })));
+        assert!(Boundaries::parse("\n").is_err());
+        let cap = Boundaries::parse(" 0.0 24.0 88.0 UMi\n").unwrap();
+        assert!(cap.constellation(&Equatorial::new(0.0, 0.0), Epoch::J2000).is_err());
+    }
+}
@@ -0,0 +1,533 @@
+use super::angle::{Degrees, Hours};
+use super::error::{check, Error, Result};
+use super::fk4;
+use super::frame::Accuracy;
+use std::ffi::CString;
+use std::str::FromStr;
+use supernovas_sys as sn;
+
+const JD_J2000: f64 = 2451545.0;
+const JD_B1950: f64 = 2433282.42345905;
+const JULIAN_YEAR_DAYS: f64 = 365.25;
+// Tropical year at B1900 (Lieske 1979). `novas_epoch()` uses 365.2568983 instead, which puts
+// Besselian epochs other than B1950 off by 0.0147 days per year.
+const BESSELIAN_YEAR_DAYS: f64 = 365.242198781;
+
+/// Coordinate epoch, given as a Julian or Besselian year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Epoch {
+    Julian(f64),
+    Besselian(f64),
+}
+
+impl Epoch {
+    pub const J2000: Epoch = Epoch::Julian(2000.0);
+    pub const B1950: Epoch = Epoch::Besselian(1950.0);
+
+    /// [day] The Julian date of the epoch.
+    pub fn jd(self) -> f64 {
+        match self {
+            Epoch::Julian(year) => JD_J2000 + (year - 2000.0) * JULIAN_YEAR_DAYS,
+            Epoch::Besselian(year) => JD_B1950 + (year - 1950.0) * BESSELIAN_YEAR_DAYS,
+        }
+    }
+
+    /// [yr] The epoch as a Julian year.
+    pub fn julian_year(self) -> f64 {
+        match self {
+            Epoch::Julian(year) => year,
+            Epoch::Besselian(_) => 2000.0 + (self.jd() - JD_J2000) / JULIAN_YEAR_DAYS,
+        }
+    }
+
+    /// [yr] The epoch as a Besselian year.
+    pub fn besselian_year(self) -> f64 {
+        match self {
+            Epoch::Julian(_) => 1950.0 + (self.jd() - JD_B1950) / BESSELIAN_YEAR_DAYS,
+            Epoch::Besselian(year) => year,
+        }
+    }
+
+    /// The Julian epoch of a Julian date.
+    pub fn from_jd(jd: f64) -> Epoch {
+        Epoch::Julian(2000.0 + (jd - JD_J2000) / JULIAN_YEAR_DAYS)
+    }
+}
+
+impl FromStr for Epoch {
+    type Err = Error;
+
+    /// Parses an epoch or catalog system as `novas_epoch()` does, e.g. "J2000", "B1950", "2016.0",
+    /// "FK4" or "HIP". The result is a Julian epoch.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = CString::new(s).map_err(|_| Error::InvalidArgument("epoch contains NUL"))?;
+        let jd = unsafe { sn::novas_epoch(s.as_ptr()) };
+        if jd.is_nan() {
+            return Err(Error::InvalidArgument("unrecognized epoch"));
+        }
+        Ok(Epoch::from_jd(jd))
+    }
+}
+
+/// Equatorial coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Equatorial {
+    /// [h] right ascension
+    pub ra: f64,
+    /// [deg] declination
+    pub dec: f64,
+}
+
+impl Equatorial {
+    pub fn new(ra: f64, dec: f64) -> Self {
+        Equatorial { ra, dec }
+    }
+
+    /// Coordinates from typed angles, e.g. `Equatorial::from_angles(Degrees(279.23), Degrees(38.78))`.
+    pub fn from_angles(ra: impl Into<Hours>, dec: impl Into<Degrees>) -> Self {
+        Equatorial { ra: ra.into().0, dec: dec.into().0 }
+    }
+
+    /// The right ascension as a typed angle.
+    pub fn right_ascension(&self) -> Hours {
+        Hours(self.ra)
+    }
+
+    /// The declination as a typed angle.
+    pub fn declination(&self) -> Degrees {
+        Degrees(self.dec)
+    }
+
+    /// ICRS to the dynamical J2000 frame (frame tie).
+    pub fn icrs_to_j2000(&self) -> Result<Equatorial> {
+        frame_tie(self, sn::novas_frametie_direction_ICRS_TO_J2000)
+    }
+
+    /// Dynamical J2000 frame to ICRS (frame tie).
+    pub fn j2000_to_icrs(&self) -> Result<Equatorial> {
+        frame_tie(self, sn::novas_frametie_direction_J2000_TO_ICRS)
+    }
+
+    /// ICRS to FK5 mean equator and equinox of `equinox`.
+    pub fn icrs_to_fk5(&self, equinox: Epoch) -> Result<Equatorial> {
+        self.icrs_to_j2000()?.precess(Epoch::J2000, equinox)
+    }
+
+    /// FK5 mean equator and equinox of `equinox` to ICRS.
+    pub fn fk5_to_icrs(&self, equinox: Epoch) -> Result<Equatorial> {
+        self.precess(equinox, Epoch::J2000)?.j2000_to_icrs()
+    }
+
+    /// FK4 B1950 coordinates of a source observed at `epoch` to FK5 J2000, removing the E-terms
+    /// of aberration (SOFA `iauFk45z()`).
+    ///
+    /// The source is assumed to have no proper motion in FK5; use
+    /// [`CatalogEntry::fk4_to_icrs`](super::CatalogEntry::fk4_to_icrs) for one with known space
+    /// motion. `epoch` is usually [`Epoch::B1950`].
+    pub fn fk4_to_fk5(&self, epoch: Epoch) -> Result<Equatorial> {
+        let (ra, dec) = fk4::fk45z(self.ra.to_radians() * 15.0, self.dec.to_radians(), epoch.besselian_year(), epoch.julian_year());
+        Ok(Equatorial { ra: ra.to_degrees() / 15.0, dec: dec.to_degrees() })
+    }
+
+    /// FK5 J2000 coordinates of a source with no proper motion in FK5 to FK4 B1950 as observed at
+    /// `epoch`, adding the E-terms of aberration (SOFA `iauFk54z()`).
+    pub fn fk5_to_fk4(&self, epoch: Epoch) -> Result<Equatorial> {
+        let (ra, dec, _, _) = fk4::fk54z(self.ra.to_radians() * 15.0, self.dec.to_radians(), epoch.besselian_year());
+        Ok(Equatorial { ra: ra.to_degrees() / 15.0, dec: dec.to_degrees() })
+    }
+
+    /// FK4 B1950 coordinates of a source observed at `epoch` to ICRS, as
+    /// [`fk4_to_fk5`](Self::fk4_to_fk5).
+    pub fn fk4_to_icrs(&self, epoch: Epoch) -> Result<Equatorial> {
+        self.fk4_to_fk5(epoch)?.j2000_to_icrs()
+    }
+
+    /// ICRS to FK4 B1950 coordinates as observed at `epoch`, as [`fk5_to_fk4`](Self::fk5_to_fk4).
+    pub fn icrs_to_fk4(&self, epoch: Epoch) -> Result<Equatorial> {
+        self.icrs_to_j2000()?.fk5_to_fk4(epoch)
+    }
+
+    /// Precesses mean coordinates from one equinox to another.
+    pub fn precess(&self, from: Epoch, to: Epoch) -> Result<Equatorial> {
+        let (from, to) = (from.jd(), to.jd());
+        let mut v = self.to_vector();
+        // NOVAS precesses only to or from J2000, so go through J2000 if needed.
+        for (jd_in, jd_out) in [(from, JD_J2000), (JD_J2000, to)] {
+            if jd_in != jd_out {
+                let input = v;
+                check("precession", || unsafe {
+                    sn::precession(jd_in, input.as_ptr(), jd_out, v.as_mut_ptr())
+                } as i32)?;
+            }
+        }
+        Ok(Equatorial::from_vector(&v))
+    }
+
+    /// Unit vector pointing to these coordinates.
+    pub fn to_vector(&self) -> [f64; 3] {
+        lonlat_to_vector(self.ra * 15.0, self.dec)
+    }
+
+    /// Coordinates of the direction of a (not necessarily unit) vector.
+    pub fn from_vector(v: &[f64; 3]) -> Equatorial {
+        let (lon, dec) = vector_to_lonlat(v);
+        Equatorial { ra: lon / 15.0, dec }
+    }
+}
+
+/// Galactic coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Galactic {
+    /// [deg] galactic longitude
+    pub l: f64,
+    /// [deg] galactic latitude
+    pub b: f64,
+}
+
+/// Supergalactic coordinates (de Vaucouleurs).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Supergalactic {
+    /// [deg] supergalactic longitude
+    pub sgl: f64,
+    /// [deg] supergalactic latitude
+    pub sgb: f64,
+}
+
+/// Proper motion on the sky, in a given spherical system.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProperMotion {
+    /// [mas/yr] motion along longitude, including the cos(latitude) factor
+    pub lon: f64,
+    /// [mas/yr] motion along latitude
+    pub lat: f64,
+}
+
+impl ProperMotion {
+    pub fn new(lon: f64, lat: f64) -> Self {
+        ProperMotion { lon, lat }
+    }
+
+    // Re-expresses the motion in a system whose north is `angle` east of the current north.
+    fn rotate(&self, angle: f64) -> ProperMotion {
+        let (s, c) = angle.to_radians().sin_cos();
+        ProperMotion {
+            lon: self.lon * c - self.lat * s,
+            lat: self.lon * s + self.lat * c,
+        }
+    }
+}
+
+/// Equator to which dynamical equatorial coordinates are referred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EquatorType {
+    /// Mean equator and equinox of date (precession only)
+    Mean,
+    /// True equator and equinox of date (precession and nutation)
+    True,
+    /// GCRS equator; the corresponding ecliptic is the mean ecliptic of J2000
+    Gcrs,
+}
+
+impl EquatorType {
+    pub(crate) fn to_raw(self) -> sn::novas_equator_type {
+        match self {
+            EquatorType::Mean => sn::novas_equator_type_NOVAS_MEAN_EQUATOR,
+            EquatorType::True => sn::novas_equator_type_NOVAS_TRUE_EQUATOR,
+            EquatorType::Gcrs => sn::novas_equator_type_NOVAS_GCRS_EQUATOR,
+        }
+    }
+}
+
+/// Ecliptic coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ecliptic {
+    /// [deg] ecliptic longitude
+    pub lon: f64,
+    /// [deg] ecliptic latitude
+    pub lat: f64,
+}
+
+impl Ecliptic {
+    pub fn new(lon: f64, lat: f64) -> Self {
+        Ecliptic { lon, lat }
+    }
+
+    /// The longitude as a typed angle.
+    pub fn longitude(&self) -> Degrees {
+        Degrees(self.lon)
+    }
+
+    /// The latitude as a typed angle.
+    pub fn latitude(&self) -> Degrees {
+        Degrees(self.lat)
+    }
+
+    /// Ecliptic coordinates of date `jd_tt` to equatorial coordinates on the given equator of the
+    /// same date. `jd_tt` is ignored for [`EquatorType::Gcrs`], where the ecliptic is that of
+    /// J2000.
+    pub fn to_equatorial(&self, equator: EquatorType, jd_tt: f64, accuracy: Accuracy) -> Result<Equatorial> {
+        let mut e = Equatorial { ra: 0.0, dec: 0.0 };
+        check("ecl2equ", || unsafe {
+            sn::ecl2equ(jd_tt, equator.to_raw(), accuracy.to_raw(), self.lon, self.lat, &mut e.ra, &mut e.dec)
+        })?;
+        Ok(e)
+    }
+
+    /// Mean ecliptic and equinox of J2000 to GCRS equatorial coordinates.
+    pub fn j2000_to_equatorial(&self) -> Result<Equatorial> {
+        self.to_equatorial(EquatorType::Gcrs, JD_J2000, Accuracy::Full)
+    }
+}
+
+// [deg] Galactic coordinates of the supergalactic north pole and origin.
+const SGP_L: f64 = 47.37;
+const SGP_B: f64 = 6.32;
+const SG0_L: f64 = 137.37;
+
+impl Equatorial {
+    /// Equatorial coordinates on the given equator of date `jd_tt` to the ecliptic of the same
+    /// date. `jd_tt` is ignored for [`EquatorType::Gcrs`], where the ecliptic is that of J2000.
+    pub fn to_ecliptic(&self, equator: EquatorType, jd_tt: f64, accuracy: Accuracy) -> Result<Ecliptic> {
+        let mut e = Ecliptic { lon: 0.0, lat: 0.0 };
+        check("equ2ecl", || unsafe {
+            sn::equ2ecl(jd_tt, equator.to_raw(), accuracy.to_raw(), self.ra, self.dec, &mut e.lon, &mut e.lat)
+        } as i32)?;
+        Ok(e)
+    }
+
+    /// GCRS equatorial coordinates to the mean ecliptic and equinox of J2000.
+    pub fn to_ecliptic_j2000(&self) -> Result<Ecliptic> {
+        self.to_ecliptic(EquatorType::Gcrs, JD_J2000, Accuracy::Full)
+    }
+
+    /// ICRS to galactic coordinates.
+    pub fn to_galactic(&self) -> Result<Galactic> {
+        let mut g = Galactic { l: 0.0, b: 0.0 };
+        check("equ2gal", || unsafe { sn::equ2gal(self.ra, self.dec, &mut g.l, &mut g.b) })?;
+        Ok(g)
+    }
+
+    /// ICRS to supergalactic coordinates.
+    pub fn to_supergalactic(&self) -> Result<Supergalactic> {
+        self.to_galactic()?.to_supergalactic()
+    }
+
+    /// Converts an ICRS proper motion at these coordinates to galactic.
+    pub fn galactic_proper_motion(&self, pm: ProperMotion) -> Result<ProperMotion> {
+        let pole = Galactic { l: 0.0, b: 90.0 }.to_equatorial()?;
+        Ok(pm.rotate(position_angle(self.ra * 15.0, self.dec, pole.ra * 15.0, pole.dec)))
+    }
+}
+
+impl Galactic {
+    pub fn new(l: f64, b: f64) -> Self {
+        Galactic { l, b }
+    }
+
+    /// The longitude as a typed angle.
+    pub fn longitude(&self) -> Degrees {
+        Degrees(self.l)
+    }
+
+    /// The latitude as a typed angle.
+    pub fn latitude(&self) -> Degrees {
+        Degrees(self.b)
+    }
+
+    /// Galactic to ICRS coordinates.
+    pub fn to_equatorial(&self) -> Result<Equatorial> {
+        let mut e = Equatorial { ra: 0.0, dec: 0.0 };
+        check("gal2equ", || unsafe { sn::gal2equ(self.l, self.b, &mut e.ra, &mut e.dec) })?;
+        Ok(e)
+    }
+
+    /// Galactic to supergalactic coordinates.
+    pub fn to_supergalactic(&self) -> Result<Supergalactic> {
+        let [x, y, z] = supergalactic_axes();
+        let v = lonlat_to_vector(self.l, self.b);
+        let (sgl, sgb) = vector_to_lonlat(&[dot(&x, &v), dot(&y, &v), dot(&z, &v)]);
+        Ok(Supergalactic { sgl, sgb })
+    }
+
+    /// Converts a galactic proper motion at these coordinates to ICRS.
+    pub fn equatorial_proper_motion(&self, pm: ProperMotion) -> Result<ProperMotion> {
+        let pole = Galactic { l: 0.0, b: 90.0 }.to_equatorial()?;
+        let equ = self.to_equatorial()?;
+        Ok(pm.rotate(-position_angle(equ.ra * 15.0, equ.dec, pole.ra * 15.0, pole.dec)))
+    }
+
+    /// Converts a galactic proper motion at these coordinates to supergalactic.
+    pub fn supergalactic_proper_motion(&self, pm: ProperMotion) -> ProperMotion {
+        pm.rotate(position_angle(self.l, self.b, SGP_L, SGP_B))
+    }
+}
+
+impl Supergalactic {
+    pub fn new(sgl: f64, sgb: f64) -> Self {
+        Supergalactic { sgl, sgb }
+    }
+
+    /// The longitude as a typed angle.
+    pub fn longitude(&self) -> Degrees {
+        Degrees(self.sgl)
+    }
+
+    /// The latitude as a typed angle.
+    pub fn latitude(&self) -> Degrees {
+        Degrees(self.sgb)
+    }
+
+    /// Supergalactic to galactic coordinates.
+    pub fn to_galactic(&self) -> Galactic {
+        let [x, y, z] = supergalactic_axes();
+        let v = lonlat_to_vector(self.sgl, self.sgb);
+        let g: [f64; 3] = std::array::from_fn(|i| x[i] * v[0] + y[i] * v[1] + z[i] * v[2]);
+        let (l, b) = vector_to_lonlat(&g);
+        Galactic { l, b }
+    }
+
+    /// Supergalactic to ICRS coordinates.
+    pub fn to_equatorial(&self) -> Result<Equatorial> {
+        self.to_galactic().to_equatorial()
+    }
+
+    /// Converts a supergalactic proper motion at these coordinates to galactic.
+    pub fn galactic_proper_motion(&self, pm: ProperMotion) -> ProperMotion {
+        let g = self.to_galactic();
+        pm.rotate(-position_angle(g.l, g.b, SGP_L, SGP_B))
+    }
+}
+
+// Supergalactic x, y, z axes expressed in galactic coordinates.
+fn supergalactic_axes() -> [[f64; 3]; 3] {
+    let z = lonlat_to_vector(SGP_L, SGP_B);
+    let x = lonlat_to_vector(SG0_L, 0.0);
+    let y = [z[1] * x[2] - z[2] * x[1], z[2] * x[0] - z[0] * x[2], z[0] * x[1] - z[1] * x[0]];
+    [x, y, z]
+}
+
+// [deg] Position angle (from north through east) of the direction toward the point
+// (lon2, lat2) as seen from (lon1, lat1).
+fn position_angle(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (lat1, lat2, dlon) = (lat1.to_radians(), lat2.to_radians(), (lon2 - lon1).to_radians());
+    let y = dlon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+    y.atan2(x).to_degrees()
+}
+
+fn lonlat_to_vector(lon: f64, lat: f64) -> [f64; 3] {
+    let (lon, lat) = (lon.to_radians(), lat.to_radians());
+    [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+}
+
+fn vector_to_lonlat(v: &[f64; 3]) -> (f64, f64) {
+    let lon = v[1].atan2(v[0]).to_degrees().rem_euclid(360.0);
+    let lat = v[2].atan2(v[0].hypot(v[1])).to_degrees();
+    (lon, lat)
+}
+
+pub(crate) fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn frame_tie(coords: &Equatorial, direction: sn::novas_frametie_direction) -> Result<Equatorial> {
+    let input = coords.to_vector();
+    let mut out = [0.0; 3];
+    check("frame_tie", || unsafe {
+        sn::frame_tie(input.as_ptr(), direction, out.as_mut_ptr())
+    })?;
+    Ok(Equatorial::from_vector(&out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64, tolerance: f64) {
+        assert!((a - b).abs() < tolerance, "{a} != {b}");
+    }
+
+    #[test]
+    fn epochs() {
+        assert_eq!(Epoch::J2000.jd(), 2451545.0);
+        assert_close(Epoch::B1950.jd(), 2433282.4234591, 1e-6);
+        assert_close(Epoch::B1950.julian_year(), 1949.9997904, 1e-7);
+        assert_close(Epoch::J2000.besselian_year(), 2000.0012775, 1e-7);
+        assert_eq!(Epoch::from_jd(2451545.0 + 365.25 * 16.0), Epoch::Julian(2016.0));
+        assert_close("J2016".parse::<Epoch>().unwrap().julian_year(), 2016.0, 1e-9);
+        assert_close("B1950".parse::<Epoch>().unwrap().jd(), Epoch::B1950.jd(), 1e-6);
+        assert!("yesterday".parse::<Epoch>().is_err());
+    }
+
+    #[test]
+    fn galactic_poles_and_center() {
+        // North galactic pole and center, ICRS (Hipparcos Vol. 1, Sect. 1.5.3)
+        let pole = Equatorial::new(192.85948 / 15.0, 27.12825);
+        assert_close(pole.to_galactic().unwrap().b, 90.0, 1e-4);
+        let center = Galactic::new(0.0, 0.0).to_equatorial().unwrap();
+        assert_close(center.ra * 15.0, 266.40500, 1e-4);
+        assert_close(center.dec, -28.93617, 1e-4);
+        let back = center.to_galactic().unwrap();
+        assert_close(back.l.rem_euclid(360.0).min(360.0 - back.l.rem_euclid(360.0)), 0.0, 1e-7);
+        assert_close(back.b, 0.0, 1e-7);
+    }
+
+    #[test]
+    fn supergalactic_plane() {
+        // The supergalactic origin is at l = 137.37, b = 0, and its pole at l = 47.37, b = 6.32
+        let origin = Supergalactic::new(0.0, 0.0).to_galactic();
+        assert_close(origin.l, 137.37, 1e-9);
+        assert_close(origin.b, 0.0, 1e-9);
+        assert_close(Galactic::new(47.37, 6.32).to_supergalactic().unwrap().sgb, 90.0, 1e-9);
+        let vega = Equatorial::new(18.6156, 38.7836);
+        let back = vega.to_supergalactic().unwrap().to_equatorial().unwrap();
+        assert_close(back.ra, vega.ra, 1e-9);
+        assert_close(back.dec, vega.dec, 1e-9);
+    }
+
+    #[test]
+    fn ecliptic_of_j2000() {
+        let pole = Ecliptic::new(0.0, 90.0).j2000_to_equatorial().unwrap();
+        assert_close(pole.ra, 18.0, 1e-6);
+        assert_close(pole.dec, 90.0 - 23.4392911, 1e-5);
+        let solstice = Ecliptic::new(90.0, 0.0).j2000_to_equatorial().unwrap();
+        assert_close(solstice.ra, 6.0, 1e-6);
+        assert_close(solstice.dec, 23.4392911, 1e-5);
+        let back = solstice.to_ecliptic_j2000().unwrap();
+        assert_close(back.lon, 90.0, 1e-9);
+        assert_close(back.lat, 0.0, 1e-9);
+    }
+
+    #[test]
+    fn frame_round_trips() {
+        let vega = Equatorial::new(18.6156, 38.7836);
+        let back = vega.icrs_to_j2000().unwrap().j2000_to_icrs().unwrap();
+        assert_close(back.ra, vega.ra, 1e-12);
+        assert_close(back.dec, vega.dec, 1e-10);
+        // The frame bias is a few tens of mas
+        assert_close(vega.icrs_to_j2000().unwrap().dec, vega.dec, 0.03 / 3600.0);
+        let precessed = vega.precess(Epoch::J2000, Epoch::Julian(2050.0)).unwrap();
+        let back = precessed.precess(Epoch::Julian(2050.0), Epoch::J2000).unwrap();
+        assert_close(back.ra, vega.ra, 1e-9);
+        assert_close(back.dec, vega.dec, 1e-8);
+        // About 50"/yr along the ecliptic
+        assert!((precessed.ra - vega.ra) * 15.0 * 3600.0 > 50.0);
+        let v = vega.to_vector();
+        assert_close(v.iter().map(|x| x * x).sum::<f64>(), 1.0, 1e-15);
+        let back = Equatorial::from_vector(&v);
+        assert_close(back.ra, vega.ra, 1e-12);
+        assert_close(back.dec, vega.dec, 1e-12);
+    }
+
+    #[test]
+    fn proper_motions_rotate_back() {
+        let vega = Equatorial::new(18.6156, 38.7836);
+        let pm = ProperMotion::new(200.94, 286.23);
+        let galactic = vega.galactic_proper_motion(pm).unwrap();
+        assert_close(galactic.lon.hypot(galactic.lat), pm.lon.hypot(pm.lat), 1e-9);
+        let back = vega.to_galactic().unwrap().equatorial_proper_motion(galactic).unwrap();
+        assert_close(back.lon, pm.lon, 1e-6);
+        assert_close(back.lat, pm.lat, 1e-6);
+    }
+}
@@ -0,0 +1,144 @@
+use super::coords::{dot, Equatorial};
+use super::error::{check, Result};
+use super::frame::{ObservingFrame, ReferenceSystem, SkyPos};
+use super::source::{Planet, Source};
+use super::state::StateUnits;
+use supernovas_sys as sn;
+
+/// Corrections applied to the geometric position of a source to obtain its apparent position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Corrections {
+    /// Gravitational deflection of light by the Sun
+    pub sun_deflection: bool,
+    /// Gravitational deflection of light by the other deflecting bodies of the frame
+    pub planet_deflection: bool,
+    /// Aberration due to the motion of the geocenter
+    pub annual_aberration: bool,
+    /// Aberration due to the motion of the observer relative to the geocenter
+    pub diurnal_aberration: bool,
+}
+
+impl Corrections {
+    /// All corrections, as in [`ObservingFrame::sky_pos`].
+    pub const ALL: Corrections = Corrections {
+        sun_deflection: true,
+        planet_deflection: true,
+        annual_aberration: true,
+        diurnal_aberration: true,
+    };
+
+    /// No corrections, i.e. the geometric position.
+    pub const NONE: Corrections = Corrections {
+        sun_deflection: false,
+        planet_deflection: false,
+        annual_aberration: false,
+        diurnal_aberration: false,
+    };
+}
+
+impl Default for Corrections {
+    fn default() -> Self {
+        Corrections::ALL
+    }
+}
+
+impl ObservingFrame {
+    /// Apparent position of a source in the given reference system, with only the selected
+    /// corrections applied to its geometric position. With [`Corrections::ALL`] this agrees with
+    /// [`ObservingFrame::sky_pos`] to the accuracy of the frame.
+    pub fn sky_pos_with(&self, source: &Source, system: ReferenceSystem, corrections: &Corrections) -> Result<SkyPos> {
+        let raw = self.as_raw();
+        let mut pos = self.geometric_state(source, ReferenceSystem::Icrs, StateUnits::Au)?.position;
+
+        let sun = 1 << Planet::Sun.to_raw();
+        let mut planets = raw.planets;
+        if !corrections.sun_deflection {
+            planets.mask &= !sun;
+        }
+        if !corrections.planet_deflection {
+            planets.mask &= sun;
+        }
+        if planets.mask != 0 {
+            let input = pos;
+            check("grav_planets", || unsafe {
+                sn::grav_planets(input.as_ptr(), raw.obs_pos.as_ptr(), &planets, pos.as_mut_ptr())
+            })?;
+        }
+
+        let velocity: [f64; 3] = std::array::from_fn(|i| {
+            let annual = if corrections.annual_aberration { raw.earth_vel[i] } else { 0.0 };
+            let diurnal = if corrections.diurnal_aberration { raw.obs_vel[i] - raw.earth_vel[i] } else { 0.0 };
+            annual + diurnal
+        });
+        if velocity.iter().any(|v| *v != 0.0) {
+            let input = pos;
+            let light_time = dot(&input, &input).sqrt() * sn::NOVAS_AU / sn::NOVAS_C / sn::NOVAS_DAY;
+            check("aberration", || unsafe {
+                sn::aberration(input.as_ptr(), velocity.as_ptr(), light_time, pos.as_mut_ptr())
+            })?;
+        }
+
+        if system != ReferenceSystem::Icrs {
+            pos = self.transform(ReferenceSystem::Icrs, system)?.vector(&pos)?;
+        }
+        let dist = dot(&pos, &pos).sqrt();
+        let coords = Equatorial::from_vector(&pos);
+        Ok(SkyPos {
+            ra: coords.ra,
+            dec: coords.dec,
+            dist,
+            rv: self.sky_pos(source, system)?.rv,
+            r_hat: pos.map(|x| x / dist),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::frame::Accuracy;
+    use crate::novas::observer::Observer;
+    use crate::novas::time::{Time, Timescale};
+
+    fn frame() -> ObservingFrame {
+        let observer = Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap();
+        let time = Time::new(Timescale::Utc, 2460850.5, 37, 0.035).unwrap();
+        ObservingFrame::new(Accuracy::Reduced, &observer, &time, 0.0, 0.0).unwrap()
+    }
+
+    // [arcsec]
+    fn separation(a: &SkyPos, b: &SkyPos) -> f64 {
+        a.separation(b) * 3600.0
+    }
+
+    #[test]
+    fn all_corrections_are_the_apparent_position() {
+        let frame = frame();
+        let vega = Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap();
+        assert_eq!(Corrections::default(), Corrections::ALL);
+        for system in [ReferenceSystem::Icrs, ReferenceSystem::Tod] {
+            let with = frame.sky_pos_with(&vega, system, &Corrections::ALL).unwrap();
+            let apparent = frame.sky_pos(&vega, system).unwrap();
+            assert!(separation(&with, &apparent) < 1e-3, "{}", separation(&with, &apparent));
+            assert_eq!(with.rv, apparent.rv);
+        }
+    }
+
+    #[test]
+    fn corrections_one_by_one() {
+        let frame = frame();
+        let vega = Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap();
+        let pos = |corrections| frame.sky_pos_with(&vega, ReferenceSystem::Icrs, &corrections).unwrap();
+        let all = pos(Corrections::ALL);
+        let geometric = pos(Corrections::NONE);
+        // Annual aberration is at most 20.5 arcsec, diurnal aberration 0.32 arcsec
+        assert!(separation(&all, &geometric) < 20.6);
+        let annual = pos(Corrections { annual_aberration: true, ..Corrections::NONE });
+        let diurnal = Corrections { diurnal_aberration: false, ..Corrections::ALL };
+        assert!(separation(&all, &pos(diurnal)) < 0.33);
+        assert!(separation(&all, &annual) < 0.34);
+        // The Sun deflects light by milliarcseconds far from it
+        let sun = Corrections { sun_deflection: false, ..Corrections::ALL };
+        assert!(separation(&all, &pos(sun)) < 0.05);
+    }
+}
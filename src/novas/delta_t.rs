@@ -0,0 +1,105 @@
+//! ΔT = TT - UT1, for dates where UT1 - UTC is not measured: before 1972, when UTC had no leap
+//! seconds, and past the IERS tables.
+
+use super::coords::Epoch;
+use super::eop::{self, MJD0};
+use super::time::TT_MINUS_TAI;
+
+/// [yr] Year at which the Espenak & Meeus polynomials join the long-term parabola.
+const LONG_TERM_YEAR: f64 = 2150.0;
+
+/// [s] ΔT = TT - UT1 at `epoch`.
+///
+/// Between 1972 and the expiry of the leap-second table in use (see [`eop::set_leap_seconds`]),
+/// ΔT is 32.184 s + TAI - UTC, within the 0.9 s bound of UT1 - UTC; [`EarthOrientation::delta_t`]
+/// adds the measured UT1 - UTC. Other dates get [`delta_t_espenak_meeus`]; past the table, the
+/// model is offset to continue from the last leap seconds, the offset fading out by 2150.
+///
+/// [`EarthOrientation::delta_t`]: eop::EarthOrientation::delta_t
+pub fn delta_t(epoch: Epoch) -> f64 {
+    let year = epoch.julian_year();
+    let table = eop::leap_seconds();
+    let mjd = epoch.jd() - MJD0;
+    let Some(leap) = table.at(mjd) else {
+        return delta_t_espenak_meeus(year);
+    };
+    let measured = TT_MINUS_TAI + leap as f64;
+    let Some(expires) = table.expires_mjd().filter(|_| table.is_stale(mjd)) else {
+        return measured;
+    };
+    let expires_year = Epoch::from_jd(expires + MJD0).julian_year();
+    if year >= LONG_TERM_YEAR || expires_year >= LONG_TERM_YEAR {
+        return delta_t_espenak_meeus(year);
+    }
+    let offset = measured - delta_t_espenak_meeus(expires_year);
+    delta_t_espenak_meeus(year) + offset * (LONG_TERM_YEAR - year) / (LONG_TERM_YEAR - expires_year)
+}
+
+/// [s] ΔT = TT - UT1 in a Julian `year`, from the polynomials of Espenak & Meeus (2006), Five
+/// Millennium Canon of Solar Eclipses, fitted to the historical record and, after 2005, to an
+/// extrapolation that overestimates the actual values by a few seconds. The uncertainty grows to
+/// hours in the distant past and future.
+pub fn delta_t_espenak_meeus(year: f64) -> f64 {
+    let parabola = |y: f64| {
+        let u = (y - 1820.0) / 100.0;
+        -20.0 + 32.0 * u * u
+    };
+    match year {
+        y if y < -500.0 => parabola(y),
+        y if y < 500.0 => poly(
+            y / 100.0,
+            &[10583.6, -1014.41, 33.78311, -5.952053, -0.1798452, 0.022174192, 0.0090316521],
+        ),
+        y if y < 1600.0 => poly(
+            (y - 1000.0) / 100.0,
+            &[1574.2, -556.01, 71.23472, 0.319781, -0.8503463, -0.005050998, 0.0083572073],
+        ),
+        y if y < 1700.0 => poly(y - 1600.0, &[120.0, -0.9808, -0.01532, 1.0 / 7129.0]),
+        y if y < 1800.0 => poly(y - 1700.0, &[8.83, 0.1603, -0.0059285, 0.00013336, -1.0 / 1174000.0]),
+        y if y < 1860.0 => poly(
+            y - 1800.0,
+            &[13.72, -0.332447, 0.0068612, 0.0041116, -0.00037436, 0.0000121272, -0.0000001699, 0.000000000875],
+        ),
+        y if y < 1900.0 => poly(y - 1860.0, &[7.62, 0.5737, -0.251754, 0.01680668, -0.0004473624, 1.0 / 233174.0]),
+        y if y < 1920.0 => poly(y - 1900.0, &[-2.79, 1.494119, -0.0598939, 0.0061966, -0.000197]),
+        y if y < 1941.0 => poly(y - 1920.0, &[21.20, 0.84493, -0.076100, 0.0020936]),
+        y if y < 1961.0 => poly(y - 1950.0, &[29.07, 0.407, -1.0 / 233.0, 1.0 / 2547.0]),
+        y if y < 1986.0 => poly(y - 1975.0, &[45.45, 1.067, -1.0 / 260.0, -1.0 / 718.0]),
+        y if y < 2005.0 => poly(y - 2000.0, &[63.86, 0.3345, -0.060374, 0.0017275, 0.000651814, 0.00002373599]),
+        y if y < 2050.0 => poly(y - 2000.0, &[62.92, 0.32217, 0.005589]),
+        y if y < LONG_TERM_YEAR => parabola(y) - 0.5628 * (LONG_TERM_YEAR - y),
+        y => parabola(y),
+    }
+}
+
+// Polynomial with coefficients in increasing powers of `t`.
+fn poly(t: f64, coefficients: &[f64]) -> f64 {
+    coefficients.iter().rev().fold(0.0, |sum, c| sum * t + c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn espenak_meeus_is_continuous() {
+        for year in [-500.0, 500.0, 1600.0, 1700.0, 1800.0, 1860.0, 1900.0, 1920.0, 1941.0, 1961.0, 1986.0, 2005.0, 2050.0, LONG_TERM_YEAR] {
+            let step = delta_t_espenak_meeus(year) - delta_t_espenak_meeus(year - 1e-9);
+            assert!(step.abs() < 5.0, "{year}: {step}");
+        }
+        assert!((delta_t_espenak_meeus(2000.0) - 63.86).abs() < 1e-9);
+        // 25400 s in 1000 BCE, from the table of the Canon
+        assert!((delta_t_espenak_meeus(-999.0) - 25400.0).abs() < 100.0);
+    }
+
+    #[test]
+    fn leap_seconds_over_their_table() {
+        assert_eq!(delta_t(Epoch::J2000), TT_MINUS_TAI + 32.0);
+        assert_eq!(delta_t(Epoch::Julian(2020.0)), TT_MINUS_TAI + 37.0);
+        assert_eq!(delta_t(Epoch::Julian(1900.0)), delta_t_espenak_meeus(1900.0));
+        // Past the table, the model continues from the last leap seconds
+        let later = delta_t(Epoch::Julian(2030.0));
+        assert!(later > TT_MINUS_TAI + 37.0 - 1.0 && later < delta_t_espenak_meeus(2030.0));
+        assert_eq!(delta_t(Epoch::Julian(2200.0)), delta_t_espenak_meeus(2200.0));
+    }
+}
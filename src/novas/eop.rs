@@ -0,0 +1,393 @@
+//! IERS leap-second and Earth orientation tables, for times and frames that follow the actual
+//! rotation of the Earth.
+
+use super::error::{Error, Result};
+use super::frame::{Accuracy, ObservingFrame};
+use super::observer::Observer;
+use super::coords::Epoch;
+use super::delta_t::delta_t;
+use super::time::{Time, Timescale, TT_MINUS_TAI};
+use std::sync::{Arc, RwLock};
+use supernovas_sys as sn;
+
+#[cfg(feature = "net")]
+mod cache;
+#[cfg(feature = "net")]
+pub use cache::{FINALS_URL, IersCache, LEAP_SECONDS_URL, Refresh};
+
+/// [day] Julian date of MJD 0.
+pub const MJD0: f64 = 2400000.5;
+
+/// Compiled-in TAI - UTC history: (UTC MJD, TAI - UTC) from that date on.
+const BUILTIN_LEAP_SECONDS: &[(f64, i32)] = &[
+    (41317.0, 10),
+    (41499.0, 11),
+    (41683.0, 12),
+    (42048.0, 13),
+    (42413.0, 14),
+    (42778.0, 15),
+    (43144.0, 16),
+    (43509.0, 17),
+    (43874.0, 18),
+    (44239.0, 19),
+    (44786.0, 20),
+    (45151.0, 21),
+    (45516.0, 22),
+    (46247.0, 23),
+    (47161.0, 24),
+    (47892.0, 25),
+    (48257.0, 26),
+    (48804.0, 27),
+    (49169.0, 28),
+    (49534.0, 29),
+    (50083.0, 30),
+    (50630.0, 31),
+    (51179.0, 32),
+    (53736.0, 33),
+    (54832.0, 34),
+    (56109.0, 35),
+    (57204.0, 36),
+    (57754.0, 37),
+];
+
+/// Expiry date of the compiled-in leap seconds, that of the IERS file they were taken from.
+const BUILTIN_EXPIRES: &str = "28 June 2026";
+
+/// TAI - UTC history, from the IERS `Leap_Second.dat` file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeapSeconds {
+    /// (MJD, TAI - UTC) from that date on
+    table: Vec<(f64, i32)>,
+    expires: Option<String>,
+    /// [day] UTC MJD of the expiry date
+    expires_mjd: Option<f64>,
+}
+
+impl LeapSeconds {
+    /// Parses the contents of a `Leap_Second.dat` file.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut table = Vec::new();
+        let mut expires = None;
+        for (n, line) in text.lines().enumerate() {
+            if let Some(comment) = line.strip_prefix('#') {
+                if let Some((_, date)) = comment.split_once("expires on") {
+                    expires = Some(date.trim().to_string());
+                }
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() {
+                continue;
+            }
+            let entry = match fields[..] {
+                [mjd, _, _, _, leap] => mjd.parse().ok().zip(leap.parse().ok()),
+                _ => None,
+            };
+            table.push(entry.ok_or(Error::Parse { what: "unexpected leap-second entry", line: n + 1 })?);
+        }
+        if table.is_empty() {
+            return Err(Error::InvalidArgument("no leap seconds in file"));
+        }
+        let expires_mjd = expires.as_deref().and_then(date_mjd);
+        Ok(LeapSeconds { table, expires, expires_mjd })
+    }
+
+    /// The compiled-in leap seconds, from 1972 to the last one, on 2017-01-01, known not to
+    /// change until their expiry date. Newer leap seconds need a current file, see
+    /// [`LeapSeconds::parse`] and [`set_leap_seconds`].
+    pub fn builtin() -> Self {
+        LeapSeconds {
+            table: BUILTIN_LEAP_SECONDS.to_vec(),
+            expires: Some(BUILTIN_EXPIRES.to_string()),
+            expires_mjd: date_mjd(BUILTIN_EXPIRES),
+        }
+    }
+
+    /// TAI - UTC [s] at a UTC MJD, or `None` before the first entry.
+    pub fn at(&self, mjd: f64) -> Option<i32> {
+        self.table.iter().rev().find(|(start, _)| *start <= mjd).map(|(_, leap)| *leap)
+    }
+
+    /// The expiry date of the file, as written in its header.
+    pub fn expires(&self) -> Option<&str> {
+        self.expires.as_deref()
+    }
+
+    /// Whether a UTC MJD is past the expiry date of the table, so that a leap second may have
+    /// been introduced since.
+    pub fn is_stale(&self, mjd: f64) -> bool {
+        self.expires_mjd.is_some_and(|expires| mjd > expires)
+    }
+
+    // [day] UTC MJD of the expiry date.
+    pub(crate) fn expires_mjd(&self) -> Option<f64> {
+        self.expires_mjd
+    }
+}
+
+static LEAP_SECONDS: RwLock<Option<Arc<LeapSeconds>>> = RwLock::new(None);
+
+/// Replaces the leap-second table of [`Time::from_utc`] and of the conversions from calendar
+/// types, by default [`LeapSeconds::builtin`], e.g. with a current IERS file.
+pub fn set_leap_seconds(table: LeapSeconds) {
+    *LEAP_SECONDS.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(table));
+}
+
+/// The leap-second table in use, see [`set_leap_seconds`].
+pub fn leap_seconds() -> Arc<LeapSeconds> {
+    let table = LEAP_SECONDS.read().unwrap_or_else(|err| err.into_inner()).clone();
+    table.unwrap_or_else(|| Arc::new(LeapSeconds::builtin()))
+}
+
+// [day] UTC MJD of a date such as "28 June 2026", as in the header of `Leap_Second.dat`.
+fn date_mjd(date: &str) -> Option<f64> {
+    const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+    let [day, month, year] = date.split_whitespace().collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let month = month.get(..3)?.to_ascii_lowercase();
+    let month = MONTHS.iter().position(|m| *m == month)? as i16 + 1;
+    Some(unsafe { sn::julian_date(year.parse().ok()?, month, day.parse().ok()?, 0.0) } - MJD0)
+}
+
+/// Earth orientation on one day, from the IERS finals2000A (Bulletin A) file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Eop {
+    /// [day] UTC MJD
+    pub mjd: f64,
+    /// [arcsec] polar motion
+    pub xp: f64,
+    /// [arcsec] polar motion
+    pub yp: f64,
+    /// [s] UT1 - UTC
+    pub dut1: f64,
+    /// [mas] celestial pole offsets
+    pub dx: Option<f64>,
+    /// [mas] celestial pole offsets
+    pub dy: Option<f64>,
+    /// Whether the values are predictions rather than IERS determinations
+    pub predicted: bool,
+}
+
+/// Daily Earth orientation values, from the IERS finals2000A file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EopTable {
+    rows: Vec<Eop>,
+}
+
+impl EopTable {
+    /// Parses the fixed-column finals2000A format, skipping the days past the predictions.
+    pub fn parse(text: &str) -> Result<Self> {
+        let column = |line: &str, start: usize, end: usize| -> Option<f64> { line.get(start..end.min(line.len()))?.trim().parse().ok() };
+        let mut rows = Vec::new();
+        for line in text.lines() {
+            let (Some(mjd), Some(dut1)) = (column(line, 7, 15), column(line, 58, 68)) else {
+                continue;
+            };
+            let (Some(xp), Some(yp)) = (column(line, 18, 27), column(line, 37, 46)) else {
+                continue;
+            };
+            rows.push(Eop {
+                mjd,
+                xp,
+                yp,
+                dut1,
+                dx: column(line, 97, 106),
+                dy: column(line, 116, 125),
+                predicted: line.get(57..58) == Some("P"),
+            });
+        }
+        if rows.is_empty() {
+            return Err(Error::InvalidArgument("no Earth orientation values in file"));
+        }
+        Ok(EopTable { rows })
+    }
+
+    /// The daily values.
+    pub fn rows(&self) -> &[Eop] {
+        &self.rows
+    }
+
+    /// Values at a UTC MJD, interpolated linearly between the days and across leap seconds in
+    /// UT1 - UTC, or `None` outside of the table.
+    pub fn at(&self, mjd: f64) -> Option<Eop> {
+        let rows = &self.rows;
+        let i = rows.partition_point(|row| row.mjd <= mjd).checked_sub(1)?;
+        let (a, b) = (rows[i], *rows.get(i + 1)?);
+        let f = (mjd - a.mjd) / (b.mjd - a.mjd);
+        let lerp = |x: f64, y: f64| x + f * (y - x);
+        let step = b.dut1 - a.dut1;
+        let b_dut1 = if step.abs() > 0.5 { b.dut1 - step.round() } else { b.dut1 };
+        Some(Eop {
+            mjd,
+            xp: lerp(a.xp, b.xp),
+            yp: lerp(a.yp, b.yp),
+            dut1: lerp(a.dut1, b_dut1),
+            dx: a.dx.zip(b.dx).map(|(x, y)| lerp(x, y)),
+            dy: a.dy.zip(b.dy).map(|(x, y)| lerp(x, y)),
+            predicted: a.predicted || b.predicted,
+        })
+    }
+}
+
+/// Leap seconds and Earth orientation together, applied to times and frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarthOrientation {
+    leap_seconds: LeapSeconds,
+    eop: EopTable,
+}
+
+impl EarthOrientation {
+    pub fn new(leap_seconds: LeapSeconds, eop: EopTable) -> Self {
+        EarthOrientation { leap_seconds, eop }
+    }
+
+    pub fn leap_seconds(&self) -> &LeapSeconds {
+        &self.leap_seconds
+    }
+
+    pub fn eop(&self) -> &EopTable {
+        &self.eop
+    }
+
+    /// UTC MJD of an instant, with the leap seconds in effect at that instant.
+    pub fn utc_mjd(&self, time: &Time) -> Result<f64> {
+        let mjd_tai = time.jd(Timescale::Tai) - MJD0;
+        // TAI runs ahead of UTC, so the leap seconds at the TAI date are an upper bound
+        let mut leap = self.leap_seconds.at(mjd_tai).ok_or(Error::InvalidArgument("date precedes the leap-second table"))?;
+        for _ in 0..2 {
+            leap = self.leap_seconds.at(mjd_tai - leap as f64 / 86400.0).unwrap_or(leap);
+        }
+        Ok(mjd_tai - leap as f64 / 86400.0)
+    }
+
+    /// The same instant as `time`, with the leap seconds and UT1 - UTC of its date. Dates
+    /// outside the tables get UT1 from the ΔT model, see [`Time::from_delta_t`].
+    pub fn apply(&self, time: &Time) -> Result<Time> {
+        match self.measured(time) {
+            Some((leap, eop)) => time.with_earth_orientation(leap, eop.dut1),
+            None => Time::from_delta_t(Timescale::Tt, time.jd(Timescale::Tt)),
+        }
+    }
+
+    /// [s] ΔT = TT - UT1 at `time`, from the tables over their dates and else from
+    /// [`delta_t`](super::delta_t).
+    pub fn delta_t(&self, time: &Time) -> f64 {
+        match self.measured(time) {
+            Some((leap, eop)) => TT_MINUS_TAI + leap as f64 - eop.dut1,
+            None => delta_t(Epoch::from_jd(time.jd(Timescale::Tt))),
+        }
+    }
+
+    /// Earth orientation at a UTC MJD.
+    pub fn at(&self, mjd: f64) -> Result<Eop> {
+        self.eop.at(mjd).ok_or(Error::InvalidArgument("date is not covered by the Earth orientation table"))
+    }
+
+    /// An observing frame at `time`, with the leap seconds, UT1 - UTC and polar motion of its
+    /// date. Dates outside the tables get UT1 from the ΔT model and no polar motion.
+    pub fn frame(&self, accuracy: Accuracy, observer: &Observer, time: &Time) -> Result<ObservingFrame> {
+        let (xp, yp) = self.measured(time).map_or((0.0, 0.0), |(_, eop)| (eop.xp, eop.yp));
+        ObservingFrame::new(accuracy, observer, &self.apply(time)?, xp * 1e3, yp * 1e3)
+    }
+
+    // Leap seconds and Earth orientation at the date of `time`, if covered by the tables.
+    fn measured(&self, time: &Time) -> Option<(i32, Eop)> {
+        let mjd = self.utc_mjd(time).ok()?;
+        Some((self.leap_seconds.at(mjd)?, self.eop.at(mjd)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEAP_SECONDS: &str = "\
+#  File expires on 28 June 2026
+#
+#    MJD        Date        TAI-UTC (s)
+#           day month year
+#    ---    --------------   ------
+#
+    41317.0    1  1 1972       10
+    57204.0    1  7 2015       36
+    57754.0    1  1 2017       37
+";
+
+    // A finals2000A line, with its values at their columns
+    fn finals(mjd: f64, xp: f64, yp: f64, flag: char, dut1: f64, dx: f64) -> String {
+        let mut line = vec![b' '; 125];
+        let mut put = |start: usize, text: String| line[start..start + text.len()].copy_from_slice(text.as_bytes());
+        put(7, format!("{mjd:8.2}"));
+        put(18, format!("{xp:9.6}"));
+        put(37, format!("{yp:9.6}"));
+        put(57, flag.to_string());
+        put(58, format!("{dut1:10.7}"));
+        put(97, format!("{dx:9.3}"));
+        put(116, format!("{dx:9.3}"));
+        String::from_utf8(line).unwrap()
+    }
+
+    #[test]
+    fn leap_seconds_from_file() {
+        let table = LeapSeconds::parse(LEAP_SECONDS).unwrap();
+        assert_eq!(table.at(41316.0), None);
+        assert_eq!(table.at(57203.5), Some(10));
+        assert_eq!(table.at(57204.0), Some(36));
+        assert_eq!(table.at(60850.0), Some(37));
+        assert_eq!(table.expires(), Some("28 June 2026"));
+        assert_eq!(table.expires_mjd(), Some(61219.0));
+        assert!(!table.is_stale(61219.0) && table.is_stale(61220.0));
+        assert_eq!(LeapSeconds::builtin().expires_mjd(), table.expires_mjd());
+        assert_eq!(LeapSeconds::builtin().at(60850.0), Some(37));
+    }
+
+    #[test]
+    fn malformed_leap_seconds() {
+        assert!(matches!(LeapSeconds::parse("# header\n41317.0 1 1 1972 ten\n"), Err(Error::Parse { line: 2, .. })));
+        assert!(LeapSeconds::parse("# expires on 28 June 2026\n").is_err());
+        assert_eq!(date_mjd("1 Janvier 2000"), Some(51544.0));
+        assert_eq!(date_mjd("June 2026"), None);
+    }
+
+    #[test]
+    fn eop_is_interpolated_across_leap_seconds() {
+        let text = [
+            finals(57753.0, 0.1, 0.3, 'I', -0.4, 0.1),
+            finals(57754.0, 0.2, 0.4, 'I', 0.58, 0.3),
+            finals(57755.0, 0.3, 0.5, 'P', 0.5, 0.5),
+            "     short line".to_string(),
+        ]
+        .join("\n");
+        let table = EopTable::parse(&text).unwrap();
+        assert_eq!(table.rows().len(), 3);
+        assert!(!table.rows()[0].predicted && table.rows()[2].predicted);
+        let eop = table.at(57753.5).unwrap();
+        assert!((eop.xp - 0.15).abs() < 1e-12 && (eop.yp - 0.35).abs() < 1e-12);
+        // UT1 - UTC steps by a second at the leap second
+        assert!((eop.dut1 + 0.41).abs() < 1e-12, "{eop:?}");
+        assert!((eop.dx.unwrap() - 0.2).abs() < 1e-12);
+        assert!(!eop.predicted && table.at(57754.5).unwrap().predicted);
+        assert_eq!(table.at(57752.0), None);
+        assert_eq!(table.at(57755.0), None);
+        assert!(EopTable::parse("no values\n").is_err());
+    }
+
+    #[test]
+    fn earth_orientation_of_times() {
+        let eop = EopTable::parse(&[finals(60850.0, 0.1, 0.3, 'I', 0.02, 0.0), finals(60851.0, 0.1, 0.3, 'I', 0.04, 0.0)].join("\n")).unwrap();
+        let orientation = EarthOrientation::new(LeapSeconds::builtin(), eop);
+        let time = Time::new(Timescale::Utc, 2460850.75, 0, 0.0).unwrap();
+        assert!((orientation.utc_mjd(&time).unwrap() - 60850.25 + 37.0 / 86400.0).abs() < 1e-9);
+        let utc = Time::new(Timescale::Utc, 2460850.75, 37, 0.0).unwrap();
+        let applied = orientation.apply(&utc).unwrap();
+        assert_eq!(applied.leap_seconds(), 37);
+        assert!((applied.dut1() - 0.025).abs() < 1e-9);
+        assert!((orientation.delta_t(&utc) - (TT_MINUS_TAI + 37.0 - 0.025)).abs() < 1e-9);
+        // Outside the table, from the ΔT model
+        let old = Time::new(Timescale::Tt, 2451545.0, 32, 0.0).unwrap();
+        assert_eq!(orientation.delta_t(&old), delta_t(Epoch::J2000));
+        assert!(orientation.at(60849.0).is_err());
+        assert!(orientation.utc_mjd(&Time::new(Timescale::Tt, 2400000.5, 0, 0.0).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Local cache of the IERS files, refreshed over HTTP(S) (feature `net`).
+
+use super::{EarthOrientation, EopTable, LeapSeconds};
+use crate::novas::error::{Error, Result};
+use reqwest::blocking::Client;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// IERS leap-second file.
+pub const LEAP_SECONDS_URL: &str = "https://hpiers.obspm.fr/iers/bul/bulc/Leap_Second.dat";
+
+/// IERS Rapid Service finals2000A file, with Bulletin A predictions.
+pub const FINALS_URL: &str = "https://maia.usno.navy.mil/ser7/finals2000A.all";
+
+const LEAP_SECONDS_FILE: &str = "Leap_Second.dat";
+const FINALS_FILE: &str = "finals2000A.all";
+
+/// Age after which the cached files are checked for updates.
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(7 * 86400);
+
+/// State of a cached file after [`IersCache::refresh`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refresh {
+    /// Younger than the maximum age, not checked
+    Fresh,
+    /// Checked, and not modified on the server
+    Unchanged,
+    /// Downloaded
+    Updated,
+}
+
+/// The IERS leap-second and finals2000A files, kept in a cache directory and refreshed over
+/// HTTP(S) with conditional requests: the server is asked for the files only if they changed
+/// since the cached copies (`If-None-Match` and `If-Modified-Since`), so that services can
+/// refresh them often.
+#[derive(Debug, Clone)]
+pub struct IersCache {
+    dir: PathBuf,
+    leap_seconds_url: String,
+    finals_url: String,
+    max_age: Duration,
+    client: Client,
+}
+
+impl IersCache {
+    /// A cache in `dir`, of the files at [`LEAP_SECONDS_URL`] and [`FINALS_URL`], checked for
+    /// updates once a week.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        IersCache {
+            dir: dir.into(),
+            leap_seconds_url: LEAP_SECONDS_URL.to_string(),
+            finals_url: FINALS_URL.to_string(),
+            max_age: DEFAULT_MAX_AGE,
+            client: Client::new(),
+        }
+    }
+
+    /// Sets the URLs of the leap-second and finals2000A files, e.g. those of a mirror.
+    pub fn with_urls(mut self, leap_seconds: &str, finals: &str) -> Self {
+        self.leap_seconds_url = leap_seconds.to_string();
+        self.finals_url = finals.to_string();
+        self
+    }
+
+    /// Sets the age after which the cached files are checked for updates.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Checks the files older than the maximum age for updates, and downloads the missing and
+    /// modified ones. Downloads are checked to parse before they replace the cached copies.
+    /// Returns the states of the leap-second and finals2000A files.
+    pub fn refresh(&self) -> Result<(Refresh, Refresh)> {
+        fs::create_dir_all(&self.dir).map_err(|err| io_error(&self.dir, err))?;
+        let leap_seconds = self.refresh_file(LEAP_SECONDS_FILE, &self.leap_seconds_url, |text| LeapSeconds::parse(text).map(drop))?;
+        let finals = self.refresh_file(FINALS_FILE, &self.finals_url, |text| EopTable::parse(text).map(drop))?;
+        Ok((leap_seconds, finals))
+    }
+
+    /// The cached files, without refreshing them.
+    pub fn load(&self) -> Result<EarthOrientation> {
+        let read = |file: &str| {
+            let path = self.dir.join(file);
+            fs::read_to_string(&path).map_err(|err| io_error(&path, err))
+        };
+        let leap_seconds = LeapSeconds::parse(&read(LEAP_SECONDS_FILE)?)?;
+        let eop = EopTable::parse(&read(FINALS_FILE)?)?;
+        Ok(EarthOrientation::new(leap_seconds, eop))
+    }
+
+    fn refresh_file(&self, file: &str, url: &str, validate: impl Fn(&str) -> Result<()>) -> Result<Refresh> {
+        let path = self.dir.join(file);
+        // The ETag and Last-Modified headers of the cached copy
+        let headers = path.with_extension("headers");
+        let age = fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .map(|modified| SystemTime::now().duration_since(modified).unwrap_or_default());
+        if age.is_some_and(|age| age < self.max_age) {
+            return Ok(Refresh::Fresh);
+        }
+
+        let http_error = |err: reqwest::Error| Error::Http { url: url.to_string(), reason: err.to_string() };
+        let mut request = self.client.get(url);
+        if age.is_some() {
+            let cached = fs::read_to_string(&headers).unwrap_or_default();
+            let mut lines = cached.lines();
+            if let Some(etag) = lines.next().filter(|etag| !etag.is_empty()) {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(modified) = lines.next().filter(|modified| !modified.is_empty()) {
+                request = request.header(IF_MODIFIED_SINCE, modified);
+            }
+        }
+        let response = request.send().map_err(http_error)?.error_for_status().map_err(http_error)?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            // Restarts the age of the cached copy
+            File::options()
+                .append(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(SystemTime::now()))
+                .map_err(|err| io_error(&path, err))?;
+            return Ok(Refresh::Unchanged);
+        }
+
+        let header = |name| response.headers().get(name).and_then(|value| value.to_str().ok()).unwrap_or_default().to_string();
+        let validators = format!("{}\n{}\n", header(ETAG), header(LAST_MODIFIED));
+        let text = response.text().map_err(http_error)?;
+        validate(&text)?;
+        let part = path.with_extension("part");
+        fs::write(&part, text)
+            .and_then(|()| fs::rename(&part, &path))
+            .map_err(|err| io_error(&path, err))?;
+        fs::write(&headers, validators).map_err(|err| io_error(&headers, err))?;
+        Ok(Refresh::Updated)
+    }
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> Error {
+    Error::Io { path: path.display().to_string(), reason: err.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEAP_SECONDS: &str = "\
+#  File expires on 28 June 2026
+    41317.0    1  1 1972       10
+    57754.0    1  1 2017       37
+";
+
+    // A line of the finals2000A file
+    fn finals(mjd: f64, dut1: f64) -> String {
+        format!("{:7}{mjd:8.2}{:3}{:9.6}{:10}{:9.6}{:11}I{dut1:10.7}", "", "", 0.1, "", 0.3, "")
+    }
+
+    fn cache(name: &str) -> IersCache {
+        let dir = std::env::temp_dir().join(format!("astrokits-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        IersCache::new(dir).with_urls("http://localhost:9/leap", "http://localhost:9/finals")
+    }
+
+    #[test]
+    fn fresh_files_are_not_downloaded() {
+        let cache = cache("fresh");
+        assert!(matches!(cache.load(), Err(Error::Io { .. })));
+        fs::create_dir_all(cache.dir()).unwrap();
+        fs::write(cache.dir().join(LEAP_SECONDS_FILE), LEAP_SECONDS).unwrap();
+        fs::write(cache.dir().join(FINALS_FILE), [finals(60850.0, 0.02), finals(60851.0, 0.04)].join("\n")).unwrap();
+        assert_eq!(cache.refresh().unwrap(), (Refresh::Fresh, Refresh::Fresh));
+        let orientation = cache.load().unwrap();
+        assert_eq!(orientation.leap_seconds().at(60850.0), Some(37));
+        assert_eq!(orientation.eop().rows().len(), 2);
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+}
@@ -0,0 +1,240 @@
+//! Ephemeris providers for solar-system bodies.
+
+use super::error::{check, Error, Result};
+use super::source::Planet;
+#[cfg(feature = "calceph")]
+use super::time::{Time, TimeOffsets};
+use std::ffi::{CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use supernovas_sys as sn;
+
+/// Loads a SPICE kernel (e.g. `de440s.bsp`) into the CSPICE kernel pool.
+pub fn load_cspice_kernel(path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref().to_str().ok_or(Error::InvalidArgument("kernel path is not UTF-8"))?;
+    let path = CString::new(path).map_err(|_| Error::InvalidArgument("kernel path contains NUL"))?;
+    check("cspice_add_kernel", || unsafe { sn::cspice_add_kernel(path.as_ptr()) })
+}
+
+/// Uses CSPICE as the provider for planet and minor-body positions, enabling full accuracy.
+///
+/// The plugin sets CSPICE to return from failed routines, rather than abort the process, for the
+/// whole process. A failure then stays latched until the error status is reset, so one left by
+/// an earlier CSPICE call is cleared here.
+pub fn use_cspice() -> Result<()> {
+    check("novas_use_cspice", || unsafe { sn::novas_use_cspice() })?;
+    #[cfg(feature = "cspice")]
+    let _lock = crate::cspice::lock();
+    unsafe {
+        if libcspice_sys::failed_c() != 0 {
+            libcspice_sys::reset_c();
+        }
+    }
+    Ok(())
+}
+
+/// Origin of the positions returned by a [`PlanetProvider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Origin {
+    /// Solar-System Barycenter
+    Barycenter,
+    /// Center of the Sun
+    Heliocenter,
+}
+
+/// A solar-system ephemeris, e.g. an analytic theory or a table, that can be registered as the
+/// NOVAS provider of planet positions with [`set_planet_provider`].
+pub trait PlanetProvider: Send + Sync {
+    /// Position [AU] and velocity [AU/day] of `body` relative to `origin`, in equatorial
+    /// coordinates of J2000, at the TDB Julian date `jd_tdb[0] + jd_tdb[1]`. Returns `None` if the
+    /// provider has no data for the body or date.
+    ///
+    /// It is called from NOVAS, through C: a panic is caught there, and reported to NOVAS as
+    /// missing data.
+    fn state(&self, jd_tdb: [f64; 2], body: Planet, origin: Origin) -> Option<([f64; 3], [f64; 3])>;
+}
+
+/// An ephemeris of minor bodies, e.g. asteroids and comets from an SPK subset, a cache of JPL
+/// Horizons results or a database, that can be registered as the NOVAS provider of
+/// [`Source::ephemeris`](super::Source::ephemeris) positions with [`set_ephem_provider`].
+pub trait EphemProvider: Send + Sync {
+    /// Origin, position [AU] and velocity [AU/day] of the body with the given `name` or ID
+    /// number, in ICRS equatorial coordinates, at the TDB Julian date `jd_tdb[0] + jd_tdb[1]`.
+    /// Returns `None` if the provider has no data for the body or date.
+    ///
+    /// It is called from NOVAS, through C: a panic is caught there, and reported to NOVAS as
+    /// missing data.
+    fn state(&self, name: &str, id: i64, jd_tdb: [f64; 2]) -> Option<(Origin, [f64; 3], [f64; 3])>;
+}
+
+static PROVIDER: RwLock<Option<Arc<dyn PlanetProvider>>> = RwLock::new(None);
+
+/// Registers `provider` as the NOVAS provider of planet positions, in both reduced and full
+/// accuracy, replacing the previous one. The provider is kept until it is replaced.
+pub fn set_planet_provider(provider: impl PlanetProvider + 'static) -> Result<()> {
+    *PROVIDER.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(provider));
+    check("set_planet_provider", || unsafe { sn::set_planet_provider(Some(planet_provider)) })?;
+    check("set_planet_provider_hp", || unsafe { sn::set_planet_provider_hp(Some(planet_provider_hp)) })
+}
+
+static EPHEM_PROVIDER: RwLock<Option<Arc<dyn EphemProvider>>> = RwLock::new(None);
+
+/// Registers `provider` as the NOVAS provider of minor-body positions, replacing the previous one
+/// (e.g. CSPICE). The provider is kept until it is replaced.
+pub fn set_ephem_provider(provider: impl EphemProvider + 'static) -> Result<()> {
+    *EPHEM_PROVIDER.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(provider));
+    check("set_ephem_provider", || unsafe { sn::set_ephem_provider(Some(ephem_provider)) })
+}
+
+// NOVAS callbacks forwarding to the registered provider. The return codes are those of the NOVAS
+// planet provider interface. Panics must not unwind into C, they are caught and reported as
+// missing data.
+unsafe extern "C" fn planet_provider(
+    jd_tdb: f64,
+    body: sn::novas_planet,
+    origin: sn::novas_origin,
+    position: *mut f64,
+    velocity: *mut f64,
+) -> std::os::raw::c_short {
+    let jd_tdb = [jd_tdb, 0.0];
+    unsafe { planet_provider_hp(&jd_tdb[0], body, origin, position, velocity) }
+}
+
+unsafe extern "C" fn planet_provider_hp(
+    jd_tdb: *const f64,
+    body: sn::novas_planet,
+    origin: sn::novas_origin,
+    position: *mut f64,
+    velocity: *mut f64,
+) -> std::os::raw::c_short {
+    if jd_tdb.is_null() {
+        return -1;
+    }
+    let jd_tdb = unsafe { [*jd_tdb, *jd_tdb.add(1)] };
+    let Some(body) = Planet::from_raw(body) else {
+        return 2;
+    };
+    let origin = match origin {
+        sn::novas_origin_NOVAS_HELIOCENTER => Origin::Heliocenter,
+        _ => Origin::Barycenter,
+    };
+    // Cloned out of the lock, so that the provider may be replaced during the call
+    let provider = PROVIDER.read().unwrap_or_else(|err| err.into_inner()).clone();
+    let state = provider.and_then(|provider| {
+        panic::catch_unwind(AssertUnwindSafe(|| provider.state(jd_tdb, body, origin))).ok().flatten()
+    });
+    let Some((pos, vel)) = state else {
+        return 3;
+    };
+    unsafe { write_state(&pos, &vel, position, velocity) };
+    0
+}
+
+unsafe extern "C" fn ephem_provider(
+    name: *const std::os::raw::c_char,
+    id: std::os::raw::c_long,
+    jd_tdb_high: f64,
+    jd_tdb_low: f64,
+    origin: *mut sn::novas_origin,
+    position: *mut f64,
+    velocity: *mut f64,
+) -> std::os::raw::c_int {
+    let name = if name.is_null() { Default::default() } else { unsafe { CStr::from_ptr(name) }.to_string_lossy() };
+    let provider = EPHEM_PROVIDER.read().unwrap_or_else(|err| err.into_inner()).clone();
+    let state = provider.and_then(|provider| {
+        panic::catch_unwind(AssertUnwindSafe(|| provider.state(&name, id as _, [jd_tdb_high, jd_tdb_low]))).ok().flatten()
+    });
+    let Some((from, pos, vel)) = state else {
+        return 1;
+    };
+    unsafe {
+        if !origin.is_null() {
+            *origin = match from {
+                Origin::Barycenter => sn::novas_origin_NOVAS_BARYCENTER,
+                Origin::Heliocenter => sn::novas_origin_NOVAS_HELIOCENTER,
+            };
+        }
+        write_state(&pos, &vel, position, velocity);
+    }
+    0
+}
+
+// Copies a state to the optional output vectors of a NOVAS callback.
+unsafe fn write_state(pos: &[f64; 3], vel: &[f64; 3], position: *mut f64, velocity: *mut f64) {
+    unsafe {
+        if !position.is_null() {
+            std::ptr::copy_nonoverlapping(pos.as_ptr(), position, 3);
+        }
+        if !velocity.is_null() {
+            std::ptr::copy_nonoverlapping(vel.as_ptr(), velocity, 3);
+        }
+    }
+}
+
+/// An ephemeris file opened with CALCEPH, e.g. an INPOP file or a DE file with a TT - TDB
+/// record.
+#[cfg(feature = "calceph")]
+pub struct Calceph {
+    eph: *mut calceph_sys::t_calcephbin,
+}
+
+#[cfg(feature = "calceph")]
+impl Calceph {
+    /// Opens an ephemeris file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_str().ok_or(Error::InvalidArgument("ephemeris path is not UTF-8"))?;
+        let path = CString::new(path).map_err(|_| Error::InvalidArgument("ephemeris path contains NUL"))?;
+        let eph = unsafe { calceph_sys::calceph_open(path.as_ptr()) };
+        if eph.is_null() {
+            return Err(Error::Novas { call: "calceph_open", code: -1, errno: 0 });
+        }
+        Ok(Calceph { eph })
+    }
+
+    /// TT - TDB [s] at `time` from the time ephemeris of the file, or `None` if the file has no
+    /// such record or does not cover the date.
+    pub fn tt_minus_tdb(&self, time: &Time) -> Option<f64> {
+        let raw = time.as_raw();
+        let mut pv = [0.0; 6];
+        // The time ephemeris takes its argument in TT
+        let found = unsafe {
+            calceph_sys::calceph_compute(
+                self.eph,
+                raw.ijd_tt as f64,
+                raw.fjd_tt,
+                calceph_sys::NAIFID_TIME_TTMTDB as _,
+                calceph_sys::NAIFID_TIME_CENTER as _,
+                pv.as_mut_ptr(),
+            )
+        };
+        (found != 0).then_some(pv[0])
+    }
+
+    /// Offsets between the timescales at `time`, with TT - TDB from the file when it has a time
+    /// ephemeris.
+    pub fn time_offsets(&self, time: &Time) -> TimeOffsets {
+        let mut offsets = time.offsets();
+        if let Some(tt_minus_tdb) = self.tt_minus_tdb(time) {
+            offsets.tt_minus_tdb = tt_minus_tdb;
+            offsets.tdb_from_ephemeris = true;
+        }
+        offsets
+    }
+}
+
+/// Uses CALCEPH with the ephemeris file `calceph` as the provider for planet and minor-body
+/// positions, enabling full accuracy. The file stays open for the rest of the process.
+#[cfg(feature = "calceph")]
+pub fn use_calceph(calceph: Calceph) -> Result<()> {
+    check("novas_use_calceph", || unsafe { sn::novas_use_calceph(calceph.eph) })?;
+    std::mem::forget(calceph);
+    Ok(())
+}
+
+#[cfg(feature = "calceph")]
+impl Drop for Calceph {
+    fn drop(&mut self) {
+        unsafe { calceph_sys::calceph_close(self.eph) }
+    }
+}
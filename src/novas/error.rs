@@ -0,0 +1,143 @@
+use std::fmt;
+
+/// Errors reported by the safe SuperNOVAS layer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// A SuperNOVAS function returned a non-zero status code. `errno` is the error number
+    /// SuperNOVAS set to describe the failure (e.g. `EINVAL`), or 0 if unknown.
+    Novas { call: &'static str, code: i32, errno: i32 },
+    /// An argument was rejected before it reached SuperNOVAS.
+    InvalidArgument(&'static str),
+    /// An iterative calculation did not converge within the allowed number of iterations.
+    NoConvergence { call: &'static str, iterations: usize },
+    /// A data file (e.g. IERS tables) could not be parsed.
+    Parse { what: &'static str, line: usize },
+    /// A document, e.g. a target list, is malformed. `what` names it, by its path for files.
+    Format { what: String, reason: String },
+    /// A file could not be read or written.
+    Io { path: String, reason: String },
+    /// A download failed.
+    Http { url: String, reason: String },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Novas { call, code, errno: 0 } => write!(f, "{} failed with status {}", call, code),
+            Error::Novas { call, code, errno } => {
+                let detail = std::io::Error::from_raw_os_error(*errno);
+                write!(f, "{} failed with status {}: {}", call, code, detail)
+            }
+            Error::InvalidArgument(what) => write!(f, "invalid argument: {}", what),
+            Error::NoConvergence { call, iterations } => {
+                write!(f, "{} did not converge in {} iterations", call, iterations)
+            }
+            Error::Parse { what, line } => write!(f, "{} on line {}", what, line),
+            Error::Format { what, reason } => write!(f, "{}: {}", what, reason),
+            Error::Io { path, reason } => write!(f, "{}: {}", path, reason),
+            Error::Http { url, reason } => write!(f, "{}: {}", url, reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Runs `f`, a call to the SuperNOVAS function `call`, and maps its status code to a `Result`,
+/// with the `errno` set by the failed call. `errno` is cleared before the call, so that a value
+/// left by an earlier call is not reported.
+pub(crate) fn check(call: &'static str, f: impl FnOnce() -> i32) -> Result<()> {
+    errno::clear();
+    let code = f();
+    if code == 0 {
+        Ok(())
+    } else {
+        Err(Error::Novas { call, code, errno: errno::get() })
+    }
+}
+
+// The `errno` of the C runtime, which SuperNOVAS sets. On Windows, that of the CRT, which
+// `std::io::Error::last_os_error` does not read (it returns `GetLastError`). Reads 0 on the
+// platforms not listed.
+mod errno {
+    use std::os::raw::c_int;
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "emscripten",
+        target_os = "fuchsia",
+        target_os = "redox",
+        target_os = "hurd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "solaris",
+        target_os = "illumos",
+        target_vendor = "apple",
+        windows
+    ))]
+    unsafe extern "C" {
+        #[cfg_attr(
+            any(target_os = "linux", target_os = "emscripten", target_os = "fuchsia", target_os = "redox", target_os = "hurd"),
+            link_name = "__errno_location"
+        )]
+        #[cfg_attr(any(target_os = "android", target_os = "netbsd", target_os = "openbsd"), link_name = "__errno")]
+        #[cfg_attr(any(target_vendor = "apple", target_os = "freebsd", target_os = "dragonfly"), link_name = "__error")]
+        #[cfg_attr(any(target_os = "solaris", target_os = "illumos"), link_name = "___errno")]
+        #[cfg_attr(windows, link_name = "_errno")]
+        fn errno_location() -> *mut c_int;
+    }
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "emscripten",
+        target_os = "fuchsia",
+        target_os = "redox",
+        target_os = "hurd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "solaris",
+        target_os = "illumos",
+        target_vendor = "apple",
+        windows
+    )))]
+    unsafe fn errno_location() -> *mut c_int {
+        std::ptr::null_mut()
+    }
+
+    pub(super) fn get() -> i32 {
+        unsafe { errno_location().as_ref() }.map_or(0, |errno| *errno)
+    }
+
+    pub(super) fn clear() {
+        if let Some(errno) = unsafe { errno_location().as_mut() } {
+            *errno = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use supernovas_sys as sn;
+
+    #[test]
+    fn check_reports_the_errno_of_the_failed_call_only() {
+        // Leaves ENOENT in errno
+        assert!(std::fs::File::open("/nonexistent/astrokits").is_err());
+        assert_eq!(check("noop", || 1), Err(Error::Novas { call: "noop", code: 1, errno: 0 }));
+
+        let err = check("novas_set_time", || unsafe {
+            sn::novas_set_time(sn::novas_timescale_NOVAS_TT, 2451545.0, 32, 0.0, std::ptr::null_mut())
+        });
+        // EINVAL
+        assert_eq!(err, Err(Error::Novas { call: "novas_set_time", code: -1, errno: 22 }));
+        assert!(err.unwrap_err().to_string().starts_with("novas_set_time failed with status -1: "));
+    }
+}
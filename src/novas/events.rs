@@ -0,0 +1,350 @@
+pub mod aspects;
+#[cfg(supernovas_1_4)]
+pub mod eclipses;
+pub mod search;
+
+use super::error::Result;
+use super::frame::{Accuracy, ObservingFrame, ReferenceSystem};
+use super::observer::Observer;
+use super::refraction::Refraction;
+use super::source::{Planet, Source};
+use super::time::Time;
+
+/// Twilight, by the Sun elevation that bounds it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Twilight {
+    /// Sun 6 deg below the horizon
+    Civil,
+    /// Sun 12 deg below the horizon
+    Nautical,
+    /// Sun 18 deg below the horizon
+    Astronomical,
+}
+
+impl Twilight {
+    /// [deg] Sun elevation at the start and end of the twilight.
+    pub fn sun_elevation(self) -> f64 {
+        match self {
+            Twilight::Civil => -6.0,
+            Twilight::Nautical => -12.0,
+            Twilight::Astronomical => -18.0,
+        }
+    }
+}
+
+/// [deg] Elevation of the Sun's center at sunrise and sunset, when its upper limb touches the
+/// refracted horizon.
+const SUNRISE_ELEVATION: f64 = -16.0 / 60.0;
+
+/// Crossings of an elevation by the Sun in a day.
+#[derive(Debug, Clone, Copy)]
+pub enum SunCrossing {
+    /// The Sun crosses the elevation. Near the polar circles, one of the crossings may fall
+    /// outside the day.
+    Crosses { rise: Option<Time>, set: Option<Time> },
+    /// The Sun stays above the elevation for the whole day.
+    AlwaysAbove,
+    /// The Sun stays below the elevation for the whole day.
+    AlwaysBelow,
+}
+
+/// Sunrise, sunset and twilights in a day, see [`sun_events`].
+#[derive(Debug, Clone, Copy)]
+pub struct SunEvents {
+    /// Sunrise and sunset, for the upper limb on the refracted horizon
+    pub sun: SunCrossing,
+    /// Start and end of civil twilight
+    pub civil: SunCrossing,
+    /// Start and end of nautical twilight
+    pub nautical: SunCrossing,
+    /// Start and end of astronomical twilight
+    pub astronomical: SunCrossing,
+}
+
+impl SunEvents {
+    /// Start and end of a twilight.
+    pub fn twilight(&self, twilight: Twilight) -> SunCrossing {
+        match twilight {
+            Twilight::Civil => self.civil,
+            Twilight::Nautical => self.nautical,
+            Twilight::Astronomical => self.astronomical,
+        }
+    }
+}
+
+/// Sunrise, sunset and the civil, nautical and astronomical twilights seen by `observer` in the
+/// day (24 hours) from `start`, including polar day and night. Twilight elevations are not
+/// refracted.
+pub fn sun_events(observer: &Observer, start: &Time) -> Result<SunEvents> {
+    let frame = ObservingFrame::new(Accuracy::Reduced, observer, start, 0.0, 0.0)?;
+    let sun = Source::planet(Planet::Sun)?;
+    let pos = frame.sky_pos(&sun, ReferenceSystem::Tod)?;
+    let el = frame.to_horizontal(&pos, ReferenceSystem::Tod, false)?.el;
+    let refracted = frame.to_horizontal(&pos, ReferenceSystem::Tod, true)?.el;
+
+    let crossing = |limit: f64, current: f64, refraction: Refraction| {
+        let rise = frame.rises_above(limit, &sun, refraction.clone());
+        let set = frame.sets_below(limit, &sun, refraction);
+        match (rise, set) {
+            (None, None) if current > limit => SunCrossing::AlwaysAbove,
+            (None, None) => SunCrossing::AlwaysBelow,
+            (rise, set) => SunCrossing::Crosses { rise, set },
+        }
+    };
+    Ok(SunEvents {
+        sun: crossing(SUNRISE_ELEVATION, refracted, Refraction::Standard),
+        civil: crossing(Twilight::Civil.sun_elevation(), el, Refraction::None),
+        nautical: crossing(Twilight::Nautical.sun_elevation(), el, Refraction::None),
+        astronomical: crossing(Twilight::Astronomical.sun_elevation(), el, Refraction::None),
+    })
+}
+
+/// Kinds of events reported by [`Events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// The source rises above the horizon.
+    Rise,
+    /// The source sets below the horizon.
+    Set,
+    /// The source transits the local meridian.
+    Transit,
+    /// Morning twilight starts: the Sun rises above the twilight elevation.
+    TwilightStart(Twilight),
+    /// Evening twilight ends: the Sun sets below the twilight elevation.
+    TwilightEnd(Twilight),
+}
+
+/// An event and its time.
+#[derive(Debug, Clone, Copy)]
+pub struct Event {
+    pub kind: EventKind,
+    pub time: Time,
+}
+
+/// [s] Searches for the next event of a kind resume this long after the last one.
+const RESUME: f64 = 60.0;
+
+/// [s] Searches that find no event resume a day later.
+const DAY: f64 = 86400.0;
+
+// Search for the next event of one kind.
+#[derive(Debug, Clone)]
+struct Search {
+    kind: EventKind,
+    from: Time,
+    next: Option<Time>,
+    done: bool,
+}
+
+/// Iterator over the rise, set and transit of a source and the twilights of the Sun in a time
+/// window, in chronological order. Created with [`events`].
+#[derive(Debug, Clone)]
+pub struct Events {
+    source: Source,
+    observer: Observer,
+    accuracy: Accuracy,
+    dx: f64,
+    dy: f64,
+    horizon: f64,
+    refraction: Refraction,
+    twilight: bool,
+    start: Time,
+    end: Time,
+    // Set up on the first call of `next()`, after the options are final
+    searches: Option<Vec<Search>>,
+}
+
+/// Events of `source` seen by `observer` from `start` to `end`, including the civil, nautical and
+/// astronomical twilights. Rise and set are for the center of the source on a refracted
+/// horizon at 0 deg elevation by default.
+pub fn events(source: &Source, observer: &Observer, start: &Time, end: &Time) -> Events {
+    Events {
+        source: *source,
+        observer: *observer,
+        accuracy: Accuracy::default(),
+        dx: 0.0,
+        dy: 0.0,
+        horizon: 0.0,
+        refraction: Refraction::Standard,
+        twilight: true,
+        start: *start,
+        end: *end,
+        searches: None,
+    }
+}
+
+impl Events {
+    /// Sets the accuracy of the position calculations.
+    pub fn with_accuracy(mut self, accuracy: Accuracy) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    /// Sets the polar offsets [mas] used for the frames.
+    pub fn with_polar_offsets(mut self, dx: f64, dy: f64) -> Self {
+        self.dx = dx;
+        self.dy = dy;
+        self
+    }
+
+    /// Sets the elevation [deg] of the horizon for rise and set.
+    pub fn with_horizon(mut self, el: f64) -> Self {
+        self.horizon = el;
+        self
+    }
+
+    /// Sets the atmospheric refraction model for rise and set, or whether the standard model is
+    /// applied.
+    pub fn with_refraction(mut self, refraction: impl Into<Refraction>) -> Self {
+        self.refraction = refraction.into();
+        self
+    }
+
+    /// Sets whether twilight events are reported.
+    pub fn with_twilight(mut self, twilight: bool) -> Self {
+        self.twilight = twilight;
+        self
+    }
+
+    // Finds the next event of a kind, within a day of `from`.
+    fn find(&self, kind: EventKind, from: &Time) -> Result<Option<Time>> {
+        let frame = ObservingFrame::new(self.accuracy, &self.observer, from, self.dx, self.dy)?;
+        let sun = || Source::planet(Planet::Sun);
+        Ok(match kind {
+            EventKind::Rise => frame.rises_above(self.horizon, &self.source, self.refraction.clone()),
+            EventKind::Set => frame.sets_below(self.horizon, &self.source, self.refraction.clone()),
+            EventKind::Transit => frame.transit_time(&self.source),
+            EventKind::TwilightStart(twilight) => frame.rises_above(twilight.sun_elevation(), &sun()?, false),
+            EventKind::TwilightEnd(twilight) => frame.sets_below(twilight.sun_elevation(), &sun()?, false),
+        })
+    }
+
+    // Fills in the next event of a search, if it is in the window.
+    fn advance(&self, search: &mut Search) -> Result<()> {
+        while search.next.is_none() && !search.done {
+            if search.from.diff(&self.end) > 0.0 {
+                search.done = true;
+            } else if let Some(time) = self.find(search.kind, &search.from)? {
+                if time.diff(&self.end) > 0.0 {
+                    search.done = true;
+                } else {
+                    search.next = Some(time);
+                }
+            } else {
+                search.from = search.from.offset(DAY)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn next_event(&mut self) -> Result<Option<Event>> {
+        let mut searches = match self.searches.take() {
+            Some(searches) => searches,
+            None => {
+                let mut kinds = vec![EventKind::Rise, EventKind::Transit, EventKind::Set];
+                if self.twilight {
+                    for twilight in [Twilight::Civil, Twilight::Nautical, Twilight::Astronomical] {
+                        kinds.push(EventKind::TwilightStart(twilight));
+                        kinds.push(EventKind::TwilightEnd(twilight));
+                    }
+                }
+                kinds
+                    .into_iter()
+                    .map(|kind| Search { kind, from: self.start, next: None, done: false })
+                    .collect()
+            }
+        };
+        let result = self.take_earliest(&mut searches);
+        self.searches = Some(searches);
+        result
+    }
+
+    fn take_earliest(&self, searches: &mut [Search]) -> Result<Option<Event>> {
+        for search in searches.iter_mut() {
+            self.advance(search)?;
+        }
+        let Some(search) = searches
+            .iter_mut()
+            .filter(|search| search.next.is_some())
+            .min_by(|a, b| a.next.unwrap().diff(&b.next.unwrap()).total_cmp(&0.0))
+        else {
+            return Ok(None);
+        };
+        let time = search.next.take().unwrap();
+        search.from = time.offset(RESUME)?;
+        Ok(Some(Event { kind: search.kind, time }))
+    }
+}
+
+impl Iterator for Events {
+    type Item = Result<Event>;
+
+    // An error ends the iteration
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_event() {
+            Ok(event) => event.map(Ok),
+            Err(err) => {
+                self.searches = Some(Vec::new());
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::time::Timescale;
+
+    fn observer(latitude: f64) -> Observer {
+        Observer::on_surface(latitude, 87.1781, 2080.0).unwrap()
+    }
+
+    fn utc(jd: f64) -> Time {
+        Time::new(Timescale::Utc, jd, 37, 0.035).unwrap()
+    }
+
+    #[test]
+    fn twilights_in_summer() {
+        let events = sun_events(&observer(43.4712), &utc(2460850.5)).unwrap();
+        let SunCrossing::Crosses { rise: Some(rise), set: Some(set) } = events.sun else {
+            panic!("{:?}", events.sun);
+        };
+        let SunCrossing::Crosses { set: Some(dusk), rise: Some(dawn) } = events.twilight(Twilight::Astronomical) else {
+            panic!("{:?}", events.astronomical);
+        };
+        // Night, from dusk to dawn, falls within the time the Sun is down
+        let down = |time: &Time| time.diff(&set).rem_euclid(86400.0);
+        assert!(down(&dusk) < down(&dawn) && down(&dawn) < down(&rise));
+        assert!(matches!(events.civil, SunCrossing::Crosses { .. }));
+        assert_eq!(Twilight::Nautical.sun_elevation(), -12.0);
+    }
+
+    #[test]
+    fn polar_day_and_night() {
+        let summer = sun_events(&observer(75.0), &utc(2460850.5)).unwrap();
+        assert!(matches!(summer.sun, SunCrossing::AlwaysAbove));
+        assert!(matches!(summer.astronomical, SunCrossing::AlwaysAbove));
+        let winter = sun_events(&observer(75.0), &utc(2461030.5)).unwrap();
+        assert!(matches!(winter.sun, SunCrossing::AlwaysBelow));
+    }
+
+    #[test]
+    fn events_in_order() {
+        let vega = Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap();
+        let (start, end) = (utc(2460850.5), utc(2460853.5));
+        let all: Vec<Event> = events(&vega, &observer(43.4712), &start, &end).with_accuracy(Accuracy::Reduced).collect::<Result<_>>().unwrap();
+        for pair in all.windows(2) {
+            assert!(pair[1].time.diff(&pair[0].time) >= 0.0);
+        }
+        assert!(all.iter().all(|event| event.time.diff(&start) >= 0.0 && end.diff(&event.time) >= 0.0));
+        let count = |kind| all.iter().filter(|event| event.kind == kind).count();
+        for kind in [EventKind::Rise, EventKind::Transit, EventKind::Set, EventKind::TwilightEnd(Twilight::Astronomical)] {
+            assert!((3..=4).contains(&count(kind)), "{kind:?}: {}", count(kind));
+        }
+        let without = events(&vega, &observer(43.4712), &start, &end).with_accuracy(Accuracy::Reduced).with_twilight(false).with_horizon(30.0);
+        let kinds: Vec<EventKind> = without.map(|event| event.unwrap().kind).collect();
+        assert!(kinds.iter().all(|kind| matches!(kind, EventKind::Rise | EventKind::Transit | EventKind::Set)));
+        assert!(kinds.len() >= 9);
+    }
+}
@@ -0,0 +1,153 @@
+//! Conjunctions, oppositions and greatest elongations of solar-system bodies and other sources,
+//! from their apparent positions seen by an observer.
+//!
+//! Conjunctions are the minima of the separation of two sources; oppositions and elongations use
+//! the ecliptic longitudes of the source and the Sun, on the true ecliptic and equinox of date.
+
+use super::search::Search;
+use crate::novas::coords::{EquatorType, Equatorial};
+use crate::novas::error::Result;
+use crate::novas::frame::{Accuracy, ObservingFrame, ReferenceSystem};
+use crate::novas::observer::Observer;
+use crate::novas::source::{Planet, Source};
+use crate::novas::time::{Time, Timescale};
+
+/// [s] Interval at which the searches sample the geometry. Events less than this apart, e.g.
+/// successive conjunctions of fast moving bodies, may be missed.
+const STEP: f64 = 86400.0;
+
+/// [deg] Largest distance from 180 deg of the longitude difference at an opposition, as found.
+const OPPOSITION_TOLERANCE: f64 = 0.01;
+
+/// Closest apparent approach of two sources.
+#[derive(Debug, Clone, Copy)]
+pub struct Conjunction {
+    pub time: Time,
+    /// [deg] separation of the sources
+    pub separation: f64,
+}
+
+/// Opposition of a source to the Sun: the difference of their ecliptic longitudes is 180 deg.
+#[derive(Debug, Clone, Copy)]
+pub struct Opposition {
+    pub time: Time,
+    /// [deg] angular distance from the Sun
+    pub elongation: f64,
+    /// [AU] distance of the source
+    pub distance: f64,
+}
+
+/// Greatest elongation of a source from the Sun.
+#[derive(Debug, Clone, Copy)]
+pub struct Elongation {
+    pub time: Time,
+    /// [deg] angular distance from the Sun
+    pub elongation: f64,
+    /// Whether the source is east of the Sun, in the evening sky
+    pub east: bool,
+}
+
+/// Conjunctions of sources `a` and `b` seen by `observer` between `start` and `end`.
+pub fn conjunctions(a: &Source, b: &Source, observer: &Observer, start: &Time, end: &Time) -> Result<Vec<Conjunction>> {
+    let separation = |time: &Time| -> Result<f64> {
+        let frame = frame(observer, time)?;
+        Ok(frame.sky_pos(a, ReferenceSystem::Tod)?.separation(&frame.sky_pos(b, ReferenceSystem::Tod)?))
+    };
+    Search::new(start, end)?.with_step(STEP)?.minima(separation)?
+        .into_iter()
+        .map(|time| Ok(Conjunction { separation: separation(&time)?, time }))
+        .collect()
+}
+
+/// Oppositions of `source` to the Sun seen by `observer` between `start` and `end`.
+pub fn oppositions(source: &Source, observer: &Observer, start: &Time, end: &Time) -> Result<Vec<Opposition>> {
+    let from_opposition = |time: &Time| Ok((longitude_from_sun(source, observer, time)? - 180.0).abs());
+    let mut found = Vec::new();
+    for time in Search::new(start, end)?.with_step(STEP)?.minima(from_opposition)? {
+        if from_opposition(&time)? > OPPOSITION_TOLERANCE {
+            // The source turned back before reaching opposition.
+            continue;
+        }
+        let frame = frame(observer, &time)?;
+        let pos = frame.sky_pos(source, ReferenceSystem::Tod)?;
+        let sun = frame.sky_pos(&Source::planet(Planet::Sun)?, ReferenceSystem::Tod)?;
+        found.push(Opposition { time, elongation: pos.separation(&sun), distance: pos.dist });
+    }
+    Ok(found)
+}
+
+/// Greatest elongations of `source` from the Sun seen by `observer` between `start` and `end`,
+/// e.g. those of Mercury and Venus. For superior planets, they fall near the oppositions.
+pub fn greatest_elongations(source: &Source, observer: &Observer, start: &Time, end: &Time) -> Result<Vec<Elongation>> {
+    let sun = Source::planet(Planet::Sun)?;
+    let elongation = |time: &Time| -> Result<f64> {
+        let frame = frame(observer, time)?;
+        Ok(frame.sky_pos(source, ReferenceSystem::Tod)?.separation(&frame.sky_pos(&sun, ReferenceSystem::Tod)?))
+    };
+    Search::new(start, end)?.with_step(STEP)?.maxima(elongation)?
+        .into_iter()
+        .map(|time| {
+            Ok(Elongation {
+                elongation: elongation(&time)?,
+                east: longitude_from_sun(source, observer, &time)? < 180.0,
+                time,
+            })
+        })
+        .collect()
+}
+
+fn frame(observer: &Observer, time: &Time) -> Result<ObservingFrame> {
+    ObservingFrame::new(Accuracy::Reduced, observer, time, 0.0, 0.0)
+}
+
+// [deg] Ecliptic longitude of a source east of the Sun, in [0, 360).
+fn longitude_from_sun(source: &Source, observer: &Observer, time: &Time) -> Result<f64> {
+    let frame = frame(observer, time)?;
+    let jd_tt = time.jd(Timescale::Tt);
+    let longitude = |source: &Source| -> Result<f64> {
+        let pos = frame.sky_pos(source, ReferenceSystem::Tod)?;
+        Ok(Equatorial::new(pos.ra, pos.dec).to_ecliptic(EquatorType::True, jd_tt, Accuracy::Reduced)?.lon)
+    };
+    Ok((longitude(source)? - longitude(&Source::planet(Planet::Sun)?)?).rem_euclid(360.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regulus() -> Source {
+        Source::star("Regulus", 10.1395, 11.9672, "ICRS").unwrap()
+    }
+
+    fn time(jd_utc: f64) -> Time {
+        Time::new(Timescale::Utc, jd_utc, 37, 0.0).unwrap()
+    }
+
+    #[test]
+    fn conjunctions_with_the_sun() {
+        let geocenter = Observer::at_geocenter().unwrap();
+        let sun = Source::planet(Planet::Sun).unwrap();
+        // 23 August 2025, as the Sun passes 150 deg of ecliptic longitude
+        let found = conjunctions(&sun, &regulus(), &geocenter, &time(2460900.5), &time(2460920.5)).unwrap();
+        assert_eq!(found.len(), 1);
+        let jd = found[0].time.jd(Timescale::Utc);
+        assert!((2460910.0..2460911.0).contains(&jd), "{jd}");
+        // Regulus is half a degree from the ecliptic
+        assert!((found[0].separation - 0.46).abs() < 0.05, "{}", found[0].separation);
+    }
+
+    #[test]
+    fn oppositions_and_elongations() {
+        let geocenter = Observer::at_geocenter().unwrap();
+        // Late February 2026
+        let found = oppositions(&regulus(), &geocenter, &time(2461080.5), &time(2461100.5)).unwrap();
+        assert_eq!(found.len(), 1);
+        assert!((found[0].elongation - 179.5).abs() < 0.1, "{}", found[0].elongation);
+        assert!(found[0].distance > 1e6);
+        let elongations = greatest_elongations(&regulus(), &geocenter, &time(2461080.5), &time(2461100.5)).unwrap();
+        assert_eq!(elongations.len(), 1);
+        assert!(elongations[0].time.diff(&found[0].time).abs() < 2.0 * 86400.0);
+        // No opposition half a year later
+        assert!(oppositions(&regulus(), &geocenter, &time(2460900.5), &time(2460920.5)).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,384 @@
+//! Solar and lunar eclipses, from the positions of the Sun and the Moon given by the planet
+//! provider (see [`ephemeris`](crate::novas::ephemeris)).
+//!
+//! Eclipses are searched around each new and full moon. Lunar eclipses use the shadow of the
+//! Earth enlarged by 2% for its atmosphere (Danjon), and global circumstances of solar eclipses
+//! use the fundamental plane through the center of the Earth, which is taken to be spherical.
+
+use super::search::{crossing, minimize, DEFAULT_TOLERANCE};
+use crate::novas::coords::dot;
+use crate::novas::error::{Error, Result};
+use crate::novas::frame::{Accuracy, ObservingFrame, ReferenceSystem};
+use crate::novas::moon;
+use crate::novas::observer::Observer;
+use crate::novas::source::{Planet, Source};
+use crate::novas::time::Time;
+use supernovas_sys as sn;
+
+/// [m] Mean radius of the Moon.
+const MOON_RADIUS: f64 = 1737.4e3;
+
+/// Enlargement of the shadow of the Earth by its atmosphere.
+const SHADOW_ENLARGEMENT: f64 = 1.02;
+
+/// Ratio of the mean radius of the Earth at the latitudes of the Moon to its equatorial radius.
+const EARTH_OBLATENESS: f64 = 0.99834;
+
+/// [s] The greatest eclipse and contacts are searched this far from the syzygy.
+const WINDOW: f64 = 12.0 * 3600.0;
+
+/// [s] Local circumstances are searched this far from the greatest eclipse.
+const LOCAL_WINDOW: f64 = 4.0 * 3600.0;
+
+/// Type of an eclipse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EclipseType {
+    PartialSolar,
+    AnnularSolar,
+    TotalSolar,
+    /// The Moon enters only the penumbra of the Earth.
+    PenumbralLunar,
+    PartialLunar,
+    TotalLunar,
+}
+
+impl EclipseType {
+    pub fn is_solar(self) -> bool {
+        matches!(self, EclipseType::PartialSolar | EclipseType::AnnularSolar | EclipseType::TotalSolar)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EclipseType::PartialSolar => "partial solar",
+            EclipseType::AnnularSolar => "annular solar",
+            EclipseType::TotalSolar => "total solar",
+            EclipseType::PenumbralLunar => "penumbral lunar",
+            EclipseType::PartialLunar => "partial lunar",
+            EclipseType::TotalLunar => "total lunar",
+        }
+    }
+}
+
+/// Start and end of a phase of an eclipse.
+#[derive(Debug, Clone, Copy)]
+pub struct Span {
+    pub begin: Time,
+    pub end: Time,
+}
+
+/// Global circumstances of an eclipse.
+#[derive(Debug, Clone, Copy)]
+pub struct Eclipse {
+    pub kind: EclipseType,
+    /// Time of greatest eclipse: when the axis of the shadow is the closest to the center of the
+    /// Earth (solar), or the Moon to the axis of the shadow of the Earth (lunar)
+    pub greatest: Time,
+    /// Umbral magnitude of lunar eclipses (penumbral magnitude of penumbral ones), or magnitude
+    /// at the point of greatest eclipse of solar eclipses: the fraction of the diameter of the
+    /// Sun covered, or the ratio of the apparent diameters of the Moon and the Sun if central
+    pub magnitude: f64,
+    /// First and last contacts with the penumbra (P1, P4): the whole eclipse
+    pub penumbral: Span,
+    /// External contacts with the umbra (U1, U4): the partial phase of lunar eclipses, or the
+    /// umbra or antumbra of the Moon touching the Earth
+    pub umbral: Option<Span>,
+    /// Internal contacts with the umbra (U2, U3): totality of lunar eclipses, or the umbra or
+    /// antumbra of the Moon entirely on the Earth
+    pub umbral_internal: Option<Span>,
+}
+
+/// Circumstances of an eclipse for an observer, see [`Eclipse::local`].
+#[derive(Debug, Clone, Copy)]
+pub struct LocalCircumstances {
+    /// Type of the eclipse as seen by the observer
+    pub kind: EclipseType,
+    pub greatest: Time,
+    pub magnitude: f64,
+    /// [deg] unrefracted elevation of the eclipsed body at greatest eclipse; negative if it is
+    /// below the horizon
+    pub elevation: f64,
+    /// First and last contacts (C1, C4) of solar eclipses, or contacts with the penumbra of lunar
+    /// eclipses
+    pub contacts: Span,
+    /// Second and third contacts (C2, C3): totality or annularity of solar eclipses, or totality
+    /// of lunar eclipses
+    pub central: Option<Span>,
+}
+
+impl Eclipse {
+    /// Circumstances of the eclipse seen by `observer`, or `None` for a solar eclipse whose
+    /// penumbra misses the observer. The times of lunar eclipses are the same for all observers,
+    /// which see it if the Moon is above their horizon.
+    pub fn local(&self, observer: &Observer) -> Result<Option<LocalCircumstances>> {
+        if !self.kind.is_solar() {
+            let frame = ObservingFrame::new(Accuracy::Reduced, observer, &self.greatest, 0.0, 0.0)?;
+            let pos = frame.sky_pos(&Source::planet(Planet::Moon)?, ReferenceSystem::Tod)?;
+            return Ok(Some(LocalCircumstances {
+                kind: self.kind,
+                greatest: self.greatest,
+                magnitude: self.magnitude,
+                elevation: frame.to_horizontal(&pos, ReferenceSystem::Tod, false)?.el,
+                contacts: self.penumbral,
+                central: self.umbral_internal,
+            }));
+        }
+
+        let disks = |t: f64| LocalDisks::at(observer, &self.greatest.offset(t)?);
+        let greatest = minimize(-LOCAL_WINDOW, LOCAL_WINDOW, DEFAULT_TOLERANCE, |t| Ok(disks(t)?.separation))?;
+        let at = disks(greatest)?;
+        if at.separation >= at.sun + at.moon {
+            return Ok(None);
+        }
+        let span = |limit: fn(&LocalDisks) -> f64| -> Result<Span> {
+            let margin = |t: f64| -> Result<f64> {
+                let d = disks(t)?;
+                Ok(d.separation - limit(&d))
+            };
+            Ok(Span {
+                begin: self.greatest.offset(crossing(greatest, -LOCAL_WINDOW, DEFAULT_TOLERANCE, margin)?)?,
+                end: self.greatest.offset(crossing(greatest, LOCAL_WINDOW, DEFAULT_TOLERANCE, margin)?)?,
+            })
+        };
+        let kind = match at.separation <= (at.moon - at.sun).abs() {
+            true if at.moon > at.sun => EclipseType::TotalSolar,
+            true => EclipseType::AnnularSolar,
+            false => EclipseType::PartialSolar,
+        };
+        Ok(Some(LocalCircumstances {
+            kind,
+            greatest: self.greatest.offset(greatest)?,
+            magnitude: (at.sun + at.moon - at.separation) / (2.0 * at.sun),
+            elevation: at.elevation,
+            contacts: span(|d| d.sun + d.moon)?,
+            central: match kind {
+                EclipseType::PartialSolar => None,
+                _ => Some(span(|d| (d.moon - d.sun).abs())?),
+            },
+        }))
+    }
+}
+
+/// Solar and lunar eclipses whose greatest eclipse is between `start` and `end`, in
+/// chronological order.
+pub fn eclipses(start: &Time, end: &Time) -> Result<Vec<Eclipse>> {
+    if end.diff(start) <= 0.0 {
+        return Err(Error::InvalidArgument("end of the search before its start"));
+    }
+    let mut found = Vec::new();
+    // Start a day early for eclipses whose greatest eclipse precedes the syzygy.
+    let mut time = start.offset(-86400.0)?;
+    loop {
+        let new_moon = moon::next_new_moon(&time)?;
+        let full_moon = moon::next_full_moon(&time)?;
+        let (syzygy, solar) = match new_moon.diff(&full_moon) < 0.0 {
+            true => (new_moon, true),
+            false => (full_moon, false),
+        };
+        if syzygy.diff(end) > WINDOW {
+            break;
+        }
+        let eclipse = if solar { solar_eclipse(&syzygy)? } else { lunar_eclipse(&syzygy)? };
+        if let Some(eclipse) = eclipse
+            && eclipse.greatest.diff(start) >= 0.0
+            && eclipse.greatest.diff(end) < 0.0
+        {
+            found.push(eclipse);
+        }
+        time = syzygy.offset(86400.0)?;
+    }
+    Ok(found)
+}
+
+/// Solar eclipse around a new moon, if any.
+fn solar_eclipse(new_moon: &Time) -> Result<Option<Eclipse>> {
+    let shadow = |t: f64| Shadow::at(&new_moon.offset(t)?);
+    let greatest = minimize(-WINDOW, WINDOW, DEFAULT_TOLERANCE, |t| Ok(shadow(t)?.axis))?;
+    let at = shadow(greatest)?;
+    let earth = sn::NOVAS_EARTH_RADIUS;
+    if at.axis >= earth + at.penumbra {
+        return Ok(None);
+    }
+
+    let span = |limit: fn(&Shadow) -> f64| -> Result<Span> {
+        let margin = |t: f64| -> Result<f64> {
+            let s = shadow(t)?;
+            Ok(s.axis - limit(&s))
+        };
+        Ok(Span {
+            begin: new_moon.offset(crossing(greatest, -WINDOW, DEFAULT_TOLERANCE, margin)?)?,
+            end: new_moon.offset(crossing(greatest, WINDOW, DEFAULT_TOLERANCE, margin)?)?,
+        })
+    };
+    let umbral = at.axis < earth + at.umbra.abs();
+    let internal = at.axis < earth - at.umbra.abs();
+
+    let (kind, magnitude) = if at.axis < earth {
+        // Central eclipse: the umbra or antumbra at the surface below the axis.
+        let distance = at.moon_distance - (earth * earth - at.axis * at.axis).sqrt();
+        let umbra = distance * at.umbra_slope - MOON_RADIUS;
+        let ratio = (MOON_RADIUS / distance) / (sn::NOVAS_SOLAR_RADIUS / (at.sun_distance + distance));
+        (if umbra < 0.0 { EclipseType::TotalSolar } else { EclipseType::AnnularSolar }, ratio)
+    } else {
+        let magnitude = (earth + at.penumbra - at.axis) / (at.penumbra + at.umbra);
+        match umbral {
+            true if at.umbra < 0.0 => (EclipseType::TotalSolar, magnitude),
+            true => (EclipseType::AnnularSolar, magnitude),
+            false => (EclipseType::PartialSolar, magnitude),
+        }
+    };
+
+    Ok(Some(Eclipse {
+        kind,
+        greatest: new_moon.offset(greatest)?,
+        magnitude,
+        penumbral: span(|s| sn::NOVAS_EARTH_RADIUS + s.penumbra)?,
+        umbral: if umbral { Some(span(|s| sn::NOVAS_EARTH_RADIUS + s.umbra.abs())?) } else { None },
+        umbral_internal: if internal { Some(span(|s| sn::NOVAS_EARTH_RADIUS - s.umbra.abs())?) } else { None },
+    }))
+}
+
+/// Lunar eclipse around a full moon, if any.
+fn lunar_eclipse(full_moon: &Time) -> Result<Option<Eclipse>> {
+    let shadow = |t: f64| EarthShadow::at(&full_moon.offset(t)?);
+    let greatest = minimize(-WINDOW, WINDOW, DEFAULT_TOLERANCE, |t| Ok(shadow(t)?.separation))?;
+    let at = shadow(greatest)?;
+    let penumbral = (at.penumbra + at.moon - at.separation) / (2.0 * at.moon);
+    if penumbral <= 0.0 {
+        return Ok(None);
+    }
+    let umbral = (at.umbra + at.moon - at.separation) / (2.0 * at.moon);
+
+    let span = |limit: fn(&EarthShadow) -> f64| -> Result<Span> {
+        let margin = |t: f64| -> Result<f64> {
+            let s = shadow(t)?;
+            Ok(s.separation - limit(&s))
+        };
+        Ok(Span {
+            begin: full_moon.offset(crossing(greatest, -WINDOW, DEFAULT_TOLERANCE, margin)?)?,
+            end: full_moon.offset(crossing(greatest, WINDOW, DEFAULT_TOLERANCE, margin)?)?,
+        })
+    };
+    let kind = match umbral {
+        m if m >= 1.0 => EclipseType::TotalLunar,
+        m if m > 0.0 => EclipseType::PartialLunar,
+        _ => EclipseType::PenumbralLunar,
+    };
+
+    Ok(Some(Eclipse {
+        kind,
+        greatest: full_moon.offset(greatest)?,
+        magnitude: if umbral > 0.0 { umbral } else { penumbral },
+        penumbral: span(|s| s.penumbra + s.moon)?,
+        umbral: if umbral > 0.0 { Some(span(|s| s.umbra + s.moon)?) } else { None },
+        umbral_internal: if umbral >= 1.0 { Some(span(|s| s.umbra - s.moon)?) } else { None },
+    }))
+}
+
+// [m] Apparent geocentric positions of the Sun and the Moon.
+fn geocentric(time: &Time) -> Result<([f64; 3], [f64; 3])> {
+    let frame = ObservingFrame::new(Accuracy::Reduced, &Observer::at_geocenter()?, time, 0.0, 0.0)?;
+    let position = |planet: Planet| -> Result<[f64; 3]> {
+        let pos = frame.sky_pos(&Source::planet(planet)?, ReferenceSystem::Gcrs)?;
+        Ok(pos.r_hat.map(|x| x * pos.dist * sn::NOVAS_AU))
+    };
+    Ok((position(Planet::Sun)?, position(Planet::Moon)?))
+}
+
+// Shadow of the Moon in the fundamental plane: the plane through the center of the Earth,
+// perpendicular to the axis of the shadow.
+struct Shadow {
+    // [m] distance of the axis from the center of the Earth
+    axis: f64,
+    // [m] radius of the penumbra
+    penumbra: f64,
+    // [m] radius of the umbra, negative for the umbra proper and positive for the antumbra
+    umbra: f64,
+    // tangent of the half-angle of the umbral cone
+    umbra_slope: f64,
+    // [m] distances of the Moon from the plane and from the Sun
+    moon_distance: f64,
+    sun_distance: f64,
+}
+
+impl Shadow {
+    fn at(time: &Time) -> Result<Self> {
+        let (sun, moon) = geocentric(time)?;
+        let axis = [moon[0] - sun[0], moon[1] - sun[1], moon[2] - sun[2]];
+        let sun_distance = dot(&axis, &axis).sqrt();
+        let axis = axis.map(|x| x / sun_distance);
+        let moon_distance = -dot(&moon, &axis);
+        let closest = [0, 1, 2].map(|i| moon[i] + moon_distance * axis[i]);
+        let penumbra_slope = ((sn::NOVAS_SOLAR_RADIUS + MOON_RADIUS) / sun_distance).asin().tan();
+        let umbra_slope = ((sn::NOVAS_SOLAR_RADIUS - MOON_RADIUS) / sun_distance).asin().tan();
+        Ok(Shadow {
+            axis: dot(&closest, &closest).sqrt(),
+            penumbra: MOON_RADIUS + moon_distance * penumbra_slope,
+            umbra: moon_distance * umbra_slope - MOON_RADIUS,
+            umbra_slope,
+            moon_distance,
+            sun_distance,
+        })
+    }
+}
+
+// [rad] Geocentric angular radii of the shadow of the Earth and of the Moon, and the distance of
+// the Moon from the axis of the shadow.
+struct EarthShadow {
+    separation: f64,
+    penumbra: f64,
+    umbra: f64,
+    moon: f64,
+}
+
+impl EarthShadow {
+    fn at(time: &Time) -> Result<Self> {
+        let (sun, moon) = geocentric(time)?;
+        let (sun_distance, moon_distance) = (dot(&sun, &sun).sqrt(), dot(&moon, &moon).sqrt());
+        let sun_radius = (sn::NOVAS_SOLAR_RADIUS / sun_distance).asin();
+        let parallax = EARTH_OBLATENESS * (sn::NOVAS_EARTH_RADIUS / moon_distance).asin()
+            + (sn::NOVAS_EARTH_RADIUS / sun_distance).asin();
+        let cos = -dot(&sun, &moon) / (sun_distance * moon_distance);
+        Ok(EarthShadow {
+            separation: cos.clamp(-1.0, 1.0).acos(),
+            penumbra: SHADOW_ENLARGEMENT * (parallax + sun_radius),
+            umbra: SHADOW_ENLARGEMENT * (parallax - sun_radius),
+            moon: (MOON_RADIUS / moon_distance).asin(),
+        })
+    }
+}
+
+// [rad] Topocentric angular radii of the Sun and the Moon and their separation, and [deg] the
+// elevation of the Sun.
+struct LocalDisks {
+    separation: f64,
+    sun: f64,
+    moon: f64,
+    elevation: f64,
+}
+
+impl LocalDisks {
+    fn at(observer: &Observer, time: &Time) -> Result<Self> {
+        let frame = ObservingFrame::new(Accuracy::Reduced, observer, time, 0.0, 0.0)?;
+        let sun = frame.sky_pos(&Source::planet(Planet::Sun)?, ReferenceSystem::Tod)?;
+        let moon = frame.sky_pos(&Source::planet(Planet::Moon)?, ReferenceSystem::Tod)?;
+        Ok(LocalDisks {
+            separation: sun.separation(&moon).to_radians(),
+            sun: (sn::NOVAS_SOLAR_RADIUS / (sun.dist * sn::NOVAS_AU)).asin(),
+            moon: (MOON_RADIUS / (moon.dist * sn::NOVAS_AU)).asin(),
+            elevation: frame.to_horizontal(&sun, ReferenceSystem::Tod, false)?.el,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eclipse_types() {
+        assert!(EclipseType::AnnularSolar.is_solar());
+        assert!(!EclipseType::PenumbralLunar.is_solar());
+        assert_eq!(EclipseType::TotalLunar.name(), "total lunar");
+        assert_eq!(EclipseType::PartialSolar.name(), "partial solar");
+    }
+}
@@ -0,0 +1,229 @@
+//! Search of events defined by a quantity over time, e.g. an elevation, a separation or an
+//! elongation computed with [`ObservingFrame`](crate::novas::ObservingFrame)s at each time.
+//!
+//! The quantity is sampled at regular steps to bracket its zeros and extrema, which are then
+//! refined by bisection and golden-section search. Events such as "the source rises above 30 deg
+//! while the Sun is below -12 deg" are the bounds of the [`Search::intervals`] of
+//! `min(el - 30, -12 - sun_el)`.
+
+use crate::novas::error::{Error, Result};
+use crate::novas::time::Time;
+
+/// [s] Default interval between samples.
+const DEFAULT_STEP: f64 = 3600.0;
+
+/// [s] Default precision of the times found.
+pub(crate) const DEFAULT_TOLERANCE: f64 = 1.0;
+
+/// A zero of a quantity.
+#[derive(Debug, Clone, Copy)]
+pub struct Crossing {
+    pub time: Time,
+    /// Whether the quantity becomes positive
+    pub rising: bool,
+}
+
+/// A search over a time window.
+#[derive(Debug, Clone, Copy)]
+pub struct Search {
+    start: Time,
+    end: Time,
+    step: f64,
+    tolerance: f64,
+}
+
+impl Search {
+    /// Search from `start` to `end`, sampling every hour and refined to a second.
+    pub fn new(start: &Time, end: &Time) -> Result<Self> {
+        if end.diff(start) <= 0.0 {
+            return Err(Error::InvalidArgument("end of the search before its start"));
+        }
+        Ok(Search { start: *start, end: *end, step: DEFAULT_STEP, tolerance: DEFAULT_TOLERANCE })
+    }
+
+    /// Sets the interval [s] between samples. Events less than this apart may be missed.
+    pub fn with_step(mut self, step: f64) -> Result<Self> {
+        if step.is_nan() || step <= 0.0 {
+            return Err(Error::InvalidArgument("search step must be positive"));
+        }
+        self.step = step;
+        Ok(self)
+    }
+
+    /// Sets the precision [s] of the times found.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Result<Self> {
+        if tolerance.is_nan() || tolerance <= 0.0 {
+            return Err(Error::InvalidArgument("search tolerance must be positive"));
+        }
+        self.tolerance = tolerance;
+        Ok(self)
+    }
+
+    /// Times at which `f` crosses zero, in chronological order.
+    pub fn crossings(&self, f: impl Fn(&Time) -> Result<f64>) -> Result<Vec<Crossing>> {
+        let at = |t: f64| f(&self.start.offset(t)?);
+        let (offsets, values) = self.sample(at)?;
+        let mut found = Vec::new();
+        for i in 1..offsets.len() {
+            let rising = values[i - 1] <= 0.0 && values[i] > 0.0;
+            if rising || (values[i - 1] > 0.0 && values[i] <= 0.0) {
+                let (inside, outside) = if rising { (offsets[i - 1], offsets[i]) } else { (offsets[i], offsets[i - 1]) };
+                let t = crossing(inside, outside, self.tolerance, at)?;
+                found.push(Crossing { time: self.start.offset(t)?, rising });
+            }
+        }
+        Ok(found)
+    }
+
+    /// Intervals in which `f` is positive, in chronological order. Intervals are cut at the
+    /// start and end of the search.
+    pub fn intervals(&self, f: impl Fn(&Time) -> Result<f64>) -> Result<Vec<(Time, Time)>> {
+        let mut begin = if f(&self.start)? > 0.0 { Some(self.start) } else { None };
+        let mut found = Vec::new();
+        for crossing in self.crossings(&f)? {
+            match (crossing.rising, begin) {
+                (true, _) => begin = Some(crossing.time),
+                (false, Some(time)) => {
+                    found.push((time, crossing.time));
+                    begin = None;
+                }
+                (false, None) => {}
+            }
+        }
+        if let Some(time) = begin {
+            found.push((time, self.end));
+        }
+        Ok(found)
+    }
+
+    /// Times of the local minima of `f`, in chronological order. Minima at the start and end of
+    /// the search are not included.
+    pub fn minima(&self, f: impl Fn(&Time) -> Result<f64>) -> Result<Vec<Time>> {
+        let at = |t: f64| f(&self.start.offset(t)?);
+        let (offsets, values) = self.sample(at)?;
+        let mut found = Vec::new();
+        for i in 1..offsets.len().saturating_sub(1) {
+            if values[i] < values[i - 1] && values[i] <= values[i + 1] {
+                found.push(self.start.offset(minimize(offsets[i - 1], offsets[i + 1], self.tolerance, at)?)?);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Times of the local maxima of `f`, in chronological order, see [`Search::minima`].
+    pub fn maxima(&self, f: impl Fn(&Time) -> Result<f64>) -> Result<Vec<Time>> {
+        self.minima(|time| Ok(-f(time)?))
+    }
+
+    // Offsets [s] from the start and values of the samples.
+    fn sample(&self, at: impl Fn(f64) -> Result<f64>) -> Result<(Vec<f64>, Vec<f64>)> {
+        let span = self.end.diff(&self.start);
+        let samples = (span / self.step).ceil() as usize;
+        let offsets: Vec<f64> = (0..=samples).map(|i| (i as f64 * self.step).min(span)).collect();
+        let values = offsets.iter().map(|&t| at(t)).collect::<Result<Vec<f64>>>()?;
+        Ok((offsets, values))
+    }
+}
+
+// Minimum of a function with a single minimum in [low, high], by golden-section search.
+pub(crate) fn minimize(mut low: f64, mut high: f64, tolerance: f64, f: impl Fn(f64) -> Result<f64>) -> Result<f64> {
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut a, mut b) = (high - ratio * (high - low), low + ratio * (high - low));
+    let (mut fa, mut fb) = (f(a)?, f(b)?);
+    while high - low > tolerance {
+        if fa < fb {
+            (high, b, fb) = (b, a, fa);
+            a = high - ratio * (high - low);
+            fa = f(a)?;
+        } else {
+            (low, a, fa) = (a, b, fb);
+            b = low + ratio * (high - low);
+            fb = f(b)?;
+        }
+    }
+    Ok((low + high) / 2.0)
+}
+
+// Zero of a function, not positive at `inside` and positive at `outside`, by bisection.
+pub(crate) fn crossing(mut inside: f64, mut outside: f64, tolerance: f64, f: impl Fn(f64) -> Result<f64>) -> Result<f64> {
+    if f(outside)? <= 0.0 {
+        return Err(Error::NoConvergence { call: "crossing", iterations: 0 });
+    }
+    while (outside - inside).abs() > tolerance {
+        let middle = (inside + outside) / 2.0;
+        if f(middle)? <= 0.0 {
+            inside = middle;
+        } else {
+            outside = middle;
+        }
+    }
+    Ok((inside + outside) / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::time::Timescale;
+
+    const PERIOD: f64 = 86400.0;
+
+    fn start() -> Time {
+        Time::new(Timescale::Tt, 2460850.5, 37, 0.0).unwrap()
+    }
+
+    // sin(2 pi t / P), with t from the start
+    fn sine(time: &Time) -> Result<f64> {
+        Ok((std::f64::consts::TAU * time.diff(&start()) / PERIOD).sin())
+    }
+
+    // cos(2 pi t / P), without zeros at the start
+    fn cosine(time: &Time) -> Result<f64> {
+        Ok((std::f64::consts::TAU * time.diff(&start()) / PERIOD).cos())
+    }
+
+    fn search(days: f64) -> Search {
+        Search::new(&start(), &start().offset(days * PERIOD).unwrap()).unwrap()
+    }
+
+    // [s] from the start
+    fn offset(time: &Time) -> f64 {
+        time.diff(&start())
+    }
+
+    #[test]
+    fn arguments_are_checked() {
+        assert!(Search::new(&start(), &start()).is_err());
+        assert!(search(1.0).with_step(0.0).is_err());
+        assert!(search(1.0).with_step(f64::NAN).is_err());
+        assert!(search(1.0).with_tolerance(-1.0).is_err());
+        assert!(crossing(0.0, 1.0, 1e-3, |t| Ok(t - 2.0)).is_err());
+    }
+
+    #[test]
+    fn crossings_and_intervals() {
+        let crossings = search(2.1).crossings(cosine).unwrap();
+        let found: Vec<(f64, bool)> = crossings.iter().map(|c| (offset(&c.time), c.rising)).collect();
+        assert_eq!(found.len(), 4);
+        for ((t, rising), (expected, up)) in found.iter().zip([(0.25, false), (0.75, true), (1.25, false), (1.75, true)]) {
+            assert!((t - expected * PERIOD).abs() <= DEFAULT_TOLERANCE, "{t}");
+            assert_eq!(*rising, up);
+        }
+        // Positive at the start, and still at the end
+        let intervals = search(2.1).with_step(1800.0).unwrap().intervals(cosine).unwrap();
+        assert_eq!(intervals.len(), 3);
+        assert_eq!(offset(&intervals[0].0), 0.0);
+        assert!((offset(&intervals[1].0) - 0.75 * PERIOD).abs() <= 1.0);
+        assert!((offset(&intervals[2].1) - 2.1 * PERIOD).abs() < 1e-6);
+    }
+
+    #[test]
+    fn extrema() {
+        let search = search(2.0).with_tolerance(0.1).unwrap();
+        let maxima: Vec<f64> = search.maxima(sine).unwrap().iter().map(offset).collect();
+        let minima: Vec<f64> = search.minima(sine).unwrap().iter().map(offset).collect();
+        assert_eq!((maxima.len(), minima.len()), (2, 2));
+        assert!((maxima[0] - 0.25 * PERIOD).abs() < 1.0 && (maxima[1] - 1.25 * PERIOD).abs() < 1.0);
+        assert!((minima[1] - 1.75 * PERIOD).abs() < 1.0);
+        assert!((minimize(0.0, 4.0, 1e-9, |x| Ok((x - 1.0) * (x - 1.0))).unwrap() - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,269 @@
+//! FK4 (B1950) to FK5 (J2000) and back, with the E-terms of aberration, after the 6×6 matrix of
+//! Standish (1982) and Aoki et al. (1983) as given in the Explanatory Supplement (1992), 3.59,
+//! and implemented by SOFA `iauFk425()`, `iauFk524()`, `iauFk45z()` and `iauFk54z()`.
+//!
+//! Angles are in radians and proper motions in radians per year; the right-ascension motion is
+//! dα/dt, without the cos(δ) factor. Parallaxes are in arcseconds and radial velocities in km/s.
+
+use super::coords::dot;
+
+// [arcsec/cy per rad/yr] Radians per year to arcseconds per century.
+const PMF: f64 = 100.0 * 206264.80624709636;
+// [AU/trop.cy per km/s] Kilometers per second to AU per tropical century.
+const VF: f64 = 21.095;
+const TINY: f64 = 1e-30;
+
+// [rad, arcsec/cy] Vector A of the E-terms of aberration and its rate.
+const A: [[f64; 3]; 2] = [[-1.62557e-6, -0.31919e-6, -0.13843e-6], [1.245e-3, -1.580e-3, -0.659e-3]];
+
+// FK4 B1950 to FK5 J2000 (Explanatory Supplement 3.591-4).
+const EM_425: [[[[f64; 3]; 2]; 3]; 2] = [
+    [
+        [[0.9999256782, -0.0111820611, -0.0048579477], [0.00000242395018, -0.00000002710663, -0.00000001177656]],
+        [[0.0111820610, 0.9999374784, -0.0000271765], [0.00000002710663, 0.00000242397878, -0.00000000006587]],
+        [[0.0048579479, -0.0000271474, 0.9999881997], [0.00000001177656, -0.00000000006582, 0.00000242410173]],
+    ],
+    [
+        [[-0.000551, -0.238565, 0.435739], [0.99994704, -0.01118251, -0.00485767]],
+        [[0.238514, -0.002667, -0.008541], [0.01118251, 0.99995883, -0.00002718]],
+        [[-0.435623, 0.012254, 0.002117], [0.00485767, -0.00002714, 1.00000956]],
+    ],
+];
+
+// FK5 J2000 to FK4 B1950 (Explanatory Supplement 3.592-1).
+const EM_524: [[[[f64; 3]; 2]; 3]; 2] = [
+    [
+        [[0.9999256795, 0.0111814828, 0.0048590039], [-0.00000242389840, -0.00000002710544, -0.00000001177742]],
+        [[-0.0111814828, 0.9999374849, -0.0000271771], [0.00000002710544, -0.00000242392702, 0.00000000006585]],
+        [[-0.0048590040, -0.0000271557, 0.9999881946], [0.00000001177742, 0.00000000006585, -0.00000242404995]],
+    ],
+    [
+        [[-0.000551, 0.238509, -0.435614], [0.99990432, 0.01118145, 0.00485852]],
+        [[-0.238560, -0.002667, 0.012254], [-0.01118145, 0.99991613, -0.00002717]],
+        [[0.435730, -0.008541, 0.002117], [-0.00485852, -0.00002716, 0.99996684]],
+    ],
+];
+
+/// A catalog position with its space motion, in the units of this module.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Star {
+    /// [rad] right ascension
+    pub ra: f64,
+    /// [rad] declination
+    pub dec: f64,
+    /// [rad/yr] dα/dt
+    pub pm_ra: f64,
+    /// [rad/yr] dδ/dt
+    pub pm_dec: f64,
+    /// [arcsec] parallax
+    pub parallax: f64,
+    /// [km/s] radial velocity
+    pub rv: f64,
+}
+
+/// FK4 B1950 (epoch and equinox) to FK5 J2000, removing the E-terms (SOFA `iauFk425()`).
+pub(super) fn fk425(star: &Star) -> Star {
+    let (r0, pxvf) = to_pv(star);
+
+    // Remove the E-terms (Explanatory Supplement 3.591-2).
+    let w = [dot(&r0[0], &A[0]), dot(&r0[0], &A[1])];
+    let pv1: [[f64; 3]; 2] = std::array::from_fn(|i| std::array::from_fn(|j| r0[i][j] - A[i][j] + w[i] * r0[0][j]));
+
+    from_pv(&rotate(&EM_425, &pv1), star, pxvf)
+}
+
+/// FK5 J2000 (epoch and equinox) to FK4 B1950, adding the E-terms (SOFA `iauFk524()`).
+pub(super) fn fk524(star: &Star) -> Star {
+    let (r0, pxvf) = to_pv(star);
+    let mut pv = rotate(&EM_524, &r0);
+
+    // Add the E-terms, with one iteration of Explanatory Supplement 3.592-3.
+    let add = |p: &[f64; 3], w: f64, a: &[f64; 3]| -> [f64; 3] {
+        let pa = dot(p, a);
+        std::array::from_fn(|i| w * a[i] - pa * p[i])
+    };
+    let p = pv[0];
+    let e = add(&p, norm(&p), &A[0]);
+    let p1: [f64; 3] = std::array::from_fn(|i| p[i] + e[i]);
+    let w = norm(&p1);
+    let e = add(&p, w, &A[0]);
+    pv[0] = std::array::from_fn(|i| p[i] + e[i]);
+    let e = add(&pv[0], w, &A[1]);
+    pv[1] = std::array::from_fn(|i| pv[1][i] + e[i]);
+
+    from_pv(&pv, star, pxvf)
+}
+
+/// FK4 B1950 position observed at Besselian epoch `bepoch` to FK5 J2000, assuming no proper
+/// motion in FK5 (SOFA `iauFk45z()`). Returns (ra, dec).
+pub(super) fn fk45z(ra: f64, dec: f64, bepoch: f64, jepoch: f64) -> (f64, f64) {
+    let r0 = spherical_to_vector(ra, dec);
+
+    // The E-terms, including their rate, to give zero proper motion in FK5.
+    let w = (bepoch - 1950.0) / PMF;
+    let a: [f64; 3] = std::array::from_fn(|i| A[0][i] + w * A[1][i]);
+    let wa = dot(&r0, &a);
+    let p: [f64; 3] = std::array::from_fn(|i| r0[i] - a[i] + wa * r0[i]);
+
+    // Position part of the FK5 pv-vector, moved by its fictitious proper motion to `jepoch`.
+    let pv: [[f64; 3]; 2] = std::array::from_fn(|i| std::array::from_fn(|j| dot(&EM_425[i][j][0], &p)));
+    let w = (jepoch - 2000.0) / PMF;
+    let v: [f64; 3] = std::array::from_fn(|i| pv[0][i] + w * pv[1][i]);
+    vector_to_spherical(&v)
+}
+
+/// FK5 J2000 position with no proper motion to FK4 B1950 as observed at Besselian epoch
+/// `bepoch` (SOFA `iauFk54z()`). Returns (ra, dec, pm_ra, pm_dec), with the fictitious FK4
+/// proper motion.
+pub(super) fn fk54z(ra: f64, dec: f64, bepoch: f64) -> (f64, f64, f64, f64) {
+    let star = fk524(&Star { ra, dec, pm_ra: 0.0, pm_dec: 0.0, parallax: 0.0, rv: 0.0 });
+    let p = spherical_to_vector(star.ra, star.dec);
+    let (sr, cr) = star.ra.sin_cos();
+    let (sd, cd) = star.dec.sin_cos();
+    let v = [
+        -star.pm_ra * p[1] - star.pm_dec * cr * sd,
+        star.pm_ra * p[0] - star.pm_dec * sr * sd,
+        star.pm_dec * cd,
+    ];
+    let w = bepoch - 1950.0;
+    let (r, d) = vector_to_spherical(&std::array::from_fn(|i| p[i] + w * v[i]));
+    (r, d, star.pm_ra, star.pm_dec)
+}
+
+// The pv-vector of a star, with velocities in arcsec per century, and the parallax times VF.
+fn to_pv(star: &Star) -> ([[f64; 3]; 2], f64) {
+    let (ur, ud) = (star.pm_ra * PMF, star.pm_dec * PMF);
+    let pxvf = star.parallax * VF;
+    let rd = star.rv * pxvf;
+    let (sr, cr) = star.ra.sin_cos();
+    let (sd, cd) = star.dec.sin_cos();
+    let p = [cr * cd, sr * cd, sd];
+    let v = [-ur * p[1] - cr * sd * ud + rd * p[0], ur * p[0] - sr * sd * ud + rd * p[1], cd * ud + rd * sd];
+    ([p, v], pxvf)
+}
+
+// The catalog form of a pv-vector, with the parallax and radial velocity of the input kept if it
+// has no parallax.
+fn from_pv(pv: &[[f64; 3]; 2], star: &Star, pxvf: f64) -> Star {
+    let [[x, y, z], [xd, yd, zd]] = *pv;
+    let rxy2 = x * x + y * y;
+    let r2 = rxy2 + z * z;
+    let rtrue = r2.sqrt();
+    // Use the direction of the velocity for a source at the pole.
+    let (x, y, z, rw) = if rtrue == 0.0 { (xd, yd, zd, (xd * xd + yd * yd + zd * zd).sqrt()) } else { (x, y, z, rtrue) };
+    let rxy2 = x * x + y * y;
+    let rxy = rxy2.sqrt();
+    let xyp = x * xd + y * yd;
+    let (ra, pm_ra, pm_dec) = if rxy2 != 0.0 {
+        (y.atan2(x), (x * yd - y * xd) / rxy2, (zd * rxy2 - z * xyp) / ((rxy2 + z * z) * rxy))
+    } else {
+        (0.0, 0.0, 0.0)
+    };
+    let dec = if rxy != 0.0 || z != 0.0 { z.atan2(rxy) } else { 0.0 };
+    let rd = if rw != 0.0 { (xyp + z * zd) / rw } else { 0.0 };
+
+    let (parallax, rv) = if star.parallax > TINY { (star.parallax / rtrue, rd / pxvf) } else { (star.parallax, star.rv) };
+    Star { ra: ra.rem_euclid(std::f64::consts::TAU), dec, pm_ra: pm_ra / PMF, pm_dec: pm_dec / PMF, parallax, rv }
+}
+
+fn rotate(em: &[[[[f64; 3]; 2]; 3]; 2], pv: &[[f64; 3]; 2]) -> [[f64; 3]; 2] {
+    std::array::from_fn(|i| std::array::from_fn(|j| dot(&em[i][j][0], &pv[0]) + dot(&em[i][j][1], &pv[1])))
+}
+
+fn norm(v: &[f64; 3]) -> f64 {
+    dot(v, v).sqrt()
+}
+
+fn spherical_to_vector(ra: f64, dec: f64) -> [f64; 3] {
+    let (sr, cr) = ra.sin_cos();
+    let (sd, cd) = dec.sin_cos();
+    [cr * cd, sr * cd, sd]
+}
+
+fn vector_to_spherical(v: &[f64; 3]) -> (f64, f64) {
+    let ra = v[1].atan2(v[0]).rem_euclid(std::f64::consts::TAU);
+    (ra, v[2].atan2(v[0].hypot(v[1])))
+}
+
+#[cfg(test)]
+#[allow(clippy::excessive_precision)]
+mod tests {
+    use super::*;
+
+    // Test cases from the SOFA test suite, t_sofa_c.c.
+
+    #[test]
+    fn fk45z_matches_sofa() {
+        let (r, d) = fk45z(0.01602284975382960982, -0.1164347929099906024, 1954.677617625256806, julian(1954.677617625256806));
+        assert!((r - 0.02719295911606862303).abs() < 1e-15);
+        assert!((d - -0.1115766001565926892).abs() < 1e-12);
+    }
+
+    #[test]
+    fn fk54z_matches_sofa() {
+        let (r, d, pr, pd) = fk54z(0.02719026625066316119, -0.1115815170738754813, 1954.677308160316374);
+        assert!((r - 0.01602015588390065476).abs() < 1e-14);
+        assert!((d - -0.1164397101110765346).abs() < 1e-13);
+        assert!((pr - -0.1175712648471090704e-7).abs() < 1e-20);
+        assert!((pd - 0.2108109051316431056e-7).abs() < 1e-20);
+    }
+
+    #[test]
+    fn fk524_matches_sofa() {
+        let star = fk524(&FK5);
+        assert!((star.ra - FK4.ra).abs() < 1e-13);
+        assert!((star.dec - FK4.dec).abs() < 1e-13);
+        assert!((star.pm_ra - FK4.pm_ra).abs() < 1e-17);
+        assert!((star.pm_dec - FK4.pm_dec).abs() < 1e-18);
+        assert!((star.parallax - FK4.parallax).abs() < 1e-13);
+        assert!((star.rv - FK4.rv).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fk425_inverts_fk524() {
+        let star = fk425(&FK4);
+        // The matrices are not exact inverses, and fk524() iterates the E-terms only once.
+        assert!((star.ra - FK5.ra).abs() < 1e-9);
+        assert!((star.dec - FK5.dec).abs() < 1e-9);
+        assert!((star.pm_ra - FK5.pm_ra).abs() < 1e-12);
+        assert!((star.pm_dec - FK5.pm_dec).abs() < 1e-12);
+        assert!((star.parallax - FK5.parallax).abs() < 1e-9);
+        assert!((star.rv - FK5.rv).abs() < 1e-6);
+    }
+
+    #[test]
+    fn matrices_are_inverse() {
+        // The velocity rows are given to fewer digits than the position rows.
+        let tolerance = [1e-9, 1e-6];
+        let product = |i: usize, j: usize, k: usize, l: usize| -> f64 {
+            (0..2).flat_map(|a| (0..3).map(move |b| (a, b))).map(|(a, b)| EM_524[i][j][a][b] * EM_425[a][b][k][l]).sum()
+        };
+        for (i, j, k, l) in (0..36).map(|n| (n / 18, n / 6 % 3, n / 3 % 2, n % 3)) {
+            let unit = if (i, j) == (k, l) { 1.0 } else { 0.0 };
+            assert!((product(i, j, k, l) - unit).abs() < tolerance[i], "element ({i}, {j}), ({k}, {l})");
+        }
+    }
+
+    const FK5: Star = Star {
+        ra: 0.8723503576487275595,
+        dec: -0.7517076365138887672,
+        pm_ra: 0.2019447755430472323e-4,
+        pm_dec: 0.3541563940505160433e-5,
+        parallax: 0.1559,
+        rv: 86.87,
+    };
+
+    const FK4: Star = Star {
+        ra: 0.8636359659799603487,
+        dec: -0.7550281733160843059,
+        pm_ra: 0.2023628192747172486e-4,
+        pm_dec: 0.3624459754935334718e-5,
+        parallax: 0.1560079963299390241,
+        rv: 86.79606353469163751,
+    };
+
+    // Julian epoch of a Besselian epoch, as SOFA iauEpb2jd() and iauEpj() compute it.
+    fn julian(bepoch: f64) -> f64 {
+        2000.0 + (2415020.31352 + (bepoch - 1900.0) * 365.242198781 - 2451545.0) / 365.25
+    }
+}
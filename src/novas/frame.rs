@@ -0,0 +1,551 @@
+use super::airmass::AirmassModel;
+use super::angle::{Degrees, Hours};
+use super::coords::Equatorial;
+use super::error::{check, Result};
+use super::observer::Observer;
+use super::redshift::Redshift;
+use super::refraction::Refraction;
+use super::source::{Planet, Source};
+use super::time::{Time, Timescale};
+use supernovas_sys as sn;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// [s] Length of the sidereal day.
+const SIDEREAL_DAY: f64 = 86164.0905;
+
+/// Accuracy of the astrometric calculations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Accuracy {
+    /// Micro-arcsecond level; requires a high-precision planet provider.
+    Full,
+    /// Milli-arcsecond level, using the built-in Earth and Sun models.
+    #[default]
+    Reduced,
+}
+
+impl Accuracy {
+    pub(crate) fn to_raw(self) -> sn::novas_accuracy {
+        match self {
+            Accuracy::Full => sn::novas_accuracy_NOVAS_FULL_ACCURACY,
+            Accuracy::Reduced => sn::novas_accuracy_NOVAS_REDUCED_ACCURACY,
+        }
+    }
+}
+
+/// Celestial coordinate reference systems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceSystem {
+    /// Geocentric Celestial Reference System
+    Gcrs,
+    /// True equator and equinox of date
+    Tod,
+    /// Celestial Intermediate Reference System
+    Cirs,
+    /// International Celestial Reference System
+    Icrs,
+    /// J2000 dynamical system
+    J2000,
+    /// Mean equator and equinox of date
+    Mod,
+    /// Terrestrial Intermediate Reference System
+    #[cfg(supernovas_1_4)]
+    Tirs,
+    /// International Terrestrial Reference System
+    #[cfg(supernovas_1_4)]
+    Itrs,
+}
+
+impl ReferenceSystem {
+    pub(crate) fn to_raw(self) -> sn::novas_reference_system {
+        match self {
+            ReferenceSystem::Gcrs => sn::novas_reference_system_NOVAS_GCRS,
+            ReferenceSystem::Tod => sn::novas_reference_system_NOVAS_TOD,
+            ReferenceSystem::Cirs => sn::novas_reference_system_NOVAS_CIRS,
+            ReferenceSystem::Icrs => sn::novas_reference_system_NOVAS_ICRS,
+            ReferenceSystem::J2000 => sn::novas_reference_system_NOVAS_J2000,
+            ReferenceSystem::Mod => sn::novas_reference_system_NOVAS_MOD,
+            #[cfg(supernovas_1_4)]
+            ReferenceSystem::Tirs => sn::novas_reference_system_NOVAS_TIRS,
+            #[cfg(supernovas_1_4)]
+            ReferenceSystem::Itrs => sn::novas_reference_system_NOVAS_ITRS,
+        }
+    }
+}
+
+/// Apparent position of a source, as returned by `novas_sky_pos`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SkyPos {
+    /// [h] right ascension
+    pub ra: f64,
+    /// [deg] declination
+    pub dec: f64,
+    /// [AU] geometric distance (very large for sources without parallax)
+    pub dist: f64,
+    /// [km/s] spectroscopic radial velocity
+    pub rv: f64,
+    /// unit vector toward the source
+    pub r_hat: [f64; 3],
+}
+
+impl SkyPos {
+    /// Observed redshift corresponding to the radial velocity.
+    pub fn redshift(&self) -> Result<Redshift> {
+        Redshift::from_velocity(self.rv)
+    }
+
+    /// The right ascension as a typed angle.
+    pub fn right_ascension(&self) -> Hours {
+        Hours(self.ra)
+    }
+
+    /// The declination as a typed angle.
+    pub fn declination(&self) -> Degrees {
+        Degrees(self.dec)
+    }
+
+    /// [deg] Angular distance to another position in the same reference system.
+    pub fn separation(&self, other: &SkyPos) -> f64 {
+        unsafe { sn::novas_equ_sep(self.ra, self.dec, other.ra, other.dec) }
+    }
+}
+
+impl From<sn::sky_pos> for SkyPos {
+    fn from(pos: sn::sky_pos) -> Self {
+        SkyPos {
+            ra: pos.ra,
+            dec: pos.dec,
+            dist: pos.dis,
+            rv: pos.rv,
+            r_hat: pos.r_hat,
+        }
+    }
+}
+
+impl From<SkyPos> for sn::sky_pos {
+    fn from(pos: SkyPos) -> Self {
+        sn::sky_pos {
+            r_hat: pos.r_hat,
+            ra: pos.ra,
+            dec: pos.dec,
+            dis: pos.dist,
+            rv: pos.rv,
+        }
+    }
+}
+
+/// Origin and direction in which azimuths are measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AzimuthConvention {
+    /// From North through East, as used by NOVAS
+    #[default]
+    NorthEast,
+    /// From South through West
+    SouthWest,
+}
+
+/// Horizontal (azimuth, elevation) position of a source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Horizontal {
+    /// [deg] azimuth, measured from North through East
+    pub az: f64,
+    /// [deg] elevation above the horizon
+    pub el: f64,
+}
+
+impl Horizontal {
+    /// A horizontal position from an azimuth [deg] measured with `convention` and an elevation
+    /// [deg].
+    pub fn from_azimuth(az: f64, el: f64, convention: AzimuthConvention) -> Self {
+        let az = match convention {
+            AzimuthConvention::NorthEast => az,
+            AzimuthConvention::SouthWest => az + 180.0,
+        };
+        Horizontal { az: az.rem_euclid(360.0), el }
+    }
+
+    /// Azimuth measured with `convention`, in [0, 360).
+    pub fn azimuth(&self, convention: AzimuthConvention) -> Degrees {
+        match convention {
+            AzimuthConvention::NorthEast => Degrees(self.az).normalized(),
+            AzimuthConvention::SouthWest => Degrees(self.az + 180.0).normalized(),
+        }
+    }
+
+    /// The elevation as a typed angle.
+    pub fn elevation(&self) -> Degrees {
+        Degrees(self.el)
+    }
+
+    /// Zenith distance.
+    pub fn zenith_distance(&self) -> Degrees {
+        Degrees(90.0 - self.el)
+    }
+
+    /// Relative airmass (Kasten & Young 1989), or `None` below the horizon. See
+    /// [`Horizontal::airmass_with`] for other formulas.
+    pub fn airmass(&self) -> Option<f64> {
+        self.airmass_with(AirmassModel::KastenYoung)
+    }
+}
+
+/// An observer place and time for which positions can be calculated.
+///
+/// The frame keeps its own copies of the observer and time, so it stays valid independently of
+/// the values it was created from.
+#[derive(Debug, Clone)]
+pub struct ObservingFrame {
+    raw: Box<sn::novas_frame>,
+}
+
+impl ObservingFrame {
+    /// Creates a frame. `dx` and `dy` are the polar offsets [mas] from IERS Bulletin A.
+    pub fn new(accuracy: Accuracy, observer: &Observer, time: &Time, dx: f64, dy: f64) -> Result<Self> {
+        let mut raw = Box::<sn::novas_frame>::default();
+        check("novas_make_frame", || unsafe {
+            sn::novas_make_frame(accuracy.to_raw(), observer.as_raw(), time.as_raw(), dx, dy, &mut *raw)
+        })?;
+        Ok(ObservingFrame { raw })
+    }
+
+    pub fn as_raw(&self) -> &sn::novas_frame {
+        &self.raw
+    }
+
+    /// The time of observation.
+    pub fn time(&self) -> Time {
+        Time::from_raw(self.raw.time)
+    }
+
+    /// The observer of the frame.
+    pub fn observer(&self) -> Observer {
+        Observer::from_raw(self.raw.observer)
+    }
+
+    /// Replaces the bodies whose gravitational deflection of light is applied to positions in
+    /// this frame. By default these are the Sun in reduced accuracy, and the Sun, Jupiter and
+    /// Saturn in full accuracy. Fails if the planet provider has no position for a body.
+    pub fn with_deflecting_bodies(mut self, bodies: &[Planet]) -> Result<Self> {
+        let mask = bodies.iter().fold(0, |mask, body| mask | (1 << body.to_raw()));
+        let jd_tdb = self.time().jd(Timescale::Tdb);
+        let raw = &mut *self.raw;
+        check("obs_planets", || unsafe {
+            sn::obs_planets(jd_tdb, raw.accuracy, raw.obs_pos.as_ptr(), mask, &mut raw.planets)
+        })?;
+        Ok(self)
+    }
+
+    /// Bodies whose gravitational deflection is applied to positions in this frame.
+    pub fn deflecting_bodies(&self) -> Vec<Planet> {
+        let mask = self.raw.planets.mask;
+        Planet::ALL.into_iter().filter(|body| mask & (1 << body.to_raw()) != 0).collect()
+    }
+
+    /// Local apparent sidereal time of the observer.
+    pub fn local_sidereal_time(&self) -> Hours {
+        Hours(unsafe { sn::novas_frame_lst(&*self.raw) })
+    }
+
+    /// Calculates the apparent position of a source in the given reference system.
+    pub fn sky_pos(&self, source: &Source, system: ReferenceSystem) -> Result<SkyPos> {
+        let mut pos = sn::sky_pos::default();
+        check("novas_sky_pos", || unsafe {
+            sn::novas_sky_pos(source.as_raw(), &*self.raw, system.to_raw(), &mut pos)
+        })?;
+        Ok(pos.into())
+    }
+
+    /// Apparent positions of many sources in the given reference system. The precession, nutation
+    /// and observer state of the frame are computed once, when it is created, and shared by all
+    /// sources, which are computed in parallel with the `rayon` feature. Fails if the position of
+    /// any source cannot be calculated.
+    pub fn sky_positions(&self, sources: &[Source], system: ReferenceSystem) -> Result<Vec<SkyPos>> {
+        #[cfg(not(feature = "rayon"))]
+        let sources = sources.iter();
+        #[cfg(feature = "rayon")]
+        let sources = sources.par_iter();
+        sources.map(|source| self.sky_pos(source, system)).collect()
+    }
+
+    /// Transformation between two reference systems at the time of this frame.
+    pub fn transform(&self, from: ReferenceSystem, to: ReferenceSystem) -> Result<Transform> {
+        let mut raw = sn::novas_transform::default();
+        check("novas_make_transform", || unsafe {
+            sn::novas_make_transform(&*self.raw, from.to_raw(), to.to_raw(), &mut raw)
+        })?;
+        Ok(Transform { raw, from, to })
+    }
+
+    /// Converts an apparent position from one reference system to another.
+    pub fn convert(&self, pos: &SkyPos, from: ReferenceSystem, to: ReferenceSystem) -> Result<SkyPos> {
+        self.transform(from, to)?.sky_pos(pos)
+    }
+
+    /// Converts an apparent position in `system` to horizontal coordinates, applying a refraction
+    /// model (`true` for the standard atmosphere). Only for observers on Earth's surface.
+    pub fn to_horizontal(&self, pos: &SkyPos, system: ReferenceSystem, refraction: impl Into<Refraction>) -> Result<Horizontal> {
+        let (mut az, mut el) = (0.0, 0.0);
+        check("novas_app_to_hor", || refraction.into().with_model(|model| unsafe {
+            sn::novas_app_to_hor(&*self.raw, system.to_raw(), pos.ra, pos.dec, model, &mut az, &mut el)
+        }))?;
+        Ok(Horizontal { az, el })
+    }
+
+    /// Apparent coordinates in `system` of a horizontal position. `refraction` is the refraction
+    /// model included in the elevation (`true` for the standard atmosphere).
+    pub fn from_horizontal(&self, hor: &Horizontal, system: ReferenceSystem, refraction: impl Into<Refraction>) -> Result<Equatorial> {
+        let mut pos = Equatorial::new(0.0, 0.0);
+        check("novas_hor_to_app", || refraction.into().with_model(|model| unsafe {
+            sn::novas_hor_to_app(&*self.raw, hor.az, hor.el, model, system.to_raw(), &mut pos.ra, &mut pos.dec)
+        }))?;
+        Ok(pos)
+    }
+
+    /// ICRS catalog coordinates of a distant source seen at apparent coordinates in `system`,
+    /// undoing aberration and gravitational deflection.
+    pub fn apparent_to_icrs(&self, pos: &Equatorial, system: ReferenceSystem) -> Result<Equatorial> {
+        let mut geom = [0.0; 3];
+        check("novas_app_to_geom", || unsafe {
+            sn::novas_app_to_geom(&*self.raw, system.to_raw(), pos.ra, pos.dec, 0.0, geom.as_mut_ptr())
+        })?;
+        Ok(Equatorial::from_vector(&geom))
+    }
+
+    /// Next time, within a day of the frame time, at which the source rises above `el` degrees of
+    /// elevation, or `None` if it stays above or below it for the whole day.
+    pub fn rises_above(&self, el: f64, source: &Source, refraction: impl Into<Refraction>) -> Option<Time> {
+        let jd = refraction.into().with_model(|model| unsafe { sn::novas_rises_above(el, source.as_raw(), &*self.raw, model) });
+        self.utc_time(jd)
+    }
+
+    /// Next time, within a day of the frame time, at which the source sets below `el` degrees of
+    /// elevation, or `None` if it stays above or below it for the whole day.
+    pub fn sets_below(&self, el: f64, source: &Source, refraction: impl Into<Refraction>) -> Option<Time> {
+        let jd = refraction.into().with_model(|model| unsafe { sn::novas_sets_below(el, source.as_raw(), &*self.raw, model) });
+        self.utc_time(jd)
+    }
+
+    /// Next rise above and set below `el` degrees of elevation of many sources, as in
+    /// [`ObservingFrame::rises_above`] and [`ObservingFrame::sets_below`]. The sources are
+    /// searched in parallel with the `rayon` feature.
+    pub fn rises_and_sets(&self, el: f64, sources: &[Source], refraction: impl Into<Refraction>) -> Vec<(Option<Time>, Option<Time>)> {
+        let refraction = refraction.into();
+        #[cfg(not(feature = "rayon"))]
+        let sources = sources.iter();
+        #[cfg(feature = "rayon")]
+        let sources = sources.par_iter();
+        sources
+            .map(|source| (self.rises_above(el, source, refraction.clone()), self.sets_below(el, source, refraction.clone())))
+            .collect()
+    }
+
+    /// Next time at which the source transits the local meridian, or `None` if it cannot be
+    /// calculated, e.g. for observers that are not on Earth or sources without positions.
+    pub fn transit_time(&self, source: &Source) -> Option<Time> {
+        let jd = unsafe { sn::novas_transit_time(source.as_raw(), &*self.raw) };
+        let transit = self.utc_time(jd)?;
+        // `novas_transit_time()` moves transits before the frame time ahead by a solar rather
+        // than a sidereal day, and returns those of catalog sources without refining them, which
+        // leaves them 3m56s late. A step on the hour angle at the returned time corrects them.
+        let hour_angle = self.at(&transit).ok()?.hour_angle(source).ok()?;
+        let transit = transit.offset(-hour_angle.0 / 24.0 * SIDEREAL_DAY).ok()?;
+        match transit.diff(&self.time()) < 0.0 {
+            true => transit.offset(SIDEREAL_DAY).ok(),
+            false => Some(transit),
+        }
+    }
+
+    // The same observer, accuracy and polar offsets at another time.
+    fn at(&self, time: &Time) -> Result<ObservingFrame> {
+        let mut raw = Box::<sn::novas_frame>::default();
+        check("novas_make_frame", || unsafe {
+            sn::novas_make_frame(self.raw.accuracy, &self.raw.observer, time.as_raw(), self.raw.dx, self.raw.dy, &mut *raw)
+        })?;
+        Ok(ObservingFrame { raw })
+    }
+
+    // Time at a UTC Julian date, with the leap seconds and UT1 - UTC of the frame.
+    fn utc_time(&self, jd_utc: f64) -> Option<Time> {
+        if jd_utc.is_nan() {
+            return None;
+        }
+        let time = self.time();
+        time.offset((jd_utc - time.jd(Timescale::Utc)) * sn::NOVAS_DAY).ok()
+    }
+}
+
+/// A transformation between two reference systems, created with [`ObservingFrame::transform`].
+#[derive(Debug, Clone, Copy)]
+pub struct Transform {
+    raw: sn::novas_transform,
+    from: ReferenceSystem,
+    to: ReferenceSystem,
+}
+
+impl Transform {
+    /// The reference system transformed from.
+    pub fn from_system(&self) -> ReferenceSystem {
+        self.from
+    }
+
+    /// The reference system transformed to.
+    pub fn to_system(&self) -> ReferenceSystem {
+        self.to
+    }
+
+    /// The reverse transformation.
+    pub fn inverse(&self) -> Result<Transform> {
+        let mut raw = sn::novas_transform::default();
+        check("novas_invert_transform", || unsafe { sn::novas_invert_transform(&self.raw, &mut raw) })?;
+        Ok(Transform { raw, from: self.to, to: self.from })
+    }
+
+    /// Transforms a position or velocity vector.
+    pub fn vector(&self, v: &[f64; 3]) -> Result<[f64; 3]> {
+        let mut out = [0.0; 3];
+        check("novas_transform_vector", || unsafe {
+            sn::novas_transform_vector(v.as_ptr(), &self.raw, out.as_mut_ptr())
+        })?;
+        Ok(out)
+    }
+
+    /// Transforms an apparent position.
+    pub fn sky_pos(&self, pos: &SkyPos) -> Result<SkyPos> {
+        let mut out = sn::sky_pos::default();
+        check("novas_transform_sky_pos", || unsafe {
+            sn::novas_transform_sky_pos(&(*pos).into(), &self.raw, &mut out)
+        })?;
+        Ok(out.into())
+    }
+
+    pub fn as_raw(&self) -> &sn::novas_transform {
+        &self.raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> ObservingFrame {
+        frame_at(2460850.5)
+    }
+
+    fn frame_at(jd_utc: f64) -> ObservingFrame {
+        let observer = Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap();
+        let time = Time::new(Timescale::Utc, jd_utc, 37, 0.035).unwrap();
+        ObservingFrame::new(Accuracy::Reduced, &observer, &time, 142.0, 443.05).unwrap()
+    }
+
+    fn vega() -> Source {
+        Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap()
+    }
+
+    #[test]
+    fn azimuth_conventions() {
+        let hor = Horizontal::from_azimuth(10.0, 20.0, AzimuthConvention::SouthWest);
+        assert_eq!(hor.az, 190.0);
+        assert_eq!(hor.azimuth(AzimuthConvention::SouthWest), Degrees(10.0));
+        assert_eq!(hor.elevation(), Degrees(20.0));
+        assert_eq!(Horizontal::from_azimuth(-10.0, 20.0, AzimuthConvention::NorthEast).az, 350.0);
+        assert_eq!(hor.zenith_distance(), Degrees(70.0));
+        assert!((Horizontal { az: 0.0, el: 90.0 }.airmass().unwrap() - 1.0).abs() < 1e-3);
+        assert!(Horizontal { az: 0.0, el: -1.0 }.airmass().is_none());
+    }
+
+    #[test]
+    fn frames_keep_their_time_and_observer() {
+        let frame = frame();
+        assert_eq!(frame.time().jd(Timescale::Utc), 2460850.5);
+        assert_eq!(frame.observer().geodetic().unwrap().latitude, 43.4712);
+        assert!((0.0..24.0).contains(&frame.local_sidereal_time().0));
+        assert_eq!(frame.deflecting_bodies(), vec![Planet::Sun]);
+    }
+
+    #[test]
+    fn positions_convert_between_systems() {
+        let frame = frame();
+        let tod = frame.sky_pos(&vega(), ReferenceSystem::Tod).unwrap();
+        let cirs = frame.convert(&tod, ReferenceSystem::Tod, ReferenceSystem::Cirs).unwrap();
+        let direct = frame.sky_pos(&vega(), ReferenceSystem::Cirs).unwrap();
+        assert!(cirs.separation(&direct) < 1e-9);
+        // The CIO and the true equinox are about 1.3 min of time apart in 2025
+        assert!((tod.ra - cirs.ra).abs() * 60.0 > 1.0);
+        let transform = frame.transform(ReferenceSystem::Icrs, ReferenceSystem::Tod).unwrap();
+        let v = transform.inverse().unwrap().vector(&transform.vector(&[0.6, 0.0, 0.8]).unwrap()).unwrap();
+        assert!((v[0] - 0.6).abs() < 1e-12 && v[1].abs() < 1e-12 && (v[2] - 0.8).abs() < 1e-12);
+        assert_eq!(transform.inverse().unwrap().to_system(), ReferenceSystem::Icrs);
+        let both = frame.sky_positions(&[vega(), vega()], ReferenceSystem::Tod).unwrap();
+        assert_eq!(both, vec![tod, tod]);
+    }
+
+    #[test]
+    fn apparent_positions_undo_to_catalog() {
+        let frame = frame();
+        let cirs = frame.sky_pos(&vega(), ReferenceSystem::Cirs).unwrap();
+        let icrs = frame.apparent_to_icrs(&Equatorial::new(cirs.ra, cirs.dec), ReferenceSystem::Cirs).unwrap();
+        // [arcsec]
+        let dra = (icrs.ra - 18.6156) * 15.0 * 38.7836f64.to_radians().cos() * 3600.0;
+        let ddec = (icrs.dec - 38.7836) * 3600.0;
+        assert!(dra.hypot(ddec) < 1e-3, "{dra} {ddec}");
+    }
+
+    #[test]
+    fn horizontal_round_trips() {
+        let frame = frame();
+        let tod = frame.sky_pos(&vega(), ReferenceSystem::Tod).unwrap();
+        for refraction in [Refraction::None, Refraction::Standard] {
+            let hor = frame.to_horizontal(&tod, ReferenceSystem::Tod, refraction.clone()).unwrap();
+            let back = frame.from_horizontal(&hor, ReferenceSystem::Tod, refraction).unwrap();
+            assert!((back.ra - tod.ra).abs() * 15.0 * 3600.0 < 1e-3);
+            assert!((back.dec - tod.dec).abs() * 3600.0 < 1e-3);
+        }
+        let observed = frame.to_horizontal(&tod, ReferenceSystem::Tod, Refraction::Standard).unwrap();
+        let geometric = frame.to_horizontal(&tod, ReferenceSystem::Tod, Refraction::None).unwrap();
+        assert!(observed.el > geometric.el);
+    }
+
+    #[test]
+    fn transits_on_the_meridian() {
+        // Vega is east of the meridian at 12h UTC, and the other stars transited 2h and 36s
+        // earlier. `novas_transit_time()` puts their next transits 3m56s late.
+        let frame = frame_at(2460851.0);
+        let west = |hours: f64| {
+            let tod = Equatorial::from_angles(frame.local_sidereal_time() - Hours(hours), Degrees(20.0));
+            let icrs = frame.apparent_to_icrs(&tod, ReferenceSystem::Tod).unwrap();
+            Source::star("west", icrs.ra, icrs.dec, "ICRS").unwrap()
+        };
+        for source in [vega(), west(2.0), west(0.01), Source::planet(Planet::Sun).unwrap()] {
+            let transit = frame.transit_time(&source).unwrap();
+            let days = transit.diff(&frame.time()) / 86400.0;
+            assert!((0.0..1.0).contains(&days), "{}: {days}", source.name());
+            let at = ObservingFrame::new(Accuracy::Reduced, &frame.observer(), &transit, 142.0, 443.05).unwrap();
+            let tod = at.sky_pos(&source, ReferenceSystem::Tod).unwrap();
+            let hour_angle = (at.local_sidereal_time() - tod.right_ascension()).wrapped();
+            assert!(hour_angle.0.abs() * 3600.0 < 1.0, "{}: {hour_angle:?}", source.name());
+        }
+        let geocenter = ObservingFrame::new(Accuracy::Reduced, &Observer::at_geocenter().unwrap(), &frame.time(), 0.0, 0.0).unwrap();
+        assert!(geocenter.transit_time(&vega()).is_none());
+    }
+
+    #[test]
+    fn batches_match_single_sources() {
+        let frame = frame();
+        let sources = [vega(), Source::star("Polaris", 2.5303, 89.2641, "ICRS").unwrap(), Source::planet(Planet::Sun).unwrap()];
+        let positions = frame.sky_positions(&sources, ReferenceSystem::Tod).unwrap();
+        for (source, pos) in sources.iter().zip(&positions) {
+            assert_eq!(*pos, frame.sky_pos(source, ReferenceSystem::Tod).unwrap());
+        }
+        let events = frame.rises_and_sets(0.0, &sources, false);
+        assert_eq!(events.len(), 3);
+        // Circumpolar at 43 N
+        assert!(events[1].0.is_none() && events[1].1.is_none());
+        for (source, (rise, set)) in sources.iter().zip(&events) {
+            assert_eq!(rise.map(|t| t.jd(Timescale::Utc)), frame.rises_above(0.0, source, false).map(|t| t.jd(Timescale::Utc)));
+            assert_eq!(set.map(|t| t.jd(Timescale::Utc)), frame.sets_below(0.0, source, false).map(|t| t.jd(Timescale::Utc)));
+        }
+        assert!(events[2].0.is_some() && events[2].1.is_some());
+    }
+}
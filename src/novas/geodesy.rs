@@ -0,0 +1,147 @@
+//! Geodetic coordinates on reference ellipsoids, and their conversion to and from geocentric
+//! Cartesian (ITRF) coordinates.
+//!
+//! NOVAS places observers on the IERS Conventions (2003) ellipsoid; geodetic surveys and GNSS
+//! receivers give WGS84 or GRS80 coordinates, which differ in height by a fraction of a meter.
+//! [`Observer::from_geodetic`](super::Observer::from_geodetic) converts between the two through
+//! the ITRF position.
+
+/// Reference ellipsoid of geodetic coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Ellipsoid {
+    /// World Geodetic System 1984, that of GPS
+    #[default]
+    Wgs84,
+    /// Geodetic Reference System 1980, that of the ITRF and ETRS89 realizations
+    Grs80,
+    /// IERS Conventions (2003), used by NOVAS for observers on Earth
+    Iers2003,
+}
+
+impl Ellipsoid {
+    /// [m] Equatorial radius.
+    pub fn equatorial_radius(self) -> f64 {
+        match self {
+            Ellipsoid::Wgs84 | Ellipsoid::Grs80 => 6378137.0,
+            Ellipsoid::Iers2003 => 6378136.6,
+        }
+    }
+
+    pub fn flattening(self) -> f64 {
+        match self {
+            Ellipsoid::Wgs84 => 1.0 / 298.257223563,
+            Ellipsoid::Grs80 => 1.0 / 298.257222101,
+            Ellipsoid::Iers2003 => 1.0 / 298.25642,
+        }
+    }
+
+    // Squared eccentricity.
+    fn e2(self) -> f64 {
+        let f = self.flattening();
+        f * (2.0 - f)
+    }
+}
+
+/// Geodetic coordinates on a reference ellipsoid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Geodetic {
+    /// [deg] geodetic latitude, north positive
+    pub latitude: f64,
+    /// [deg] longitude, east positive
+    pub longitude: f64,
+    /// [m] height above the ellipsoid
+    pub height: f64,
+}
+
+impl Geodetic {
+    pub fn new(latitude: f64, longitude: f64, height: f64) -> Self {
+        Geodetic { latitude, longitude, height }
+    }
+
+    /// [m] Geocentric Cartesian (ITRF) position of coordinates on `ellipsoid`.
+    pub fn to_itrf(&self, ellipsoid: Ellipsoid) -> [f64; 3] {
+        let e2 = ellipsoid.e2();
+        let (sin_lat, cos_lat) = self.latitude.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = self.longitude.to_radians().sin_cos();
+        // Radius of curvature in the prime vertical
+        let n = ellipsoid.equatorial_radius() / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+        let axial = (n + self.height) * cos_lat;
+        [axial * cos_lon, axial * sin_lon, (n * (1.0 - e2) + self.height) * sin_lat]
+    }
+
+    /// Coordinates on `ellipsoid` of a geocentric Cartesian (ITRF) position [m]. Longitudes are
+    /// in (-180, 180].
+    pub fn from_itrf(position: &[f64; 3], ellipsoid: Ellipsoid) -> Self {
+        let [x, y, z] = *position;
+        let a = ellipsoid.equatorial_radius();
+        let e2 = ellipsoid.e2();
+        let p = x.hypot(y);
+        let longitude = y.atan2(x).to_degrees();
+
+        if p < 1e-3 {
+            let b = a * (1.0 - ellipsoid.flattening());
+            return Geodetic::new(90.0f64.copysign(z), longitude, z.abs() - b);
+        }
+
+        let mut lat = z.atan2(p * (1.0 - e2));
+        let mut height = 0.0;
+        for _ in 0..10 {
+            let n = a / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+            height = p / lat.cos() - n;
+            let next = z.atan2(p * (1.0 - e2 * n / (n + height)));
+            let done = (next - lat).abs() < 1e-14;
+            lat = next;
+            if done {
+                break;
+            }
+        }
+        Geodetic::new(lat.to_degrees(), longitude, height)
+    }
+
+    /// The same point, given on the ellipsoid `from`, as coordinates on the ellipsoid `to`.
+    pub fn on_ellipsoid(&self, from: Ellipsoid, to: Ellipsoid) -> Self {
+        if from == to {
+            return *self;
+        }
+        Geodetic::from_itrf(&self.to_itrf(from), to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn itrf_round_trips() {
+        for ellipsoid in [Ellipsoid::Wgs84, Ellipsoid::Grs80, Ellipsoid::Iers2003] {
+            for geodetic in [Geodetic::new(43.4712, 87.1781, 2080.0), Geodetic::new(-89.99, -120.0, -30.0), Geodetic::new(0.0, 180.0, 0.0)] {
+                let back = Geodetic::from_itrf(&geodetic.to_itrf(ellipsoid), ellipsoid);
+                assert!((back.latitude - geodetic.latitude).abs() < 1e-10);
+                assert!((back.longitude - geodetic.longitude).abs() < 1e-10);
+                assert!((back.height - geodetic.height).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn axes_and_poles() {
+        let [x, y, z] = Geodetic::new(0.0, 90.0, 100.0).to_itrf(Ellipsoid::Wgs84);
+        assert!(x.abs() < 1e-6 && (y - 6378237.0).abs() < 1e-6 && z.abs() < 1e-9);
+        // Polar radius of WGS84
+        let pole = Geodetic::from_itrf(&[0.0, 0.0, -6356752.3142], Ellipsoid::Wgs84);
+        assert_eq!(pole.latitude, -90.0);
+        assert!(pole.height.abs() < 1e-3);
+    }
+
+    #[test]
+    fn ellipsoids_differ_by_centimeters_to_meters() {
+        let site = Geodetic::new(43.4712, 87.1781, 2080.0);
+        assert_eq!(site.on_ellipsoid(Ellipsoid::Wgs84, Ellipsoid::Wgs84), site);
+        let grs80 = site.on_ellipsoid(Ellipsoid::Wgs84, Ellipsoid::Grs80);
+        assert!((grs80.height - site.height).abs() < 1e-3);
+        let iers = site.on_ellipsoid(Ellipsoid::Wgs84, Ellipsoid::Iers2003);
+        assert!((iers.height - site.height).abs() > 0.1 && (iers.height - site.height).abs() < 1.0);
+        assert!((iers.latitude - site.latitude).abs() < 1e-6);
+    }
+}
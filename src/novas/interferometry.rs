@@ -0,0 +1,212 @@
+//! Baseline coordinates (u, v, w) of radio interferometers.
+//!
+//! The baselines of an [`Interferometer`] are projected on the plane of the sky at the phase
+//! center: `w` points to the phase center, `v` toward the celestial pole of date and `u` to the
+//! east. The phase center is at its apparent position seen from the geocenter; the axes are
+//! rotated to the ITRS, the frame of the antenna positions, with the Earth orientation of the
+//! [`Time`] (UT1) and the polar offsets.
+
+use super::coords::dot;
+use super::error::{Error, Result};
+use super::frame::{Accuracy, ObservingFrame, ReferenceSystem};
+use super::observer::Observer;
+use super::source::Source;
+use super::time::Time;
+use supernovas_sys as sn;
+
+/// Baseline coordinates, in meters or wavelengths.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Uvw {
+    pub u: f64,
+    pub v: f64,
+    pub w: f64,
+}
+
+impl Uvw {
+    /// Coordinates in meters converted to wavelengths at `frequency` [Hz].
+    pub fn wavelengths(&self, frequency: f64) -> Uvw {
+        let scale = frequency / sn::NOVAS_C;
+        Uvw { u: self.u * scale, v: self.v * scale, w: self.w * scale }
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<Uvw> for nalgebra::Vector3<f64> {
+    fn from(uvw: Uvw) -> Self {
+        nalgebra::Vector3::new(uvw.u, uvw.v, uvw.w)
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::Vector3<f64>> for Uvw {
+    fn from(v: nalgebra::Vector3<f64>) -> Self {
+        Uvw { u: v[0], v: v[1], w: v[2] }
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<Uvw> for glam::DVec3 {
+    fn from(uvw: Uvw) -> Self {
+        glam::DVec3::new(uvw.u, uvw.v, uvw.w)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::DVec3> for Uvw {
+    fn from(v: glam::DVec3) -> Self {
+        Uvw { u: v.x, v: v.y, w: v.z }
+    }
+}
+
+/// Baseline from antenna `first` to antenna `second`, by their index in the interferometer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Baseline {
+    pub first: usize,
+    pub second: usize,
+    /// [m] coordinates of the position of `second` relative to `first`
+    pub uvw: Uvw,
+}
+
+/// An array of antennas at fixed ITRF positions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interferometer {
+    antennas: Vec<[f64; 3]>,
+    dx: f64,
+    dy: f64,
+}
+
+impl Interferometer {
+    /// An interferometer with antennas at ITRF positions [m].
+    pub fn new(antennas: Vec<[f64; 3]>) -> Result<Self> {
+        if antennas.len() < 2 {
+            return Err(Error::InvalidArgument("interferometer needs at least two antennas"));
+        }
+        if antennas.iter().flatten().any(|c| !c.is_finite()) {
+            return Err(Error::InvalidArgument("ITRF position is not finite"));
+        }
+        Ok(Interferometer { antennas, dx: 0.0, dy: 0.0 })
+    }
+
+    /// Sets the polar offsets [mas] from IERS Bulletin A, which rotate the ITRS.
+    pub fn with_polar_motion(mut self, dx: f64, dy: f64) -> Self {
+        self.dx = dx;
+        self.dy = dy;
+        self
+    }
+
+    /// [m] ITRF positions of the antennas.
+    pub fn antennas(&self) -> &[[f64; 3]] {
+        &self.antennas
+    }
+
+    /// [m] Coordinates of an ITRF baseline vector toward `phase_center` at `time`.
+    pub fn uvw(&self, baseline: &[f64; 3], phase_center: &Source, time: &Time) -> Result<Uvw> {
+        let axes = self.axes(phase_center, time)?;
+        Ok(project(&axes, baseline))
+    }
+
+    /// All the baselines between pairs of antennas, toward `phase_center` at `time`, with
+    /// `first < second`.
+    pub fn baselines(&self, phase_center: &Source, time: &Time) -> Result<Vec<Baseline>> {
+        let axes = self.axes(phase_center, time)?;
+        let mut baselines = Vec::with_capacity(self.antennas.len() * (self.antennas.len() - 1) / 2);
+        for (first, a) in self.antennas.iter().enumerate() {
+            for (second, b) in self.antennas.iter().enumerate().skip(first + 1) {
+                let baseline = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+                baselines.push(Baseline { first, second, uvw: project(&axes, &baseline) });
+            }
+        }
+        Ok(baselines)
+    }
+
+    // Unit vectors of u, v and w in the ITRS, from the true equator of date.
+    fn axes(&self, phase_center: &Source, time: &Time) -> Result<[[f64; 3]; 3]> {
+        let frame = ObservingFrame::new(Accuracy::Reduced, &Observer::at_geocenter()?, time, self.dx, self.dy)?;
+        let w = frame.sky_pos(phase_center, ReferenceSystem::Tod)?.r_hat;
+        let norm = w[0].hypot(w[1]);
+        if norm == 0.0 {
+            return Err(Error::InvalidArgument("phase center at the celestial pole"));
+        }
+        let u = [-w[1] / norm, w[0] / norm, 0.0];
+        let v = [-w[2] * u[1], w[2] * u[0], w[0] * u[1] - w[1] * u[0]];
+        let to_itrs = frame.transform(ReferenceSystem::Tod, ReferenceSystem::Itrs)?;
+        Ok([to_itrs.vector(&u)?, to_itrs.vector(&v)?, to_itrs.vector(&w)?])
+    }
+}
+
+fn project(axes: &[[f64; 3]; 3], baseline: &[f64; 3]) -> Uvw {
+    Uvw { u: dot(&axes[0], baseline), v: dot(&axes[1], baseline), w: dot(&axes[2], baseline) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::time::Timescale;
+
+    fn vega() -> Source {
+        Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap()
+    }
+
+    fn time() -> Time {
+        Time::new(Timescale::Utc, 2460850.5, 37, 0.035).unwrap()
+    }
+
+    fn length(uvw: &Uvw) -> f64 {
+        (uvw.u * uvw.u + uvw.v * uvw.v + uvw.w * uvw.w).sqrt()
+    }
+
+    #[test]
+    fn arrays_need_two_finite_antennas() {
+        assert!(Interferometer::new(vec![[0.0; 3]]).is_err());
+        assert!(Interferometer::new(vec![[0.0; 3], [f64::NAN, 0.0, 0.0]]).is_err());
+        let array = Interferometer::new(vec![[0.0; 3], [1.0, 0.0, 0.0]]).unwrap().with_polar_motion(0.1, 0.2);
+        assert_eq!(array.antennas().len(), 2);
+    }
+
+    #[test]
+    fn baselines_rotate_rigidly() {
+        let antennas = vec![[-1.0e3, 0.0, 0.0], [0.0, 2.0e3, 0.0], [0.0, 0.0, 3.0e3], [5.0e2, 5.0e2, 5.0e2]];
+        let array = Interferometer::new(antennas.clone()).unwrap();
+        let baselines = array.baselines(&vega(), &time()).unwrap();
+        assert_eq!(baselines.len(), 6);
+        assert_eq!((baselines[0].first, baselines[0].second), (0, 1));
+        for b in &baselines {
+            let (a, c) = (antennas[b.first], antennas[b.second]);
+            let itrf = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            assert!((length(&b.uvw) - itrf.iter().map(|x| x * x).sum::<f64>().sqrt()).abs() < 1e-6);
+        }
+        // 0 -> 2 = 0 -> 1 + 1 -> 2
+        let sum = baselines[0].uvw.u + baselines[3].uvw.u;
+        assert!((baselines[1].uvw.u - sum).abs() < 1e-6);
+    }
+
+    #[test]
+    fn polar_baselines_lie_along_v_and_w() {
+        let array = Interferometer::new(vec![[0.0; 3], [0.0, 0.0, 1.0e3]]).unwrap();
+        let uvw = array.uvw(&[0.0, 0.0, 1.0e3], &vega(), &time()).unwrap();
+        let dec = 38.7836f64.to_radians();
+        assert!(uvw.u.abs() < 1.0);
+        assert!((uvw.v - 1.0e3 * dec.cos()).abs() < 1.0);
+        assert!((uvw.w - 1.0e3 * dec.sin()).abs() < 1.0);
+        let lambda = uvw.wavelengths(sn::NOVAS_C / 0.21);
+        assert!((lambda.v - uvw.v / 0.21).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn nalgebra_vectors() {
+        let uvw = Uvw { u: 1.0, v: -2.0, w: 3.5 };
+        let v = nalgebra::Vector3::from(uvw);
+        assert_eq!((v[0], v[1], v[2]), (1.0, -2.0, 3.5));
+        assert_eq!(Uvw::from(v), uvw);
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn glam_vectors() {
+        let uvw = Uvw { u: 1.0, v: -2.0, w: 3.5 };
+        assert_eq!(glam::DVec3::from(uvw), glam::DVec3::new(1.0, -2.0, 3.5));
+        assert_eq!(Uvw::from(glam::DVec3::from(uvw)), uvw);
+    }
+}
@@ -0,0 +1,267 @@
+//! Julian dates, modified Julian dates and calendar dates.
+//!
+//! Dates carry no timescale: a Julian date in TT converts to a calendar date in TT. See
+//! [`Time`](super::Time) for instants, and [`Time::date`](super::Time::date) for their calendar
+//! date in a timescale.
+
+use super::eop::MJD0;
+use super::error::{Error, Result};
+use std::fmt;
+use std::ops::{Add, Sub};
+use supernovas_sys as sn;
+
+/// Calendar of the year, month and day of a date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Calendar {
+    /// Julian (Roman) calendar before 15 October 1582 and Gregorian calendar from then on, as
+    /// the dates of the astronomical literature
+    #[default]
+    Astronomical,
+    /// Proleptic Gregorian calendar, that of ISO 8601
+    Gregorian,
+    /// Proleptic Julian (Roman) calendar
+    Roman,
+}
+
+impl Calendar {
+    pub(crate) fn to_raw(self) -> sn::novas_calendar_type {
+        match self {
+            Calendar::Astronomical => sn::novas_calendar_type_NOVAS_ASTRONOMICAL_CALENDAR,
+            Calendar::Gregorian => sn::novas_calendar_type_NOVAS_GREGORIAN_CALENDAR,
+            Calendar::Roman => sn::novas_calendar_type_NOVAS_ROMAN_CALENDAR,
+        }
+    }
+}
+
+/// A date and time of day in a calendar. Years before 1 AD are counted astronomically: 0 is
+/// 1 BC, -1 is 2 BC.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalendarDate {
+    pub year: i32,
+    /// [1, 12]
+    pub month: i32,
+    /// [1, 31]
+    pub day: i32,
+    /// [h] time of day, in [0, 24)
+    pub hour: f64,
+}
+
+impl CalendarDate {
+    pub fn new(year: i32, month: i32, day: i32, hour: f64) -> Self {
+        CalendarDate { year, month, day, hour }
+    }
+
+    /// Hours, minutes and seconds of the time of day.
+    pub fn hms(&self) -> (i32, i32, f64) {
+        let seconds = self.hour * 3600.0;
+        let hours = (seconds / 3600.0).floor();
+        let minutes = ((seconds - hours * 3600.0) / 60.0).floor();
+        (hours as i32, minutes as i32, seconds - hours * 3600.0 - minutes * 60.0)
+    }
+}
+
+impl fmt::Display for CalendarDate {
+    /// Formats the date as ISO 8601, e.g. "2025-06-24T12:29:36.000", with the precision of the
+    /// formatter as the decimals of the seconds (default 3). The seconds are truncated, so that
+    /// they never show as 60; see [`JulianDate::iso`] for rounded timestamps.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimals = f.precision().unwrap_or(3);
+        let (hours, minutes, seconds) = self.hms();
+        let scale = 10f64.powi(decimals as i32);
+        let seconds = (seconds * scale).floor() / scale;
+        let width = if decimals == 0 { 2 } else { decimals + 3 };
+        if self.year < 0 {
+            write!(f, "-{:04}", -self.year)?;
+        } else {
+            write!(f, "{:04}", self.year)?;
+        }
+        write!(f, "-{:02}-{:02}T{:02}:{:02}:{:0width$.decimals$}", self.month, self.day, hours, minutes, seconds)
+    }
+}
+
+/// [day] A Julian date.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JulianDate(pub f64);
+
+/// [day] A modified Julian date, JD - 2400000.5.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModifiedJulianDate(pub f64);
+
+impl JulianDate {
+    /// The Julian date of a calendar date.
+    pub fn from_date(calendar: Calendar, date: &CalendarDate) -> Result<Self> {
+        let jd = unsafe { sn::novas_jd_from_date(calendar.to_raw(), date.year, date.month, date.day, date.hour) };
+        if jd.is_nan() {
+            return Err(Error::InvalidArgument("month or day out of range"));
+        }
+        Ok(JulianDate(jd))
+    }
+
+    /// The calendar date of the Julian date.
+    pub fn to_date(self, calendar: Calendar) -> CalendarDate {
+        let mut date = CalendarDate::new(0, 0, 0, 0.0);
+        // Fails only for an invalid calendar
+        unsafe { sn::novas_jd_to_date(self.0, calendar.to_raw(), &mut date.year, &mut date.month, &mut date.day, &mut date.hour) };
+        date
+    }
+
+    pub fn mjd(self) -> ModifiedJulianDate {
+        ModifiedJulianDate(self.0 - MJD0)
+    }
+
+    /// Fraction of the calendar day elapsed since midnight, in [0, 1).
+    pub fn day_fraction(self) -> f64 {
+        (self.0 - 0.5).rem_euclid(1.0)
+    }
+
+    /// The preceding midnight, the start of the calendar day.
+    pub fn midnight(self) -> JulianDate {
+        JulianDate((self.0 - 0.5).floor() + 0.5)
+    }
+
+    /// Day of the week, from 1 for Monday to 7 for Sunday.
+    #[cfg(supernovas_1_4)]
+    pub fn day_of_week(self) -> i32 {
+        unsafe { sn::novas_day_of_week(self.0) }
+    }
+
+    /// Year and day of the year, from 1, of the calendar date.
+    #[cfg(supernovas_1_4)]
+    pub fn day_of_year(self, calendar: Calendar) -> (i32, i32) {
+        let mut year = 0;
+        let day = unsafe { sn::novas_day_of_year(self.0, calendar.to_raw(), &mut year) };
+        (year, day)
+    }
+
+    /// ISO 8601 timestamp of the date in the proleptic Gregorian calendar, e.g.
+    /// "2025-06-24T12:29:36.000", with the seconds rounded to `decimals`.
+    pub fn iso(self, decimals: usize) -> String {
+        let ijd = self.0.floor();
+        timestamp(ijd as i64, self.0 - ijd, Calendar::Gregorian, decimals)
+    }
+}
+
+/// Most digits of the seconds in timestamps, those of nanoseconds.
+const MAX_DECIMALS: usize = 9;
+
+// Formats the Julian date `ijd + fjd` as "YYYY-MM-DDThh:mm:ss.sss", with the seconds rounded to
+// `decimals` (at most 9) and the carry reaching the date.
+pub(crate) fn timestamp(ijd: i64, fjd: f64, calendar: Calendar, decimals: usize) -> String {
+    let decimals = decimals.min(MAX_DECIMALS);
+    let units = 10i64.pow(decimals as u32);
+    let per_day = 86400 * units;
+    // Counted from the midnight that starts the day of the noon of `ijd`
+    let ticks = ((fjd + 0.5) * per_day as f64).round() as i64;
+    let ijd = ijd + ticks.div_euclid(per_day);
+    let ticks = ticks.rem_euclid(per_day);
+    let date = JulianDate(ijd as f64).to_date(calendar);
+    let (seconds, fraction) = (ticks / units, ticks % units);
+    let year = if date.year < 0 { format!("-{:04}", -date.year) } else { format!("{:04}", date.year) };
+    let mut stamp = format!(
+        "{}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        date.month,
+        date.day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    if decimals > 0 {
+        stamp.push_str(&format!(".{:0decimals$}", fraction));
+    }
+    stamp
+}
+
+impl ModifiedJulianDate {
+    pub fn jd(self) -> JulianDate {
+        JulianDate(self.0 + MJD0)
+    }
+}
+
+impl From<ModifiedJulianDate> for JulianDate {
+    fn from(mjd: ModifiedJulianDate) -> Self {
+        mjd.jd()
+    }
+}
+
+impl From<JulianDate> for ModifiedJulianDate {
+    fn from(jd: JulianDate) -> Self {
+        jd.mjd()
+    }
+}
+
+// Shifts by a number of days, and differences in days.
+macro_rules! date_ops {
+    ($date:ident) => {
+        impl Add<f64> for $date {
+            type Output = $date;
+            fn add(self, days: f64) -> $date {
+                $date(self.0 + days)
+            }
+        }
+
+        impl Sub<f64> for $date {
+            type Output = $date;
+            fn sub(self, days: f64) -> $date {
+                $date(self.0 - days)
+            }
+        }
+
+        impl Sub for $date {
+            type Output = f64;
+            fn sub(self, other: $date) -> f64 {
+                self.0 - other.0
+            }
+        }
+    };
+}
+
+date_ops!(JulianDate);
+date_ops!(ModifiedJulianDate);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calendars_split_at_the_gregorian_reform() {
+        let reform = JulianDate(2299160.5);
+        assert_eq!(reform.to_date(Calendar::Astronomical), CalendarDate::new(1582, 10, 15, 0.0));
+        assert_eq!((reform - 1.0).to_date(Calendar::Astronomical), CalendarDate::new(1582, 10, 4, 0.0));
+        assert_eq!((reform - 1.0).to_date(Calendar::Gregorian), CalendarDate::new(1582, 10, 14, 0.0));
+        assert_eq!(reform.to_date(Calendar::Roman), CalendarDate::new(1582, 10, 5, 0.0));
+        let j2000 = JulianDate::from_date(Calendar::Gregorian, &CalendarDate::new(2000, 1, 1, 12.0)).unwrap();
+        assert_eq!(j2000, JulianDate(2451545.0));
+        assert!(JulianDate::from_date(Calendar::Gregorian, &CalendarDate::new(2000, 13, 1, 0.0)).is_err());
+    }
+
+    #[test]
+    fn days() {
+        let jd = JulianDate(2451545.25);
+        assert_eq!(jd.mjd(), ModifiedJulianDate(51544.75));
+        assert_eq!(JulianDate::from(jd.mjd()), jd);
+        assert_eq!(jd.day_fraction(), 0.75);
+        assert_eq!(jd.midnight(), JulianDate(2451544.5));
+        assert_eq!(jd + 1.0 - jd, 1.0);
+    }
+
+    #[cfg(supernovas_1_4)]
+    #[test]
+    fn weekdays_and_days_of_the_year() {
+        // 2000-01-01 was a Saturday
+        assert_eq!(JulianDate(2451545.25).day_of_week(), 6);
+        assert_eq!(JulianDate(2460675.5).day_of_year(Calendar::Gregorian), (2024, 366));
+    }
+
+    #[test]
+    fn timestamps_carry_into_the_date() {
+        assert_eq!(JulianDate(2451545.0).iso(3), "2000-01-01T12:00:00.000");
+        assert_eq!(JulianDate(2451544.5 - 1e-9).iso(0), "2000-01-01T00:00:00");
+        assert_eq!(JulianDate(1720694.5).iso(0), "-0001-01-01T00:00:00");
+        assert_eq!(CalendarDate::new(2025, 6, 24, 12.5 - 0.5 / 3600.0).to_string(), "2025-06-24T12:29:59.500");
+        assert_eq!(format!("{:.0}", CalendarDate::new(2025, 6, 24, 12.5 - 1e-9)), "2025-06-24T12:29:59");
+    }
+}
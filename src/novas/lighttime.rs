@@ -0,0 +1,154 @@
+use super::error::{check, Error, Result};
+use super::frame::{Accuracy, ReferenceSystem};
+use super::source::{Source, SourceKind};
+use super::state::{BarycentricState, StateUnits};
+use super::time::{Time, Timescale};
+use supernovas_sys as sn;
+
+/// Light-time solution for a Solar-System source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightTimeSolution {
+    /// [AU] ICRS position of the source at the retarded epoch, relative to the observer
+    pub position: [f64; 3],
+    /// [AU/day] ICRS velocity of the source at the retarded epoch, relative to the SSB
+    pub velocity: [f64; 3],
+    /// [day] light travel time from the source to the observer
+    pub light_time: f64,
+    /// number of iterations used
+    pub iterations: usize,
+}
+
+/// Iterative light-time solver, following the same scheme as the NOVAS `light_time2()`
+/// function, but with configurable convergence criteria.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightTime {
+    accuracy: Accuracy,
+    tolerance: f64,
+    max_iterations: usize,
+}
+
+impl Default for LightTime {
+    fn default() -> Self {
+        LightTime::new(Accuracy::default())
+    }
+}
+
+impl LightTime {
+    /// Solver with the NOVAS default tolerance for the given accuracy (1e-12 day for full, and
+    /// 1e-9 day for reduced accuracy).
+    pub fn new(accuracy: Accuracy) -> Self {
+        let tolerance = match accuracy {
+            Accuracy::Full => 1e-12,
+            Accuracy::Reduced => 1e-9,
+        };
+        LightTime { accuracy, tolerance, max_iterations: 10 }
+    }
+
+    /// Sets the convergence tolerance [day] on the light time.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the maximum number of iterations before giving up.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Solves for the state of a Solar-System `source` at the epoch when the light observed at
+    /// `time` left it, for an observer with the given barycentric ICRS state.
+    pub fn solve(&self, source: &Source, time: &Time, observer: &BarycentricState) -> Result<LightTimeSolution> {
+        if source.kind() == SourceKind::Catalog {
+            return Err(Error::InvalidArgument("light-time solution requires a Solar-System source"));
+        }
+        if observer.system != ReferenceSystem::Icrs {
+            return Err(Error::InvalidArgument("observer state must be in ICRS"));
+        }
+        if self.tolerance.is_nan() || self.tolerance <= 0.0 {
+            return Err(Error::InvalidArgument("light-time tolerance must be positive"));
+        }
+
+        let scale = match observer.units {
+            StateUnits::Au => 1.0,
+            StateUnits::Km => sn::NOVAS_KM / sn::NOVAS_AU,
+        };
+        let obs = observer.position.map(|x| x * scale);
+        let c = sn::NOVAS_C * sn::NOVAS_DAY / sn::NOVAS_AU;
+
+        let mut jd = [time.jd(Timescale::Tdb), 0.0];
+        if self.accuracy == Accuracy::Full {
+            let day = jd[0].floor();
+            jd = [day, jd[0] - day];
+        }
+
+        let mut velocity = [0.0; 3];
+        let mut light_time = 0.0;
+        for iterations in 1..=self.max_iterations {
+            let mut pos = [0.0; 3];
+            check("ephemeris", || unsafe {
+                sn::ephemeris(
+                    jd.as_ptr(),
+                    source.as_raw(),
+                    sn::novas_origin_NOVAS_BARYCENTER,
+                    self.accuracy.to_raw(),
+                    pos.as_mut_ptr(),
+                    velocity.as_mut_ptr(),
+                )
+            } as i32)?;
+            let position: [f64; 3] = std::array::from_fn(|i| pos[i] - obs[i]);
+            let dt = position.iter().map(|x| x * x).sum::<f64>().sqrt() / c - light_time;
+            light_time += dt;
+            if dt.abs() <= self.tolerance {
+                return Ok(LightTimeSolution { position, velocity, light_time, iterations });
+            }
+            jd[1] -= dt;
+        }
+
+        Err(Error::NoConvergence { call: "light time", iterations: self.max_iterations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::frame::ObservingFrame;
+    use crate::novas::observer::Observer;
+    use crate::novas::source::Planet;
+
+    fn frame() -> ObservingFrame {
+        let observer = Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap();
+        let time = Time::new(Timescale::Utc, 2460850.5, 37, 0.035).unwrap();
+        ObservingFrame::new(Accuracy::Reduced, &observer, &time, 0.0, 0.0).unwrap()
+    }
+
+    #[test]
+    fn solves_for_the_retarded_sun() {
+        let frame = frame();
+        let sun = Source::planet(Planet::Sun).unwrap();
+        let observer = frame.observer_state(ReferenceSystem::Icrs, StateUnits::Km).unwrap();
+        let solution = LightTime::new(Accuracy::Reduced).solve(&sun, &frame.time(), &observer).unwrap();
+        assert!((solution.light_time * 86400.0 - 507.0).abs() < 1.0);
+        assert!(solution.iterations > 1 && solution.iterations <= 4);
+        let geometric = frame.geometric_state(&sun, ReferenceSystem::Icrs, StateUnits::Au).unwrap();
+        assert!(solution.position.iter().zip(&geometric.position).all(|(a, b)| (a - b).abs() < 1e-9));
+    }
+
+    #[test]
+    fn rejects_what_it_cannot_solve() {
+        let frame = frame();
+        let time = frame.time();
+        let sun = Source::planet(Planet::Sun).unwrap();
+        let icrs = frame.observer_state(ReferenceSystem::Icrs, StateUnits::Au).unwrap();
+        let tod = frame.observer_state(ReferenceSystem::Tod, StateUnits::Au).unwrap();
+        let star = Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap();
+        let solver = LightTime::default();
+        assert!(solver.solve(&star, &time, &icrs).is_err());
+        assert!(solver.solve(&sun, &time, &tod).is_err());
+        assert!(solver.with_tolerance(0.0).solve(&sun, &time, &icrs).is_err());
+        assert!(matches!(
+            solver.with_tolerance(1e-15).with_max_iterations(1).solve(&sun, &time, &icrs),
+            Err(Error::NoConvergence { iterations: 1, .. })
+        ));
+    }
+}
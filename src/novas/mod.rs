@@ -0,0 +1,72 @@
+//! Safe wrappers around the SuperNOVAS astrometry library.
+
+mod airmass;
+mod angle;
+mod barycentric;
+pub mod constellations;
+mod coords;
+mod corrections;
+mod delta_t;
+pub mod ephemeris;
+pub mod eop;
+mod error;
+mod events;
+mod fk4;
+mod frame;
+pub mod geodesy;
+#[cfg(supernovas_1_4)]
+pub mod interferometry;
+pub mod jd;
+mod lighttime;
+pub mod moon;
+mod mount;
+pub mod mpc;
+mod observer;
+mod orbit;
+mod parallactic;
+mod plan;
+mod redshift;
+mod refraction;
+pub mod rotation;
+pub mod rv;
+#[cfg(feature = "satellite")]
+pub mod satellite;
+mod simulation;
+pub mod sites;
+mod source;
+mod state;
+#[cfg(feature = "serde")]
+mod targets;
+mod time;
+mod trajectory;
+mod uncertainty;
+mod visibility;
+
+pub use airmass::{airmass, mag_above_atmosphere, AirmassModel};
+pub use angle::{Degrees, Hours, Radians};
+pub use barycentric::{BarycentricCorrection, BarycentricDelays};
+pub use coords::{Ecliptic, Epoch, EquatorType, Equatorial, Galactic, ProperMotion, Supergalactic};
+pub use corrections::Corrections;
+pub use delta_t::{delta_t, delta_t_espenak_meeus};
+pub use error::{Error, Result};
+pub use events::{aspects, search};
+#[cfg(supernovas_1_4)]
+pub use events::eclipses;
+pub use events::{events, sun_events, Event, EventKind, Events, SunCrossing, SunEvents, Twilight};
+pub use frame::{Accuracy, AzimuthConvention, Horizontal, ObservingFrame, ReferenceSystem, SkyPos, Transform};
+pub use lighttime::{LightTime, LightTimeSolution};
+pub use mount::{HaDec, PierSide};
+pub use observer::{Observer, ObserverBuilder};
+pub use orbit::{CometElements, CometMagnitude, NonGravitational, Orbit};
+pub use plan::{Constraints, NightReport, Plan, PlanTarget, ScheduledObservation, TargetReport, Visibility};
+pub use redshift::Redshift;
+pub use refraction::{CustomRefraction, Refraction};
+pub use simulation::Simulation;
+pub use source::{Astrometry, CatalogEntry, NaturalBody, Planet, Source, SourceKind};
+pub use state::{BarycentricState, Body, StateUnits, StateVector};
+#[cfg(feature = "serde")]
+pub use targets::load_targets;
+pub use time::{Time, TimeOffsets, TimeRange, Timescale};
+pub use trajectory::{Trajectory, TrajectoryPoint};
+pub use uncertainty::{AstrometricErrors, ErrorEllipse};
+pub use visibility::VisibilityPlanner;
@@ -0,0 +1,193 @@
+//! Phase, illumination and age of the Moon, and the times of its principal phases.
+//!
+//! Phases follow the difference of the geocentric ecliptic longitudes of the Moon and the Sun:
+//! 0 at new moon, 90 at first quarter, ±180 at full moon and -90 at last quarter. They come from
+//! approximate orbits of the Moon and the Earth, and the times of the phases are accurate to a
+//! few hours. The illumination uses the ephemeris of the Moon.
+//!
+//! The phases and [`Moon`] need SuperNOVAS 1.4 (`supernovas_1_4`); [`illuminated_fraction`] is
+//! available with older releases too.
+
+use super::error::{Error, Result};
+use super::frame::ObservingFrame;
+use super::source::{Planet, Source};
+#[cfg(supernovas_1_4)]
+use super::time::{Time, Timescale};
+use supernovas_sys as sn;
+
+/// [day] Mean length of a lunation.
+#[cfg(supernovas_1_4)]
+const SYNODIC_MONTH: f64 = 29.530589;
+
+/// Name of the phase of the Moon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PhaseName {
+    New,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    Full,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+impl PhaseName {
+    /// Name of a phase [deg], with the principal phases spanning 45 degrees around their exact
+    /// phase.
+    pub fn from_phase(phase: f64) -> Self {
+        const NAMES: [PhaseName; 8] = [
+            PhaseName::New,
+            PhaseName::WaxingCrescent,
+            PhaseName::FirstQuarter,
+            PhaseName::WaxingGibbous,
+            PhaseName::Full,
+            PhaseName::WaningGibbous,
+            PhaseName::LastQuarter,
+            PhaseName::WaningCrescent,
+        ];
+        NAMES[((phase + 22.5).rem_euclid(360.0) / 45.0) as usize % 8]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PhaseName::New => "new moon",
+            PhaseName::WaxingCrescent => "waxing crescent",
+            PhaseName::FirstQuarter => "first quarter",
+            PhaseName::WaxingGibbous => "waxing gibbous",
+            PhaseName::Full => "full moon",
+            PhaseName::WaningGibbous => "waning gibbous",
+            PhaseName::LastQuarter => "last quarter",
+            PhaseName::WaningCrescent => "waning crescent",
+        }
+    }
+}
+
+/// The Moon as seen by the observer of a frame, see [`Moon::at`].
+#[cfg(supernovas_1_4)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moon {
+    /// [deg] phase, in (-180, 180]
+    pub phase: f64,
+    /// [deg] Sun-Moon-observer angle, in [0, 180]
+    pub phase_angle: f64,
+    /// Illuminated fraction of the disk, in [0, 1]
+    pub illuminated_fraction: f64,
+    /// [day] time since the last new moon
+    pub age: f64,
+}
+
+#[cfg(supernovas_1_4)]
+impl Moon {
+    /// Phase, illumination and age of the Moon at the time of a frame, seen by its observer.
+    pub fn at(frame: &ObservingFrame) -> Result<Self> {
+        let time = frame.time();
+        let illuminated_fraction = illuminated_fraction(frame)?;
+        Ok(Moon {
+            phase: phase(&time)?,
+            phase_angle: (2.0 * illuminated_fraction - 1.0).clamp(-1.0, 1.0).acos().to_degrees(),
+            illuminated_fraction,
+            age: age(&time)?,
+        })
+    }
+
+    pub fn phase_name(&self) -> PhaseName {
+        PhaseName::from_phase(self.phase)
+    }
+}
+
+/// Illuminated fraction of the disk of the Moon, in [0, 1], at the time of a frame, seen by its
+/// observer.
+pub fn illuminated_fraction(frame: &ObservingFrame) -> Result<f64> {
+    let moon = Source::planet(Planet::Moon)?;
+    let fraction = unsafe { sn::novas_solar_illum(moon.as_raw(), frame.as_raw()) };
+    if fraction.is_nan() {
+        return Err(Error::InvalidArgument("cannot compute the illumination of the Moon"));
+    }
+    Ok(fraction)
+}
+
+/// [deg] Phase of the Moon, in (-180, 180].
+#[cfg(supernovas_1_4)]
+pub fn phase(time: &Time) -> Result<f64> {
+    let phase = unsafe { sn::novas_moon_phase(time.jd(Timescale::Tdb)) };
+    if phase.is_nan() {
+        return Err(Error::InvalidArgument("cannot compute the phase of the Moon"));
+    }
+    Ok(phase)
+}
+
+/// Next time, after `time`, at which the Moon reaches `phase` [deg].
+#[cfg(supernovas_1_4)]
+pub fn next_phase(phase: f64, time: &Time) -> Result<Time> {
+    let jd = unsafe { sn::novas_next_moon_phase(phase, time.jd(Timescale::Tdb)) };
+    if jd.is_nan() {
+        return Err(Error::InvalidArgument("cannot find the phase of the Moon"));
+    }
+    Time::new(Timescale::Tdb, jd, time.leap_seconds(), time.dut1())
+}
+
+/// Next new moon after `time`.
+#[cfg(supernovas_1_4)]
+pub fn next_new_moon(time: &Time) -> Result<Time> {
+    next_phase(0.0, time)
+}
+
+/// Next full moon after `time`.
+#[cfg(supernovas_1_4)]
+pub fn next_full_moon(time: &Time) -> Result<Time> {
+    next_phase(180.0, time)
+}
+
+/// [day] Age of the Moon: time since the last new moon.
+#[cfg(supernovas_1_4)]
+pub fn age(time: &Time) -> Result<f64> {
+    // The last new moon is the latest one found from a lunation and a margin before.
+    let mut new_moon = next_new_moon(&time.offset(-1.1 * SYNODIC_MONTH * 86400.0)?)?;
+    loop {
+        let next = next_new_moon(&new_moon.offset(86400.0)?)?;
+        if next.diff(time) > 0.0 {
+            return Ok(time.diff(&new_moon) / 86400.0);
+        }
+        new_moon = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(supernovas_1_4)]
+    fn utc(jd: f64) -> Time {
+        Time::new(Timescale::Utc, jd, 37, 0.035).unwrap()
+    }
+
+    #[test]
+    fn phase_names() {
+        assert_eq!(PhaseName::from_phase(0.0), PhaseName::New);
+        assert_eq!(PhaseName::from_phase(22.4), PhaseName::New);
+        assert_eq!(PhaseName::from_phase(30.0), PhaseName::WaxingCrescent);
+        assert_eq!(PhaseName::from_phase(90.0), PhaseName::FirstQuarter);
+        assert_eq!(PhaseName::from_phase(180.0), PhaseName::Full);
+        assert_eq!(PhaseName::from_phase(-180.0), PhaseName::Full);
+        assert_eq!(PhaseName::from_phase(-90.0), PhaseName::LastQuarter);
+        assert_eq!(PhaseName::from_phase(-30.0), PhaseName::WaningCrescent);
+        assert_eq!(PhaseName::WaningGibbous.name(), "waning gibbous");
+    }
+
+    #[cfg(supernovas_1_4)]
+    #[test]
+    fn principal_phases() {
+        let start = utc(2460846.5);
+        // 2025 June 25 10:31 UTC and July 10 20:37 UTC, within a few hours
+        let new = next_new_moon(&start).unwrap();
+        assert!((new.jd(Timescale::Utc) - 2460851.938).abs() < 0.25);
+        let full = next_full_moon(&start).unwrap();
+        assert!((full.jd(Timescale::Utc) - 2460867.359).abs() < 0.25);
+        assert_eq!(new.leap_seconds(), 37);
+        assert!(phase(&new).unwrap().abs() < 1e-3);
+        assert!((age(&full).unwrap() - full.diff(&new) / 86400.0).abs() < 1e-6);
+        let age = age(&start).unwrap();
+        assert!(age > 23.0 && age < 24.5);
+    }
+}
@@ -0,0 +1,138 @@
+use super::angle::{Degrees, Hours};
+use super::error::{Error, Result};
+use super::frame::{Horizontal, ObservingFrame, ReferenceSystem};
+use super::source::Source;
+use supernovas_sys as sn;
+
+/// Side of the pier on which the tube of a German equatorial mount sits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PierSide {
+    /// Tube east of the pier, pointing west of the meridian
+    East,
+    /// Tube west of the pier, pointing east of the meridian
+    West,
+}
+
+/// Local equatorial coordinates of the true equator of date, as used by equatorial mounts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HaDec {
+    /// [h] hour angle, in [-12, 12), positive west of the meridian
+    pub ha: f64,
+    /// [deg] declination
+    pub dec: f64,
+}
+
+impl HaDec {
+    /// Horizontal coordinates, without refraction, at geodetic `latitude` [deg].
+    pub fn to_horizontal(&self, latitude: f64) -> Horizontal {
+        let (lat, ha, dec) = (latitude.to_radians(), (self.ha * 15.0).to_radians(), self.dec.to_radians());
+        let el = (lat.sin() * dec.sin() + lat.cos() * dec.cos() * ha.cos()).asin();
+        let north = lat.cos() * dec.sin() - lat.sin() * dec.cos() * ha.cos();
+        let east = -dec.cos() * ha.sin();
+        Horizontal { az: east.atan2(north).to_degrees().rem_euclid(360.0), el: el.to_degrees() }
+    }
+
+    /// Local equatorial coordinates of unrefracted horizontal coordinates at geodetic `latitude`
+    /// [deg].
+    pub fn from_horizontal(hor: &Horizontal, latitude: f64) -> Self {
+        let (lat, az, el) = (latitude.to_radians(), hor.az.to_radians(), hor.el.to_radians());
+        let dec = (lat.sin() * el.sin() + lat.cos() * el.cos() * az.cos()).asin();
+        let y = -el.cos() * az.sin();
+        let x = lat.cos() * el.sin() - lat.sin() * el.cos() * az.cos();
+        HaDec { ha: Hours::from(Degrees(y.atan2(x).to_degrees())).wrapped().0, dec: dec.to_degrees() }
+    }
+
+    /// The hour angle as a typed angle.
+    pub fn hour_angle(&self) -> Hours {
+        Hours(self.ha)
+    }
+
+    /// The declination as a typed angle.
+    pub fn declination(&self) -> Degrees {
+        Degrees(self.dec)
+    }
+
+    /// Side of the pier for pointing without the counterweight above the tube: east of the pier
+    /// for sources west of the meridian, and west of it otherwise.
+    pub fn pier_side(&self) -> PierSide {
+        if self.ha >= 0.0 { PierSide::East } else { PierSide::West }
+    }
+
+    /// [h] Sidereal time until the hour angle reaches `limit` [h] past the meridian, when a mount
+    /// tracking on the west side of the pier must flip; zero if it already has.
+    pub fn time_to_flip(&self, limit: f64) -> f64 {
+        (limit - self.ha).max(0.0)
+    }
+}
+
+impl ObservingFrame {
+    /// Hour angle and declination of the apparent position of a source. Only for observers on or
+    /// above Earth's surface.
+    pub fn ha_dec(&self, source: &Source) -> Result<HaDec> {
+        if !matches!(
+            self.as_raw().observer.where_,
+            sn::novas_observer_place_NOVAS_OBSERVER_ON_EARTH | sn::novas_observer_place_NOVAS_AIRBORNE_OBSERVER
+        ) {
+            return Err(Error::InvalidArgument("hour angle requires an observer on Earth"));
+        }
+        let pos = self.sky_pos(source, ReferenceSystem::Tod)?;
+        Ok(HaDec { ha: (self.local_sidereal_time() - pos.right_ascension()).wrapped().0, dec: pos.dec })
+    }
+
+    /// Hour angle of a source, in [-12, 12), positive west of the meridian.
+    pub fn hour_angle(&self, source: &Source) -> Result<Hours> {
+        Ok(self.ha_dec(source)?.hour_angle())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::frame::Accuracy;
+    use crate::novas::observer::Observer;
+    use crate::novas::time::{Time, Timescale};
+
+    const LATITUDE: f64 = 43.4712;
+
+    #[test]
+    fn meridian_and_round_trips() {
+        let north = HaDec { ha: 0.0, dec: 60.0 }.to_horizontal(LATITUDE);
+        assert!(north.az.abs() < 1e-9 && (north.el - (90.0 - 60.0 + LATITUDE)).abs() < 1e-9);
+        let south = HaDec { ha: 0.0, dec: 20.0 }.to_horizontal(LATITUDE);
+        assert!((south.az - 180.0).abs() < 1e-9 && (south.el - (90.0 - LATITUDE + 20.0)).abs() < 1e-9);
+        // West of the meridian is west in azimuth
+        let west = HaDec { ha: 3.0, dec: 0.0 }.to_horizontal(LATITUDE);
+        assert!(west.az > 180.0 && west.az < 270.0);
+        for (ha, dec) in [(3.0, 0.0), (-5.5, 70.0), (-11.9, -30.0)] {
+            let back = HaDec::from_horizontal(&HaDec { ha, dec }.to_horizontal(LATITUDE), LATITUDE);
+            assert!((back.ha - ha).abs() < 1e-9 && (back.dec - dec).abs() < 1e-9, "{back:?}");
+        }
+    }
+
+    #[test]
+    fn pier_sides_and_flips() {
+        let west = HaDec { ha: 1.5, dec: 20.0 };
+        assert_eq!(west.pier_side(), PierSide::East);
+        assert_eq!(HaDec { ha: -0.5, ..west }.pier_side(), PierSide::West);
+        assert_eq!(west.time_to_flip(1.0), 0.0);
+        assert_eq!(HaDec { ha: -0.5, ..west }.time_to_flip(1.0), 1.5);
+    }
+
+    #[test]
+    fn hour_angles_need_an_observer_on_earth() {
+        let time = Time::new(Timescale::Utc, 2460850.5, 37, 0.035).unwrap();
+        let vega = Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap();
+        let observer = Observer::on_surface(LATITUDE, 87.1781, 2080.0).unwrap();
+        let frame = ObservingFrame::new(Accuracy::Reduced, &observer, &time, 0.0, 0.0).unwrap();
+        let ha_dec = frame.ha_dec(&vega).unwrap();
+        assert!((-12.0..12.0).contains(&ha_dec.ha));
+        assert_eq!(frame.hour_angle(&vega).unwrap(), Hours(ha_dec.ha));
+        // Unrefracted horizontal coordinates of the frame
+        let tod = frame.sky_pos(&vega, ReferenceSystem::Tod).unwrap();
+        let hor = frame.to_horizontal(&tod, ReferenceSystem::Tod, false).unwrap();
+        let local = ha_dec.to_horizontal(LATITUDE);
+        assert!((local.el - hor.el).abs() < 1e-3 && (local.az - hor.az).abs() < 1e-3, "{local:?} {hor:?}");
+        let geocenter = ObservingFrame::new(Accuracy::Reduced, &Observer::at_geocenter().unwrap(), &time, 0.0, 0.0).unwrap();
+        assert!(geocenter.ha_dec(&vega).is_err());
+    }
+}
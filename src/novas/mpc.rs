@@ -0,0 +1,227 @@
+//! Orbital elements published by the Minor Planet Center: the `MPCORB.DAT` file of asteroid
+//! elements and the one-line comet element format of `CometEls.txt`.
+//!
+//! Both formats are fixed-column. Times in the files are TT, which is used for TDB; the two
+//! differ by less than 2 ms.
+
+use super::error::{Error, Result};
+use super::orbit::{CometElements, CometMagnitude, Orbit};
+use super::source::Source;
+use supernovas_sys as sn;
+
+/// An asteroid from `MPCORB.DAT`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinorPlanet {
+    /// Packed designation, e.g. `00001` or `K24A00A`
+    pub designation: String,
+    /// Readable designation, e.g. `(1) Ceres` or `2024 AA`
+    pub name: String,
+    /// Absolute magnitude H, if given
+    pub h: Option<f64>,
+    /// Slope parameter G, if given
+    pub g: Option<f64>,
+    /// Osculating elements
+    pub orbit: Orbit,
+}
+
+impl MinorPlanet {
+    /// Parses the contents of an `MPCORB.DAT` file, or an extract of it. The header, up to the
+    /// line of dashes, and blank lines are skipped.
+    pub fn parse(text: &str) -> Result<Vec<Self>> {
+        let start = text.lines().position(|line| line.starts_with("-----")).map_or(0, |n| n + 1);
+        let mut planets = Vec::new();
+        for (n, line) in text.lines().enumerate().skip(start) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            planets.push(Self::from_line(line).ok_or(Error::Parse { what: "malformed MPCORB entry", line: n + 1 })?);
+        }
+        Ok(planets)
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let designation = field(line, 1, 7)?;
+        let name = field(line, 167, 194).filter(|name| !name.is_empty()).unwrap_or(designation);
+        Some(MinorPlanet {
+            designation: designation.to_string(),
+            name: name.to_string(),
+            h: number(line, 9, 13),
+            g: number(line, 15, 19),
+            orbit: Orbit {
+                jd_tdb: packed_date(field(line, 21, 25)?)?,
+                mean_anomaly: number(line, 27, 35)?,
+                arg_perihelion: number(line, 38, 46)?,
+                node: number(line, 49, 57)?,
+                inclination: number(line, 60, 68)?,
+                e: number(line, 71, 79)?,
+                mean_motion: number(line, 81, 91)?,
+                a: number(line, 93, 103)?,
+            },
+        })
+    }
+
+    /// The asteroid as a source, named by its readable designation.
+    pub fn source(&self) -> Result<Source> {
+        Source::orbital(&self.name, &self.orbit)
+    }
+}
+
+/// A comet from the one-line element format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comet {
+    /// Designation, e.g. `0001P` or `CJ95O010`, as packed in the file
+    pub designation: String,
+    /// Designation and name, e.g. `C/1995 O1 (Hale-Bopp)`
+    pub name: String,
+    /// [day] TDB Julian date of the osculation epoch, if given
+    pub epoch: Option<f64>,
+    /// Perihelion elements, with the magnitude law if given
+    pub elements: CometElements,
+}
+
+impl Comet {
+    /// Parses comet elements in the one-line format, one comet per line. Blank lines are skipped.
+    pub fn parse(text: &str) -> Result<Vec<Self>> {
+        let mut comets = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            comets.push(Self::from_line(line).ok_or(Error::Parse { what: "malformed comet entry", line: n + 1 })?);
+        }
+        Ok(comets)
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let designation = field(line, 1, 12).filter(|des| !des.is_empty())?;
+        let name = field(line, 103, 158).filter(|name| !name.is_empty()).unwrap_or(designation);
+        let day = number(line, 23, 29)?;
+        let perihelion_time = julian_date(integer(line, 15, 18)?, integer(line, 20, 21)?, day.floor() as i16, 24.0 * day.fract())?;
+        let epoch = field(line, 82, 89).filter(|epoch| epoch.len() == 8).and_then(|epoch| {
+            julian_date(epoch[..4].parse().ok()?, epoch[4..6].parse().ok()?, epoch[6..].parse().ok()?, 0.0)
+        });
+        let magnitude = number(line, 92, 95).zip(number(line, 97, 100)).map(|(g, k)| CometMagnitude { g, k: 2.5 * k });
+        Some(Comet {
+            designation: designation.to_string(),
+            name: name.to_string(),
+            epoch,
+            elements: CometElements {
+                perihelion_time,
+                q: number(line, 31, 39)?,
+                e: number(line, 42, 49)?,
+                arg_perihelion: number(line, 52, 59)?,
+                node: number(line, 62, 69)?,
+                inclination: number(line, 72, 79)?,
+                nongrav: None,
+                magnitude,
+            },
+        })
+    }
+
+    /// The comet as a source, on its osculating orbit at `jd_tdb`, see [`CometElements::source`].
+    pub fn source(&self, jd_tdb: f64) -> Result<Source> {
+        self.elements.source(&self.name, jd_tdb)
+    }
+}
+
+// Trimmed text in the 1-based, inclusive columns `from` to `to`; lines may end early.
+fn field(line: &str, from: usize, to: usize) -> Option<&str> {
+    let end = to.min(line.len());
+    if from > end {
+        return Some("");
+    }
+    line.get(from - 1..end).map(str::trim)
+}
+
+fn number(line: &str, from: usize, to: usize) -> Option<f64> {
+    field(line, from, to)?.parse().ok()
+}
+
+fn integer(line: &str, from: usize, to: usize) -> Option<i16> {
+    field(line, from, to)?.parse().ok()
+}
+
+// Julian date of a calendar date, validated.
+fn julian_date(year: i16, month: i16, day: i16, hour: f64) -> Option<f64> {
+    if !(1..=12).contains(&month) || !(0..=31).contains(&day) {
+        return None;
+    }
+    Some(unsafe { sn::julian_date(year, month, day, hour) })
+}
+
+// Date at 0h of a packed MPC date, e.g. `K24AH` for 2024 October 17.
+fn packed_date(packed: &str) -> Option<f64> {
+    let &[century, y1, y2, month, day] = packed.as_bytes() else {
+        return None;
+    };
+    let century = match century {
+        b'I'..=b'L' => i16::from(century - b'I') + 18,
+        _ => return None,
+    };
+    let digit = |c: u8| c.is_ascii_digit().then(|| i16::from(c - b'0'));
+    let unpack = |c: u8| match c {
+        b'1'..=b'9' => Some(i16::from(c - b'0')),
+        b'A'..=b'V' => Some(i16::from(c - b'A') + 10),
+        _ => None,
+    };
+    julian_date(100 * century + 10 * digit(y1)? + digit(y2)?, unpack(month)?, unpack(day)?, 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MPCORB: &str = "\
+MINOR PLANET CENTER ORBIT DATABASE (MPCORB)
+Des'n     H     G   Epoch     M        Peri.      Node       Incl.       e            n           a        Reference #Obs #Opp    Arc    rms  Perts   Computer
+----------------------------------------------------------------------------------------------------------------------------------------------------------------
+
+00001    3.34  0.15 K2555 188.70269   73.27343   80.25221   10.58790  0.0795434 0.21424745   2.7660046   0 E2024-V47  7330 125 1801-2024 0.80 M-v 30k MPCLINUX   0000 (1) Ceres                   20241101
+K24A00A             K24AH 10.00000    20.00000   30.00000    5.00000  0.1500000 0.25000000   2.5000000
+";
+
+    const HALE_BOPP: &str = "    CJ95O010  1997 03 29.6180  0.890677  0.995078  130.6164  282.4577   89.2148  20250113  -2.0  4.0  C/1995 O1 (Hale-Bopp)                                    MPC106342";
+
+    #[test]
+    fn minor_planets() {
+        let planets = MinorPlanet::parse(MPCORB).unwrap();
+        assert_eq!(planets.len(), 2);
+        let ceres = &planets[0];
+        assert_eq!((ceres.designation.as_str(), ceres.name.as_str()), ("00001", "(1) Ceres"));
+        assert_eq!((ceres.h, ceres.g), (Some(3.34), Some(0.15)));
+        assert_eq!(ceres.orbit.jd_tdb, 2460800.5);
+        assert_eq!(ceres.orbit.a, 2.7660046);
+        assert_eq!(ceres.orbit.mean_motion, 0.21424745);
+        assert_eq!(ceres.source().unwrap().orbit(), Some(ceres.orbit));
+        // Without H, G or name
+        assert_eq!(planets[1].name, "K24A00A");
+        assert_eq!((planets[1].h, planets[1].g), (None, None));
+        assert_eq!(planets[1].orbit.jd_tdb, 2460600.5);
+        assert!(matches!(MinorPlanet::parse("-----\n00001 3.34\n"), Err(Error::Parse { line: 2, .. })));
+    }
+
+    #[test]
+    fn packed_dates() {
+        assert_eq!(packed_date("J9611"), julian_date(1996, 1, 1, 0.0));
+        assert_eq!(packed_date("K2555"), Some(2460800.5));
+        assert_eq!(packed_date("K24AV"), julian_date(2024, 10, 31, 0.0));
+        assert_eq!(packed_date("M2555"), None);
+        assert_eq!(packed_date("K25D5"), None);
+        assert_eq!(packed_date("K255"), None);
+    }
+
+    #[test]
+    fn comets() {
+        let comets = Comet::parse(&format!("\n{HALE_BOPP}\n")).unwrap();
+        let hale_bopp = &comets[0];
+        assert_eq!(hale_bopp.designation, "CJ95O010");
+        assert_eq!(hale_bopp.name, "C/1995 O1 (Hale-Bopp)");
+        assert_eq!(hale_bopp.epoch, Some(2460688.5));
+        let elements = &hale_bopp.elements;
+        assert!((elements.perihelion_time - 2450537.118).abs() < 1e-9);
+        assert_eq!((elements.q, elements.e, elements.inclination), (0.890677, 0.995078, 89.2148));
+        assert_eq!(elements.magnitude, Some(CometMagnitude { g: -2.0, k: 10.0 }));
+        assert!(hale_bopp.source(2460688.5).is_ok());
+        assert!(matches!(Comet::parse(&HALE_BOPP[..40]), Err(Error::Parse { line: 1, .. })));
+    }
+}
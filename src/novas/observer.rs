@@ -0,0 +1,415 @@
+use super::coords::Epoch;
+use super::error::{check, Error, Result};
+use super::frame::{Accuracy, ReferenceSystem};
+use super::geodesy::{Ellipsoid, Geodetic};
+use super::source::Planet;
+use super::state::{Body, StateUnits, StateVector};
+use super::time::{Time, Timescale};
+use supernovas_sys as sn;
+
+// IERS Conventions (2003) equatorial radius, as used by NOVAS `terra()`.
+pub(crate) const EARTH_RADIUS: f64 = 6378136.6;
+
+/// Observer location, wrapping the NOVAS `observer` structure.
+///
+/// With the `serde` feature, observers are serialized with a `place` tag (`geocenter`,
+/// `on_earth`, `in_earth_orbit`, `airborne` or `solar_system`) and the parameters of that place.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "ObserverRecord", into = "ObserverRecord"))]
+pub struct Observer {
+    raw: sn::observer,
+}
+
+impl Observer {
+    /// Stationary observer on Earth's surface.
+    ///
+    /// `latitude` and `longitude` are geodetic degrees (east positive), `height` is meters above
+    /// sea level.
+    pub fn on_surface(latitude: f64, longitude: f64, height: f64) -> Result<Self> {
+        let mut raw = sn::observer::default();
+        check("make_observer_on_surface", || unsafe {
+            sn::make_observer_on_surface(latitude, longitude, height, 0.0, 0.0, &mut raw)
+        })?;
+        Ok(Observer { raw })
+    }
+
+    /// Stationary observer at ITRF Cartesian station coordinates [m].
+    pub fn from_itrf(position: [f64; 3]) -> Result<Self> {
+        if position.iter().any(|c| !c.is_finite()) {
+            return Err(Error::InvalidArgument("ITRF position is not finite"));
+        }
+        let geodetic = Geodetic::from_itrf(&position, Ellipsoid::Iers2003);
+        Observer::on_surface(geodetic.latitude, geodetic.longitude, geodetic.height)
+    }
+
+    /// Stationary observer at geodetic coordinates on `ellipsoid`, e.g. WGS84 coordinates from a
+    /// GNSS survey, converted to the IERS (2003) ellipsoid of NOVAS.
+    pub fn from_geodetic(geodetic: &Geodetic, ellipsoid: Ellipsoid) -> Result<Self> {
+        if ![geodetic.latitude, geodetic.longitude, geodetic.height].iter().all(|c| c.is_finite()) {
+            return Err(Error::InvalidArgument("observer location is not finite"));
+        }
+        if geodetic.latitude.abs() > 90.0 {
+            return Err(Error::InvalidArgument("latitude is outside [-90, 90] deg"));
+        }
+        let geodetic = geodetic.on_ellipsoid(ellipsoid, Ellipsoid::Iers2003);
+        Observer::on_surface(geodetic.latitude, geodetic.longitude, geodetic.height)
+    }
+
+    /// Stationary observer at ITRF station coordinates [m] catalogued for the `reference` epoch,
+    /// moved to `time` with the station velocity [m/yr] (plate motion).
+    pub fn from_itrf_with_motion(position: [f64; 3], velocity: [f64; 3], reference: Epoch, time: &Time) -> Result<Self> {
+        let years = (time.jd(Timescale::Tt) - reference.jd()) / 365.25;
+        Observer::from_itrf(std::array::from_fn(|i| position[i] + velocity[i] * years))
+    }
+
+    /// Observer moving relative to Earth's surface, e.g. an aircraft or balloon.
+    ///
+    /// `velocity` is the surface-relative velocity in km/s.
+    pub fn airborne(latitude: f64, longitude: f64, height: f64, velocity: [f64; 3]) -> Result<Self> {
+        let mut loc = sn::on_surface::default();
+        check("make_on_surface", || unsafe {
+            sn::make_on_surface(latitude, longitude, height, 0.0, 0.0, &mut loc)
+        })?;
+        let mut raw = sn::observer::default();
+        check("make_airborne_observer", || unsafe {
+            sn::make_airborne_observer(&loc, velocity.as_ptr(), &mut raw)
+        })?;
+        Ok(Observer { raw })
+    }
+
+    /// Observer in Earth orbit, with geocentric position [km] and velocity [km/s].
+    pub fn in_space(position: [f64; 3], velocity: [f64; 3]) -> Result<Self> {
+        let mut raw = sn::observer::default();
+        check("make_observer_in_space", || unsafe {
+            sn::make_observer_in_space(position.as_ptr(), velocity.as_ptr(), &mut raw)
+        })?;
+        Ok(Observer { raw })
+    }
+
+    /// Observer at the geocenter.
+    pub fn at_geocenter() -> Result<Self> {
+        let mut raw = sn::observer::default();
+        check("make_observer_at_geocenter", || unsafe { sn::make_observer_at_geocenter(&mut raw) })?;
+        Ok(Observer { raw })
+    }
+
+    /// Observer at rest at the Solar-System Barycenter, e.g. for barycentric directions.
+    pub fn solar_system_barycenter() -> Result<Self> {
+        Observer::in_solar_system([0.0; 3], [0.0; 3])
+    }
+
+    /// Observer in Earth orbit at a geocentric state, e.g. of a spacecraft. The state must be
+    /// relative to the geocenter, in GCRS or ICRS axes.
+    pub fn in_earth_orbit(state: &StateVector) -> Result<Self> {
+        if state.center != Body::Planet(Planet::Earth) {
+            return Err(Error::InvalidArgument("state vector is not geocentric"));
+        }
+        if !matches!(state.system, ReferenceSystem::Gcrs | ReferenceSystem::Icrs) {
+            return Err(Error::InvalidArgument("state vector is not in GCRS"));
+        }
+        let state = state.in_units(StateUnits::Km);
+        Observer::in_space(state.position, state.velocity)
+    }
+
+    /// Observer anywhere in the Solar System, with ICRS barycentric position [AU] and velocity
+    /// [AU/day].
+    pub fn in_solar_system(position: [f64; 3], velocity: [f64; 3]) -> Result<Self> {
+        let mut raw = sn::observer::default();
+        check("make_solar_system_observer", || unsafe {
+            sn::make_solar_system_observer(position.as_ptr(), velocity.as_ptr(), &mut raw)
+        })?;
+        Ok(Observer { raw })
+    }
+
+    /// Geodetic coordinates on the IERS (2003) ellipsoid of an observer on Earth's surface or
+    /// airborne, see [`Geodetic::on_ellipsoid`] for other ellipsoids.
+    pub fn geodetic(&self) -> Option<Geodetic> {
+        self.on_earth().then(|| {
+            let surface = &self.raw.on_surf;
+            Geodetic::new(surface.latitude, surface.longitude, surface.height)
+        })
+    }
+
+    /// [m] ITRF position of an observer on Earth's surface or airborne.
+    pub fn itrf(&self) -> Option<[f64; 3]> {
+        self.geodetic().map(|geodetic| geodetic.to_itrf(Ellipsoid::Iers2003))
+    }
+
+    /// Position [AU] and velocity [AU/day] of the observer relative to the geocenter at `time`,
+    /// in GCRS, as NOVAS `geo_posvel()` computes them with `terra()` for observers on Earth.
+    /// Polar motion is neglected.
+    pub fn geocentric_state(&self, accuracy: Accuracy, time: &Time) -> Result<StateVector> {
+        let mut position = [0.0; 3];
+        let mut velocity = [0.0; 3];
+        check("geo_posvel", || unsafe {
+            sn::geo_posvel(
+                time.jd(Timescale::Tt),
+                time.as_raw().ut1_to_tt,
+                accuracy.to_raw(),
+                &self.raw,
+                position.as_mut_ptr(),
+                velocity.as_mut_ptr(),
+            )
+        } as i32)?;
+        Ok(StateVector {
+            position,
+            velocity,
+            target: Body::Observer,
+            center: Body::Planet(Planet::Earth),
+            system: ReferenceSystem::Gcrs,
+            units: StateUnits::Au,
+            epoch: *time,
+        })
+    }
+
+    // Whether the observer is on Earth's surface or airborne.
+    fn on_earth(&self) -> bool {
+        matches!(
+            self.raw.where_,
+            sn::novas_observer_place_NOVAS_OBSERVER_ON_EARTH | sn::novas_observer_place_NOVAS_AIRBORNE_OBSERVER
+        )
+    }
+
+    /// Wraps an already initialized `observer`.
+    pub fn from_raw(raw: sn::observer) -> Self {
+        Observer { raw }
+    }
+
+    pub fn as_raw(&self) -> &sn::observer {
+        &self.raw
+    }
+}
+
+// Local weather at a surface or airborne observer, used for refraction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Weather {
+    temperature: f64,
+    pressure: f64,
+    humidity: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Place {
+    Surface { latitude: f64, longitude: f64, height: f64, velocity: Option<[f64; 3]> },
+    Space { position: [f64; 3], velocity: [f64; 3] },
+}
+
+/// Builder of an [`Observer`] on Earth's surface, airborne or in Earth orbit, validating the
+/// parameters before they are passed to NOVAS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObserverBuilder {
+    place: Place,
+    weather: Option<Weather>,
+}
+
+impl ObserverBuilder {
+    /// Observer on Earth's surface, at geodetic `latitude` and `longitude` [deg] (east positive)
+    /// and `height` [m] above sea level.
+    pub fn on_surface(latitude: f64, longitude: f64, height: f64) -> Self {
+        ObserverBuilder { place: Place::Surface { latitude, longitude, height, velocity: None }, weather: None }
+    }
+
+    /// Airborne observer, as [`ObserverBuilder::on_surface`] moving with the surface-relative
+    /// `velocity` [km/s].
+    pub fn airborne(latitude: f64, longitude: f64, height: f64, velocity: [f64; 3]) -> Self {
+        ObserverBuilder::on_surface(latitude, longitude, height).with_velocity(velocity)
+    }
+
+    /// Observer in Earth orbit, with geocentric position [km] and velocity [km/s].
+    pub fn in_space(position: [f64; 3], velocity: [f64; 3]) -> Self {
+        ObserverBuilder { place: Place::Space { position, velocity }, weather: None }
+    }
+
+    /// Sets the surface-relative velocity [km/s], making a surface observer airborne, or the
+    /// geocentric velocity [km/s] of an observer in space.
+    pub fn with_velocity(mut self, v: [f64; 3]) -> Self {
+        match &mut self.place {
+            Place::Surface { velocity, .. } => *velocity = Some(v),
+            Place::Space { velocity, .. } => *velocity = v,
+        }
+        self
+    }
+
+    /// Sets the local weather: `temperature` [C], `pressure` [mbar] and relative `humidity` [%].
+    /// Only observers on or near Earth's surface have weather.
+    pub fn with_weather(mut self, temperature: f64, pressure: f64, humidity: f64) -> Self {
+        self.weather = Some(Weather { temperature, pressure, humidity });
+        self
+    }
+
+    /// Validates the parameters and creates the observer.
+    pub fn build(&self) -> Result<Observer> {
+        let finite = |v: &[f64]| v.iter().all(|x| x.is_finite());
+        match self.place {
+            Place::Surface { latitude, longitude, height, velocity } => {
+                if !finite(&[latitude, longitude, height]) {
+                    return Err(Error::InvalidArgument("observer location is not finite"));
+                }
+                if latitude.abs() > 90.0 {
+                    return Err(Error::InvalidArgument("latitude is outside [-90, 90] deg"));
+                }
+                let weather = self.weather.unwrap_or(Weather { temperature: 0.0, pressure: 0.0, humidity: 0.0 });
+                if !finite(&[weather.temperature, weather.pressure, weather.humidity]) {
+                    return Err(Error::InvalidArgument("weather is not finite"));
+                }
+                if weather.temperature < -273.15 {
+                    return Err(Error::InvalidArgument("temperature is below absolute zero"));
+                }
+                if weather.pressure < 0.0 {
+                    return Err(Error::InvalidArgument("pressure is negative"));
+                }
+                if !(0.0..=100.0).contains(&weather.humidity) {
+                    return Err(Error::InvalidArgument("humidity is outside [0, 100] %"));
+                }
+
+                let mut loc = sn::on_surface::default();
+                check("make_on_surface", || unsafe {
+                    sn::make_on_surface(latitude, longitude, height, weather.temperature, weather.pressure, &mut loc)
+                })?;
+                loc.humidity = weather.humidity;
+
+                let mut raw = sn::observer::default();
+                match velocity {
+                    Some(velocity) => {
+                        if !finite(&velocity) {
+                            return Err(Error::InvalidArgument("observer velocity is not finite"));
+                        }
+                        check("make_airborne_observer", || unsafe {
+                            sn::make_airborne_observer(&loc, velocity.as_ptr(), &mut raw)
+                        })?;
+                    }
+                    None => check("make_observer", || unsafe {
+                        sn::make_observer(sn::novas_observer_place_NOVAS_OBSERVER_ON_EARTH, &loc, std::ptr::null(), &mut raw)
+                    } as i32)?,
+                }
+                Ok(Observer { raw })
+            }
+            Place::Space { position, velocity } => {
+                if self.weather.is_some() {
+                    return Err(Error::InvalidArgument("observers in space have no weather"));
+                }
+                if !finite(&position) || !finite(&velocity) {
+                    return Err(Error::InvalidArgument("observer position or velocity is not finite"));
+                }
+                Observer::in_space(position, velocity)
+            }
+        }
+    }
+}
+
+// Serialized form of an `Observer`, by the kind of place. Positions and velocities are in the
+// units of the NOVAS `observer`: km and km/s near Earth, AU and AU/day in the Solar System.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "place", rename_all = "snake_case")]
+enum ObserverRecord {
+    Geocenter,
+    OnEarth { latitude: f64, longitude: f64, height: f64, temperature: f64, pressure: f64, humidity: f64 },
+    InEarthOrbit { position: [f64; 3], velocity: [f64; 3] },
+    Airborne { latitude: f64, longitude: f64, height: f64, temperature: f64, pressure: f64, humidity: f64, velocity: [f64; 3] },
+    SolarSystem { position: [f64; 3], velocity: [f64; 3] },
+}
+
+#[cfg(feature = "serde")]
+impl From<Observer> for ObserverRecord {
+    fn from(observer: Observer) -> Self {
+        let sn::on_surface { latitude, longitude, height, temperature, pressure, humidity } = observer.raw.on_surf;
+        let sn::in_space { sc_pos: position, sc_vel: velocity } = observer.raw.near_earth;
+        match observer.raw.where_ {
+            sn::novas_observer_place_NOVAS_OBSERVER_ON_EARTH => {
+                ObserverRecord::OnEarth { latitude, longitude, height, temperature, pressure, humidity }
+            }
+            sn::novas_observer_place_NOVAS_OBSERVER_IN_EARTH_ORBIT => ObserverRecord::InEarthOrbit { position, velocity },
+            sn::novas_observer_place_NOVAS_AIRBORNE_OBSERVER => {
+                ObserverRecord::Airborne { latitude, longitude, height, temperature, pressure, humidity, velocity }
+            }
+            sn::novas_observer_place_NOVAS_SOLAR_SYSTEM_OBSERVER => ObserverRecord::SolarSystem { position, velocity },
+            _ => ObserverRecord::Geocenter,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ObserverRecord> for Observer {
+    type Error = Error;
+
+    fn try_from(record: ObserverRecord) -> Result<Self> {
+        let mut loc = sn::on_surface::default();
+        let mut motion = sn::in_space::default();
+        let place = match record {
+            ObserverRecord::Geocenter => sn::novas_observer_place_NOVAS_OBSERVER_AT_GEOCENTER,
+            ObserverRecord::OnEarth { latitude, longitude, height, temperature, pressure, humidity } => {
+                loc = sn::on_surface { latitude, longitude, height, temperature, pressure, humidity };
+                sn::novas_observer_place_NOVAS_OBSERVER_ON_EARTH
+            }
+            ObserverRecord::InEarthOrbit { position, velocity } => {
+                motion = sn::in_space { sc_pos: position, sc_vel: velocity };
+                sn::novas_observer_place_NOVAS_OBSERVER_IN_EARTH_ORBIT
+            }
+            ObserverRecord::Airborne { latitude, longitude, height, temperature, pressure, humidity, velocity } => {
+                loc = sn::on_surface { latitude, longitude, height, temperature, pressure, humidity };
+                motion.sc_vel = velocity;
+                sn::novas_observer_place_NOVAS_AIRBORNE_OBSERVER
+            }
+            ObserverRecord::SolarSystem { position, velocity } => {
+                motion = sn::in_space { sc_pos: position, sc_vel: velocity };
+                sn::novas_observer_place_NOVAS_SOLAR_SYSTEM_OBSERVER
+            }
+        };
+        let mut raw = sn::observer::default();
+        check("make_observer", || unsafe { sn::make_observer(place, &loc, &motion, &mut raw) } as i32)?;
+        Ok(Observer { raw })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surface_observers() {
+        let observer = Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap();
+        assert_eq!(observer.geodetic(), Some(Geodetic::new(43.4712, 87.1781, 2080.0)));
+        let itrf = observer.itrf().unwrap();
+        let radius = itrf.iter().map(|x| x * x).sum::<f64>().sqrt();
+        assert!(radius > 6.365e6 && radius < 6.375e6);
+        assert!(Observer::at_geocenter().unwrap().geodetic().is_none());
+        assert!(Observer::in_space([7000.0, 0.0, 0.0], [0.0, 7.5, 0.0]).unwrap().itrf().is_none());
+    }
+
+    #[test]
+    fn geodetic_and_itrf_observers_agree() {
+        let geodetic = Geodetic::new(43.4712, 87.1781, 2080.0);
+        let from_itrf = Observer::from_itrf(geodetic.to_itrf(Ellipsoid::Wgs84)).unwrap();
+        let from_geodetic = Observer::from_geodetic(&geodetic, Ellipsoid::Wgs84).unwrap();
+        let (a, b) = (from_itrf.itrf().unwrap(), from_geodetic.itrf().unwrap());
+        assert!((0..3).all(|i| (a[i] - b[i]).abs() < 1e-6));
+        assert!((a[0] - geodetic.to_itrf(Ellipsoid::Wgs84)[0]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn geocentric_states() {
+        let time = Time::new(Timescale::Tt, 2460850.5, 37, 0.0).unwrap();
+        let geocenter = Observer::at_geocenter().unwrap().geocentric_state(Accuracy::Reduced, &time).unwrap();
+        assert_eq!(geocenter.position, [0.0; 3]);
+        let site = Observer::on_surface(0.0, 0.0, 0.0).unwrap().geocentric_state(Accuracy::Reduced, &time).unwrap();
+        let km = site.position.iter().map(|x| x * x).sum::<f64>().sqrt() * sn::NOVAS_AU / 1000.0;
+        assert!((km - 6378.1366).abs() < 1e-3);
+        // 465 m/s of rotation at the equator
+        let speed = site.velocity.iter().map(|x| x * x).sum::<f64>().sqrt() * sn::NOVAS_AU / 86400.0;
+        assert!((speed - 465.1).abs() < 0.5);
+    }
+
+    #[test]
+    fn builder_validates() {
+        let observer = ObserverBuilder::on_surface(43.4712, 87.1781, 2080.0).with_weather(-5.0, 790.0, 40.0).build().unwrap();
+        let surface = observer.as_raw().on_surf;
+        assert_eq!((surface.temperature, surface.pressure, surface.humidity), (-5.0, 790.0, 40.0));
+        let airborne = ObserverBuilder::airborne(43.0, 87.0, 12000.0, [0.2, 0.0, 0.0]).build().unwrap();
+        assert_eq!(airborne.as_raw().where_, sn::novas_observer_place_NOVAS_AIRBORNE_OBSERVER);
+        assert!(ObserverBuilder::on_surface(91.0, 0.0, 0.0).build().is_err());
+        assert!(ObserverBuilder::on_surface(f64::NAN, 0.0, 0.0).build().is_err());
+        assert!(ObserverBuilder::in_space([7000.0, 0.0, 0.0], [0.0, 7.5, 0.0]).build().is_ok());
+    }
+}
@@ -0,0 +1,419 @@
+use super::coords::dot;
+use super::error::{check, Error, Result};
+use super::frame::{Accuracy, ObservingFrame, ReferenceSystem};
+use super::source::{Planet, Source, SourceKind};
+use super::state::StateUnits;
+use std::ffi::CString;
+use supernovas_sys as sn;
+
+/// [rad/day] Gaussian gravitational constant, i.e. the heliocentric (GM)^1/2 in AU^3/2 / day.
+const GAUSS_K: f64 = 0.01720209895;
+
+/// Heliocentric Keplerian orbital elements, referred to the J2000 ecliptic as published by the
+/// Minor Planet Center. Only elliptic orbits are supported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Orbit {
+    /// [day] TDB Julian date of the elements
+    pub jd_tdb: f64,
+    /// [AU] semi-major axis
+    pub a: f64,
+    /// eccentricity
+    pub e: f64,
+    /// [deg] argument of perihelion
+    pub arg_perihelion: f64,
+    /// [deg] longitude of the ascending node
+    pub node: f64,
+    /// [deg] inclination
+    pub inclination: f64,
+    /// [deg] mean anomaly at `jd_tdb`
+    pub mean_anomaly: f64,
+    /// [deg/day] mean daily motion
+    pub mean_motion: f64,
+}
+
+impl Orbit {
+    /// Elements of a heliocentric orbit, with the mean motion of the Sun's gravity alone.
+    pub fn heliocentric(jd_tdb: f64, a: f64, e: f64, arg_perihelion: f64, node: f64, inclination: f64, mean_anomaly: f64) -> Self {
+        Orbit {
+            jd_tdb,
+            a,
+            e,
+            arg_perihelion,
+            node,
+            inclination,
+            mean_anomaly,
+            mean_motion: (GAUSS_K / a.powf(1.5)).to_degrees(),
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> sn::novas_orbital {
+        sn::novas_orbital {
+            system: sn::novas_orbital_system {
+                center: Planet::Sun.to_raw(),
+                plane: sn::novas_reference_plane_NOVAS_ECLIPTIC_PLANE,
+                type_: sn::novas_reference_system_NOVAS_GCRS,
+                obl: 0.0,
+                Omega: 0.0,
+            },
+            jd_tdb: self.jd_tdb,
+            a: self.a,
+            e: self.e,
+            omega: self.arg_perihelion,
+            Omega: self.node,
+            i: self.inclination,
+            M0: self.mean_anomaly,
+            n: self.mean_motion,
+            apsis_period: 0.0,
+            node_period: 0.0,
+        }
+    }
+
+    /// Elements from a NOVAS `novas_orbital` of a heliocentric orbit referred to the ecliptic.
+    pub fn from_raw(raw: &sn::novas_orbital) -> Result<Self> {
+        if raw.system.center != Planet::Sun.to_raw() || raw.system.plane != sn::novas_reference_plane_NOVAS_ECLIPTIC_PLANE {
+            return Err(Error::InvalidArgument("orbit is not heliocentric ecliptic"));
+        }
+        Ok(Orbit {
+            jd_tdb: raw.jd_tdb,
+            a: raw.a,
+            e: raw.e,
+            arg_perihelion: raw.omega,
+            node: raw.Omega,
+            inclination: raw.i,
+            mean_anomaly: raw.M0,
+            mean_motion: raw.n,
+        })
+    }
+
+    /// Checks that the elements describe an elliptic orbit.
+    pub fn validate(&self) -> Result<()> {
+        let values = [self.jd_tdb, self.a, self.e, self.arg_perihelion, self.node, self.inclination, self.mean_anomaly, self.mean_motion];
+        if values.iter().any(|x| !x.is_finite()) {
+            return Err(Error::InvalidArgument("orbital elements are not finite"));
+        }
+        if !(0.0..1.0).contains(&self.e) {
+            return Err(Error::InvalidArgument("eccentricity is outside [0, 1)"));
+        }
+        if self.a <= 0.0 || self.mean_motion <= 0.0 {
+            return Err(Error::InvalidArgument("semi-major axis and mean motion must be positive"));
+        }
+        Ok(())
+    }
+
+    /// Heliocentric ICRS position [AU] and velocity [AU/day] at `jd_tdb`.
+    pub fn state(&self, jd_tdb: f64, accuracy: Accuracy) -> Result<([f64; 3], [f64; 3])> {
+        let mut pos = [0.0; 3];
+        let mut vel = [0.0; 3];
+        check("novas_orbit_posvel", || unsafe {
+            sn::novas_orbit_posvel(jd_tdb, &self.to_raw(), accuracy.to_raw(), pos.as_mut_ptr(), vel.as_mut_ptr())
+        })?;
+        Ok((pos, vel))
+    }
+
+    /// Osculating elements of a heliocentric ecliptic state [AU, AU/day] at `jd_tdb`.
+    fn from_state(jd_tdb: f64, r: [f64; 3], v: [f64; 3]) -> Result<Self> {
+        let mu = GAUSS_K * GAUSS_K;
+        let h = cross(&r, &v);
+        let rn = dot(&r, &r).sqrt();
+        let hv = cross(&v, &h);
+        let ev: [f64; 3] = std::array::from_fn(|i| hv[i] / mu - r[i] / rn);
+        let e = dot(&ev, &ev).sqrt();
+        let a = 1.0 / (2.0 / rn - dot(&v, &v) / mu);
+        if !(e < 1.0 && a > 0.0) {
+            return Err(Error::InvalidArgument("orbit is not elliptic"));
+        }
+        let hn = dot(&h, &h).sqrt();
+        let inclination = (h[2] / hn).acos();
+        // Node vector k x h, along the x axis for orbits in the ecliptic
+        let nv = [-h[1], h[0], 0.0];
+        let nn = nv[0].hypot(nv[1]);
+        let (node, nv) = if nn > 0.0 { (nv[1].atan2(nv[0]), [nv[0] / nn, nv[1] / nn, 0.0]) } else { (0.0, [1.0, 0.0, 0.0]) };
+        // Angles in the orbital plane, from the node
+        let in_plane = |x: &[f64; 3]| dot(&cross(&nv, x), &h).atan2(dot(&nv, x) * hn);
+        let arg_perihelion = if e > 0.0 { in_plane(&ev) } else { 0.0 };
+        let true_anomaly = in_plane(&r) - arg_perihelion;
+        let ecc_anomaly = 2.0 * ((1.0 - e).sqrt() * (0.5 * true_anomaly).sin()).atan2((1.0 + e).sqrt() * (0.5 * true_anomaly).cos());
+        let mean_anomaly = ecc_anomaly - e * ecc_anomaly.sin();
+        Ok(Orbit::heliocentric(
+            jd_tdb,
+            a,
+            e,
+            arg_perihelion.to_degrees().rem_euclid(360.0),
+            node.to_degrees().rem_euclid(360.0),
+            inclination.to_degrees(),
+            mean_anomaly.to_degrees().rem_euclid(360.0),
+        ))
+    }
+}
+
+impl Source {
+    /// A Solar-System body moving on a Keplerian orbit.
+    pub fn orbital(name: &str, orbit: &Orbit) -> Result<Self> {
+        orbit.validate()?;
+        let name = CString::new(name).map_err(|_| Error::InvalidArgument("name contains NUL"))?;
+        let mut raw = sn::object::default();
+        check("make_orbital_object", || unsafe {
+            sn::make_orbital_object(name.as_ptr(), -1, &orbit.to_raw(), &mut raw)
+        })?;
+        Ok(Source::from_raw(raw))
+    }
+
+    /// The orbit of an orbital source, if it is a heliocentric ecliptic one.
+    pub fn orbit(&self) -> Option<Orbit> {
+        if self.kind() != SourceKind::Orbital {
+            return None;
+        }
+        Orbit::from_raw(&self.as_raw().orbit).ok()
+    }
+}
+
+/// Non-gravitational acceleration parameters of a comet [AU/day^2], scaled by the
+/// Marsden-Sekanina water-ice sublimation law.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NonGravitational {
+    /// radial, away from the Sun
+    pub a1: f64,
+    /// transverse, in the orbital plane along the motion
+    pub a2: f64,
+    /// normal to the orbital plane
+    pub a3: f64,
+}
+
+impl NonGravitational {
+    // Acceleration at heliocentric position `r` with velocity `v`.
+    fn acceleration(&self, r: &[f64; 3], v: &[f64; 3]) -> [f64; 3] {
+        let rn = dot(r, r).sqrt();
+        let x = rn / 2.808;
+        let g = 0.1112620426 * x.powf(-2.15) * (1.0 + x.powf(5.093)).powf(-4.6142);
+        let h = cross(r, v);
+        let hn = dot(&h, &h).sqrt();
+        let radial = r.map(|x| x / rn);
+        let normal = h.map(|x| x / hn);
+        let transverse = cross(&normal, &radial);
+        std::array::from_fn(|i| g * (self.a1 * radial[i] + self.a2 * transverse[i] + self.a3 * normal[i]))
+    }
+}
+
+/// Total magnitude law of a comet, `m = g + 5 log10(delta) + k log10(r)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CometMagnitude {
+    /// absolute total magnitude
+    pub g: f64,
+    /// activity slope (2.5 n)
+    pub k: f64,
+}
+
+impl CometMagnitude {
+    /// Magnitude at `r` [AU] from the Sun and `delta` [AU] from the observer.
+    pub fn magnitude(&self, r: f64, delta: f64) -> f64 {
+        self.g + 5.0 * delta.log10() + self.k * r.log10()
+    }
+
+    /// Magnitude of `source` seen from the observer of `frame`, at its geometric distances.
+    pub fn observed(&self, frame: &ObservingFrame, source: &Source) -> Result<f64> {
+        let state = frame.target_state(source, ReferenceSystem::Icrs, StateUnits::Au)?;
+        let raw = frame.as_raw();
+        let distance = |from: &[f64; 3]| {
+            let d: [f64; 3] = std::array::from_fn(|i| state.position[i] - from[i]);
+            dot(&d, &d).sqrt()
+        };
+        Ok(self.magnitude(distance(&raw.sun_pos), distance(&raw.obs_pos)))
+    }
+}
+
+/// Cometary orbital elements, in the perihelion time and distance convention of the Minor Planet
+/// Center, referred to the J2000 ecliptic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CometElements {
+    /// [day] TDB Julian date of perihelion passage
+    pub perihelion_time: f64,
+    /// [AU] perihelion distance
+    pub q: f64,
+    /// eccentricity
+    pub e: f64,
+    /// [deg] argument of perihelion
+    pub arg_perihelion: f64,
+    /// [deg] longitude of the ascending node
+    pub node: f64,
+    /// [deg] inclination
+    pub inclination: f64,
+    /// Non-gravitational parameters, if determined
+    pub nongrav: Option<NonGravitational>,
+    /// Magnitude law, if known
+    pub magnitude: Option<CometMagnitude>,
+}
+
+impl CometElements {
+    /// Elliptic osculating elements at `jd_tdb`. Without non-gravitational terms these are the
+    /// elements of the comet themselves; with them, the orbit is integrated from perihelion with
+    /// the non-gravitational acceleration. Fails for orbits that are not elliptic at `jd_tdb`.
+    pub fn orbit_at(&self, jd_tdb: f64) -> Result<Orbit> {
+        let Some(nongrav) = self.nongrav else {
+            if !(self.e < 1.0 && self.q > 0.0) {
+                return Err(Error::InvalidArgument("orbit is not elliptic"));
+            }
+            let a = self.q / (1.0 - self.e);
+            return Ok(Orbit::heliocentric(self.perihelion_time, a, self.e, self.arg_perihelion, self.node, self.inclination, 0.0));
+        };
+        let (r, v) = self.perihelion_state();
+        let (r, v) = integrate(r, v, jd_tdb - self.perihelion_time, |r, v| nongrav.acceleration(r, v));
+        Orbit::from_state(jd_tdb, r, v)
+    }
+
+    /// The comet as a source, on its osculating orbit at `jd_tdb`.
+    pub fn source(&self, name: &str, jd_tdb: f64) -> Result<Source> {
+        Source::orbital(name, &self.orbit_at(jd_tdb)?)
+    }
+
+    // Heliocentric ecliptic position [AU] and velocity [AU/day] at perihelion.
+    fn perihelion_state(&self) -> ([f64; 3], [f64; 3]) {
+        let speed = GAUSS_K * ((1.0 + self.e) / self.q).sqrt();
+        let (so, co) = self.arg_perihelion.to_radians().sin_cos();
+        let (s_node, c_node) = self.node.to_radians().sin_cos();
+        let (si, ci) = self.inclination.to_radians().sin_cos();
+        // Directions of the perihelion and of the motion at perihelion
+        let p = [c_node * co - s_node * so * ci, s_node * co + c_node * so * ci, so * si];
+        let q = [-c_node * so - s_node * co * ci, -s_node * so + c_node * co * ci, co * si];
+        (p.map(|x| x * self.q), q.map(|x| x * speed))
+    }
+}
+
+/// Integrates heliocentric motion under the Sun's gravity and an additional acceleration over
+/// `span` days, with fixed-fraction RK4 steps of about 1/600 of the local circular period.
+fn integrate(mut r: [f64; 3], mut v: [f64; 3], span: f64, extra: impl Fn(&[f64; 3], &[f64; 3]) -> [f64; 3]) -> ([f64; 3], [f64; 3]) {
+    let mu = GAUSS_K * GAUSS_K;
+    let accel = |r: &[f64; 3], v: &[f64; 3]| -> [f64; 3] {
+        let rn = dot(r, r).sqrt();
+        let a = extra(r, v);
+        std::array::from_fn(|i| -mu * r[i] / (rn * rn * rn) + a[i])
+    };
+    let add = |x: &[f64; 3], d: &[f64; 3], f: f64| -> [f64; 3] { std::array::from_fn(|i| x[i] + f * d[i]) };
+
+    let mut t: f64 = 0.0;
+    while t.abs() < span.abs() {
+        let rn = dot(&r, &r).sqrt();
+        let h = (std::f64::consts::TAU * rn.powf(1.5) / GAUSS_K / 600.0).min(span.abs() - t.abs()) * span.signum();
+        let k1v = accel(&r, &v);
+        let k1r = v;
+        let k2v = accel(&add(&r, &k1r, h / 2.0), &add(&v, &k1v, h / 2.0));
+        let k2r = add(&v, &k1v, h / 2.0);
+        let k3v = accel(&add(&r, &k2r, h / 2.0), &add(&v, &k2v, h / 2.0));
+        let k3r = add(&v, &k2v, h / 2.0);
+        let k4v = accel(&add(&r, &k3r, h), &add(&v, &k3v, h));
+        let k4r = add(&v, &k3v, h);
+        r = std::array::from_fn(|i| r[i] + h / 6.0 * (k1r[i] + 2.0 * k2r[i] + 2.0 * k3r[i] + k4r[i]));
+        v = std::array::from_fn(|i| v[i] + h / 6.0 * (k1v[i] + 2.0 * k2v[i] + 2.0 * k3v[i] + k4v[i]));
+        t += h;
+    }
+    (r, v)
+}
+
+fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ceres() -> Orbit {
+        Orbit::heliocentric(2460800.5, 2.766, 0.0795, 73.27, 80.25, 10.59, 188.70)
+    }
+
+    fn comet() -> CometElements {
+        CometElements {
+            perihelion_time: 2460800.5,
+            q: 1.35,
+            e: 0.64,
+            arg_perihelion: 12.8,
+            node: 50.1,
+            inclination: 7.0,
+            nongrav: None,
+            magnitude: None,
+        }
+    }
+
+    fn norm(v: &[f64; 3]) -> f64 {
+        dot(v, v).sqrt()
+    }
+
+    #[test]
+    fn elements_are_validated() {
+        // [deg/day] The Earth's mean motion
+        assert!((Orbit::heliocentric(0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0).mean_motion - 0.98560766).abs() < 1e-6);
+        assert!(ceres().validate().is_ok());
+        assert!(Orbit { e: 1.0, ..ceres() }.validate().is_err());
+        assert!(Orbit { a: -1.0, ..ceres() }.validate().is_err());
+        assert!(Orbit { node: f64::NAN, ..ceres() }.validate().is_err());
+        assert!(Source::orbital("Ceres", &Orbit { e: 1.5, ..ceres() }).is_err());
+        assert_eq!(Source::orbital("Ceres", &ceres()).unwrap().orbit(), Some(ceres()));
+        assert_eq!(Source::planet(Planet::Mars).unwrap().orbit(), None);
+    }
+
+    #[test]
+    fn states_stay_between_the_apsides() {
+        let orbit = ceres();
+        for days in [0.0, 400.0, 800.0, 1200.0] {
+            let (r, v) = orbit.state(orbit.jd_tdb + days, Accuracy::Reduced).unwrap();
+            let r = norm(&r);
+            assert!(r > orbit.a * (1.0 - orbit.e) && r < orbit.a * (1.0 + orbit.e));
+            // Vis-viva
+            let v2 = GAUSS_K * GAUSS_K * (2.0 / r - 1.0 / orbit.a);
+            assert!((dot(&v, &v) / v2 - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn osculating_elements_of_states() {
+        let comet = comet();
+        let (r, v) = comet.perihelion_state();
+        assert!((norm(&r) - comet.q).abs() < 1e-15);
+        assert!(dot(&r, &v).abs() < 1e-15);
+        let orbit = Orbit::from_state(comet.perihelion_time, r, v).unwrap();
+        let expected = comet.orbit_at(comet.perihelion_time).unwrap();
+        assert!((orbit.a - expected.a).abs() < 1e-12 && (orbit.e - expected.e).abs() < 1e-12);
+        for (a, b) in [(orbit.arg_perihelion, 12.8), (orbit.node, 50.1), (orbit.inclination, 7.0)] {
+            assert!((a - b).abs() < 1e-9, "{a} {b}");
+        }
+        assert!(orbit.mean_anomaly.min(360.0 - orbit.mean_anomaly) < 1e-9);
+        assert!(Orbit::from_state(0.0, r, v.map(|x| 2.0 * x)).is_err());
+    }
+
+    #[test]
+    fn integration_follows_kepler() {
+        let comet = comet();
+        let (r0, v0) = comet.perihelion_state();
+        let a = comet.q / (1.0 - comet.e);
+        let period = std::f64::consts::TAU * a.powf(1.5) / GAUSS_K;
+        let (r, v) = integrate(r0, v0, period, |_, _| [0.0; 3]);
+        // [AU] RK4 steps of 1/600 of the period drift by some 20 km over a revolution
+        assert!(r.iter().zip(&r0).all(|(a, b)| (a - b).abs() < 1e-6));
+        assert!(v.iter().zip(&v0).all(|(a, b)| (a - b).abs() < 1e-8));
+        let (back, _) = integrate(r, v, -period / 2.0, |_, _| [0.0; 3]);
+        assert!((norm(&back) - a * (1.0 + comet.e)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn nongravitational_terms() {
+        let zero = CometElements { nongrav: Some(NonGravitational::default()), ..comet() };
+        let orbit = zero.orbit_at(2460900.5).unwrap();
+        assert!((orbit.a - comet().orbit_at(2460900.5).unwrap().a).abs() < 1e-8);
+        assert_eq!(orbit.jd_tdb, 2460900.5);
+        assert!((orbit.mean_anomaly - 100.0 * orbit.mean_motion).abs() < 1e-6);
+        // A transverse push along the motion raises the orbit
+        let pushed = CometElements { nongrav: Some(NonGravitational { a1: 0.0, a2: 1e-7, a3: 0.0 }), ..comet() };
+        assert!(pushed.orbit_at(2460900.5).unwrap().a > orbit.a);
+        assert!(CometElements { e: 1.2, ..comet() }.orbit_at(2460900.5).is_err());
+    }
+
+    #[test]
+    fn comet_magnitudes() {
+        let law = CometMagnitude { g: 5.5, k: 10.0 };
+        assert_eq!(law.magnitude(1.0, 1.0), 5.5);
+        assert!((law.magnitude(10.0, 0.1) - 10.5).abs() < 1e-12);
+    }
+}
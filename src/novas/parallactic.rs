@@ -0,0 +1,70 @@
+use super::error::Result;
+use super::frame::ObservingFrame;
+use super::source::Source;
+
+/// [deg/s] Rotation rate of the Earth relative to the equinox.
+const SIDEREAL_RATE: f64 = 360.0 / 86164.0905;
+
+impl ObservingFrame {
+    /// [deg] Parallactic angle of a source: the position angle of the zenith at the source,
+    /// measured from North through East, in (-180, 180]. It is positive west of the meridian.
+    /// Only for observers on or above Earth's surface.
+    pub fn parallactic_angle(&self, source: &Source) -> Result<f64> {
+        let (lat, ha, dec) = self.local_angles(source)?;
+        let q = ha.sin().atan2(lat.tan() * dec.cos() - dec.sin() * ha.cos());
+        Ok(q.to_degrees())
+    }
+
+    /// [deg/s] Rate of change of the parallactic angle of a source, i.e. the rate at which the
+    /// field of an alt-az telescope rotates. Diverges for sources that pass through the zenith.
+    pub fn parallactic_angle_rate(&self, source: &Source) -> Result<f64> {
+        let (lat, ha, dec) = self.local_angles(source)?;
+        let sin_el = lat.sin() * dec.sin() + lat.cos() * dec.cos() * ha.cos();
+        // cos(az) cos(el), with the azimuth measured from North
+        let north = lat.cos() * dec.sin() - lat.sin() * dec.cos() * ha.cos();
+        Ok(-SIDEREAL_RATE * lat.cos() * north / (1.0 - sin_el * sin_el))
+    }
+
+    // [rad] Geodetic latitude of the observer, and hour angle and declination of the source, of
+    // the true equator and equinox of date.
+    fn local_angles(&self, source: &Source) -> Result<(f64, f64, f64)> {
+        let pos = self.ha_dec(source)?;
+        let lat = self.as_raw().observer.on_surf.latitude;
+        Ok((lat.to_radians(), (pos.ha * 15.0).to_radians(), pos.dec.to_radians()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::frame::Accuracy;
+    use crate::novas::observer::Observer;
+    use crate::novas::time::{Time, Timescale};
+
+    fn frame(jd: f64) -> ObservingFrame {
+        let observer = Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap();
+        let time = Time::new(Timescale::Utc, jd, 37, 0.035).unwrap();
+        ObservingFrame::new(Accuracy::Reduced, &observer, &time, 0.0, 0.0).unwrap()
+    }
+
+    #[test]
+    fn angle_follows_the_hour_angle() {
+        // Vega passes south of the zenith
+        let vega = Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap();
+        for jd in [2460850.5, 2460850.75, 2460851.0] {
+            let frame = frame(jd);
+            let q = frame.parallactic_angle(&vega).unwrap();
+            assert_eq!(q.signum(), frame.hour_angle(&vega).unwrap().0.signum());
+            assert!(q.abs() < 180.0);
+        }
+    }
+
+    #[test]
+    fn rate_is_the_derivative() {
+        let vega = Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap();
+        let (jd, dt) = (2460850.5, 30.0);
+        let q = |seconds: f64| frame(jd + seconds / 86400.0).parallactic_angle(&vega).unwrap();
+        let rate = frame(jd).parallactic_angle_rate(&vega).unwrap();
+        assert!(((q(dt) - q(-dt)) / (2.0 * dt) - rate).abs() < 1e-3 * rate.abs(), "{rate}");
+    }
+}
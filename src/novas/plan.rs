@@ -0,0 +1,496 @@
+use super::error::{Error, Result};
+use super::frame::{Accuracy, ObservingFrame, ReferenceSystem, SkyPos};
+use super::moon::illuminated_fraction;
+use super::observer::Observer;
+use super::refraction::Refraction;
+use super::source::{Planet, Source};
+use super::time::Time;
+use std::fmt;
+
+/// Observing constraints of a planned target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Constraints {
+    /// [deg] lowest usable elevation, after refraction
+    pub min_elevation: f64,
+    /// Highest usable airmass, if limited
+    pub max_airmass: Option<f64>,
+    /// [deg] smallest usable distance from the Moon, if limited
+    pub min_moon_separation: Option<f64>,
+    /// [deg] highest usable elevation of the Sun, unrefracted, if limited beyond the night
+    pub max_sun_elevation: Option<f64>,
+    /// [s] continuous observing time needed
+    pub duration: f64,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Constraints {
+            min_elevation: 0.0,
+            max_airmass: None,
+            min_moon_separation: None,
+            max_sun_elevation: None,
+            duration: 0.0,
+        }
+    }
+}
+
+/// A source to schedule, with its constraints.
+#[derive(Debug, Clone)]
+pub struct PlanTarget {
+    pub name: String,
+    pub source: Source,
+    pub constraints: Constraints,
+    /// Interval out of which the target may not be observed, e.g. for time-critical observations
+    pub window: Option<(Time, Time)>,
+}
+
+impl PlanTarget {
+    /// A target without a time window.
+    pub fn new(name: &str, source: Source, constraints: Constraints) -> Self {
+        PlanTarget { name: name.to_string(), source, constraints, window: None }
+    }
+
+    /// Restricts observations to the interval from `start` to `end`.
+    pub fn with_window(mut self, start: &Time, end: &Time) -> Self {
+        self.window = Some((*start, *end));
+        self
+    }
+}
+
+/// Visibility of a target over the planned interval.
+#[derive(Debug, Clone)]
+pub struct Visibility {
+    pub name: String,
+    /// Intervals in which all constraints are met, to the sampling step
+    pub windows: Vec<(Time, Time)>,
+    /// [deg] highest elevation in the interval
+    pub max_elevation: f64,
+    /// Time of the highest elevation
+    pub culmination: Time,
+    /// Whether a window is at least as long as the requested duration
+    pub feasible: bool,
+}
+
+/// Circumstances of a target in a night, see [`Plan::night_report`].
+#[derive(Debug, Clone)]
+pub struct TargetReport {
+    pub name: String,
+    /// Rise and set of the center of the target on the refracted horizon, and its transit, in the
+    /// day from 12 hours before dusk; `None` if it stays up or down
+    pub rise: Option<Time>,
+    pub set: Option<Time>,
+    pub transit: Option<Time>,
+    /// [deg] highest elevation during the night
+    pub max_elevation: f64,
+    /// Time of the highest elevation during the night
+    pub culmination: Time,
+    /// [deg] distance from the Moon at culmination
+    pub moon_separation: f64,
+    /// [s] time in which the constraints of the target are met
+    pub observable: f64,
+}
+
+/// Circumstances of a target list in a night, see [`Plan::night_report`]. Displayed as a table
+/// with UTC times.
+#[derive(Debug, Clone)]
+pub struct NightReport {
+    pub dusk: Time,
+    pub dawn: Time,
+    /// Illuminated fraction of the Moon in the middle of the night
+    pub moon_illumination: f64,
+    pub targets: Vec<TargetReport>,
+}
+
+impl fmt::Display for NightReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let clock = |time: Option<&Time>| time.map_or("-".to_string(), clock);
+        writeln!(f, "Night {} to {}", self.dusk.iso_timestamp(), self.dawn.iso_timestamp())?;
+        writeln!(f, "Moon {:.0}% illuminated", self.moon_illumination * 100.0)?;
+        writeln!(f)?;
+        write!(
+            f,
+            "{:<16}  {:<5}  {:<5}  {:<5}  {:>8}  {:<5}  {:>7}  {:>10}",
+            "Target", "Rise", "Trans", "Set", "Max El", "Culm.", "Moon", "Observable"
+        )?;
+        for target in &self.targets {
+            write!(
+                f,
+                "\n{:<16}  {:<5}  {:<5}  {:<5}  {:>8.2}  {:<5}  {:>7.1}  {:>9.2}h",
+                target.name,
+                clock(target.rise.as_ref()),
+                clock(target.transit.as_ref()),
+                clock(target.set.as_ref()),
+                target.max_elevation,
+                clock(Some(&target.culmination)),
+                target.moon_separation,
+                target.observable / 3600.0,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// An observation placed by [`Plan::schedule`].
+#[derive(Debug, Clone)]
+pub struct ScheduledObservation {
+    pub name: String,
+    pub start: Time,
+    pub end: Time,
+}
+
+/// Evaluates a target list over a night for one observer, sampling the constraints at a fixed
+/// step. Positions are refracted.
+#[derive(Debug, Clone)]
+pub struct Plan {
+    observer: Observer,
+    accuracy: Accuracy,
+    dx: f64,
+    dy: f64,
+    step: f64,
+    sun_elevation: f64,
+}
+
+impl Plan {
+    /// A plan sampled every 5 minutes, with nights bounded by nautical twilight.
+    pub fn new(observer: Observer) -> Self {
+        Plan {
+            observer,
+            accuracy: Accuracy::default(),
+            dx: 0.0,
+            dy: 0.0,
+            step: 300.0,
+            sun_elevation: -12.0,
+        }
+    }
+
+    /// Sets the accuracy of the position calculations.
+    pub fn with_accuracy(mut self, accuracy: Accuracy) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    /// Sets the polar offsets [mas] used for the frames.
+    pub fn with_polar_offsets(mut self, dx: f64, dy: f64) -> Self {
+        self.dx = dx;
+        self.dy = dy;
+        self
+    }
+
+    /// Sets the sampling step [s].
+    pub fn with_step(mut self, seconds: f64) -> Self {
+        self.step = seconds;
+        self
+    }
+
+    /// Sets the Sun elevation [deg] that bounds the night.
+    pub fn with_sun_elevation(mut self, el: f64) -> Self {
+        self.sun_elevation = el;
+        self
+    }
+
+    /// Dusk and dawn of the first night after `time`, or `None` if the Sun does not set below the
+    /// limit within a day.
+    pub fn night(&self, time: &Time) -> Result<Option<(Time, Time)>> {
+        let sun = Source::planet(Planet::Sun)?;
+        let frame = ObservingFrame::new(self.accuracy, &self.observer, time, self.dx, self.dy)?;
+        let Some(dusk) = frame.sets_below(self.sun_elevation, &sun, false) else {
+            return Ok(None);
+        };
+        let frame = ObservingFrame::new(self.accuracy, &self.observer, &dusk, self.dx, self.dy)?;
+        Ok(frame.rises_above(self.sun_elevation, &sun, false).map(|dawn| (dusk, dawn)))
+    }
+
+    /// Samples every target from `start` to `end` and reports when its constraints are met.
+    pub fn evaluate(&self, start: &Time, end: &Time, targets: &[PlanTarget]) -> Result<Vec<Visibility>> {
+        let span = end.diff(start);
+        if !self.step.is_finite() || self.step <= 0.0 || span < 0.0 {
+            return Err(Error::InvalidArgument("empty plan interval or step"));
+        }
+        let samples = (span / self.step).ceil() as usize + 1;
+        let moon = match targets.iter().any(|t| t.constraints.min_moon_separation.is_some()) {
+            true => Some(Source::planet(Planet::Moon)?),
+            false => None,
+        };
+        let sun = match targets.iter().any(|t| t.constraints.max_sun_elevation.is_some()) {
+            true => Some(Source::planet(Planet::Sun)?),
+            false => None,
+        };
+
+        let mut results: Vec<Visibility> = targets
+            .iter()
+            .map(|t| Visibility {
+                name: t.name.clone(),
+                windows: Vec::new(),
+                max_elevation: f64::NEG_INFINITY,
+                culmination: *start,
+                feasible: false,
+            })
+            .collect();
+        // Start of the window each target is currently in
+        let mut open: Vec<Option<Time>> = vec![None; targets.len()];
+        let mut last = *start;
+
+        for i in 0..samples {
+            let time = if i + 1 == samples { *end } else { start.offset(i as f64 * self.step)? };
+            let frame = ObservingFrame::new(self.accuracy, &self.observer, &time, self.dx, self.dy)?;
+            let moon = moon.as_ref().map(|m| frame.sky_pos(m, ReferenceSystem::Tod)).transpose()?;
+            let sun_el = match &sun {
+                Some(sun) => {
+                    let pos = frame.sky_pos(sun, ReferenceSystem::Tod)?;
+                    Some(frame.to_horizontal(&pos, ReferenceSystem::Tod, false)?.el)
+                }
+                None => None,
+            };
+
+            for ((target, result), open) in targets.iter().zip(&mut results).zip(&mut open) {
+                let pos = frame.sky_pos(&target.source, ReferenceSystem::Tod)?;
+                let hor = frame.to_horizontal(&pos, ReferenceSystem::Tod, true)?;
+                if hor.el > result.max_elevation {
+                    result.max_elevation = hor.el;
+                    result.culmination = time;
+                }
+                let usable = meets(&target.constraints, hor.el, hor.airmass(), &pos, moon.as_ref(), sun_el)
+                    && target.window.is_none_or(|(from, to)| time.diff(&from) >= 0.0 && to.diff(&time) >= 0.0);
+                match (usable, *open) {
+                    (true, None) => *open = Some(time),
+                    (false, Some(from)) => {
+                        result.windows.push((from, last));
+                        *open = None;
+                    }
+                    _ => {}
+                }
+            }
+            last = time;
+        }
+
+        for ((target, result), open) in targets.iter().zip(&mut results).zip(open) {
+            if let Some(from) = open {
+                result.windows.push((from, last));
+            }
+            result.feasible = result
+                .windows
+                .iter()
+                .any(|(from, to)| to.diff(from) >= target.constraints.duration);
+        }
+        Ok(results)
+    }
+
+    /// Rise, set, transit, culmination, distance from the Moon and observable time of each target
+    /// in the first night after `time`, or `None` if the Sun does not set below the limit within a
+    /// day.
+    pub fn night_report(&self, time: &Time, targets: &[PlanTarget]) -> Result<Option<NightReport>> {
+        let Some((dusk, dawn)) = self.night(time)? else {
+            return Ok(None);
+        };
+        let visibility = self.evaluate(&dusk, &dawn, targets)?;
+        let moon = Source::planet(Planet::Moon)?;
+        let day = self.frame(&dusk.offset(-43200.0)?)?;
+
+        let mut reports = Vec::with_capacity(targets.len());
+        for (target, visibility) in targets.iter().zip(visibility) {
+            let rise = day.rises_above(0.0, &target.source, Refraction::Standard);
+            let set = match &rise {
+                Some(rise) => self.frame(rise)?.sets_below(0.0, &target.source, Refraction::Standard),
+                None => day.sets_below(0.0, &target.source, Refraction::Standard),
+            };
+            let culmination = self.frame(&visibility.culmination)?;
+            let pos = culmination.sky_pos(&target.source, ReferenceSystem::Tod)?;
+            reports.push(TargetReport {
+                name: target.name.clone(),
+                rise,
+                set,
+                transit: day.transit_time(&target.source),
+                max_elevation: visibility.max_elevation,
+                culmination: visibility.culmination,
+                moon_separation: pos.separation(&culmination.sky_pos(&moon, ReferenceSystem::Tod)?),
+                observable: visibility.windows.iter().map(|(from, to)| to.diff(from)).sum(),
+            });
+        }
+
+        let midnight = dusk.offset(dawn.diff(&dusk) / 2.0)?;
+        Ok(Some(NightReport {
+            dusk,
+            dawn,
+            moon_illumination: illuminated_fraction(&self.frame(&midnight)?)?,
+            targets: reports,
+        }))
+    }
+
+    /// Schedules each target once, for its requested duration, from `start` to `end`: the next
+    /// observation is always the one that can start the earliest, and targets earlier in the list
+    /// win ties. Targets without a duration are not scheduled.
+    pub fn schedule(&self, start: &Time, end: &Time, targets: &[PlanTarget]) -> Result<Vec<ScheduledObservation>> {
+        let visibility = self.evaluate(start, end, targets)?;
+        let mut pending: Vec<usize> = (0..targets.len()).filter(|&i| targets[i].constraints.duration > 0.0).collect();
+        let mut schedule = Vec::new();
+        let mut time = *start;
+
+        loop {
+            // Earliest start of each pending target in one of its windows
+            let next = pending
+                .iter()
+                .enumerate()
+                .filter_map(|(k, &i)| {
+                    let duration = targets[i].constraints.duration;
+                    visibility[i]
+                        .windows
+                        .iter()
+                        .map(|(from, to)| (if from.diff(&time) > 0.0 { *from } else { time }, *to))
+                        .find(|(from, to)| to.diff(from) >= duration)
+                        .map(|(from, _)| (k, from))
+                })
+                .min_by(|(_, a), (_, b)| a.diff(b).total_cmp(&0.0));
+            let Some((k, from)) = next else {
+                break;
+            };
+            let target = &targets[pending.remove(k)];
+            let to = from.offset(target.constraints.duration)?;
+            schedule.push(ScheduledObservation { name: target.name.clone(), start: from, end: to });
+            time = to;
+        }
+        Ok(schedule)
+    }
+
+    fn frame(&self, time: &Time) -> Result<ObservingFrame> {
+        ObservingFrame::new(self.accuracy, &self.observer, time, self.dx, self.dy)
+    }
+}
+
+/// `HH:MM` of a UTC time.
+fn clock(time: &Time) -> String {
+    time.iso_timestamp().get(11..16).unwrap_or_default().to_string()
+}
+
+fn meets(
+    constraints: &Constraints,
+    el: f64,
+    airmass: Option<f64>,
+    pos: &SkyPos,
+    moon: Option<&SkyPos>,
+    sun_el: Option<f64>,
+) -> bool {
+    if el < constraints.min_elevation {
+        return false;
+    }
+    if let Some(max) = constraints.max_airmass
+        && airmass.is_none_or(|airmass| airmass > max)
+    {
+        return false;
+    }
+    if let (Some(max), Some(sun_el)) = (constraints.max_sun_elevation, sun_el)
+        && sun_el > max
+    {
+        return false;
+    }
+    match (constraints.min_moon_separation, moon) {
+        (Some(min), Some(moon)) => pos.separation(moon) >= min,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::time::Timescale;
+
+    fn plan() -> Plan {
+        Plan::new(Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap()).with_accuracy(Accuracy::Reduced).with_step(600.0)
+    }
+
+    fn utc(jd: f64) -> Time {
+        Time::new(Timescale::Utc, jd, 37, 0.035).unwrap()
+    }
+
+    fn target(name: &str, ra: f64, dec: f64, constraints: Constraints) -> PlanTarget {
+        PlanTarget::new(name, Source::star(name, ra, dec, "ICRS").unwrap(), constraints)
+    }
+
+    fn hours(constraints: Constraints, duration: f64) -> Constraints {
+        Constraints { duration: duration * 3600.0, ..constraints }
+    }
+
+    #[test]
+    fn short_summer_nights() {
+        let start = utc(2460850.5);
+        let (dusk, dawn) = plan().night(&start).unwrap().unwrap();
+        assert!(dusk.diff(&start) > 0.0);
+        // About 6 hours between nautical dusk and dawn, around 19h UTC at 87 E
+        let night = dawn.diff(&dusk) / 3600.0;
+        assert!(night > 5.0 && night < 8.0, "{night}");
+        assert_eq!(clock(&dusk).len(), 5);
+        let (astronomical, _) = plan().with_sun_elevation(-18.0).night(&start).unwrap().unwrap();
+        assert!(astronomical.diff(&dusk) > 0.0);
+    }
+
+    #[test]
+    fn constraints_are_sampled() {
+        let plan = plan();
+        let (dusk, dawn) = plan.night(&utc(2460850.5)).unwrap().unwrap();
+        let high = Constraints { min_elevation: 30.0, max_airmass: Some(2.0), ..Default::default() };
+        let targets = [
+            target("Vega", 18.6156, 38.7836, hours(high, 1.0)),
+            target("Canopus", 6.3992, -52.6957, high),
+            target("Vega", 18.6156, 38.7836, hours(high, 12.0)),
+        ];
+        let visibility = plan.evaluate(&dusk, &dawn, &targets).unwrap();
+        let vega = &visibility[0];
+        assert!(vega.feasible && !vega.windows.is_empty());
+        assert!(vega.max_elevation > 80.0 && vega.max_elevation <= 90.0);
+        for (from, to) in &vega.windows {
+            assert!(from.diff(&dusk) >= 0.0 && dawn.diff(to) >= 0.0);
+        }
+        assert!(visibility[1].windows.is_empty() && !visibility[1].feasible);
+        assert!(visibility[1].max_elevation < 0.0);
+        assert!(!visibility[2].feasible);
+        assert!(plan.evaluate(&dawn, &dusk, &targets).is_err());
+        assert!(plan.clone().with_step(0.0).evaluate(&dusk, &dawn, &targets).is_err());
+    }
+
+    #[test]
+    fn windows_restrict_targets() {
+        let plan = plan();
+        let (dusk, dawn) = plan.night(&utc(2460850.5)).unwrap().unwrap();
+        let (from, to) = (dusk.offset(3600.0).unwrap(), dusk.offset(7200.0).unwrap());
+        let polaris = target("Polaris", 2.5303, 89.2641, Constraints::default()).with_window(&from, &to);
+        let visibility = plan.evaluate(&dusk, &dawn, &[polaris]).unwrap();
+        let [(start, end)] = visibility[0].windows[..] else {
+            panic!("{:?}", visibility[0].windows);
+        };
+        assert!(start.diff(&from) >= 0.0 && start.diff(&from) < 600.0);
+        assert!(to.diff(&end) >= 0.0 && to.diff(&end) < 600.0);
+    }
+
+    #[test]
+    fn schedules_in_order_of_availability() {
+        let plan = plan();
+        let (dusk, dawn) = plan.night(&utc(2460850.5)).unwrap().unwrap();
+        let up = Constraints { min_elevation: 20.0, ..Default::default() };
+        let targets = [
+            target("Polaris", 2.5303, 89.2641, hours(up, 1.0)),
+            target("Kochab", 14.8451, 74.1555, hours(up, 0.5)),
+            target("Dubhe", 11.0621, 61.7510, up),
+        ];
+        let schedule = plan.schedule(&dusk, &dawn, &targets).unwrap();
+        let names: Vec<&str> = schedule.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, ["Polaris", "Kochab"]);
+        assert_eq!(schedule[0].start.diff(&dusk), 0.0);
+        assert_eq!(schedule[1].start.diff(&schedule[0].end), 0.0);
+        assert!((schedule[1].end.diff(&schedule[1].start) - 1800.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn meeting_constraints() {
+        let pos = SkyPos { ra: 12.0, dec: 10.0, dist: 1e9, rv: 0.0, r_hat: [0.0; 3] };
+        let moon = SkyPos { ra: 12.0, dec: 25.0, ..pos };
+        let constraints = Constraints { min_elevation: 30.0, max_airmass: Some(1.5), min_moon_separation: Some(20.0), max_sun_elevation: Some(-18.0), duration: 0.0 };
+        assert!(!meets(&constraints, 45.0, Some(1.4), &pos, Some(&moon), Some(-20.0)));
+        let far = SkyPos { dec: 40.0, ..moon };
+        assert!(meets(&constraints, 45.0, Some(1.4), &pos, Some(&far), Some(-20.0)));
+        assert!(!meets(&constraints, 25.0, Some(1.4), &pos, Some(&far), Some(-20.0)));
+        assert!(!meets(&constraints, 45.0, Some(1.6), &pos, Some(&far), Some(-20.0)));
+        assert!(!meets(&constraints, 45.0, None, &pos, Some(&far), Some(-20.0)));
+        assert!(!meets(&constraints, 45.0, Some(1.4), &pos, Some(&far), Some(-10.0)));
+        assert!(meets(&Constraints::default(), 1.0, None, &pos, None, None));
+    }
+}
@@ -0,0 +1,123 @@
+use super::error::{Error, Result};
+use super::source::Planet;
+use supernovas_sys as sn;
+
+/// Redshift value z = &delta;&lambda; / &lambda;<sub>rest</sub>, valid for z &gt; -1.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Redshift(f64);
+
+impl Redshift {
+    /// Redshift from its numerical value.
+    pub fn new(z: f64) -> Result<Self> {
+        if z.is_finite() && z > -1.0 {
+            Ok(Redshift(z))
+        } else {
+            Err(Error::InvalidArgument("redshift must be finite and greater than -1"))
+        }
+    }
+
+    /// Redshift for a relativistic recession velocity [km/s].
+    pub fn from_velocity(velocity: f64) -> Result<Self> {
+        Redshift::new(unsafe { sn::novas_v2z(velocity) })
+    }
+
+    /// Gravitational redshift seen by a distant observer, for light emitted at radius `radius` [m]
+    /// around a spherical body of mass `mass` [kg].
+    pub fn gravitational(mass: f64, radius: f64) -> Result<Self> {
+        Redshift::new(unsafe { sn::grav_redshift(mass, radius) })
+    }
+
+    /// Gravitational redshift seen by a distant observer, for light emitted at `distance` [m]
+    /// from the center of a major body, e.g. from its surface.
+    pub fn gravitational_at(body: Planet, distance: f64) -> Result<Self> {
+        let mass = body.mass().ok_or(Error::InvalidArgument("body has no mass"))?;
+        if distance.is_nan() || distance <= 0.0 {
+            return Err(Error::InvalidArgument("distance must be positive"));
+        }
+        Redshift::gravitational(mass, distance)
+    }
+
+    /// Redshift for the relativistic Doppler factor f<sub>obs</sub> / f<sub>rest</sub>.
+    pub fn from_doppler_factor(factor: f64) -> Result<Self> {
+        if factor.is_nan() || factor <= 0.0 {
+            return Err(Error::InvalidArgument("Doppler factor must be positive"));
+        }
+        Redshift::new(1.0 / factor - 1.0)
+    }
+
+    /// Numerical value of the redshift.
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    /// [km/s] Relativistic recession velocity.
+    pub fn velocity(self) -> f64 {
+        unsafe { sn::novas_z2v(self.0) }
+    }
+
+    /// Relativistic Doppler factor f<sub>obs</sub> / f<sub>rest</sub> = 1 / (1 + z).
+    pub fn doppler_factor(self) -> f64 {
+        1.0 / (1.0 + self.0)
+    }
+
+    /// Fractional frequency shift (f<sub>obs</sub> - f<sub>rest</sub>) / f<sub>rest</sub> =
+    /// -z / (1 + z), e.g. for clock comparisons.
+    pub fn fractional_frequency_shift(self) -> f64 {
+        -self.0 / (1.0 + self.0)
+    }
+
+    /// Compound redshift of applying this and `other` in series.
+    pub fn compound(self, other: Redshift) -> Result<Self> {
+        Redshift::new(unsafe { sn::novas_z_add(self.0, other.0) })
+    }
+
+    /// Redshift of a body moving with the same speed in the opposite direction.
+    pub fn inverse(self) -> Result<Self> {
+        Redshift::new(unsafe { sn::novas_z_inv(self.0) })
+    }
+
+    /// Applies this redshift as an incremental correction to a radial velocity [km/s].
+    pub fn apply(self, radial_velocity: f64) -> f64 {
+        unsafe { sn::redshift_vrad(radial_velocity, self.0) }
+    }
+
+    /// Removes this redshift correction from a radial velocity [km/s].
+    pub fn remove(self, radial_velocity: f64) -> f64 {
+        unsafe { sn::unredshift_vrad(radial_velocity, self.0) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn velocities_and_doppler_factors() {
+        assert!(Redshift::new(-1.0).is_err());
+        assert!(Redshift::new(f64::NAN).is_err());
+        let z = Redshift::new(0.158339).unwrap();
+        assert!((Redshift::from_velocity(z.velocity()).unwrap().value() - z.value()).abs() < 1e-12);
+        // [km/s] Relativistic, rather than cz
+        assert!((z.velocity() - 43751.0).abs() < 1.0);
+        assert!((Redshift::from_doppler_factor(z.doppler_factor()).unwrap().value() - z.value()).abs() < 1e-12);
+        assert!((z.fractional_frequency_shift() + z.value() * z.doppler_factor()).abs() < 1e-15);
+        assert!(Redshift::from_doppler_factor(0.0).is_err());
+    }
+
+    #[test]
+    fn compounds_and_inverts() {
+        let z = Redshift::new(0.5).unwrap();
+        assert!((z.compound(Redshift::new(1.0).unwrap()).unwrap().value() - 2.0).abs() < 1e-12);
+        assert!(z.compound(z.inverse().unwrap()).unwrap().value().abs() < 1e-12);
+        assert!((z.remove(z.apply(100.0)) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn gravitational() {
+        // The Sun at its photosphere, 636 m/s
+        let sun = Redshift::gravitational_at(Planet::Sun, 6.957e8).unwrap();
+        assert!((sun.velocity() - 0.636).abs() < 1e-3);
+        assert!(Redshift::gravitational_at(Planet::Sun, 0.0).is_err());
+        assert!(Redshift::gravitational_at(Planet::Ssb, 1.0).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::Arc;
+use supernovas_sys as sn;
+
+/// A custom refraction model: refraction [deg] as a function of the astrometric (unrefracted)
+/// elevation [deg] and the observer location and weather.
+pub type CustomRefraction = dyn Fn(f64, &sn::on_surface) -> f64 + Send + Sync;
+
+/// Atmospheric refraction model for horizontal positions and rise and set searches.
+///
+/// `true` and `false` convert to [`Refraction::Standard`] and [`Refraction::None`].
+#[derive(Clone)]
+pub enum Refraction {
+    /// No refraction
+    None,
+    /// Optical refraction for a standard atmosphere
+    Standard,
+    /// Optical refraction with the weather of the observer
+    Optical,
+    /// Radio refraction with the weather of the observer
+    Radio,
+    /// A custom model. Models are evaluated on the calling thread; a panic in the model aborts.
+    Custom(Arc<CustomRefraction>),
+}
+
+impl Refraction {
+    /// A custom model from a closure, see [`CustomRefraction`].
+    pub fn custom(model: impl Fn(f64, &sn::on_surface) -> f64 + Send + Sync + 'static) -> Self {
+        Refraction::Custom(Arc::new(model))
+    }
+
+    // Calls `f` with the NOVAS callback of the model. Custom models are reached through a
+    // thread-local slot, which is set for the duration of the call.
+    pub(crate) fn with_model<R>(&self, f: impl FnOnce(sn::RefractionModel) -> R) -> R {
+        let model = match self {
+            Refraction::None => return f(None),
+            Refraction::Standard => return f(Some(sn::novas_standard_refraction)),
+            Refraction::Optical => return f(Some(sn::novas_optical_refraction)),
+            Refraction::Radio => return f(Some(sn::novas_radio_refraction)),
+            Refraction::Custom(model) => model.clone(),
+        };
+        let previous = CUSTOM.with(|slot| slot.replace(Some(model)));
+        let result = f(Some(custom));
+        CUSTOM.with(|slot| slot.replace(previous));
+        result
+    }
+}
+
+impl From<bool> for Refraction {
+    fn from(refraction: bool) -> Self {
+        if refraction { Refraction::Standard } else { Refraction::None }
+    }
+}
+
+impl fmt::Debug for Refraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refraction::None => f.write_str("None"),
+            Refraction::Standard => f.write_str("Standard"),
+            Refraction::Optical => f.write_str("Optical"),
+            Refraction::Radio => f.write_str("Radio"),
+            Refraction::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+thread_local! {
+    static CUSTOM: RefCell<Option<Arc<CustomRefraction>>> = const { RefCell::new(None) };
+}
+
+// NOVAS callback of the custom model in use on this thread. Refraction for observed elevations is
+// obtained by inverting the model, as NOVAS does for its radio model.
+unsafe extern "C" fn custom(jd_tt: f64, loc: *const sn::on_surface, type_: sn::novas_refraction_type, el: f64) -> f64 {
+    if type_ == sn::novas_refraction_type_NOVAS_REFRACT_OBSERVED {
+        return unsafe { sn::novas_inv_refract(Some(custom), jd_tt, loc, sn::novas_refraction_type_NOVAS_REFRACT_ASTROMETRIC, el) };
+    }
+    let Some(loc) = (unsafe { loc.as_ref() }) else {
+        return f64::NAN;
+    };
+    // Cloned out of the slot, so that the model may itself use custom refraction
+    match CUSTOM.with(|slot| slot.borrow().clone()) {
+        Some(model) => model(el, loc),
+        None => f64::NAN,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(refraction: &Refraction, type_: sn::novas_refraction_type, el: f64) -> f64 {
+        let loc = sn::on_surface { latitude: 43.4712, longitude: 87.1781, height: 0.0, temperature: 10.0, pressure: 1010.0, humidity: 40.0 };
+        refraction.with_model(|model| unsafe { model.unwrap()(2460850.5, &loc, type_, el) })
+    }
+
+    #[test]
+    fn standard_models() {
+        assert!(Refraction::from(false).with_model(|model| model.is_none()));
+        let standard = evaluate(&true.into(), sn::novas_refraction_type_NOVAS_REFRACT_ASTROMETRIC, 45.0);
+        // About 1 arcmin at 45 degrees
+        assert!((standard * 60.0 - 1.0).abs() < 0.1, "{standard}");
+        assert!(evaluate(&Refraction::Optical, sn::novas_refraction_type_NOVAS_REFRACT_ASTROMETRIC, 10.0) > standard);
+        assert_eq!(format!("{:?}", Refraction::custom(|_, _| 0.0)), "Custom(..)");
+    }
+
+    #[test]
+    fn custom_models_invert_for_observed_elevations() {
+        let model = Refraction::custom(|el, loc| 0.01 * (90.0 - el) / 90.0 * loc.pressure / 1010.0);
+        assert!((evaluate(&model, sn::novas_refraction_type_NOVAS_REFRACT_ASTROMETRIC, 0.0) - 0.01).abs() < 1e-12);
+        let observed = 30.0 + evaluate(&model, sn::novas_refraction_type_NOVAS_REFRACT_ASTROMETRIC, 30.0);
+        let inverse = evaluate(&model, sn::novas_refraction_type_NOVAS_REFRACT_OBSERVED, observed);
+        assert!((observed - inverse - 30.0).abs() < 1e-6);
+        // The slot is cleared after the call
+        assert!(CUSTOM.with(|slot| slot.borrow().is_none()));
+    }
+}
@@ -0,0 +1,350 @@
+//! Rotation matrices of the IAU precession-nutation models, Earth rotation measures and the full
+//! ITRS to GCRS rotation, for custom transformation chains.
+
+use super::error::{check, Result};
+use super::frame::Accuracy;
+use super::time::{Time, Timescale};
+use std::sync::{Arc, RwLock};
+use supernovas_sys as sn;
+
+const JD_J2000: f64 = 2451545.0;
+
+/// A 3x3 rotation matrix, applied to column vectors as `v' = M v`.
+pub type Matrix = [[f64; 3]; 3];
+
+/// Frame bias matrix, from ICRS to the J2000 dynamical frame.
+pub fn frame_bias_matrix() -> Result<Matrix> {
+    matrix(|v, out| {
+        check("frame_tie", || unsafe {
+            sn::frame_tie(v.as_ptr(), sn::novas_frametie_direction_ICRS_TO_J2000, out.as_mut_ptr())
+        })
+    })
+}
+
+/// Precession matrix (IAU 2006), from the J2000 mean equator and equinox to that of `jd_tdb`.
+pub fn precession_matrix(jd_tdb: f64) -> Result<Matrix> {
+    matrix(|v, out| {
+        check("precession", || unsafe {
+            sn::precession(JD_J2000, v.as_ptr(), jd_tdb, out.as_mut_ptr())
+        } as i32)
+    })
+}
+
+/// Nutation matrix, from the mean to the true equator and equinox of `jd_tdb`.
+pub fn nutation_matrix(jd_tdb: f64, accuracy: Accuracy) -> Result<Matrix> {
+    matrix(|v, out| {
+        check("nutation", || unsafe {
+            sn::nutation(jd_tdb, sn::novas_nutation_direction_NUTATE_MEAN_TO_TRUE, accuracy.to_raw(), v.as_ptr(), out.as_mut_ptr())
+        })
+    })
+}
+
+/// Composite bias-precession-nutation matrix `N P B`, from ICRS to the true equator and
+/// equinox of `jd_tdb`.
+pub fn icrs_to_tod_matrix(jd_tdb: f64, accuracy: Accuracy) -> Result<Matrix> {
+    let bias = frame_bias_matrix()?;
+    let precession = precession_matrix(jd_tdb)?;
+    let nutation = nutation_matrix(jd_tdb, accuracy)?;
+    Ok(multiply(&nutation, &multiply(&precession, &bias)))
+}
+
+/// Rotation matrix from the GCRS to the Celestial Intermediate Reference System of `jd_tdb`,
+/// whose rows are the CIO, the y axis and the Celestial Intermediate Pole in the GCRS.
+pub fn gcrs_to_cirs_matrix(jd_tdb: f64, accuracy: Accuracy) -> Result<Matrix> {
+    matrix(|v, out| {
+        check("gcrs_to_cirs", || unsafe {
+            sn::gcrs_to_cirs(jd_tdb, accuracy.to_raw(), v.as_ptr(), out.as_mut_ptr())
+        })
+    })
+}
+
+/// [h] Right ascension of the Celestial Intermediate Origin relative to the true equinox of
+/// `jd_tt`, the negative of the equation of the origins.
+pub fn cio_ra(jd_tt: f64, accuracy: Accuracy) -> Result<f64> {
+    let mut ra = 0.0;
+    check("cio_ra", || unsafe { sn::cio_ra(jd_tt, accuracy.to_raw(), &mut ra) } as i32)?;
+    Ok(ra)
+}
+
+/// [deg] Earth Rotation Angle (IAU 2000) at the UT1 Julian date `jd_ut1`.
+pub fn era(jd_ut1: f64) -> f64 {
+    unsafe { sn::era(jd_ut1, 0.0) }
+}
+
+/// [h] Greenwich Mean Sidereal Time at the UT1 Julian date `jd_ut1`. `ut1_to_tt` is TT - UT1
+/// in seconds.
+pub fn gmst(jd_ut1: f64, ut1_to_tt: f64) -> Result<f64> {
+    sidereal_time(jd_ut1, ut1_to_tt, sn::novas_equinox_type_NOVAS_MEAN_EQUINOX, Accuracy::Reduced)
+}
+
+/// [h] Greenwich Apparent Sidereal Time at the UT1 Julian date `jd_ut1`, including the equation
+/// of the equinoxes. `ut1_to_tt` is TT - UT1 in seconds.
+pub fn gast(jd_ut1: f64, ut1_to_tt: f64, accuracy: Accuracy) -> Result<f64> {
+    sidereal_time(jd_ut1, ut1_to_tt, sn::novas_equinox_type_NOVAS_TRUE_EQUINOX, accuracy)
+}
+
+fn sidereal_time(jd_ut1: f64, ut1_to_tt: f64, equinox: sn::novas_equinox_type, accuracy: Accuracy) -> Result<f64> {
+    let mut gst = 0.0;
+    check("sidereal_time", || unsafe {
+        sn::sidereal_time(jd_ut1, 0.0, ut1_to_tt, equinox, sn::novas_earth_rotation_measure_EROT_ERA, accuracy.to_raw(), &mut gst)
+    } as i32)?;
+    Ok(gst)
+}
+
+/// Rotation matrix from the ITRS to the GCRS at `time`: polar motion, the Earth Rotation Angle
+/// and the CIO-based precession-nutation, as NOVAS `ter2cel()`. `dx` and `dy` are the polar
+/// offsets [mas] from IERS Bulletin A; UT1 is that of `time`.
+pub fn itrs_to_gcrs_matrix(time: &Time, accuracy: Accuracy, dx: f64, dy: f64) -> Result<Matrix> {
+    matrix(|v, out| ters_to_cels_into(time, accuracy, dx, dy, v, out))
+}
+
+/// Rotation matrix from the GCRS to the ITRS at `time`, the inverse of [`itrs_to_gcrs_matrix`].
+pub fn gcrs_to_itrs_matrix(time: &Time, accuracy: Accuracy, dx: f64, dy: f64) -> Result<Matrix> {
+    matrix(|v, out| cels_to_ters_into(time, accuracy, dx, dy, v, out))
+}
+
+/// Rotates an ITRS vector, e.g. a station position or an Earth-fixed satellite velocity
+/// component, to the GCRS at `time`. `dx` and `dy` are the polar offsets [mas].
+pub fn ters_to_cels(time: &Time, accuracy: Accuracy, dx: f64, dy: f64, v: &[f64; 3]) -> Result<[f64; 3]> {
+    let mut out = [0.0; 3];
+    ters_to_cels_into(time, accuracy, dx, dy, v, &mut out)?;
+    Ok(out)
+}
+
+/// Rotates a GCRS vector to the ITRS at `time`. `dx` and `dy` are the polar offsets [mas].
+pub fn cels_to_ters(time: &Time, accuracy: Accuracy, dx: f64, dy: f64, v: &[f64; 3]) -> Result<[f64; 3]> {
+    let mut out = [0.0; 3];
+    cels_to_ters_into(time, accuracy, dx, dy, v, &mut out)?;
+    Ok(out)
+}
+
+fn ters_to_cels_into(time: &Time, accuracy: Accuracy, dx: f64, dy: f64, v: &[f64; 3], out: &mut [f64; 3]) -> Result<()> {
+    let (ijd, fjd) = time.split(Timescale::Ut1);
+    check("ter2cel", || unsafe {
+        sn::ter2cel(
+            ijd as f64,
+            fjd,
+            time.as_raw().ut1_to_tt,
+            sn::novas_earth_rotation_measure_EROT_ERA,
+            accuracy.to_raw(),
+            sn::novas_equatorial_class_NOVAS_REFERENCE_CLASS,
+            dx / 1000.0,
+            dy / 1000.0,
+            v.as_ptr(),
+            out.as_mut_ptr(),
+        )
+    } as i32)
+}
+
+fn cels_to_ters_into(time: &Time, accuracy: Accuracy, dx: f64, dy: f64, v: &[f64; 3], out: &mut [f64; 3]) -> Result<()> {
+    let (ijd, fjd) = time.split(Timescale::Ut1);
+    check("cel2ter", || unsafe {
+        sn::cel2ter(
+            ijd as f64,
+            fjd,
+            time.as_raw().ut1_to_tt,
+            sn::novas_earth_rotation_measure_EROT_ERA,
+            accuracy.to_raw(),
+            sn::novas_equatorial_class_NOVAS_REFERENCE_CLASS,
+            dx / 1000.0,
+            dy / 1000.0,
+            v.as_ptr(),
+            out.as_mut_ptr(),
+        )
+    } as i32)
+}
+
+/// A nutation series, e.g. a truncated IAU 2000B series or a full IAU 2000A implementation, that
+/// can replace the NOVAS low-precision nutation model with [`set_nutation_model`].
+pub trait NutationModel: Send + Sync {
+    /// Nutation in longitude and in obliquity [rad], at the TT Julian date
+    /// `jd_tt[0] + jd_tt[1]`.
+    fn nutation(&self, jd_tt: [f64; 2]) -> (f64, f64);
+}
+
+static NUTATION: RwLock<Option<Arc<dyn NutationModel>>> = RwLock::new(None);
+
+/// Registers `model` as the nutation model of reduced-accuracy calculations, replacing the
+/// previous one. Full accuracy always uses the IAU 2000A model of NOVAS. The model is kept until
+/// it is replaced. NOVAS caches the nutation of the last date it computed, which is not
+/// recomputed with the new model.
+pub fn set_nutation_model(model: impl NutationModel + 'static) -> Result<()> {
+    *NUTATION.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(model));
+    check("set_nutation_lp_provider", || unsafe { sn::set_nutation_lp_provider(Some(nutation_provider)) })
+}
+
+// NOVAS callback forwarding to the registered model.
+unsafe extern "C" fn nutation_provider(jd_tt_high: f64, jd_tt_low: f64, dpsi: *mut f64, deps: *mut f64) -> std::os::raw::c_int {
+    if dpsi.is_null() || deps.is_null() {
+        return -1;
+    }
+    // Cloned out of the lock, so that the model may be replaced during the call
+    let Some(model) = NUTATION.read().unwrap_or_else(|err| err.into_inner()).clone() else {
+        return -1;
+    };
+    let (psi, eps) = model.nutation([jd_tt_high, jd_tt_low]);
+    unsafe {
+        *dpsi = psi;
+        *deps = eps;
+    }
+    0
+}
+
+/// Matrix product `a b`.
+pub fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut m = [[0.0; 3]; 3];
+    for (row, a) in m.iter_mut().zip(a) {
+        for (j, x) in row.iter_mut().enumerate() {
+            *x = a[0] * b[0][j] + a[1] * b[1][j] + a[2] * b[2][j];
+        }
+    }
+    m
+}
+
+/// Transpose, i.e. the inverse rotation.
+pub fn transpose(m: &Matrix) -> Matrix {
+    let mut t = [[0.0; 3]; 3];
+    for (i, row) in m.iter().enumerate() {
+        for (j, x) in row.iter().enumerate() {
+            t[j][i] = *x;
+        }
+    }
+    t
+}
+
+/// The matrix as an nalgebra matrix.
+#[cfg(feature = "nalgebra")]
+pub fn to_nalgebra(m: &Matrix) -> nalgebra::Matrix3<f64> {
+    nalgebra::Matrix3::new(m[0][0], m[0][1], m[0][2], m[1][0], m[1][1], m[1][2], m[2][0], m[2][1], m[2][2])
+}
+
+/// An nalgebra matrix as a [`Matrix`].
+#[cfg(feature = "nalgebra")]
+pub fn from_nalgebra(m: &nalgebra::Matrix3<f64>) -> Matrix {
+    std::array::from_fn(|i| std::array::from_fn(|j| m[(i, j)]))
+}
+
+/// The matrix as a glam matrix.
+#[cfg(feature = "glam")]
+pub fn to_glam(m: &Matrix) -> glam::DMat3 {
+    // glam matrices are built from their columns
+    glam::DMat3::from_cols_array_2d(&transpose(m))
+}
+
+/// A glam matrix as a [`Matrix`].
+#[cfg(feature = "glam")]
+pub fn from_glam(m: &glam::DMat3) -> Matrix {
+    transpose(&m.to_cols_array_2d())
+}
+
+/// Builds the matrix of a linear transform from the images of the unit vectors.
+fn matrix(mut apply: impl FnMut(&[f64; 3], &mut [f64; 3]) -> Result<()>) -> Result<Matrix> {
+    let mut m = [[0.0; 3]; 3];
+    for j in 0..3 {
+        let mut unit = [0.0; 3];
+        unit[j] = 1.0;
+        let mut column = [0.0; 3];
+        apply(&unit, &mut column)?;
+        for (row, x) in m.iter_mut().zip(column) {
+            row[j] = x;
+        }
+    }
+    Ok(m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // J2025.5
+    const JD: f64 = 2460676.125;
+
+    fn assert_near(a: &Matrix, b: &Matrix, tolerance: f64) {
+        for (a, b) in a.iter().flatten().zip(b.iter().flatten()) {
+            assert!((a - b).abs() < tolerance, "{a:?} != {b:?}");
+        }
+    }
+
+    fn apply(m: &Matrix, v: &[f64; 3]) -> [f64; 3] {
+        std::array::from_fn(|i| m[i][0] * v[0] + m[i][1] * v[1] + m[i][2] * v[2])
+    }
+
+    const IDENTITY: Matrix = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+    #[test]
+    fn matrices_are_rotations() {
+        for m in [
+            frame_bias_matrix().unwrap(),
+            precession_matrix(JD).unwrap(),
+            nutation_matrix(JD, Accuracy::Reduced).unwrap(),
+            icrs_to_tod_matrix(JD, Accuracy::Reduced).unwrap(),
+            gcrs_to_cirs_matrix(JD, Accuracy::Reduced).unwrap(),
+        ] {
+            assert_near(&multiply(&m, &transpose(&m)), &IDENTITY, 1e-14);
+        }
+        assert_near(&precession_matrix(2451545.0).unwrap(), &IDENTITY, 1e-15);
+        // The frame bias is 23 mas
+        let bias = frame_bias_matrix().unwrap();
+        assert!(bias[0][1].abs() > 3e-8 && bias[0][1].abs() < 1.5e-7);
+    }
+
+    #[test]
+    fn equinox_and_cio_systems_share_their_pole() {
+        let tod = icrs_to_tod_matrix(JD, Accuracy::Reduced).unwrap();
+        let cirs = gcrs_to_cirs_matrix(JD, Accuracy::Reduced).unwrap();
+        for (a, b) in tod[2].iter().zip(&cirs[2]) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        // The CIO, in true equator and equinox coordinates
+        let cio = apply(&tod, &cirs[0]);
+        let ra = cio[1].atan2(cio[0]).to_degrees() / 15.0;
+        assert!((ra - cio_ra(JD, Accuracy::Reduced).unwrap()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn earth_rotation() {
+        assert!((era(2451545.0) - 280.46061837504).abs() < 1e-9);
+        let gmst = gmst(2451545.0, 64.184).unwrap();
+        assert!((gmst - 18.697374558).abs() < 1e-6, "{gmst}");
+        // The equation of the equinoxes is at most 1.2 s
+        let equation = gast(2451545.0, 64.184, Accuracy::Reduced).unwrap() - gmst;
+        assert!(equation.abs() * 3600.0 < 1.2);
+    }
+
+    #[test]
+    fn itrs_and_gcrs() {
+        let time = Time::new(Timescale::Utc, 2460850.5, 37, 0.035).unwrap();
+        let to_gcrs = itrs_to_gcrs_matrix(&time, Accuracy::Reduced, 0.1, -0.2).unwrap();
+        assert_near(&gcrs_to_itrs_matrix(&time, Accuracy::Reduced, 0.1, -0.2).unwrap(), &transpose(&to_gcrs), 1e-14);
+        let station = [1.0e6, 4.0e6, 4.5e6];
+        let gcrs = ters_to_cels(&time, Accuracy::Reduced, 0.1, -0.2, &station).unwrap();
+        let expected = apply(&to_gcrs, &station);
+        assert!(gcrs.iter().zip(&expected).all(|(a, b)| (a - b).abs() < 1e-6));
+        let back = cels_to_ters(&time, Accuracy::Reduced, 0.1, -0.2, &gcrs).unwrap();
+        assert!(back.iter().zip(&station).all(|(a, b)| (a - b).abs() < 1e-6));
+        // The pole stays near the pole, away from the 0.5 arcsec of polar motion
+        assert!((to_gcrs[2][2] - 1.0).abs() < 1e-3);
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn nalgebra_matrices() {
+        let m = precession_matrix(JD).unwrap();
+        let n = to_nalgebra(&m);
+        // Same rows and columns
+        assert_eq!(n[(0, 1)], m[0][1]);
+        assert_eq!(n[(2, 0)], m[2][0]);
+        assert_eq!(from_nalgebra(&n), m);
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn glam_matrices() {
+        let m = precession_matrix(JD).unwrap();
+        let g = to_glam(&m);
+        // Columns, unlike the rows of a Matrix
+        assert_eq!(g.x_axis.y, m[1][0]);
+        assert_eq!(g.z_axis.x, m[0][2]);
+        assert_eq!(from_glam(&g), m);
+    }
+}
@@ -0,0 +1,114 @@
+//! Radial velocities and redshifts.
+//!
+//! Radial velocities are in km/s, positive for receding sources, and are the relativistic
+//! velocities of [`Redshift::velocity`]. Redshifts combine in series with
+//! [`Redshift::compound`], e.g. a cosmological redshift with the kinematic redshift of the
+//! source in its galaxy, rather than by adding velocities.
+//!
+//! For spectral-line observations, [`frame_velocity`] gives the radial velocity seen by an
+//! observer of a source at rest in a [`VelocityFrame`], and [`sky_frequency`] the frequency at
+//! which to observe a line of a source moving in that frame. They include the motion of the
+//! observer and the gravitational redshifts computed by NOVAS for a source at rest at the
+//! barycenter.
+
+use super::coords::{dot, Epoch, Equatorial};
+use super::error::Result;
+use super::frame::{ObservingFrame, ReferenceSystem};
+use super::source::Source;
+use supernovas_sys as sn;
+
+pub use super::redshift::Redshift;
+
+/// Standards of rest of radial velocities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VelocityFrame {
+    /// At rest with the observer
+    Topocentric,
+    /// At rest with the center of the Earth
+    Geocentric,
+    /// At rest with the Solar-System Barycenter
+    Barycentric,
+    /// At rest with the Sun
+    Heliocentric,
+    /// Kinematic Local Standard of Rest, see [`lsr_to_ssb`]
+    Lsr,
+}
+
+/// [km/s] Barycentric radial velocity of a source at `coords` (in the mean equator and equinox
+/// of `epoch`) with radial velocity `v_lsr` relative to the Local Standard of Rest.
+pub fn lsr_to_ssb(epoch: Epoch, coords: &Equatorial, v_lsr: f64) -> f64 {
+    unsafe { sn::novas_lsr_to_ssb_vel(epoch.julian_year(), coords.ra, coords.dec, v_lsr) }
+}
+
+/// [km/s] Radial velocity relative to the Local Standard of Rest of a source at `coords` (in the
+/// mean equator and equinox of `epoch`) with barycentric radial velocity `v_ssb`.
+pub fn ssb_to_lsr(epoch: Epoch, coords: &Equatorial, v_ssb: f64) -> f64 {
+    unsafe { sn::novas_ssb_to_lsr_vel(epoch.julian_year(), coords.ra, coords.dec, v_ssb) }
+}
+
+/// [km/s] Radial velocity, seen by the observer of `frame`, of a source in the ICRS direction
+/// `icrs` that is at rest in `rest`. Adding it to a velocity in `rest`, with
+/// [`Redshift::compound`], gives the velocity seen by the observer.
+pub fn frame_velocity(frame: &ObservingFrame, icrs: &Equatorial, rest: VelocityFrame) -> Result<f64> {
+    let raw = frame.as_raw();
+    let direction = icrs.to_vector();
+    // [km/s] barycentric radial velocity of a source at rest in the frame
+    let projected = |velocity: &[f64; 3]| dot(velocity, &direction) * sn::NOVAS_AU / sn::NOVAS_DAY / 1e3;
+    let source = match rest {
+        VelocityFrame::Topocentric => return Ok(0.0),
+        VelocityFrame::Geocentric => projected(&raw.earth_vel),
+        VelocityFrame::Barycentric => 0.0,
+        VelocityFrame::Heliocentric => projected(&raw.sun_vel),
+        VelocityFrame::Lsr => lsr_to_ssb(Epoch::J2000, icrs, 0.0),
+    };
+    // A source at rest at the barycenter, seen by the observer, with the gravitational redshifts
+    let at_rest = frame.sky_pos(&Source::star("", icrs.ra, icrs.dec, "ICRS")?, ReferenceSystem::Icrs)?.rv;
+    Ok(Redshift::from_velocity(source)?.compound(Redshift::from_velocity(at_rest)?)?.velocity())
+}
+
+/// Frequency seen by the observer of `frame` of a line at `rest_frequency`, in any unit, emitted
+/// by a source in the ICRS direction `icrs` with radial velocity `velocity` [km/s] in `rest`.
+pub fn sky_frequency(frame: &ObservingFrame, icrs: &Equatorial, rest: VelocityFrame, velocity: f64, rest_frequency: f64) -> Result<f64> {
+    let observed = Redshift::from_velocity(velocity)?.compound(Redshift::from_velocity(frame_velocity(frame, icrs, rest)?)?)?;
+    Ok(rest_frequency * observed.doppler_factor())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::frame::Accuracy;
+    use crate::novas::observer::Observer;
+    use crate::novas::time::{Time, Timescale};
+
+    fn frame() -> ObservingFrame {
+        let observer = Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap();
+        let time = Time::new(Timescale::Utc, 2460850.5, 37, 0.035).unwrap();
+        ObservingFrame::new(Accuracy::Reduced, &observer, &time, 0.0, 0.0).unwrap()
+    }
+
+    #[test]
+    fn lsr_round_trips() {
+        let coords = Equatorial::new(18.0, 30.0);
+        let v_ssb = lsr_to_ssb(Epoch::J2000, &coords, 10.0);
+        // The Sun moves at 18 km/s relative to the LSR
+        assert!(v_ssb != 10.0 && (v_ssb - 10.0).abs() < 18.0, "{v_ssb}");
+        // [km/s] The velocities add relativistically, per component
+        assert!((ssb_to_lsr(Epoch::J2000, &coords, v_ssb) - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn frame_velocities() {
+        let frame = frame();
+        let icrs = Equatorial::new(18.6156, 38.7836);
+        assert_eq!(frame_velocity(&frame, &icrs, VelocityFrame::Topocentric).unwrap(), 0.0);
+        // The orbital motion of the Earth is at most 30 km/s, and its rotation 0.5 km/s
+        let barycentric = frame_velocity(&frame, &icrs, VelocityFrame::Barycentric).unwrap();
+        let geocentric = frame_velocity(&frame, &icrs, VelocityFrame::Geocentric).unwrap();
+        assert!(barycentric.abs() < 30.5);
+        assert!(geocentric.abs() < 0.5);
+        let rest = 1420.405751;
+        assert_eq!(sky_frequency(&frame, &icrs, VelocityFrame::Topocentric, 0.0, rest).unwrap(), rest);
+        let sky = sky_frequency(&frame, &icrs, VelocityFrame::Barycentric, 0.0, rest).unwrap();
+        assert!((sky / rest - 1.0 + barycentric / 299792.458).abs() < 1e-8);
+    }
+}
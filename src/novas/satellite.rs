@@ -0,0 +1,462 @@
+//! Earth satellites from two-line element sets (TLEs), propagated with SGP4.
+//!
+//! A [`Satellite`] can be used as a [`Source`] in observing frames. Its passes over an observer
+//! are found with [`Satellite::passes`], which samples the elevation every few seconds with the
+//! [`events::search`](super::search) module: the rise and set times of NOVAS assume sources that
+//! move slowly over the sky and do not apply to satellites. Satellite sources are served by a NOVAS ephemeris
+//! provider that forwards other bodies to the provider installed before, e.g. CSPICE; installing
+//! another provider afterwards disables satellite sources until the next [`Satellite::source`].
+//!
+//! Only near-Earth orbits, with periods under 225 minutes, are supported.
+
+mod sgp4;
+
+use super::coords::dot;
+use super::error::{check, Error, Result};
+use super::events::search::Search;
+use super::frame::{Accuracy, ObservingFrame, ReferenceSystem};
+use super::observer::Observer;
+use super::source::{Planet, Source};
+use super::time::{Time, Timescale};
+use sgp4::Model;
+use std::ffi::CStr;
+use std::sync::{Arc, RwLock};
+use supernovas_sys as sn;
+
+/// A two-line element set, as distributed by CelesTrak and Space-Track.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tle {
+    /// Name from the title line, or the catalog number if there is none
+    pub name: String,
+    /// NORAD catalog number
+    pub norad_id: u32,
+    /// International designator, e.g. `98067A`
+    pub designator: String,
+    /// [day] UTC Julian date of the epoch
+    pub epoch: f64,
+    /// [rev/day^2] first derivative of the mean motion, divided by 2
+    pub mean_motion_dot: f64,
+    /// [1/Earth radii] drag term
+    pub bstar: f64,
+    /// [deg] inclination
+    pub inclination: f64,
+    /// [deg] right ascension of the ascending node
+    pub raan: f64,
+    pub eccentricity: f64,
+    /// [deg] argument of perigee
+    pub arg_perigee: f64,
+    /// [deg] mean anomaly
+    pub mean_anomaly: f64,
+    /// [rev/day] mean motion
+    pub mean_motion: f64,
+    /// Revolution number at epoch
+    pub revolution: u32,
+}
+
+impl Tle {
+    /// Parses TLEs in the two- or three-line format, verifying the checksums.
+    pub fn parse(text: &str) -> Result<Vec<Tle>> {
+        let lines: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(n, line)| (n, line.trim_end()))
+            .filter(|(_, line)| !line.is_empty())
+            .collect();
+        let mut tles = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let (n, line) = lines[i];
+            let title = match line.starts_with("1 ") {
+                true => None,
+                false => {
+                    i += 1;
+                    Some(line.trim_start_matches("0 ").trim())
+                }
+            };
+            let (Some(&(n1, line1)), Some(&(n2, line2))) = (lines.get(i), lines.get(i + 1)) else {
+                return Err(Error::Parse { what: "incomplete TLE", line: n + 1 });
+            };
+            tles.push(Tle::from_lines(title, (n1, line1), (n2, line2))?);
+            i += 2;
+        }
+        Ok(tles)
+    }
+
+    fn from_lines(title: Option<&str>, (n1, line1): (usize, &str), (n2, line2): (usize, &str)) -> Result<Self> {
+        let line1 = checked(line1, '1').ok_or(Error::Parse { what: "invalid TLE line 1", line: n1 + 1 })?;
+        let line2 = checked(line2, '2').ok_or(Error::Parse { what: "invalid TLE line 2", line: n2 + 1 })?;
+        let first = || -> Option<Tle> {
+            let year: i32 = field(line1, 18, 20)?;
+            let year = if year < 57 { 2000 + year } else { 1900 + year };
+            let day: f64 = field(line1, 20, 32)?;
+            let norad_id: u32 = field(line1, 2, 7)?;
+            Some(Tle {
+                name: title.filter(|t| !t.is_empty()).map_or_else(|| norad_id.to_string(), str::to_string),
+                norad_id,
+                designator: line1.get(9..17)?.trim().to_string(),
+                epoch: unsafe { sn::julian_date(year as _, 1, 1, 0.0) } + day - 1.0,
+                mean_motion_dot: field(line1, 33, 43)?,
+                bstar: exponential(line1.get(53..61)?)?,
+                inclination: 0.0,
+                raan: 0.0,
+                eccentricity: 0.0,
+                arg_perigee: 0.0,
+                mean_anomaly: 0.0,
+                mean_motion: 0.0,
+                revolution: 0,
+            })
+        };
+        let mut tle = first().ok_or(Error::Parse { what: "invalid TLE line 1", line: n1 + 1 })?;
+        let second = |tle: &mut Tle| -> Option<()> {
+            if field::<u32>(line2, 2, 7)? != tle.norad_id {
+                return None;
+            }
+            tle.inclination = field(line2, 8, 16)?;
+            tle.raan = field(line2, 17, 25)?;
+            tle.eccentricity = format!("0.{}", line2.get(26..33)?.trim()).parse().ok()?;
+            tle.arg_perigee = field(line2, 34, 42)?;
+            tle.mean_anomaly = field(line2, 43, 51)?;
+            tle.mean_motion = field(line2, 52, 63)?;
+            tle.revolution = field(line2, 63, 68).unwrap_or(0);
+            Some(())
+        };
+        second(&mut tle).ok_or(Error::Parse { what: "invalid TLE line 2", line: n2 + 1 })?;
+        Ok(tle)
+    }
+}
+
+// The line, if it has the given line number, is long enough and its checksum matches.
+fn checked(line: &str, number: char) -> Option<&str> {
+    if !line.is_ascii() || line.len() < 69 || !line.starts_with(number) {
+        return None;
+    }
+    let sum: u32 = line[..68]
+        .chars()
+        .map(|c| match c {
+            '-' => 1,
+            c => c.to_digit(10).unwrap_or(0),
+        })
+        .sum();
+    (line[68..69].parse::<u32>().ok()? == sum % 10).then_some(line)
+}
+
+// A numeric field of a TLE line, from 0-based column `from` to `to` (exclusive).
+fn field<T: std::str::FromStr>(line: &str, from: usize, to: usize) -> Option<T> {
+    line.get(from..to)?.trim().parse().ok()
+}
+
+// A TLE number with an implied decimal point and exponent, e.g. " 28098-4" for 0.28098e-4.
+fn exponential(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Some(0.0);
+    }
+    let (mantissa, exponent) = text.split_at(text.len().checked_sub(2)?);
+    let (sign, digits) = match mantissa.strip_prefix('-') {
+        Some(digits) => (-1.0, digits),
+        None => (1.0, mantissa.trim_start_matches('+')),
+    };
+    let mantissa: f64 = format!("0.{}", digits).parse().ok()?;
+    Some(sign * mantissa * 10f64.powi(exponent.parse().ok()?))
+}
+
+/// An Earth satellite propagated with SGP4 from its TLE.
+/// [s] Interval at which passes are searched. Passes shorter than this may be missed.
+const PASS_STEP: f64 = 30.0;
+
+/// A pass of a satellite above an elevation limit.
+#[derive(Debug, Clone, Copy)]
+pub struct Pass {
+    /// Time the satellite rises above the limit, or the start of the search
+    pub rise: Time,
+    /// Time of the highest elevation
+    pub culmination: Time,
+    /// [deg] highest elevation
+    pub max_elevation: f64,
+    /// Time the satellite sets below the limit, or the end of the search
+    pub set: Time,
+    /// Whether the satellite is lit by the Sun at culmination
+    pub sunlit: bool,
+    /// Time the satellite enters the shadow of the Earth during the pass
+    pub shadow_entry: Option<Time>,
+    /// Time the satellite leaves the shadow of the Earth during the pass
+    pub shadow_exit: Option<Time>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Satellite {
+    tle: Tle,
+    /// [day] TDB Julian date of the epoch
+    epoch_tdb: f64,
+    model: Model,
+}
+
+impl Satellite {
+    /// A satellite from its TLE, with `leap_seconds` (TAI - UTC) at its epoch. Fails for
+    /// deep-space orbits.
+    pub fn new(tle: Tle, leap_seconds: i32) -> Result<Self> {
+        let model = Model::new(&tle)?;
+        let epoch_tdb = Time::new(Timescale::Utc, tle.epoch, leap_seconds, 0.0)?.jd(Timescale::Tdb);
+        Ok(Satellite { tle, epoch_tdb, model })
+    }
+
+    pub fn tle(&self) -> &Tle {
+        &self.tle
+    }
+
+    /// [km, km/s] Position and velocity in the TEME frame of SGP4.
+    pub fn teme(&self, time: &Time) -> Result<([f64; 3], [f64; 3])> {
+        self.model.propagate((time.jd(Timescale::Tdb) - self.epoch_tdb) * 1440.0)
+    }
+
+    /// [km, km/s] Geocentric position and velocity in the GCRS.
+    pub fn gcrs(&self, time: &Time) -> Result<([f64; 3], [f64; 3])> {
+        self.gcrs_at(time.jd(Timescale::Tdb))
+    }
+
+    /// The satellite as a NOVAS source, named after it and numbered with its catalog number.
+    pub fn source(&self) -> Result<Source> {
+        let name: String = self.tle.name.chars().take(sn::SIZE_OF_OBJ_NAME as usize - 1).collect();
+        {
+            let mut satellites = SATELLITES.write().unwrap_or_else(|err| err.into_inner());
+            satellites.retain(|(n, s)| *n != name || s.tle.norad_id != self.tle.norad_id);
+            satellites.push((name.clone(), Arc::new(self.clone())));
+        }
+        install_provider()?;
+        Source::ephemeris(&name, self.tle.norad_id as i64)
+    }
+
+    /// Passes above `min_elevation` [deg] seen by `observer` between `start` and `end`, from
+    /// geometric elevations.
+    pub fn passes(&self, observer: &Observer, start: &Time, end: &Time, min_elevation: f64) -> Result<Vec<Pass>> {
+        let source = self.source()?;
+        let elevation = |time: &Time| -> Result<f64> {
+            let frame = ObservingFrame::new(Accuracy::Reduced, observer, time, 0.0, 0.0)?;
+            let pos = frame.sky_pos(&source, ReferenceSystem::Tod)?;
+            Ok(frame.to_horizontal(&pos, ReferenceSystem::Tod, false)?.el)
+        };
+        let search = Search::new(start, end)?.with_step(PASS_STEP)?;
+        let mut passes = Vec::new();
+        for (rise, set) in search.intervals(|time| Ok(elevation(time)? - min_elevation))? {
+            let within = Search::new(&rise, &set).ok();
+            // A culmination within a step of the bounds is not bracketed by the samples.
+            let culmination = match within {
+                Some(within) => within.with_step(set.diff(&rise) / 4.0)?.maxima(elevation)?.into_iter().next(),
+                None => None,
+            };
+            let culmination = match culmination {
+                Some(time) => time,
+                None if elevation(&rise)? >= elevation(&set)? => rise,
+                None => set,
+            };
+            let (mut shadow_entry, mut shadow_exit) = (None, None);
+            if let Some(within) = within {
+                for crossing in within.with_step(PASS_STEP)?.crossings(|time| self.light(time))? {
+                    match crossing.rising {
+                        true => shadow_exit = shadow_exit.or(Some(crossing.time)),
+                        false => shadow_entry = shadow_entry.or(Some(crossing.time)),
+                    }
+                }
+            }
+            passes.push(Pass {
+                rise,
+                culmination,
+                max_elevation: elevation(&culmination)?,
+                set,
+                sunlit: self.light(&culmination)? > 0.0,
+                shadow_entry,
+                shadow_exit,
+            });
+        }
+        Ok(passes)
+    }
+
+    /// Whether the satellite is lit by the Sun, outside the cylindrical shadow of the Earth.
+    pub fn sunlit(&self, time: &Time) -> Result<bool> {
+        Ok(self.light(time)? > 0.0)
+    }
+
+    // [m] Distance of the satellite from the shadow of the Earth, negative inside. The shadow is
+    // the cylinder of the radius of the Earth behind it, ignoring the penumbra.
+    fn light(&self, time: &Time) -> Result<f64> {
+        let frame = ObservingFrame::new(Accuracy::Reduced, &Observer::at_geocenter()?, time, 0.0, 0.0)?;
+        let sun = frame.sky_pos(&Source::planet(Planet::Sun)?, ReferenceSystem::Gcrs)?.r_hat;
+        let pos = self.gcrs(time)?.0.map(|x| x * 1e3);
+        let along = dot(&pos, &sun);
+        let distance = match along < 0.0 {
+            true => (dot(&pos, &pos) - along * along).max(0.0).sqrt(),
+            false => dot(&pos, &pos).sqrt(),
+        };
+        Ok(distance - sn::NOVAS_EARTH_RADIUS)
+    }
+
+    fn gcrs_at(&self, jd_tdb: f64) -> Result<([f64; 3], [f64; 3])> {
+        let (pos, vel) = self.model.propagate((jd_tdb - self.epoch_tdb) * 1440.0)?;
+        // TEME to the true equator and equinox of date, by the equation of the equinoxes
+        let (mut mobl, mut tobl, mut ee, mut dpsi, mut deps) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        check("e_tilt", || unsafe {
+            sn::e_tilt(jd_tdb, Accuracy::Reduced.to_raw(), &mut mobl, &mut tobl, &mut ee, &mut dpsi, &mut deps)
+        })?;
+        let (sin, cos) = (ee * 15.0 / 3600.0).to_radians().sin_cos();
+        let rotate = |v: [f64; 3]| [cos * v[0] - sin * v[1], sin * v[0] + cos * v[1], v[2]];
+        let to_gcrs = |v: [f64; 3]| -> Result<[f64; 3]> {
+            let mut out = [0.0; 3];
+            check("tod_to_gcrs", || unsafe { sn::tod_to_gcrs(jd_tdb, Accuracy::Reduced.to_raw(), v.as_ptr(), out.as_mut_ptr()) })?;
+            Ok(out)
+        };
+        Ok((to_gcrs(rotate(pos))?, to_gcrs(rotate(vel))?))
+    }
+}
+
+// Satellites served as ephemeris sources, by name and catalog number, and the provider they were
+// installed over.
+static SATELLITES: RwLock<Vec<(String, Arc<Satellite>)>> = RwLock::new(Vec::new());
+static PREVIOUS: RwLock<sn::novas_ephem_provider> = RwLock::new(None);
+
+fn install_provider() -> Result<()> {
+    let current = unsafe { sn::get_ephem_provider() };
+    if current.is_some_and(|f| std::ptr::fn_addr_eq(f, satellite_provider as unsafe extern "C" fn(_, _, _, _, _, _, _) -> _)) {
+        return Ok(());
+    }
+    *PREVIOUS.write().unwrap_or_else(|err| err.into_inner()) = current;
+    check("set_ephem_provider", || unsafe { sn::set_ephem_provider(Some(satellite_provider)) })
+}
+
+// NOVAS ephemeris provider of the satellites, forwarding other bodies to the previous provider.
+unsafe extern "C" fn satellite_provider(
+    name: *const std::os::raw::c_char,
+    id: std::os::raw::c_long,
+    jd_tdb_high: f64,
+    jd_tdb_low: f64,
+    origin: *mut sn::novas_origin,
+    position: *mut f64,
+    velocity: *mut f64,
+) -> std::os::raw::c_int {
+    let satellite = match name.is_null() {
+        true => None,
+        false => {
+            let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+            let satellites = SATELLITES.read().unwrap_or_else(|err| err.into_inner());
+            satellites
+                .iter()
+                .find(|(n, s)| *n == name && s.tle.norad_id as std::os::raw::c_long == id)
+                .map(|(_, s)| s.clone())
+        }
+    };
+    let Some(satellite) = satellite else {
+        let previous = *PREVIOUS.read().unwrap_or_else(|err| err.into_inner());
+        return match previous {
+            Some(previous) => unsafe { previous(name, id, jd_tdb_high, jd_tdb_low, origin, position, velocity) },
+            None => 1,
+        };
+    };
+
+    let Ok((pos, vel)) = satellite.gcrs_at(jd_tdb_high + jd_tdb_low) else {
+        return 2;
+    };
+    // Barycentric position of the Earth
+    let jd_tdb = [jd_tdb_high, jd_tdb_low];
+    let (mut earth_pos, mut earth_vel) = ([0.0; 3], [0.0; 3]);
+    let Ok(earth) = Source::planet(Planet::Earth) else {
+        return 3;
+    };
+    let status = unsafe {
+        sn::ephemeris(
+            jd_tdb.as_ptr(),
+            earth.as_raw(),
+            sn::novas_origin_NOVAS_BARYCENTER,
+            Accuracy::Reduced.to_raw(),
+            earth_pos.as_mut_ptr(),
+            earth_vel.as_mut_ptr(),
+        )
+    };
+    if status != 0 {
+        return 3;
+    }
+    let au = sn::NOVAS_AU / 1000.0;
+    unsafe {
+        if !origin.is_null() {
+            *origin = sn::novas_origin_NOVAS_BARYCENTER;
+        }
+        if !position.is_null() {
+            for i in 0..3 {
+                *position.add(i) = earth_pos[i] + pos[i] / au;
+            }
+        }
+        if !velocity.is_null() {
+            for i in 0..3 {
+                *velocity.add(i) = earth_vel[i] + vel[i] * 86400.0 / au;
+            }
+        }
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VANGUARD: &str = "\
+0 VANGUARD 1
+1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753
+2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667
+";
+
+    fn vanguard() -> Satellite {
+        Satellite::new(Tle::parse(VANGUARD).unwrap().remove(0), 32).unwrap()
+    }
+
+    fn norm(v: &[f64; 3]) -> f64 {
+        dot(v, v).sqrt()
+    }
+
+    #[test]
+    fn two_and_three_line_elements() {
+        let tle = &Tle::parse(VANGUARD).unwrap()[0];
+        assert_eq!((tle.name.as_str(), tle.norad_id, tle.designator.as_str()), ("VANGUARD 1", 5, "58002B"));
+        // 2000-06-27T18:50:19.733568 UTC
+        assert!((tle.epoch - 2451723.28495062).abs() < 1e-8);
+        assert!((tle.bstar - 2.8098e-5).abs() < 1e-15);
+        assert_eq!((tle.eccentricity, tle.mean_motion, tle.revolution), (0.1859667, 10.82419157, 41366));
+        let untitled = Tle::parse(VANGUARD.split_once('\n').unwrap().1).unwrap();
+        assert_eq!(untitled[0].name, "5");
+        assert_eq!(exponential("-11606-4"), Some(-1.1606e-5));
+        assert_eq!(exponential(" 00000-0"), Some(0.0));
+    }
+
+    #[test]
+    fn malformed_elements() {
+        let corrupt = VANGUARD.replace("34.2682", "34.2683");
+        assert!(matches!(Tle::parse(&corrupt), Err(Error::Parse { line: 3, .. })));
+        let truncated: String = VANGUARD.lines().take(2).collect::<Vec<_>>().join("\n");
+        assert!(matches!(Tle::parse(&truncated), Err(Error::Parse { what: "incomplete TLE", .. })));
+        assert!(Tle::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn gcrs_states_rotate_teme() {
+        let satellite = vanguard();
+        let time = Time::new(Timescale::Utc, 2451723.5, 32, 0.0).unwrap();
+        let (teme, teme_vel) = satellite.teme(&time).unwrap();
+        let (gcrs, gcrs_vel) = satellite.gcrs(&time).unwrap();
+        assert!((norm(&teme) - norm(&gcrs)).abs() < 1e-6);
+        assert!((norm(&teme_vel) - norm(&gcrs_vel)).abs() < 1e-9);
+        // Precession since J2000 is small half a year later
+        assert!(dot(&teme, &gcrs) / (norm(&teme) * norm(&gcrs)) > 0.9999);
+    }
+
+    #[test]
+    fn passes_over_a_site() {
+        let satellite = vanguard();
+        let site = Observer::on_surface(30.0, -100.0, 0.0).unwrap();
+        let start = Time::new(Timescale::Utc, 2451723.5, 32, 0.0).unwrap();
+        let end = start.offset(86400.0).unwrap();
+        let passes = satellite.passes(&site, &start, &end, 10.0).unwrap();
+        assert!(!passes.is_empty());
+        for pass in &passes {
+            assert!(pass.rise.diff(&pass.culmination) <= 0.0 && pass.culmination.diff(&pass.set) <= 0.0);
+            assert!(pass.max_elevation >= 10.0);
+            assert_eq!(pass.sunlit, satellite.sunlit(&pass.culmination).unwrap());
+        }
+    }
+}
@@ -0,0 +1,341 @@
+// Near-Earth SGP4 propagation, after Vallado et al. (2006), "Revisiting Spacetrack Report #3"
+// (AIAA 2006-6753), with the WGS-72 constants used to fit TLEs. Deep-space orbits (SDP4) are not
+// supported.
+
+use super::Tle;
+use crate::novas::error::{Error, Result};
+use std::f64::consts::TAU;
+
+// [km, km^3/s^2] WGS-72 radius and gravitational parameter of the Earth, and zonal harmonics.
+const RADIUS: f64 = 6378.135;
+const MU: f64 = 398600.8;
+const J2: f64 = 0.001082616;
+const J3: f64 = -0.00000253881;
+const J4: f64 = -0.00000165597;
+const J3OJ2: f64 = J3 / J2;
+
+/// [min] Shortest period of deep-space orbits.
+const DEEP_SPACE_PERIOD: f64 = 225.0;
+
+// [1/min] sqrt(mu) in Earth radii and minutes.
+fn xke() -> f64 {
+    60.0 / (RADIUS * RADIUS * RADIUS / MU).sqrt()
+}
+
+/// SGP4 model of a TLE: the mean elements at epoch and the coefficients of the secular and
+/// periodic perturbations.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Model {
+    ecco: f64,
+    inclo: f64,
+    argpo: f64,
+    nodeo: f64,
+    mo: f64,
+    no: f64,
+    bstar: f64,
+    simple: bool,
+    con41: f64,
+    x1mth2: f64,
+    x7thm1: f64,
+    cc1: f64,
+    cc4: f64,
+    cc5: f64,
+    d2: f64,
+    d3: f64,
+    d4: f64,
+    delmo: f64,
+    eta: f64,
+    argpdot: f64,
+    omgcof: f64,
+    sinmao: f64,
+    t2cof: f64,
+    t3cof: f64,
+    t4cof: f64,
+    t5cof: f64,
+    xlcof: f64,
+    aycof: f64,
+    xmcof: f64,
+    mdot: f64,
+    nodecf: f64,
+    nodedot: f64,
+}
+
+impl Model {
+    pub(super) fn new(tle: &Tle) -> Result<Self> {
+        let xke = xke();
+        let ecco = tle.eccentricity;
+        let inclo = tle.inclination.to_radians();
+        let argpo = tle.arg_perigee.to_radians();
+        let mo = tle.mean_anomaly.to_radians();
+        let no_kozai = tle.mean_motion * TAU / 1440.0;
+        if !(0.0..1.0).contains(&ecco) || no_kozai <= 0.0 {
+            return Err(Error::InvalidArgument("invalid TLE mean elements"));
+        }
+
+        // Brouwer mean motion and semi-major axis
+        let ak = (xke / no_kozai).powf(2.0 / 3.0);
+        let eccsq = ecco * ecco;
+        let omeosq = 1.0 - eccsq;
+        let rteosq = omeosq.sqrt();
+        let cosio = inclo.cos();
+        let cosio2 = cosio * cosio;
+        let d1 = 0.75 * J2 * (3.0 * cosio2 - 1.0) / (rteosq * omeosq);
+        let del = d1 / (ak * ak);
+        let adel = ak * (1.0 - del * del - del * (1.0 / 3.0 + 134.0 * del * del / 81.0));
+        let del = d1 / (adel * adel);
+        let no = no_kozai / (1.0 + del);
+        if TAU / no >= DEEP_SPACE_PERIOD {
+            return Err(Error::InvalidArgument("deep-space orbits (period of 225 min or more) are not supported"));
+        }
+        let ao = (xke / no).powf(2.0 / 3.0);
+        let sinio = inclo.sin();
+        let po = ao * omeosq;
+        let con42 = 1.0 - 5.0 * cosio2;
+        let con41 = -con42 - cosio2 - cosio2;
+        let posq = po * po;
+        let rp = ao * (1.0 - ecco);
+
+        // Atmospheric density parameters, adjusted for low perigees
+        let mut sfour = 78.0 / RADIUS + 1.0;
+        let mut qzms24 = ((120.0 - 78.0) / RADIUS).powi(4);
+        let perigee = (rp - 1.0) * RADIUS;
+        if perigee < 156.0 {
+            let s = if perigee < 98.0 { 20.0 } else { perigee - 78.0 };
+            qzms24 = ((120.0 - s) / RADIUS).powi(4);
+            sfour = s / RADIUS + 1.0;
+        }
+
+        let pinvsq = 1.0 / posq;
+        let tsi = 1.0 / (ao - sfour);
+        let eta = ao * ecco * tsi;
+        let etasq = eta * eta;
+        let eeta = ecco * eta;
+        let psisq = (1.0 - etasq).abs();
+        let coef = qzms24 * tsi.powi(4);
+        let coef1 = coef / psisq.powf(3.5);
+        let cc2 = coef1
+            * no
+            * (ao * (1.0 + 1.5 * etasq + eeta * (4.0 + etasq))
+                + 0.375 * J2 * tsi / psisq * con41 * (8.0 + 3.0 * etasq * (8.0 + etasq)));
+        let bstar = tle.bstar;
+        let cc1 = bstar * cc2;
+        let cc3 = if ecco > 1.0e-4 { -2.0 * coef * tsi * J3OJ2 * no * sinio / ecco } else { 0.0 };
+        let x1mth2 = 1.0 - cosio2;
+        let cc4 = 2.0
+            * no
+            * coef1
+            * ao
+            * omeosq
+            * (eta * (2.0 + 0.5 * etasq) + ecco * (0.5 + 2.0 * etasq)
+                - J2 * tsi / (ao * psisq)
+                    * (-3.0 * con41 * (1.0 - 2.0 * eeta + etasq * (1.5 - 0.5 * eeta))
+                        + 0.75 * x1mth2 * (2.0 * etasq - eeta * (1.0 + etasq)) * (2.0 * argpo).cos()));
+        let cc5 = 2.0 * coef1 * ao * omeosq * (1.0 + 2.75 * (etasq + eeta) + eeta * etasq);
+
+        // Secular rates
+        let cosio4 = cosio2 * cosio2;
+        let temp1 = 1.5 * J2 * pinvsq * no;
+        let temp2 = 0.5 * temp1 * J2 * pinvsq;
+        let temp3 = -0.46875 * J4 * pinvsq * pinvsq * no;
+        let mdot = no + 0.5 * temp1 * rteosq * con41 + 0.0625 * temp2 * rteosq * (13.0 - 78.0 * cosio2 + 137.0 * cosio4);
+        let argpdot = -0.5 * temp1 * con42
+            + 0.0625 * temp2 * (7.0 - 114.0 * cosio2 + 395.0 * cosio4)
+            + temp3 * (3.0 - 36.0 * cosio2 + 49.0 * cosio4);
+        let xhdot1 = -temp1 * cosio;
+        let nodedot = xhdot1 + (0.5 * temp2 * (4.0 - 19.0 * cosio2) + 2.0 * temp3 * (3.0 - 7.0 * cosio2)) * cosio;
+
+        let denominator = if (cosio + 1.0).abs() > 1.5e-12 { 1.0 + cosio } else { 1.5e-12 };
+        let mut model = Model {
+            ecco,
+            inclo,
+            argpo,
+            nodeo: tle.raan.to_radians(),
+            mo,
+            no,
+            bstar,
+            simple: rp < 220.0 / RADIUS + 1.0,
+            con41,
+            x1mth2,
+            x7thm1: 7.0 * cosio2 - 1.0,
+            cc1,
+            cc4,
+            cc5,
+            d2: 0.0,
+            d3: 0.0,
+            d4: 0.0,
+            delmo: (1.0 + eta * mo.cos()).powi(3),
+            eta,
+            argpdot,
+            omgcof: bstar * cc3 * argpo.cos(),
+            sinmao: mo.sin(),
+            t2cof: 1.5 * cc1,
+            t3cof: 0.0,
+            t4cof: 0.0,
+            t5cof: 0.0,
+            xlcof: -0.25 * J3OJ2 * sinio * (3.0 + 5.0 * cosio) / denominator,
+            aycof: -0.5 * J3OJ2 * sinio,
+            xmcof: if ecco > 1.0e-4 { -2.0 / 3.0 * coef * bstar / eeta } else { 0.0 },
+            mdot,
+            nodecf: 3.5 * omeosq * xhdot1 * cc1,
+            nodedot,
+        };
+
+        // Higher-order drag terms, except for perigees below 220 km
+        if !model.simple {
+            let cc1sq = cc1 * cc1;
+            model.d2 = 4.0 * ao * tsi * cc1sq;
+            let temp = model.d2 * tsi * cc1 / 3.0;
+            model.d3 = (17.0 * ao + sfour) * temp;
+            model.d4 = 0.5 * temp * ao * tsi * (221.0 * ao + 31.0 * sfour) * cc1;
+            model.t3cof = model.d2 + 2.0 * cc1sq;
+            model.t4cof = 0.25 * (3.0 * model.d3 + cc1 * (12.0 * model.d2 + 10.0 * cc1sq));
+            model.t5cof = 0.2
+                * (3.0 * model.d4 + 12.0 * cc1 * model.d3 + 6.0 * model.d2 * model.d2 + 15.0 * cc1sq * (2.0 * model.d2 + cc1sq));
+        }
+        Ok(model)
+    }
+
+    /// [km, km/s] TEME position and velocity `t` minutes after the epoch of the TLE.
+    pub(super) fn propagate(&self, t: f64) -> Result<([f64; 3], [f64; 3])> {
+        let xke = xke();
+
+        // Secular gravity and drag
+        let xmdf = self.mo + self.mdot * t;
+        let argpdf = self.argpo + self.argpdot * t;
+        let nodedf = self.nodeo + self.nodedot * t;
+        let t2 = t * t;
+        let nodem = nodedf + self.nodecf * t2;
+        let mut argpm = argpdf;
+        let mut mm = xmdf;
+        let mut tempa = 1.0 - self.cc1 * t;
+        let mut tempe = self.bstar * self.cc4 * t;
+        let mut templ = self.t2cof * t2;
+        if !self.simple {
+            let delomg = self.omgcof * t;
+            let delm = self.xmcof * ((1.0 + self.eta * xmdf.cos()).powi(3) - self.delmo);
+            mm = xmdf + delomg + delm;
+            argpm = argpdf - delomg - delm;
+            let t3 = t2 * t;
+            let t4 = t3 * t;
+            tempa -= self.d2 * t2 + self.d3 * t3 + self.d4 * t4;
+            tempe += self.bstar * self.cc5 * (mm.sin() - self.sinmao);
+            templ += self.t3cof * t3 + t4 * (self.t4cof + t * self.t5cof);
+        }
+
+        let am = (xke / self.no).powf(2.0 / 3.0) * tempa * tempa;
+        let nm = xke / am.powf(1.5);
+        let mut em = self.ecco - tempe;
+        if !(-0.001..1.0).contains(&em) {
+            return Err(Error::InvalidArgument("satellite orbit is no longer elliptical"));
+        }
+        em = em.max(1.0e-6);
+        mm += self.no * templ;
+        let xlm = mm + argpm + nodem;
+        let nodem = nodem.rem_euclid(TAU);
+        let argpm = argpm.rem_euclid(TAU);
+        let mm = (xlm.rem_euclid(TAU) - argpm - nodem).rem_euclid(TAU);
+        let (sinip, cosip) = self.inclo.sin_cos();
+
+        // Long-period periodics
+        let axnl = em * argpm.cos();
+        let temp = 1.0 / (am * (1.0 - em * em));
+        let aynl = em * argpm.sin() + temp * self.aycof;
+        let xl = mm + argpm + nodem + temp * self.xlcof * axnl;
+
+        // Kepler's equation
+        let u = (xl - nodem).rem_euclid(TAU);
+        let mut eo1 = u;
+        let (mut sineo1, mut coseo1) = (0.0, 0.0);
+        for _ in 0..10 {
+            (sineo1, coseo1) = eo1.sin_cos();
+            let step = (u - aynl * coseo1 + axnl * sineo1 - eo1) / (1.0 - coseo1 * axnl - sineo1 * aynl);
+            eo1 += step.clamp(-0.95, 0.95);
+            if step.abs() < 1.0e-12 {
+                break;
+            }
+        }
+
+        // Short-period periodics
+        let ecose = axnl * coseo1 + aynl * sineo1;
+        let esine = axnl * sineo1 - aynl * coseo1;
+        let el2 = axnl * axnl + aynl * aynl;
+        let pl = am * (1.0 - el2);
+        if pl < 0.0 {
+            return Err(Error::InvalidArgument("satellite orbit is no longer elliptical"));
+        }
+        let rl = am * (1.0 - ecose);
+        let rdotl = am.sqrt() * esine / rl;
+        let rvdotl = pl.sqrt() / rl;
+        let betal = (1.0 - el2).sqrt();
+        let temp = esine / (1.0 + betal);
+        let sinu = am / rl * (sineo1 - aynl - axnl * temp);
+        let cosu = am / rl * (coseo1 - axnl + aynl * temp);
+        let su = sinu.atan2(cosu);
+        let sin2u = 2.0 * cosu * sinu;
+        let cos2u = 1.0 - 2.0 * sinu * sinu;
+        let temp = 1.0 / pl;
+        let temp1 = 0.5 * J2 * temp;
+        let temp2 = temp1 * temp;
+
+        let mrt = rl * (1.0 - 1.5 * temp2 * betal * self.con41) + 0.5 * temp1 * self.x1mth2 * cos2u;
+        if mrt < 1.0 {
+            return Err(Error::InvalidArgument("satellite has decayed"));
+        }
+        let su = su - 0.25 * temp2 * self.x7thm1 * sin2u;
+        let xnode = nodem + 1.5 * temp2 * cosip * sin2u;
+        let xinc = self.inclo + 1.5 * temp2 * cosip * sinip * cos2u;
+        let mvt = rdotl - nm * temp1 * self.x1mth2 * sin2u / xke;
+        let rvdot = rvdotl + nm * temp1 * (self.x1mth2 * cos2u + 1.5 * self.con41) / xke;
+
+        // Orientation vectors
+        let (sinsu, cossu) = su.sin_cos();
+        let (snod, cnod) = xnode.sin_cos();
+        let (sini, cosi) = xinc.sin_cos();
+        let xmx = -snod * cosi;
+        let xmy = cnod * cosi;
+        let ux = [xmx * sinsu + cnod * cossu, xmy * sinsu + snod * cossu, sini * sinsu];
+        let vx = [xmx * cossu - cnod * sinsu, xmy * cossu - snod * sinsu, sini * cossu];
+        let speed = RADIUS * xke / 60.0;
+        Ok((
+            ux.map(|x| mrt * x * RADIUS),
+            [0, 1, 2].map(|i| (mvt * ux[i] + rvdot * vx[i]) * speed),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vallado et al. (2006), test case 00005
+    const VANGUARD: &str = "\
+1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753
+2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+
+    fn assert_near(a: [f64; 3], b: [f64; 3], tolerance: f64) {
+        for (a, b) in a.iter().zip(&b) {
+            assert!((a - b).abs() < tolerance, "{a} != {b}");
+        }
+    }
+
+    #[test]
+    fn matches_the_reference_states() {
+        let model = Model::new(&Tle::parse(VANGUARD).unwrap()[0]).unwrap();
+        let (pos, vel) = model.propagate(0.0).unwrap();
+        assert_near(pos, [7022.46529266, -1400.08296755, 0.03995155], 1e-6);
+        assert_near(vel, [1.893841015, 6.405893759, 4.534807250], 1e-9);
+        let (pos, vel) = model.propagate(360.0).unwrap();
+        assert_near(pos, [-7154.03120202, -3783.17682504, -3536.19412294], 1e-6);
+        assert_near(vel, [4.741887409, -4.151817765, -2.093935425], 1e-9);
+    }
+
+    #[test]
+    fn rejects_deep_space_and_invalid_orbits() {
+        let mut tle = Tle::parse(VANGUARD).unwrap().remove(0);
+        tle.mean_motion = 2.0;
+        assert!(Model::new(&tle).is_err());
+        tle.mean_motion = 10.8;
+        tle.eccentricity = 1.0;
+        assert!(Model::new(&tle).is_err());
+    }
+}
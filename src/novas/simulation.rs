@@ -0,0 +1,66 @@
+use super::eop::EarthOrientation;
+use super::error::Result;
+use super::frame::{Accuracy, ObservingFrame};
+use super::observer::Observer;
+use super::time::{Time, TimeRange};
+
+/// Steps observing frames over long intervals (months to years), giving each step the leap
+/// seconds, UT1 - UTC and polar motion of its own date.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    observer: Observer,
+    accuracy: Accuracy,
+    orientation: EarthOrientation,
+}
+
+impl Simulation {
+    pub fn new(observer: Observer, orientation: EarthOrientation) -> Self {
+        Simulation { observer, accuracy: Accuracy::default(), orientation }
+    }
+
+    /// Sets the accuracy of the frames.
+    pub fn with_accuracy(mut self, accuracy: Accuracy) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    /// The frame at an instant, with the Earth orientation interpolated to its date, or UT1 from
+    /// the ΔT model outside the tables.
+    pub fn frame(&self, time: &Time) -> Result<ObservingFrame> {
+        self.orientation.frame(self.accuracy, &self.observer, time)
+    }
+
+    /// The frames at the samples of `range`. The samples are evenly spaced in TT, so steps that
+    /// cross a leap second keep their length.
+    pub fn frames<'a>(&'a self, range: &'a TimeRange) -> impl Iterator<Item = Result<ObservingFrame>> + 'a {
+        range.iter().map(|time| self.frame(&time?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::eop::{EopTable, LeapSeconds};
+    use crate::novas::time::Timescale;
+
+    // A line of the finals2000A file
+    fn finals(mjd: f64, dut1: f64) -> String {
+        format!("{:7}{mjd:8.2}{:3}{:9.6}{:10}{:9.6}{:11}I{dut1:10.7}", "", "", 0.1, "", 0.3, "")
+    }
+
+    #[test]
+    fn frames_follow_the_tables() {
+        let eop = EopTable::parse(&[finals(60850.0, 0.02), finals(60851.0, 0.04)].join("\n")).unwrap();
+        let observer = Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap();
+        let simulation = Simulation::new(observer, EarthOrientation::new(LeapSeconds::builtin(), eop)).with_accuracy(Accuracy::Reduced);
+        let start = Time::new(Timescale::Utc, 2460850.5, 37, 0.0).unwrap();
+        let range = TimeRange::with_count(&start, &start.offset(86400.0).unwrap(), 3).unwrap();
+        let frames: Vec<ObservingFrame> = simulation.frames(&range).collect::<Result<_>>().unwrap();
+        assert_eq!(frames.len(), 3);
+        assert!((frames[0].time().dut1() - 0.02).abs() < 1e-6);
+        assert!((frames[1].time().dut1() - 0.03).abs() < 1e-6);
+        // On the last day of the table, UT1 from the ΔT model
+        assert!(frames[2].time().dut1().abs() < 0.9);
+        assert_eq!(frames[2].time().leap_seconds(), 37);
+    }
+}
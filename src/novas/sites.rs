@@ -0,0 +1,169 @@
+//! Observatory sites by their Minor Planet Center observatory code, as listed in the MPC
+//! `ObsCodes` file.
+//!
+//! A few major sites are compiled in, see [`Sites::builtin`]; the full list can be loaded with
+//! [`Sites::parse`].
+
+use super::error::{Error, Result};
+use super::observer::{Observer, EARTH_RADIUS};
+
+/// Compiled-in sites: code, longitude [deg], rho cos(phi'), rho sin(phi') and name.
+const BUILTIN: &[(&str, f64, f64, f64, &str)] = &[
+    ("000", 0.0, 0.62411, 0.77873, "Greenwich"),
+    ("309", 289.59720, 0.909943, -0.414336, "Cerro Paranal"),
+    ("413", 149.0661, 0.85563, -0.51621, "Siding Spring Observatory"),
+    ("500", 0.0, 0.0, 0.0, "Geocentric"),
+    ("568", 204.52780, 0.941706, 0.337237, "Mauna Kea"),
+    ("950", 342.1176, 0.87764, 0.47847, "La Palma"),
+    ("F51", 203.74409, 0.936241, 0.351543, "Pan-STARRS 1, Haleakala"),
+    ("G96", 249.21128, 0.845111, 0.533614, "Mt. Lemmon Survey"),
+];
+
+/// A ground-based observatory, by its geocentric parallax constants.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Site {
+    /// Three-character MPC observatory code
+    pub code: String,
+    pub name: String,
+    /// [deg] longitude, east of Greenwich
+    pub longitude: f64,
+    /// [Earth radii] distance from the Earth's axis, rho cos(phi')
+    pub rho_cos_phi: f64,
+    /// [Earth radii] distance from the equatorial plane, rho sin(phi')
+    pub rho_sin_phi: f64,
+}
+
+impl Site {
+    /// [m] ITRF position of the site.
+    pub fn itrf(&self) -> [f64; 3] {
+        let (sin_lon, cos_lon) = self.longitude.to_radians().sin_cos();
+        let axial = self.rho_cos_phi * EARTH_RADIUS;
+        [axial * cos_lon, axial * sin_lon, self.rho_sin_phi * EARTH_RADIUS]
+    }
+
+    /// A stationary observer at the site, or at the geocenter for code 500.
+    pub fn observer(&self) -> Result<Observer> {
+        if self.rho_cos_phi == 0.0 && self.rho_sin_phi == 0.0 {
+            return Observer::at_geocenter();
+        }
+        Observer::from_itrf(self.itrf())
+    }
+}
+
+/// A table of observatory sites, looked up by code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sites {
+    sites: Vec<Site>,
+}
+
+impl Sites {
+    /// The compiled-in sites: a few major observatories and the geocenter.
+    pub fn builtin() -> Self {
+        let sites = BUILTIN
+            .iter()
+            .map(|&(code, longitude, rho_cos_phi, rho_sin_phi, name)| Site {
+                code: code.to_string(),
+                name: name.to_string(),
+                longitude,
+                rho_cos_phi,
+                rho_sin_phi,
+            })
+            .collect();
+        Sites { sites }
+    }
+
+    /// Parses the contents of the MPC `ObsCodes` file. The header line and space-based
+    /// observatories, which have no parallax constants, are skipped.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut sites = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with("Code") {
+                continue;
+            }
+            let field = |from: usize, to: usize| line.get(from..to.min(line.len())).map_or("", str::trim);
+            let numbers = [field(3, 13), field(13, 21), field(21, 30)];
+            if numbers.iter().all(|field| field.is_empty()) {
+                continue;
+            }
+            let [longitude, rho_cos_phi, rho_sin_phi] = numbers.map(|field| field.parse::<f64>().ok());
+            let site = match (longitude, rho_cos_phi, rho_sin_phi) {
+                (Some(longitude), Some(rho_cos_phi), Some(rho_sin_phi)) if field(0, 3).len() == 3 => Some(Site {
+                    code: field(0, 3).to_string(),
+                    name: field(30, line.len()).to_string(),
+                    longitude,
+                    rho_cos_phi,
+                    rho_sin_phi,
+                }),
+                _ => None,
+            };
+            sites.push(site.ok_or(Error::Parse { what: "malformed observatory code entry", line: n + 1 })?);
+        }
+        if sites.is_empty() {
+            return Err(Error::InvalidArgument("no observatory sites in file"));
+        }
+        Ok(Sites { sites })
+    }
+
+    /// The site with an MPC observatory code, e.g. `"568"`.
+    pub fn get(&self, code: &str) -> Option<&Site> {
+        self.sites.iter().find(|site| site.code == code)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Site> {
+        self.sites.iter()
+    }
+}
+
+impl Observer {
+    /// Stationary observer at a compiled-in site, by its MPC observatory code, see
+    /// [`Sites::builtin`]. Other sites can be looked up in a loaded [`Sites`] table.
+    pub fn from_mpc_code(code: &str) -> Result<Self> {
+        Sites::builtin()
+            .get(code)
+            .ok_or(Error::InvalidArgument("unknown observatory code"))?
+            .observer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OBS_CODES: &str = "\
+Code  Long.   cos      sin    Name
+000   0.0000 0.62411 +0.77873 Greenwich
+C49                           STEREO-A
+568 204.5278 0.94171 +0.33725 Mauna Kea
+";
+
+    #[test]
+    fn builtin_sites() {
+        let sites = Sites::builtin();
+        let mauna_kea = sites.get("568").unwrap();
+        assert_eq!(mauna_kea.name, "Mauna Kea");
+        let geodetic = Observer::from_mpc_code("568").unwrap().geodetic().unwrap();
+        assert!((geodetic.latitude - 19.8261).abs() < 0.01);
+        assert!((geodetic.longitude + 155.4722).abs() < 1e-6);
+        // The parallax constants give the height to some 10 meters
+        assert!((geodetic.height - 4200.0).abs() < 100.0, "{}", geodetic.height);
+        assert!(Observer::from_mpc_code("500").unwrap().geodetic().is_none());
+        assert!(Observer::from_mpc_code("XYZ").is_err());
+        assert_eq!(sites.iter().count(), BUILTIN.len());
+    }
+
+    #[test]
+    fn obs_codes_file() {
+        let sites = Sites::parse(OBS_CODES).unwrap();
+        assert_eq!(sites.iter().map(|site| site.code.as_str()).collect::<Vec<_>>(), ["000", "568"]);
+        let greenwich = sites.get("000").unwrap();
+        assert_eq!((greenwich.longitude, greenwich.rho_cos_phi, greenwich.rho_sin_phi), (0.0, 0.62411, 0.77873));
+        assert_eq!(greenwich.name, "Greenwich");
+        // The builtin constants have more digits
+        let parsed = sites.get("568").unwrap().itrf();
+        let builtin = Sites::builtin().get("568").unwrap().itrf();
+        assert!(parsed.iter().zip(&builtin).all(|(a, b)| (a - b).abs() < 100.0));
+        assert!(matches!(Sites::parse("000   0.0000 0.62411 +0.7787x Greenwich\n"), Err(Error::Parse { line: 1, .. })));
+        assert!(matches!(Sites::parse("Code\n00   0.0000 0.62411 +0.77873 Greenwich\n"), Err(Error::Parse { line: 2, .. })));
+        assert!(Sites::parse("Code  Long.   cos      sin    Name\n").is_err());
+    }
+}
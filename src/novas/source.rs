@@ -0,0 +1,745 @@
+use super::coords::Epoch;
+use super::error::{check, Error, Result};
+use super::fk4;
+#[cfg(feature = "serde")]
+use super::orbit::Orbit;
+use super::redshift::Redshift;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::str::FromStr;
+use supernovas_sys as sn;
+
+/// [m^3 kg^-1 s^-2] Constant of gravitation, as used by NOVAS `grav_redshift()`.
+const GRAVITATIONAL_CONSTANT: f64 = 6.6743e-11;
+
+/// Major solar-system bodies known to NOVAS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Planet {
+    /// Solar-system barycenter
+    Ssb,
+    Mercury,
+    Venus,
+    Earth,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune,
+    Pluto,
+    Sun,
+    Moon,
+    /// Earth-Moon barycenter
+    Emb,
+    /// Barycenter of the Pluto system
+    PlutoBarycenter,
+}
+
+impl Planet {
+    pub(crate) const ALL: [Planet; 14] = [
+        Planet::Ssb,
+        Planet::Mercury,
+        Planet::Venus,
+        Planet::Earth,
+        Planet::Mars,
+        Planet::Jupiter,
+        Planet::Saturn,
+        Planet::Uranus,
+        Planet::Neptune,
+        Planet::Pluto,
+        Planet::Sun,
+        Planet::Moon,
+        Planet::Emb,
+        Planet::PlutoBarycenter,
+    ];
+
+    pub(crate) fn to_raw(self) -> sn::novas_planet {
+        match self {
+            Planet::Ssb => sn::novas_planet_NOVAS_SSB,
+            Planet::Mercury => sn::novas_planet_NOVAS_MERCURY,
+            Planet::Venus => sn::novas_planet_NOVAS_VENUS,
+            Planet::Earth => sn::novas_planet_NOVAS_EARTH,
+            Planet::Mars => sn::novas_planet_NOVAS_MARS,
+            Planet::Jupiter => sn::novas_planet_NOVAS_JUPITER,
+            Planet::Saturn => sn::novas_planet_NOVAS_SATURN,
+            Planet::Uranus => sn::novas_planet_NOVAS_URANUS,
+            Planet::Neptune => sn::novas_planet_NOVAS_NEPTUNE,
+            Planet::Pluto => sn::novas_planet_NOVAS_PLUTO,
+            Planet::Sun => sn::novas_planet_NOVAS_SUN,
+            Planet::Moon => sn::novas_planet_NOVAS_MOON,
+            Planet::Emb => sn::novas_planet_NOVAS_EMB,
+            Planet::PlutoBarycenter => sn::novas_planet_NOVAS_PLUTO_BARYCENTER,
+        }
+    }
+
+    pub(crate) fn from_raw(raw: sn::novas_planet) -> Option<Self> {
+        Planet::ALL.into_iter().find(|planet| planet.to_raw() == raw)
+    }
+
+    /// The NOVAS number of the body.
+    pub fn number(self) -> i32 {
+        self.to_raw() as i32
+    }
+
+    /// The body with a NOVAS number, if any.
+    pub fn from_number(number: i32) -> Option<Self> {
+        u32::try_from(number).ok().and_then(Planet::from_raw)
+    }
+
+    /// The NAIF ID of the body, e.g. 499 for Mars.
+    pub fn naif_id(self) -> i64 {
+        unsafe { sn::novas_to_naif_planet(self.to_raw()) as i64 }
+    }
+
+    /// The body with a NAIF ID, if it is one of the bodies known to NOVAS.
+    pub fn from_naif_id(id: i64) -> Option<Self> {
+        let id = std::os::raw::c_long::try_from(id).ok()?;
+        Planet::from_raw(unsafe { sn::naif_to_novas_planet(id) })
+    }
+
+    /// [kg] Mass of the body, from the mass ratios of `NOVAS_RMASS_INIT`, or `None` for
+    /// barycenters.
+    pub fn mass(self) -> Option<f64> {
+        // Sun mass / body mass
+        let ratio = match self {
+            Planet::Mercury => 6023657.9450387,
+            Planet::Venus => 408523.718656268,
+            Planet::Earth => 332946.048773067,
+            Planet::Mars => 3098703.54671961,
+            Planet::Jupiter => 1047.348631244,
+            Planet::Saturn => 3497.9018007932,
+            Planet::Uranus => 22902.9507834766,
+            Planet::Neptune => 19412.2597758766,
+            Planet::Pluto => 136045556.16738,
+            Planet::Sun => 1.0,
+            Planet::Moon => 27068702.9548773,
+            Planet::Emb => 328900.559708565,
+            Planet::Ssb | Planet::PlutoBarycenter => return None,
+        };
+        Some(sn::NOVAS_G_SUN / GRAVITATIONAL_CONSTANT / ratio)
+    }
+
+    /// The name of the body, as in `NOVAS_PLANET_NAMES_INIT`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Planet::Ssb => "SSB",
+            Planet::Mercury => "Mercury",
+            Planet::Venus => "Venus",
+            Planet::Earth => "Earth",
+            Planet::Mars => "Mars",
+            Planet::Jupiter => "Jupiter",
+            Planet::Saturn => "Saturn",
+            Planet::Uranus => "Uranus",
+            Planet::Neptune => "Neptune",
+            Planet::Pluto => "Pluto",
+            Planet::Sun => "Sun",
+            Planet::Moon => "Moon",
+            Planet::Emb => "EMB",
+            Planet::PlutoBarycenter => "Pluto-Barycenter",
+        }
+    }
+}
+
+impl fmt::Display for Planet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Planet {
+    type Err = Error;
+
+    /// Parses a body name as in `NOVAS_PLANET_NAMES_INIT` (case-insensitive).
+    fn from_str(s: &str) -> Result<Self> {
+        let planet = match s.trim().to_ascii_lowercase().as_str() {
+            "ssb" => Planet::Ssb,
+            "mercury" => Planet::Mercury,
+            "venus" => Planet::Venus,
+            "earth" => Planet::Earth,
+            "mars" => Planet::Mars,
+            "jupiter" => Planet::Jupiter,
+            "saturn" => Planet::Saturn,
+            "uranus" => Planet::Uranus,
+            "neptune" => Planet::Neptune,
+            "pluto" => Planet::Pluto,
+            "sun" => Planet::Sun,
+            "moon" => Planet::Moon,
+            "emb" => Planet::Emb,
+            "pluto-barycenter" => Planet::PlutoBarycenter,
+            _ => return Err(Error::InvalidArgument("unknown planet name")),
+        };
+        Ok(planet)
+    }
+}
+
+/// Natural solar-system bodies and barycenters, identified by their NAIF IDs.
+///
+/// Unlike [`Planet`], which follows NOVAS in using the numbers 1-9 for the planets, this tells
+/// the planets (NAIF 199-999) apart from the barycenters of their systems (NAIF 1-9), as the
+/// JPL DE ephemerides do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum NaturalBody {
+    /// Solar-system barycenter
+    Ssb,
+    Sun,
+    Mercury,
+    Venus,
+    Earth,
+    Moon,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune,
+    Pluto,
+    MercuryBarycenter,
+    VenusBarycenter,
+    /// Earth-Moon barycenter
+    Emb,
+    MarsBarycenter,
+    JupiterBarycenter,
+    SaturnBarycenter,
+    UranusBarycenter,
+    NeptuneBarycenter,
+    PlutoBarycenter,
+}
+
+impl NaturalBody {
+    pub(crate) const ALL: [NaturalBody; 21] = [
+        NaturalBody::Ssb,
+        NaturalBody::Sun,
+        NaturalBody::Mercury,
+        NaturalBody::Venus,
+        NaturalBody::Earth,
+        NaturalBody::Moon,
+        NaturalBody::Mars,
+        NaturalBody::Jupiter,
+        NaturalBody::Saturn,
+        NaturalBody::Uranus,
+        NaturalBody::Neptune,
+        NaturalBody::Pluto,
+        NaturalBody::MercuryBarycenter,
+        NaturalBody::VenusBarycenter,
+        NaturalBody::Emb,
+        NaturalBody::MarsBarycenter,
+        NaturalBody::JupiterBarycenter,
+        NaturalBody::SaturnBarycenter,
+        NaturalBody::UranusBarycenter,
+        NaturalBody::NeptuneBarycenter,
+        NaturalBody::PlutoBarycenter,
+    ];
+
+    /// The NAIF ID of the body, e.g. 499 for Mars and 4 for the Mars barycenter.
+    pub fn naif_id(self) -> i64 {
+        match self {
+            NaturalBody::Ssb => 0,
+            NaturalBody::Sun => 10,
+            NaturalBody::Mercury => 199,
+            NaturalBody::Venus => 299,
+            NaturalBody::Earth => 399,
+            NaturalBody::Moon => 301,
+            NaturalBody::Mars => 499,
+            NaturalBody::Jupiter => 599,
+            NaturalBody::Saturn => 699,
+            NaturalBody::Uranus => 799,
+            NaturalBody::Neptune => 899,
+            NaturalBody::Pluto => 999,
+            NaturalBody::MercuryBarycenter => 1,
+            NaturalBody::VenusBarycenter => 2,
+            NaturalBody::Emb => 3,
+            NaturalBody::MarsBarycenter => 4,
+            NaturalBody::JupiterBarycenter => 5,
+            NaturalBody::SaturnBarycenter => 6,
+            NaturalBody::UranusBarycenter => 7,
+            NaturalBody::NeptuneBarycenter => 8,
+            NaturalBody::PlutoBarycenter => 9,
+        }
+    }
+
+    /// The body with a NAIF ID, if any.
+    pub fn from_naif_id(id: i64) -> Option<Self> {
+        NaturalBody::ALL.into_iter().find(|body| body.naif_id() == id)
+    }
+
+    /// The same body in NOVAS, if NOVAS knows it as a major planet.
+    pub fn planet(self) -> Option<Planet> {
+        let planet = match self {
+            NaturalBody::Ssb => Planet::Ssb,
+            NaturalBody::Sun => Planet::Sun,
+            NaturalBody::Mercury => Planet::Mercury,
+            NaturalBody::Venus => Planet::Venus,
+            NaturalBody::Earth => Planet::Earth,
+            NaturalBody::Moon => Planet::Moon,
+            NaturalBody::Mars => Planet::Mars,
+            NaturalBody::Jupiter => Planet::Jupiter,
+            NaturalBody::Saturn => Planet::Saturn,
+            NaturalBody::Uranus => Planet::Uranus,
+            NaturalBody::Neptune => Planet::Neptune,
+            NaturalBody::Pluto => Planet::Pluto,
+            NaturalBody::Emb => Planet::Emb,
+            NaturalBody::PlutoBarycenter => Planet::PlutoBarycenter,
+            _ => return None,
+        };
+        Some(planet)
+    }
+
+    /// The name of the body, as for [`Planet`] with the other barycenters named alike.
+    pub fn name(self) -> &'static str {
+        match self {
+            NaturalBody::MercuryBarycenter => "Mercury-Barycenter",
+            NaturalBody::VenusBarycenter => "Venus-Barycenter",
+            NaturalBody::MarsBarycenter => "Mars-Barycenter",
+            NaturalBody::JupiterBarycenter => "Jupiter-Barycenter",
+            NaturalBody::SaturnBarycenter => "Saturn-Barycenter",
+            NaturalBody::UranusBarycenter => "Uranus-Barycenter",
+            NaturalBody::NeptuneBarycenter => "Neptune-Barycenter",
+            body => body.planet().map_or("", Planet::name),
+        }
+    }
+}
+
+impl From<Planet> for NaturalBody {
+    fn from(planet: Planet) -> Self {
+        match planet {
+            Planet::Ssb => NaturalBody::Ssb,
+            Planet::Mercury => NaturalBody::Mercury,
+            Planet::Venus => NaturalBody::Venus,
+            Planet::Earth => NaturalBody::Earth,
+            Planet::Mars => NaturalBody::Mars,
+            Planet::Jupiter => NaturalBody::Jupiter,
+            Planet::Saturn => NaturalBody::Saturn,
+            Planet::Uranus => NaturalBody::Uranus,
+            Planet::Neptune => NaturalBody::Neptune,
+            Planet::Pluto => NaturalBody::Pluto,
+            Planet::Sun => NaturalBody::Sun,
+            Planet::Moon => NaturalBody::Moon,
+            Planet::Emb => NaturalBody::Emb,
+            Planet::PlutoBarycenter => NaturalBody::PlutoBarycenter,
+        }
+    }
+}
+
+impl fmt::Display for NaturalBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for NaturalBody {
+    type Err = Error;
+
+    /// Parses a body name as given by [`NaturalBody::name`] (case-insensitive).
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        NaturalBody::ALL
+            .into_iter()
+            .find(|body| body.name().eq_ignore_ascii_case(s))
+            .ok_or(Error::InvalidArgument("unknown body name"))
+    }
+}
+
+/// Catalog astrometry of a sidereal source.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Astrometry {
+    /// [h] right ascension
+    pub ra: f64,
+    /// [deg] declination
+    pub dec: f64,
+    /// [mas/yr] proper motion in right ascension, including the cos(dec) factor
+    pub pm_ra: f64,
+    /// [mas/yr] proper motion in declination
+    pub pm_dec: f64,
+    /// [mas] parallax
+    pub parallax: f64,
+    /// [km/s] radial velocity
+    pub rv: f64,
+}
+
+/// A catalog entry of a sidereal source, with the epoch of its coordinates.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CatalogEntry {
+    /// Name of the source, up to 49 characters
+    pub name: String,
+    /// Catalog designator, up to 5 characters
+    pub catalog: String,
+    /// Number of the source in the catalog
+    pub number: i64,
+    /// Epoch of the position
+    pub epoch: Epoch,
+    /// Equinox of the coordinates
+    pub equinox: Epoch,
+    pub astrometry: Astrometry,
+}
+
+impl CatalogEntry {
+    /// An entry of the `name`d source at J2000, with no catalog designation.
+    pub fn new(name: &str, astrometry: Astrometry) -> Self {
+        CatalogEntry {
+            name: name.to_string(),
+            catalog: String::new(),
+            number: 0,
+            epoch: Epoch::J2000,
+            equinox: Epoch::J2000,
+            astrometry,
+        }
+    }
+
+    /// Converts from a NOVAS `cat_entry` with the position at `epoch`, in coordinates of
+    /// `equinox`.
+    pub fn from_raw(raw: &sn::cat_entry, epoch: Epoch, equinox: Epoch) -> Self {
+        let text = |s: &[std::os::raw::c_char]| unsafe { CStr::from_ptr(s.as_ptr()) }.to_string_lossy().into_owned();
+        CatalogEntry {
+            name: text(&raw.starname),
+            catalog: text(&raw.catalog),
+            number: raw.starnumber as _,
+            epoch,
+            equinox,
+            astrometry: Astrometry {
+                ra: raw.ra,
+                dec: raw.dec,
+                pm_ra: raw.promora,
+                pm_dec: raw.promodec,
+                parallax: raw.parallax,
+                rv: raw.radialvelocity,
+            },
+        }
+    }
+
+    /// Converts to a NOVAS `cat_entry`. Fails if the name or catalog are too long.
+    pub fn to_raw(&self) -> Result<sn::cat_entry> {
+        let name = CString::new(self.name.as_str()).map_err(|_| Error::InvalidArgument("name contains NUL"))?;
+        let catalog = CString::new(self.catalog.as_str()).map_err(|_| Error::InvalidArgument("catalog contains NUL"))?;
+        let number = std::os::raw::c_long::try_from(self.number).map_err(|_| Error::InvalidArgument("catalog number out of range"))?;
+        let a = &self.astrometry;
+        let mut raw = sn::cat_entry::default();
+        check("make_cat_entry", || unsafe {
+            sn::make_cat_entry(name.as_ptr(), catalog.as_ptr(), number, a.ra, a.dec, a.pm_ra, a.pm_dec, a.parallax, a.rv, &mut raw)
+        } as i32)?;
+        Ok(raw)
+    }
+
+    /// Moves the source with its space motion (proper motion, parallax and radial velocity) to
+    /// `epoch`, keeping the equinox of the coordinates.
+    pub fn with_proper_motion(&self, epoch: Epoch) -> Result<CatalogEntry> {
+        self.transform(sn::novas_transform_type_PROPER_MOTION, self.epoch, epoch, epoch, self.equinox)
+    }
+
+    /// The entry propagated with its space motion to the TT Julian date `jd_tt`, e.g. the date of
+    /// an observation.
+    pub fn at_epoch(&self, jd_tt: f64) -> Result<CatalogEntry> {
+        self.with_proper_motion(Epoch::from_jd(jd_tt))
+    }
+
+    /// Precesses the coordinates to the mean equator and equinox of `equinox`, without moving
+    /// the source.
+    pub fn precessed(&self, equinox: Epoch) -> Result<CatalogEntry> {
+        self.transform(sn::novas_transform_type_PRECESSION, self.equinox, equinox, self.epoch, equinox)
+    }
+
+    /// Moves the source with its space motion to `epoch`, and precesses the coordinates to the
+    /// equinox of `epoch`.
+    pub fn to_epoch(&self, epoch: Epoch) -> Result<CatalogEntry> {
+        self.with_proper_motion(epoch)?.precessed(epoch)
+    }
+
+    /// Dynamical J2000 frame to ICRS (frame tie), for coordinates of the J2000 equinox.
+    pub fn j2000_to_icrs(&self) -> Result<CatalogEntry> {
+        let (epoch, equinox) = (self.epoch, self.equinox);
+        self.transform(sn::novas_transform_type_CHANGE_J2000_TO_ICRS, equinox, equinox, epoch, equinox)
+    }
+
+    /// ICRS to the dynamical J2000 frame (frame tie).
+    pub fn icrs_to_j2000(&self) -> Result<CatalogEntry> {
+        let (epoch, equinox) = (self.epoch, self.equinox);
+        self.transform(sn::novas_transform_type_CHANGE_ICRS_TO_J2000, equinox, equinox, epoch, equinox)
+    }
+
+    /// FK5 entry to ICRS at J2000.
+    pub fn fk5_to_icrs(&self) -> Result<CatalogEntry> {
+        self.to_epoch(Epoch::J2000)?.j2000_to_icrs()
+    }
+
+    /// FK4 entry to ICRS at J2000, removing the E-terms of aberration with the 6×6 matrix of
+    /// SOFA `iauFk425()`.
+    ///
+    /// Entries of other epochs or equinoxes than [`Epoch::B1950`] are first moved to B1950 and
+    /// precessed with NOVAS, which uses the IAU 2006 rather than the Newcomb precession. The
+    /// proper motion in right ascension is taken per tropical year, as FK4 catalogs give it.
+    pub fn fk4_to_icrs(&self) -> Result<CatalogEntry> {
+        let mut entry = self.clone();
+        if entry.epoch != Epoch::B1950 {
+            entry = entry.with_proper_motion(Epoch::B1950)?;
+        }
+        if entry.equinox != Epoch::B1950 {
+            entry = entry.precessed(Epoch::B1950)?;
+        }
+
+        let a = &entry.astrometry;
+        let dec = a.dec.to_radians();
+        let mas = (1e-3 / 3600.0f64).to_radians();
+        let star = fk4::fk425(&fk4::Star {
+            ra: (a.ra * 15.0).to_radians(),
+            dec,
+            pm_ra: a.pm_ra * mas / dec.cos(),
+            pm_dec: a.pm_dec * mas,
+            parallax: a.parallax * 1e-3,
+            rv: a.rv,
+        });
+        entry.astrometry = Astrometry {
+            ra: star.ra.to_degrees() / 15.0,
+            dec: star.dec.to_degrees(),
+            pm_ra: star.pm_ra * star.dec.cos() / mas,
+            pm_dec: star.pm_dec / mas,
+            parallax: star.parallax * 1e3,
+            rv: star.rv,
+        };
+        entry.epoch = Epoch::J2000;
+        entry.equinox = Epoch::J2000;
+        entry.j2000_to_icrs()
+    }
+
+    // Applies `transform_cat` from date `from` to date `to`, resulting in an entry at `epoch` in
+    // coordinates of `equinox`.
+    fn transform(&self, option: sn::novas_transform_type, from: Epoch, to: Epoch, epoch: Epoch, equinox: Epoch) -> Result<CatalogEntry> {
+        let input = self.to_raw()?;
+        let catalog = CString::new(self.catalog.as_str()).map_err(|_| Error::InvalidArgument("catalog contains NUL"))?;
+        let mut raw = sn::cat_entry::default();
+        check("transform_cat", || unsafe {
+            sn::transform_cat(option, from.jd(), &input, to.jd(), catalog.as_ptr(), &mut raw)
+        } as i32)?;
+        Ok(CatalogEntry::from_raw(&raw, epoch, equinox))
+    }
+}
+
+/// Kinds of [`Source`], by how NOVAS computes their positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceKind {
+    /// A major solar-system body, from the planet provider
+    Planet,
+    /// A solar-system body from an ephemeris provider, by name or ID number
+    Ephemeris,
+    /// A sidereal or redshifted source with catalog coordinates
+    Catalog,
+    /// A solar-system body with Keplerian orbital elements
+    Orbital,
+}
+
+/// An observed source, wrapping the NOVAS `object` structure.
+///
+/// With the `serde` feature, sources are serialized with a `kind` tag (`planet`, `ephemeris`,
+/// `catalog` or `orbital`) and what defines them: the ICRS catalog entry for catalog sources and
+/// the elements for orbital ones, which must be heliocentric and ecliptic.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "SourceRecord"))]
+pub struct Source {
+    raw: sn::object,
+}
+
+// Serialized form of a `Source`, by kind.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum SourceRecord {
+    Planet { planet: Planet },
+    Ephemeris { name: String, number: i64 },
+    Catalog { entry: CatalogEntry },
+    Orbital { name: String, orbit: Orbit },
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<&Source> for SourceRecord {
+    type Error = Error;
+
+    fn try_from(source: &Source) -> Result<Self> {
+        Ok(match source.kind() {
+            SourceKind::Planet => SourceRecord::Planet {
+                planet: Planet::from_number(source.raw.number as i32).ok_or(Error::InvalidArgument("unknown planet number"))?,
+            },
+            SourceKind::Ephemeris => SourceRecord::Ephemeris { name: source.name(), number: source.raw.number as _ },
+            SourceKind::Catalog => SourceRecord::Catalog { entry: CatalogEntry::from_raw(&source.raw.star, Epoch::J2000, Epoch::J2000) },
+            SourceKind::Orbital => SourceRecord::Orbital { name: source.name(), orbit: Orbit::from_raw(&source.raw.orbit)? },
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SourceRecord> for Source {
+    type Error = Error;
+
+    fn try_from(record: SourceRecord) -> Result<Self> {
+        match record {
+            SourceRecord::Planet { planet } => Source::planet(planet),
+            SourceRecord::Ephemeris { name, number } => Source::ephemeris(&name, number),
+            SourceRecord::Catalog { entry } => Source::from_catalog_entry(&entry, "ICRS"),
+            SourceRecord::Orbital { name, orbit } => Source::orbital(&name, &orbit),
+        }
+    }
+}
+
+// Fails for orbital sources with elements that are not heliocentric and ecliptic, which
+// `Source::orbital` cannot recreate.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Source {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let record = SourceRecord::try_from(self).map_err(serde::ser::Error::custom)?;
+        serde::Serialize::serialize(&record, serializer)
+    }
+}
+
+impl Source {
+    /// A sidereal source at fixed catalog coordinates.
+    ///
+    /// `ra` is in hours and `dec` in degrees, both in the given catalog system (e.g. "ICRS",
+    /// "J2000", "B1950", "FK4").
+    pub fn star(name: &str, ra: f64, dec: f64, system: &str) -> Result<Self> {
+        Self::catalog(name, &Astrometry { ra, dec, ..Default::default() }, system)
+    }
+
+    /// A sidereal source with full catalog astrometry, in the given catalog system.
+    pub fn catalog(name: &str, astrometry: &Astrometry, system: &str) -> Result<Self> {
+        Self::from_catalog_entry(&CatalogEntry::new(name, *astrometry), system)
+    }
+
+    /// A sidereal source from a catalog entry, in the given catalog system.
+    pub fn from_catalog_entry(entry: &CatalogEntry, system: &str) -> Result<Self> {
+        let system = CString::new(system).map_err(|_| Error::InvalidArgument("system contains NUL"))?;
+        let star = entry.to_raw()?;
+        let mut raw = sn::object::default();
+        check("make_cat_object_sys", || unsafe {
+            sn::make_cat_object_sys(&star, system.as_ptr(), &mut raw)
+        })?;
+        Ok(Source { raw })
+    }
+
+    /// A major solar-system body. Its positions come from the configured planet provider.
+    pub fn planet(planet: Planet) -> Result<Self> {
+        let mut raw = sn::object::default();
+        check("make_planet", || unsafe { sn::make_planet(planet.to_raw(), &mut raw) })?;
+        Ok(Source { raw })
+    }
+
+    /// A natural solar-system body or barycenter. Bodies known to NOVAS are major planets, as for
+    /// [`Source::planet`], and the others come from the configured ephemeris provider by NAIF ID.
+    pub fn natural_body(body: NaturalBody) -> Result<Self> {
+        match body.planet() {
+            Some(planet) => Self::planet(planet),
+            None => Self::ephemeris(body.name(), body.naif_id()),
+        }
+    }
+
+    /// A solar-system body whose positions come from the configured ephemeris provider, looked
+    /// up by `name` or ID `number` (e.g. a NAIF ID), depending on the provider.
+    pub fn ephemeris(name: &str, number: i64) -> Result<Self> {
+        let name = CString::new(name).map_err(|_| Error::InvalidArgument("name contains NUL"))?;
+        let mut raw = sn::object::default();
+        check("make_ephem_object", || unsafe {
+            sn::make_ephem_object(name.as_ptr(), number as _, &mut raw)
+        })?;
+        Ok(Source { raw })
+    }
+
+    /// A distant (extragalactic) source at catalog coordinates with the given redshift.
+    ///
+    /// `ra` is in hours and `dec` in degrees, both in the given catalog system.
+    pub fn redshifted(name: &str, ra: f64, dec: f64, system: &str, z: Redshift) -> Result<Self> {
+        let name = CString::new(name).map_err(|_| Error::InvalidArgument("name contains NUL"))?;
+        let system = CString::new(system).map_err(|_| Error::InvalidArgument("system contains NUL"))?;
+        let mut raw = sn::object::default();
+        check("make_redshifted_object_sys", || unsafe {
+            sn::make_redshifted_object_sys(name.as_ptr(), ra, dec, system.as_ptr(), z.value(), &mut raw)
+        })?;
+        Ok(Source { raw })
+    }
+
+    /// Wraps an already initialized `object`.
+    pub fn from_raw(raw: sn::object) -> Self {
+        Source { raw }
+    }
+
+    pub fn as_raw(&self) -> &sn::object {
+        &self.raw
+    }
+
+    /// The kind of the source.
+    pub fn kind(&self) -> SourceKind {
+        match self.raw.type_ {
+            sn::novas_object_type_NOVAS_PLANET => SourceKind::Planet,
+            sn::novas_object_type_NOVAS_EPHEM_OBJECT => SourceKind::Ephemeris,
+            sn::novas_object_type_NOVAS_ORBITAL_OBJECT => SourceKind::Orbital,
+            _ => SourceKind::Catalog,
+        }
+    }
+
+    /// The name of the source.
+    pub fn name(&self) -> String {
+        unsafe { CStr::from_ptr(self.raw.name.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hipparcos (ESA 1997) astrometry at J1991.25, propagated and compared with the Gaia DR3
+    // positions at J2016.0 and those positions moved back to J2000 (as listed by SIMBAD). The
+    // Hipparcos proper motions of these stars are off from Gaia by up to ~8 mas/yr, hence the
+    // wider tolerance at J2016.
+    fn assert_propagates(hipparcos: Astrometry, j2000: (f64, f64), j2016: (f64, f64), tolerance: [f64; 2]) {
+        let mut entry = CatalogEntry::new("star", hipparcos);
+        entry.epoch = Epoch::Julian(1991.25);
+        for ((ra, dec), epoch, tolerance) in [(j2000, Epoch::J2000, tolerance[0]), (j2016, Epoch::Julian(2016.0), tolerance[1])] {
+            let at = entry.at_epoch(epoch.jd()).unwrap();
+            assert_eq!(at.epoch, epoch);
+            assert_eq!(at.equinox, Epoch::J2000);
+            // [arcsec]
+            let dra = (at.astrometry.ra * 15.0 - ra) * dec.to_radians().cos() * 3600.0;
+            let ddec = (at.astrometry.dec - dec) * 3600.0;
+            assert!(dra.hypot(ddec) < tolerance, "{epoch:?}: off by {dra:.3}″, {ddec:.3}″");
+        }
+    }
+
+    #[test]
+    fn natural_bodies_by_naif_id() {
+        for body in NaturalBody::ALL {
+            assert_eq!(NaturalBody::from_naif_id(body.naif_id()), Some(body));
+            assert_eq!(body.to_string().to_uppercase().parse::<NaturalBody>().unwrap(), body);
+            if let Some(planet) = body.planet() {
+                assert_eq!(planet.naif_id(), body.naif_id(), "{body}");
+                assert_eq!(NaturalBody::from(planet), body);
+            }
+        }
+        assert_eq!(NaturalBody::from_naif_id(5), Some(NaturalBody::JupiterBarycenter));
+        assert_eq!(NaturalBody::JupiterBarycenter.planet(), None);
+        assert_eq!(NaturalBody::from_naif_id(-82), None);
+        assert!("Ceres".parse::<NaturalBody>().is_err());
+
+        let source = Source::natural_body(NaturalBody::SaturnBarycenter).unwrap();
+        assert_eq!(source.kind(), SourceKind::Ephemeris);
+        assert_eq!(source.name().parse::<NaturalBody>().unwrap(), NaturalBody::SaturnBarycenter);
+        assert_eq!(Source::natural_body(NaturalBody::Moon).unwrap().kind(), SourceKind::Planet);
+    }
+
+    #[test]
+    fn barnards_star_at_epoch() {
+        let hip = Astrometry { ra: 269.45402305 / 15.0, dec: 4.66828815, pm_ra: -797.84, pm_dec: 10326.93, parallax: 549.01, rv: -110.6 };
+        // 17h57m48.49803s +04°41′36.2072″ and Gaia DR3 4472832130942575872
+        let j2000 = ((17.0 + 57.0 / 60.0 + 48.49803 / 3600.0) * 15.0, 4.0 + 41.0 / 60.0 + 36.2072 / 3600.0);
+        assert_propagates(hip, j2000, (269.44850252, 4.73942005), [0.02, 0.25]);
+    }
+
+    #[test]
+    fn proxima_centauri_at_epoch() {
+        let hip = Astrometry { ra: 217.44894751 / 15.0, dec: -62.68135207, pm_ra: -3775.75, pm_dec: 765.54, parallax: 772.33, rv: -22.4 };
+        // 14h29m42.94853s -62°40′46.1631″ and Gaia DR3 5853498713190525696
+        let j2000 = ((14.0 + 29.0 / 60.0 + 42.94853 / 3600.0) * 15.0, -(62.0 + 40.0 / 60.0 + 46.1631 / 3600.0));
+        assert_propagates(hip, j2000, (217.39232147, -62.67607512), [0.02, 0.25]);
+    }
+}
@@ -0,0 +1,395 @@
+use super::error::{check, Error, Result};
+use super::frame::{ObservingFrame, ReferenceSystem, SkyPos};
+use super::source::{Planet, Source, SourceKind};
+use super::time::Time;
+use supernovas_sys as sn;
+
+/// Units of position and velocity vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StateUnits {
+    /// [AU] and [AU/day]
+    #[default]
+    Au,
+    /// [km] and [km/s]
+    Km,
+}
+
+impl StateUnits {
+    // Scaling of distances and velocities from AU and AU/day.
+    fn scale(self) -> (f64, f64) {
+        match self {
+            StateUnits::Au => (1.0, 1.0),
+            StateUnits::Km => {
+                let au_km = sn::NOVAS_AU / sn::NOVAS_KM;
+                (au_km, au_km / sn::NOVAS_DAY)
+            }
+        }
+    }
+}
+
+/// Position and velocity relative to the Solar-System Barycenter (SSB).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BarycentricState {
+    /// Position vector, in `units`
+    pub position: [f64; 3],
+    /// Velocity vector, in `units`
+    pub velocity: [f64; 3],
+    /// Coordinate system of the vectors
+    pub system: ReferenceSystem,
+    /// Units of the vectors
+    pub units: StateUnits,
+}
+
+impl ObservingFrame {
+    /// Barycentric state of the observer of this frame.
+    pub fn observer_state(&self, system: ReferenceSystem, units: StateUnits) -> Result<BarycentricState> {
+        let raw = self.as_raw();
+        self.barycentric_state(raw.obs_pos, raw.obs_vel, system, units)
+    }
+
+    /// Barycentric state of a source at the retarded epoch at which the observed light left it.
+    /// This is the same geometric state that the apparent place calculation starts from.
+    pub fn target_state(&self, source: &Source, system: ReferenceSystem, units: StateUnits) -> Result<BarycentricState> {
+        let mut pos = [0.0; 3];
+        let mut vel = [0.0; 3];
+        check("novas_geom_posvel", || unsafe {
+            sn::novas_geom_posvel(
+                source.as_raw(),
+                self.as_raw(),
+                sn::novas_reference_system_NOVAS_ICRS,
+                pos.as_mut_ptr(),
+                vel.as_mut_ptr(),
+            )
+        })?;
+        let obs = self.as_raw().obs_pos;
+        self.barycentric_state(std::array::from_fn(|i| obs[i] + pos[i]), vel, system, units)
+    }
+
+    /// Geometric state of a source relative to the observer of this frame, with the source at
+    /// the retarded epoch at which the observed light left it, without aberration or
+    /// gravitational deflection. The state is tagged with the time of the frame.
+    pub fn geometric_state(&self, source: &Source, system: ReferenceSystem, units: StateUnits) -> Result<StateVector> {
+        let mut pos = [0.0; 3];
+        let mut vel = [0.0; 3];
+        check("novas_geom_posvel", || unsafe {
+            sn::novas_geom_posvel(source.as_raw(), self.as_raw(), system.to_raw(), pos.as_mut_ptr(), vel.as_mut_ptr())
+        })?;
+        let (ds, vs) = units.scale();
+        Ok(StateVector {
+            position: pos.map(|x| x * ds),
+            velocity: vel.map(|x| x * vs),
+            target: Body::of(source),
+            center: Body::Observer,
+            system,
+            units,
+            epoch: self.time(),
+        })
+    }
+
+    /// Apparent position in `system` of a geometric state relative to the observer of this frame,
+    /// e.g. from [`ObservingFrame::geometric_state`], applying aberration and gravitational
+    /// deflection.
+    pub fn geometric_to_apparent(&self, state: &StateVector, system: ReferenceSystem) -> Result<SkyPos> {
+        if state.center != Body::Observer {
+            return Err(Error::InvalidArgument("state vector is not relative to the observer"));
+        }
+        if state.epoch.diff(&self.time()).abs() > EPOCH_TOLERANCE {
+            return Err(Error::InvalidArgument("state vector is not at the time of the frame"));
+        }
+        let mut pos = state.in_units(StateUnits::Au).position;
+        if state.system != ReferenceSystem::Icrs {
+            pos = self.transform(state.system, ReferenceSystem::Icrs)?.vector(&pos)?;
+        }
+        let mut out = sn::sky_pos::default();
+        check("novas_geom_to_app", || unsafe {
+            sn::novas_geom_to_app(self.as_raw(), pos.as_ptr(), system.to_raw(), &mut out)
+        })?;
+        Ok(out.into())
+    }
+
+    // Converts ICRS vectors [AU, AU/day] to the requested system and units.
+    fn barycentric_state(
+        &self,
+        position: [f64; 3],
+        velocity: [f64; 3],
+        system: ReferenceSystem,
+        units: StateUnits,
+    ) -> Result<BarycentricState> {
+        let (position, velocity) = if system == ReferenceSystem::Icrs {
+            (position, velocity)
+        } else {
+            let transform = self.transform(ReferenceSystem::Icrs, system)?;
+            (transform.vector(&position)?, transform.vector(&velocity)?)
+        };
+        let (ds, vs) = units.scale();
+        Ok(BarycentricState {
+            position: position.map(|x| x * ds),
+            velocity: velocity.map(|x| x * vs),
+            system,
+            units,
+        })
+    }
+}
+
+/// The body a state vector describes, or is relative to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Body {
+    /// A major body or barycenter, e.g. `Planet::Ssb` or `Planet::Earth` for the geocenter
+    Planet(Planet),
+    /// The observer of a frame
+    Observer,
+    /// Any other body, by name
+    Named(String),
+}
+
+impl Body {
+    // The body of a source: a planet for major bodies, otherwise its name.
+    fn of(source: &Source) -> Body {
+        let raw = source.as_raw();
+        let planet = u32::try_from(raw.number).ok().and_then(Planet::from_raw);
+        match planet {
+            Some(planet) if source.kind() == SourceKind::Planet => Body::Planet(planet),
+            _ => Body::Named(source.name()),
+        }
+    }
+}
+
+/// Position and velocity of a `target` relative to a `center`, tagged with the reference system,
+/// units and epoch they refer to. Combining vectors checks the tags, so that e.g. an ITRS
+/// topocentric state cannot be added to an ICRS barycentric one by mistake.
+#[derive(Debug, Clone)]
+pub struct StateVector {
+    /// Position vector, in `units`
+    pub position: [f64; 3],
+    /// Velocity vector, in `units`
+    pub velocity: [f64; 3],
+    /// The body described
+    pub target: Body,
+    /// Origin of the vectors
+    pub center: Body,
+    /// Coordinate system of the vectors
+    pub system: ReferenceSystem,
+    /// Units of the vectors
+    pub units: StateUnits,
+    /// Time the state refers to
+    pub epoch: Time,
+}
+
+// Conversions of the vectors to linear-algebra types, for dynamics code built on them.
+macro_rules! vector_conversions {
+    ($state:ident) => {
+        impl $state {
+            /// Position and velocity as nalgebra vectors, in `units`.
+            #[cfg(feature = "nalgebra")]
+            pub fn to_nalgebra(&self) -> (nalgebra::Vector3<f64>, nalgebra::Vector3<f64>) {
+                let [x, y, z] = self.position;
+                let [vx, vy, vz] = self.velocity;
+                (nalgebra::Vector3::new(x, y, z), nalgebra::Vector3::new(vx, vy, vz))
+            }
+
+            /// Position and velocity as glam vectors, in `units`.
+            #[cfg(feature = "glam")]
+            pub fn to_glam(&self) -> (glam::DVec3, glam::DVec3) {
+                (glam::DVec3::from(self.position), glam::DVec3::from(self.velocity))
+            }
+        }
+    };
+}
+
+vector_conversions!(BarycentricState);
+vector_conversions!(StateVector);
+
+/// [s] Largest difference between epochs that are considered the same.
+const EPOCH_TOLERANCE: f64 = 1e-6;
+
+impl BarycentricState {
+    /// Tags this state with the body it describes and its epoch.
+    pub fn tagged(self, target: Body, epoch: &Time) -> StateVector {
+        StateVector {
+            position: self.position,
+            velocity: self.velocity,
+            target,
+            center: Body::Planet(Planet::Ssb),
+            system: self.system,
+            units: self.units,
+            epoch: *epoch,
+        }
+    }
+}
+
+impl StateVector {
+    /// [s] Light travel time over the distance of the target from the center.
+    pub fn light_time(&self) -> f64 {
+        let (ds, _) = self.units.scale();
+        let r = self.position.iter().map(|x| x * x).sum::<f64>().sqrt();
+        r / ds * sn::NOVAS_AU / sn::NOVAS_C
+    }
+
+    /// The same state in other units.
+    pub fn in_units(&self, units: StateUnits) -> StateVector {
+        let (from_d, from_v) = self.units.scale();
+        let (to_d, to_v) = units.scale();
+        StateVector {
+            position: self.position.map(|x| x / from_d * to_d),
+            velocity: self.velocity.map(|x| x / from_v * to_v),
+            units,
+            ..self.clone()
+        }
+    }
+
+    /// State of the target relative to the target of `origin`, both given relative to the same
+    /// center, e.g. a planet relative to the observer from their barycentric states.
+    pub fn relative_to(&self, origin: &StateVector) -> Result<StateVector> {
+        self.check_compatible(origin)?;
+        if self.center != origin.center {
+            return Err(Error::InvalidArgument("state vectors relative to different centers"));
+        }
+        Ok(StateVector {
+            position: std::array::from_fn(|i| self.position[i] - origin.position[i]),
+            velocity: std::array::from_fn(|i| self.velocity[i] - origin.velocity[i]),
+            center: origin.target.clone(),
+            ..self.clone()
+        })
+    }
+
+    /// State of the target relative to the center of `center_state`, which must describe the
+    /// center of this state, e.g. a topocentric state made geocentric with the observer's
+    /// geocentric state.
+    pub fn recentered(&self, center_state: &StateVector) -> Result<StateVector> {
+        self.check_compatible(center_state)?;
+        if self.center != center_state.target {
+            return Err(Error::InvalidArgument("state vector does not describe the center"));
+        }
+        Ok(StateVector {
+            position: std::array::from_fn(|i| self.position[i] + center_state.position[i]),
+            velocity: std::array::from_fn(|i| self.velocity[i] + center_state.velocity[i]),
+            center: center_state.center.clone(),
+            ..self.clone()
+        })
+    }
+
+    // Vectors can be combined in the same system and units at the same epoch.
+    fn check_compatible(&self, other: &StateVector) -> Result<()> {
+        if self.system != other.system {
+            return Err(Error::InvalidArgument("state vectors in different reference systems"));
+        }
+        if self.units != other.units {
+            return Err(Error::InvalidArgument("state vectors in different units"));
+        }
+        if self.epoch.diff(&other.epoch).abs() > EPOCH_TOLERANCE {
+            return Err(Error::InvalidArgument("state vectors at different epochs"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::frame::Accuracy;
+    use crate::novas::observer::Observer;
+    use crate::novas::source::Planet;
+    use crate::novas::time::{Time, Timescale};
+
+    fn frame() -> ObservingFrame {
+        let observer = Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap();
+        let time = Time::new(Timescale::Utc, 2460850.5, 37, 0.035).unwrap();
+        ObservingFrame::new(Accuracy::Reduced, &observer, &time, 0.0, 0.0).unwrap()
+    }
+
+    fn norm(v: &[f64; 3]) -> f64 {
+        v.iter().map(|x| x * x).sum::<f64>().sqrt()
+    }
+
+    fn sun() -> Source {
+        Source::planet(Planet::Sun).unwrap()
+    }
+
+    #[test]
+    fn barycentric_states() {
+        let frame = frame();
+        let observer = frame.observer_state(ReferenceSystem::Icrs, StateUnits::Au).unwrap();
+        // The Sun is up to 0.01 AU from the barycenter
+        assert!((norm(&observer.position) - 1.0).abs() < 0.03);
+        let km = frame.observer_state(ReferenceSystem::Icrs, StateUnits::Km).unwrap();
+        assert!((norm(&km.velocity) - 29.5).abs() < 1.0);
+        // Rotations keep the lengths
+        let tod = frame.observer_state(ReferenceSystem::Tod, StateUnits::Au).unwrap();
+        assert!((norm(&tod.position) - norm(&observer.position)).abs() < 1e-12);
+        assert_eq!(tod.system, ReferenceSystem::Tod);
+        let sun = frame.target_state(&sun(), ReferenceSystem::Icrs, StateUnits::Au).unwrap();
+        assert!(norm(&sun.position) < 0.01);
+    }
+
+    #[test]
+    fn geometric_states_are_tagged() {
+        let frame = frame();
+        let state = frame.geometric_state(&sun(), ReferenceSystem::Icrs, StateUnits::Au).unwrap();
+        assert_eq!(state.target, Body::Planet(Planet::Sun));
+        assert_eq!(state.center, Body::Observer);
+        // Near aphelion, 8m27s
+        assert!((state.light_time() - 507.0).abs() < 1.0, "{}", state.light_time());
+        let km = state.in_units(StateUnits::Km);
+        assert!((km.light_time() - state.light_time()).abs() < 1e-9);
+        let back = km.in_units(StateUnits::Au);
+        assert!(back.position.iter().zip(&state.position).all(|(a, b)| (a - b).abs() < 1e-15));
+        let star = Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap();
+        assert_eq!(Body::of(&star), Body::Named(star.name()));
+    }
+
+    #[test]
+    fn geometric_to_apparent_positions() {
+        let frame = frame();
+        for system in [ReferenceSystem::Icrs, ReferenceSystem::Tod] {
+            let state = frame.geometric_state(&sun(), system, StateUnits::Km).unwrap();
+            let apparent = frame.geometric_to_apparent(&state, ReferenceSystem::Cirs).unwrap();
+            let expected = frame.sky_pos(&sun(), ReferenceSystem::Cirs).unwrap();
+            assert!(apparent.separation(&expected) * 3600.0 < 1e-3);
+        }
+        let state = frame.geometric_state(&sun(), ReferenceSystem::Icrs, StateUnits::Au).unwrap();
+        let moved = StateVector { epoch: state.epoch.offset(1.0).unwrap(), ..state.clone() };
+        assert!(frame.geometric_to_apparent(&moved, ReferenceSystem::Icrs).is_err());
+        let geocentric = StateVector { center: Body::Planet(Planet::Earth), ..state };
+        assert!(frame.geometric_to_apparent(&geocentric, ReferenceSystem::Icrs).is_err());
+    }
+
+    #[test]
+    fn combining_checks_the_tags() {
+        let frame = frame();
+        let epoch = frame.time();
+        let observer = frame.observer_state(ReferenceSystem::Icrs, StateUnits::Au).unwrap().tagged(Body::Observer, &epoch);
+        let sun_state = frame.target_state(&sun(), ReferenceSystem::Icrs, StateUnits::Au).unwrap().tagged(Body::Planet(Planet::Sun), &epoch);
+        let topocentric = sun_state.relative_to(&observer).unwrap();
+        assert_eq!(topocentric.center, Body::Observer);
+        let geometric = frame.geometric_state(&sun(), ReferenceSystem::Icrs, StateUnits::Au).unwrap();
+        assert!(topocentric.position.iter().zip(&geometric.position).all(|(a, b)| (a - b).abs() < 1e-12));
+        let barycentric = topocentric.recentered(&observer).unwrap();
+        assert_eq!(barycentric.center, Body::Planet(Planet::Ssb));
+        assert!(barycentric.position.iter().zip(&sun_state.position).all(|(a, b)| (a - b).abs() < 1e-15));
+
+        assert!(sun_state.relative_to(&observer.in_units(StateUnits::Km)).is_err());
+        assert!(sun_state.recentered(&observer).is_err());
+        let tod = frame.observer_state(ReferenceSystem::Tod, StateUnits::Au).unwrap().tagged(Body::Observer, &epoch);
+        assert!(sun_state.relative_to(&tod).is_err());
+        let later = frame.observer_state(ReferenceSystem::Icrs, StateUnits::Au).unwrap().tagged(Body::Observer, &epoch.offset(60.0).unwrap());
+        assert!(sun_state.relative_to(&later).is_err());
+    }
+
+    #[cfg(any(feature = "nalgebra", feature = "glam"))]
+    #[test]
+    fn linear_algebra_vectors() {
+        let state = frame().observer_state(ReferenceSystem::Icrs, StateUnits::Km).unwrap();
+        #[cfg(feature = "nalgebra")]
+        {
+            let (position, velocity) = state.to_nalgebra();
+            assert_eq!([position[0], position[1], position[2]], state.position);
+            assert_eq!(velocity[2], state.velocity[2]);
+        }
+        #[cfg(feature = "glam")]
+        {
+            let (position, velocity) = state.to_glam();
+            assert_eq!(position.to_array(), state.position);
+            assert_eq!(velocity.to_array(), state.velocity);
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use super::angle::{Degrees, Hours};
+use super::error::{Error, Result};
+use super::plan::{Constraints, PlanTarget};
+use super::source::{Planet, Source};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+// A target list, see `PlanTarget::from_toml`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TargetList {
+    #[serde(default)]
+    defaults: Limits,
+    #[serde(default, rename = "target", alias = "targets")]
+    targets: Vec<Entry>,
+}
+
+// Constraints as written in the list, each optional so that targets can fall back on the defaults.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct Limits {
+    min_elevation: Option<Value>,
+    max_airmass: Option<f64>,
+    min_moon_separation: Option<Value>,
+    max_sun_elevation: Option<Value>,
+    duration: Option<Value>,
+}
+
+// A `[[target]]` entry. The constraints are repeated rather than flattened from `Limits`, which
+// serde does not support along with `deny_unknown_fields`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    name: String,
+    ra: Option<Value>,
+    dec: Option<Value>,
+    system: Option<String>,
+    min_elevation: Option<Value>,
+    max_airmass: Option<f64>,
+    min_moon_separation: Option<Value>,
+    max_sun_elevation: Option<Value>,
+    duration: Option<Value>,
+}
+
+// Coordinates, angles and durations are given as numbers or as strings with sexagesimal values or
+// units.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+impl Value {
+    // [deg]
+    fn degrees(&self) -> Result<f64> {
+        match self {
+            Value::Number(value) => Ok(*value),
+            Value::Text(text) => Ok(text.parse::<Degrees>()?.0),
+        }
+    }
+
+    // [h]
+    fn hours(&self) -> Result<f64> {
+        match self {
+            Value::Number(value) => Ok(*value),
+            Value::Text(text) => Ok(text.parse::<Hours>()?.0),
+        }
+    }
+
+    // [s] seconds, or a string with a unit of s, m, h or d
+    fn seconds(&self) -> Result<f64> {
+        let text = match self {
+            Value::Number(value) => return Ok(*value),
+            Value::Text(text) => text.trim(),
+        };
+        let split = text.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(text.len());
+        let (number, unit) = text.split_at(split);
+        let number: f64 = number.trim().parse().map_err(|_| Error::InvalidArgument("duration is not a number"))?;
+        let scale = match unit {
+            "" | "s" | "sec" => 1.0,
+            "m" | "min" => 60.0,
+            "h" | "hr" => 3600.0,
+            "d" | "day" => 86400.0,
+            _ => return Err(Error::InvalidArgument("duration unit is not s, m, h or d")),
+        };
+        Ok(number * scale)
+    }
+}
+
+impl Entry {
+    fn target(self, defaults: &Limits) -> Result<PlanTarget> {
+        let source = match (&self.ra, &self.dec) {
+            (Some(ra), Some(dec)) => Source::star(&self.name, ra.hours()?, dec.degrees()?, self.system.as_deref().unwrap_or("ICRS"))?,
+            (None, None) => Source::planet(self.name.parse::<Planet>()?)?,
+            _ => return Err(Error::InvalidArgument("ra and dec must be given together")),
+        };
+        let angle = |value: &Option<Value>, default: &Option<Value>| value.as_ref().or(default.as_ref()).map(Value::degrees).transpose();
+        let constraints = Constraints {
+            min_elevation: angle(&self.min_elevation, &defaults.min_elevation)?.unwrap_or(0.0),
+            max_airmass: self.max_airmass.or(defaults.max_airmass),
+            min_moon_separation: angle(&self.min_moon_separation, &defaults.min_moon_separation)?,
+            max_sun_elevation: angle(&self.max_sun_elevation, &defaults.max_sun_elevation)?,
+            duration: self.duration.as_ref().or(defaults.duration.as_ref()).map(Value::seconds).transpose()?.unwrap_or(0.0),
+        };
+        Ok(PlanTarget::new(&self.name, source, constraints))
+    }
+}
+
+impl TargetList {
+    fn targets(self, what: &str) -> Result<Vec<PlanTarget>> {
+        let defaults = self.defaults;
+        self.targets
+            .into_iter()
+            .map(|entry| {
+                let name = entry.name.clone();
+                entry.target(&defaults).map_err(|err| Error::Format {
+                    what: what.to_string(),
+                    reason: format!("target `{}`: {}", name, err),
+                })
+            })
+            .collect()
+    }
+}
+
+impl PlanTarget {
+    /// Parses a TOML target list, with the constraints of all targets in a `[defaults]` table and
+    /// one `[[target]]` table per target, which can override them:
+    ///
+    /// ```toml
+    /// [defaults]
+    /// min_elevation = 30         # [deg]
+    /// max_airmass = 2.0
+    /// min_moon_separation = 20   # [deg]
+    /// max_sun_elevation = -18    # [deg]
+    /// duration = "45m"           # [s], or with a unit of s, m, h or d
+    ///
+    /// [[target]]
+    /// name = "M31"
+    /// ra = "00:42:44.3"          # [h]
+    /// dec = "+41:16:09"          # [deg]
+    /// system = "ICRS"            # the default
+    /// duration = "1h"
+    ///
+    /// [[target]]
+    /// name = "Jupiter"           # solar-system bodies have no ra and dec
+    /// ```
+    ///
+    /// Angles are decimal numbers or sexagesimal strings, see [`Degrees`] and [`Hours`].
+    pub fn from_toml(text: &str) -> Result<Vec<PlanTarget>> {
+        Self::from_toml_named(text, "target list")
+    }
+
+    /// Parses a YAML target list, with the same keys as [`PlanTarget::from_toml`] and the targets
+    /// in a `target` or `targets` sequence.
+    pub fn from_yaml(text: &str) -> Result<Vec<PlanTarget>> {
+        Self::from_yaml_named(text, "target list")
+    }
+
+    fn from_toml_named(text: &str, what: &str) -> Result<Vec<PlanTarget>> {
+        let list: TargetList = toml::from_str(text).map_err(|err| format_error(what, err))?;
+        list.targets(what)
+    }
+
+    fn from_yaml_named(text: &str, what: &str) -> Result<Vec<PlanTarget>> {
+        let list: TargetList = serde_yaml::from_str(text).map_err(|err| format_error(what, err))?;
+        list.targets(what)
+    }
+}
+
+/// Loads a target list from a YAML file if its extension is `.yaml` or `.yml`, or else from a
+/// TOML file, see [`PlanTarget::from_toml`].
+pub fn load_targets(path: impl AsRef<Path>) -> Result<Vec<PlanTarget>> {
+    let path = path.as_ref();
+    let what = path.display().to_string();
+    let text = fs::read_to_string(path).map_err(|err| Error::Io { path: what.clone(), reason: err.to_string() })?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml" | "yml") => PlanTarget::from_yaml_named(&text, &what),
+        _ => PlanTarget::from_toml_named(&text, &what),
+    }
+}
+
+fn format_error(what: &str, err: impl std::fmt::Display) -> Error {
+    Error::Format { what: what.to_string(), reason: err.to_string().trim_end().to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(defaults: Limits, targets: Vec<Entry>) -> TargetList {
+        TargetList { defaults, targets }
+    }
+
+    fn entry(name: &str, ra: Option<Value>, dec: Option<Value>) -> Entry {
+        Entry {
+            name: name.to_string(),
+            ra,
+            dec,
+            system: None,
+            min_elevation: None,
+            max_airmass: None,
+            min_moon_separation: None,
+            max_sun_elevation: None,
+            duration: None,
+        }
+    }
+
+    fn text(value: &str) -> Option<Value> {
+        Some(Value::Text(value.to_string()))
+    }
+
+    #[test]
+    fn values_are_numbers_or_strings() {
+        assert_eq!(Value::Number(1.5).hours().unwrap(), 1.5);
+        assert!((Value::Text("00:42:44.3".into()).hours().unwrap() - (42.0 + 44.3 / 60.0) / 60.0).abs() < 1e-12);
+        assert!((Value::Text("-18:30".into()).degrees().unwrap() + 18.5).abs() < 1e-12);
+        assert_eq!(Value::Text("45m".into()).seconds().unwrap(), 2700.0);
+        assert_eq!(Value::Text(" 1.5 h".into()).seconds().unwrap(), 5400.0);
+        assert_eq!(Value::Number(90.0).seconds().unwrap(), 90.0);
+        assert!(Value::Text("1w".into()).seconds().is_err());
+        assert!(Value::Text("north".into()).degrees().is_err());
+    }
+
+    #[test]
+    fn targets_fall_back_on_the_defaults() {
+        let defaults = Limits {
+            min_elevation: Some(Value::Number(30.0)),
+            max_airmass: Some(2.0),
+            duration: text("45m"),
+            ..Default::default()
+        };
+        let mut m31 = entry("M31", text("00:42:44.3"), text("+41:16:09"));
+        m31.duration = text("1h");
+        m31.max_sun_elevation = Some(Value::Number(-18.0));
+        let targets = list(defaults, vec![m31, entry("Jupiter", None, None)]).targets("test").unwrap();
+
+        assert_eq!(targets[0].name, "M31");
+        assert_eq!(
+            targets[0].constraints,
+            Constraints {
+                min_elevation: 30.0,
+                max_airmass: Some(2.0),
+                min_moon_separation: None,
+                max_sun_elevation: Some(-18.0),
+                duration: 3600.0,
+            }
+        );
+        assert_eq!(targets[1].name, "Jupiter");
+        assert_eq!(targets[1].constraints.duration, 2700.0);
+    }
+
+    #[test]
+    fn toml_and_yaml_lists_agree() {
+        let toml = "\
+[defaults]
+min_elevation = 30
+duration = \"45m\"
+
+[[target]]
+name = \"M31\"
+ra = \"00:42:44.3\"
+dec = 41.269
+max_airmass = 2
+
+[[target]]
+name = \"Jupiter\"
+duration = 600
+";
+        let yaml = "\
+defaults:
+  min_elevation: 30
+  duration: 45m
+targets:
+  - name: M31
+    ra: \"00:42:44.3\"
+    dec: 41.269
+    max_airmass: 2
+  - name: Jupiter
+    duration: 600
+";
+        let from_toml = PlanTarget::from_toml(toml).unwrap();
+        let from_yaml = PlanTarget::from_yaml(yaml).unwrap();
+        assert_eq!(from_toml.len(), 2);
+        for (a, b) in from_toml.iter().zip(&from_yaml) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.constraints, b.constraints);
+        }
+        assert_eq!(from_toml[0].constraints.max_airmass, Some(2.0));
+        assert_eq!(from_toml[0].constraints.duration, 2700.0);
+        assert_eq!(from_toml[1].constraints.duration, 600.0);
+        assert_eq!(from_toml[1].constraints.min_elevation, 30.0);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(PlanTarget::from_toml("[[target]]\nname = \"M31\"\nmin_elevaton = 30\n").is_err());
+        assert!(PlanTarget::from_toml("[limits]\nmin_elevation = 30\n").is_err());
+    }
+
+    #[test]
+    fn errors_name_the_target() {
+        let err = list(Limits::default(), vec![entry("M31", text("00:42:44.3"), None)]).targets("list.toml").unwrap_err();
+        assert_eq!(err.to_string(), "list.toml: target `M31`: invalid argument: ra and dec must be given together");
+        assert!(list(Limits::default(), vec![entry("Vulcan", None, None)]).targets("list.toml").is_err());
+    }
+}
@@ -0,0 +1,697 @@
+use super::coords::Epoch;
+use super::delta_t::delta_t;
+use super::eop::{self, MJD0};
+use super::error::{check, Error, Result};
+use super::jd::{timestamp, Calendar, CalendarDate, JulianDate};
+use std::ffi::CString;
+use std::fmt;
+use std::str::FromStr;
+use supernovas_sys as sn;
+
+/// Astronomical timescales understood by SuperNOVAS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Timescale {
+    /// Barycentric Coordinate Time
+    Tcb,
+    /// Barycentric Dynamical Time
+    Tdb,
+    /// Geocentric Coordinate Time
+    Tcg,
+    /// Terrestrial Time
+    Tt,
+    /// International Atomic Time
+    Tai,
+    /// GPS Time
+    Gps,
+    /// Coordinated Universal Time
+    Utc,
+    /// UT1 Earth rotation time
+    Ut1,
+}
+
+impl Timescale {
+    pub(crate) fn to_raw(self) -> sn::novas_timescale {
+        match self {
+            Timescale::Tcb => sn::novas_timescale_NOVAS_TCB,
+            Timescale::Tdb => sn::novas_timescale_NOVAS_TDB,
+            Timescale::Tcg => sn::novas_timescale_NOVAS_TCG,
+            Timescale::Tt => sn::novas_timescale_NOVAS_TT,
+            Timescale::Tai => sn::novas_timescale_NOVAS_TAI,
+            Timescale::Gps => sn::novas_timescale_NOVAS_GPS,
+            Timescale::Utc => sn::novas_timescale_NOVAS_UTC,
+            Timescale::Ut1 => sn::novas_timescale_NOVAS_UT1,
+        }
+    }
+}
+
+impl FromStr for Timescale {
+    type Err = Error;
+
+    /// Parses a timescale abbreviation such as "UTC" or "tdb" (case-insensitive).
+    fn from_str(s: &str) -> Result<Self> {
+        let scale = match s.trim().to_ascii_uppercase().as_str() {
+            "TCB" => Timescale::Tcb,
+            "TDB" => Timescale::Tdb,
+            "TCG" => Timescale::Tcg,
+            "TT" | "TDT" => Timescale::Tt,
+            "TAI" => Timescale::Tai,
+            "GPS" => Timescale::Gps,
+            "UTC" => Timescale::Utc,
+            "UT1" => Timescale::Ut1,
+            _ => return Err(Error::InvalidArgument("unknown timescale")),
+        };
+        Ok(scale)
+    }
+}
+
+impl fmt::Display for Timescale {
+    /// Formats the timescale as its abbreviation, e.g. "UTC" or "TDB".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Timescale::Tcb => "TCB",
+            Timescale::Tdb => "TDB",
+            Timescale::Tcg => "TCG",
+            Timescale::Tt => "TT",
+            Timescale::Tai => "TAI",
+            Timescale::Gps => "GPS",
+            Timescale::Utc => "UTC",
+            Timescale::Ut1 => "UT1",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Timescale {
+    // TT seconds per second of this timescale. The coordinate times run faster than TT (IAU 2000
+    // Resolution B1.9 and 2006 Resolution B3); the others differ from TT by constant or periodic
+    // offsets only.
+    fn tt_rate(self) -> f64 {
+        match self {
+            Timescale::Tcg => 1.0 - 6.969290134e-10,
+            Timescale::Tcb => 1.0 - 1.550519768e-8,
+            _ => 1.0,
+        }
+    }
+}
+
+/// An astronomical instant, wrapping `novas_timespec`.
+///
+/// With the `serde` feature, times are serialized as the fields of the `novas_timespec`, which
+/// round-trip exactly.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "TimeRecord", into = "TimeRecord"))]
+pub struct Time {
+    raw: sn::novas_timespec,
+}
+
+// Serialized form of a `Time`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TimeRecord {
+    /// [day] integer part of the TT Julian date
+    ijd_tt: i64,
+    /// [day] fractional part of the TT Julian date
+    fjd_tt: f64,
+    /// [s] TDB - TT
+    tt2tdb: f64,
+    /// [s] TT - UT1
+    ut1_to_tt: f64,
+    /// [s] UT1 - UTC
+    dut1: f64,
+}
+
+#[cfg(feature = "serde")]
+impl From<Time> for TimeRecord {
+    fn from(time: Time) -> Self {
+        let raw = time.raw;
+        TimeRecord {
+            ijd_tt: raw.ijd_tt as _,
+            fjd_tt: raw.fjd_tt,
+            tt2tdb: raw.tt2tdb,
+            ut1_to_tt: raw.ut1_to_tt,
+            dut1: raw.dut1,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<TimeRecord> for Time {
+    fn from(record: TimeRecord) -> Self {
+        Time::from_raw(sn::novas_timespec {
+            ijd_tt: record.ijd_tt as _,
+            fjd_tt: record.fjd_tt,
+            tt2tdb: record.tt2tdb,
+            ut1_to_tt: record.ut1_to_tt,
+            dut1: record.dut1,
+        })
+    }
+}
+
+impl Time {
+    /// Creates a time from a Julian date in the given timescale.
+    ///
+    /// `leap_seconds` is TAI - UTC and `dut1` is UT1 - UTC in seconds.
+    pub fn new(scale: Timescale, jd: f64, leap_seconds: i32, dut1: f64) -> Result<Self> {
+        let mut raw = sn::novas_timespec::default();
+        check("novas_set_time", || unsafe {
+            sn::novas_set_time(scale.to_raw(), jd, leap_seconds, dut1, &mut raw)
+        })?;
+        Ok(Time { raw })
+    }
+
+    /// Creates a time from a UTC Julian date, with the leap seconds of its date from the table of
+    /// [`eop::set_leap_seconds`]. Fails before 1972. Dates past the expiry of the table, see
+    /// [`LeapSeconds::is_stale`](eop::LeapSeconds::is_stale), get its last leap seconds.
+    pub fn from_utc(jd: f64, dut1: f64) -> Result<Self> {
+        let leap_seconds = eop::leap_seconds().at(jd - MJD0).ok_or(Error::InvalidArgument("date precedes the leap-second table"))?;
+        Time::new(Timescale::Utc, jd, leap_seconds, dut1)
+    }
+
+    /// Creates a time from a TAI Julian date, with the leap seconds of its date from the table of
+    /// [`eop::set_leap_seconds`]. Fails before 1972.
+    pub fn from_tai(jd: f64, dut1: f64) -> Result<Self> {
+        Time::new(Timescale::Tai, jd, leap_seconds_at_tai(jd - MJD0)?, dut1)
+    }
+
+    /// Creates a time from a GPS week, counted from 1980-01-06 without the rollovers of the
+    /// broadcast 10-bit week, and seconds into the week, with the leap seconds of its date from
+    /// the table of [`eop::set_leap_seconds`].
+    pub fn from_gps_week_seconds(week: u32, seconds: f64, dut1: f64) -> Result<Self> {
+        if !(0.0..SECONDS_PER_WEEK).contains(&seconds) {
+            return Err(Error::InvalidArgument("GPS seconds of week out of range"));
+        }
+        let ijd = JD_GPS_EPOCH + 7 * i64::from(week);
+        let fjd = 0.5 + seconds / 86400.0;
+        // GPS is TAI - 19 s
+        let leap_seconds = leap_seconds_at_tai(ijd as f64 + fjd - MJD0 + GPS_TO_TAI / 86400.0)?;
+        let mut raw = sn::novas_timespec::default();
+        check("novas_set_split_time", || unsafe {
+            sn::novas_set_split_time(sn::novas_timescale_NOVAS_GPS, ijd as _, fjd, leap_seconds, dut1, &mut raw)
+        })?;
+        Ok(Time { raw })
+    }
+
+    /// GPS week, counted from 1980-01-06 without rollovers, and seconds into the week. Fails
+    /// before the GPS epoch.
+    pub fn gps_week_seconds(&self) -> Result<(u32, f64)> {
+        let (ijd, fjd) = self.split(Timescale::Gps);
+        // Days and fraction since the midnight of the GPS epoch
+        let days = ijd - JD_GPS_EPOCH;
+        let whole = (fjd - 0.5).floor();
+        let days = days + whole as i64;
+        if days < 0 {
+            return Err(Error::InvalidArgument("date precedes the GPS epoch"));
+        }
+        let week = u32::try_from(days / 7).map_err(|_| Error::InvalidArgument("GPS week out of range"))?;
+        Ok((week, (days % 7) as f64 * 86400.0 + (fjd - 0.5 - whole) * 86400.0))
+    }
+
+    /// Creates a time from a Julian date in the given timescale, with UT1 from the ΔT of
+    /// [`delta_t`](super::delta_t) at its date, for dates without a measured UT1 - UTC: in the
+    /// past, or in the future beyond the IERS predictions. The leap seconds are those of the
+    /// table in use over its dates, and elsewhere keep UTC within half a second of UT1, so that
+    /// UTC stands for the civil time of historical dates.
+    pub fn from_delta_t(scale: Timescale, jd: f64) -> Result<Self> {
+        let delta_t = delta_t(Epoch::from_jd(jd));
+        let table = eop::leap_seconds();
+        let mjd = jd - MJD0;
+        let leap_seconds = match table.at(mjd) {
+            Some(leap) if !table.is_stale(mjd) => leap,
+            _ => (delta_t - TT_MINUS_TAI).round() as i32,
+        };
+        Time::new(scale, jd, leap_seconds, leap_seconds as f64 + TT_MINUS_TAI - delta_t)
+    }
+
+    /// Creates a time from a UNIX timestamp (UTC seconds and nanoseconds).
+    pub fn from_unix(seconds: i64, nanos: i32, leap_seconds: i32, dut1: f64) -> Result<Self> {
+        let mut raw = sn::novas_timespec::default();
+        check("novas_set_unix_time", || unsafe {
+            sn::novas_set_unix_time(seconds as _, nanos as _, leap_seconds, dut1, &mut raw)
+        })?;
+        Ok(Time { raw })
+    }
+
+    /// Parses a date such as "2025-06-24T12:29:36Z" or "2025-06-24 12:29", given in the timescale
+    /// `scale`. A time zone in the string, if any, is applied.
+    pub fn parse(date: &str, scale: Timescale, leap_seconds: i32, dut1: f64) -> Result<Self> {
+        let date = CString::new(date).map_err(|_| Error::InvalidArgument("date contains NUL"))?;
+        let jd = unsafe { sn::novas_date(date.as_ptr()) };
+        if jd.is_nan() {
+            return Err(Error::InvalidArgument("unrecognized date"));
+        }
+        Self::new(scale, jd, leap_seconds, dut1)
+    }
+
+    /// Wraps an already initialized `novas_timespec`.
+    pub fn from_raw(raw: sn::novas_timespec) -> Self {
+        Time { raw }
+    }
+
+    pub fn as_raw(&self) -> &sn::novas_timespec {
+        &self.raw
+    }
+
+    /// Returns the Julian date of this instant in the given timescale.
+    pub fn jd(&self, scale: Timescale) -> f64 {
+        unsafe { sn::novas_get_time(&self.raw, scale.to_raw()) }
+    }
+
+    /// Returns this instant shifted by the given number of seconds.
+    pub fn offset(&self, seconds: f64) -> Result<Self> {
+        let mut raw = sn::novas_timespec::default();
+        check("novas_offset_time", || unsafe {
+            sn::novas_offset_time(&self.raw, seconds, &mut raw)
+        })?;
+        Ok(Time { raw })
+    }
+
+    /// Calendar date of this instant in the given timescale.
+    pub fn date(&self, scale: Timescale, calendar: Calendar) -> CalendarDate {
+        JulianDate(self.jd(scale)).to_date(calendar)
+    }
+
+    /// ISO 8601 UTC timestamp with millisecond precision, e.g. "2025-01-26T21:32:49.701Z".
+    pub fn iso_timestamp(&self) -> String {
+        self.iso_timestamp_with_decimals(3)
+    }
+
+    /// ISO 8601 UTC timestamp with `decimals` (up to 9) digits of the seconds, e.g.
+    /// "2025-01-26T21:32:49.701234Z" with 6. The date is in the proleptic Gregorian calendar.
+    pub fn iso_timestamp_with_decimals(&self, decimals: usize) -> String {
+        let (ijd, fjd) = self.split(Timescale::Utc);
+        format!("{}Z", timestamp(ijd, fjd, Calendar::Gregorian, decimals))
+    }
+
+    /// Timestamp in the given timescale, e.g. "2025-01-26T21:33:58.885 TT".
+    pub fn timestamp(&self, scale: Timescale) -> String {
+        self.timestamp_with_decimals(scale, 3)
+    }
+
+    /// Timestamp in the given timescale with `decimals` (up to 9) digits of the seconds, e.g.
+    /// "2025-01-26T21:33:58.885234 TT" with 6. Unlike ISO 8601 timestamps, dates before the
+    /// Gregorian calendar reform of 1582 are in the Julian calendar.
+    pub fn timestamp_with_decimals(&self, scale: Timescale, decimals: usize) -> String {
+        let (ijd, fjd) = self.split(scale);
+        format!("{} {}", timestamp(ijd, fjd, Calendar::Astronomical, decimals), scale)
+    }
+
+    // Integer and fractional parts of the Julian date in the given timescale, without the
+    // rounding of the full date to a double.
+    pub(crate) fn split(&self, scale: Timescale) -> (i64, f64) {
+        let mut ijd = 0;
+        let fjd = unsafe { sn::novas_get_split_time(&self.raw, scale.to_raw(), &mut ijd) };
+        (ijd as _, fjd)
+    }
+
+    /// UNIX time as seconds and nanoseconds.
+    pub fn unix(&self) -> (i64, i32) {
+        let mut nanos = 0;
+        let seconds = unsafe { sn::novas_get_unix_time(&self.raw, &mut nanos) };
+        (seconds as i64, nanos as i32)
+    }
+
+    /// Returns `self - other` in seconds of TT.
+    pub fn diff(&self, other: &Time) -> f64 {
+        unsafe { sn::novas_diff_time(&self.raw, &other.raw) }
+    }
+
+    /// TAI - UTC [s] this time was created with.
+    pub fn leap_seconds(&self) -> i32 {
+        (self.raw.ut1_to_tt + self.raw.dut1 - TT_MINUS_TAI).round() as i32
+    }
+
+    /// UT1 - UTC [s] this time was created with.
+    pub fn dut1(&self) -> f64 {
+        self.raw.dut1
+    }
+
+    /// The same instant with other leap seconds (TAI - UTC) and UT1 - UTC [s], e.g. those of its
+    /// date when stepping across a leap second.
+    pub fn with_earth_orientation(&self, leap_seconds: i32, dut1: f64) -> Result<Self> {
+        let mut raw = sn::novas_timespec::default();
+        check("novas_set_split_time", || unsafe {
+            sn::novas_set_split_time(sn::novas_timescale_NOVAS_TT, self.raw.ijd_tt, self.raw.fjd_tt, leap_seconds, dut1, &mut raw)
+        })?;
+        Ok(Time { raw })
+    }
+
+    /// Offsets between the timescales applied at this instant. TT - TDB is that of the
+    /// SuperNOVAS series; see [`crate::novas::ephemeris::Calceph::time_offsets`] for the value of an
+    /// ephemeris.
+    pub fn offsets(&self) -> TimeOffsets {
+        TimeOffsets {
+            tai_minus_utc: self.leap_seconds() as f64,
+            tt_minus_tai: TT_MINUS_TAI,
+            tt_minus_tdb: -self.raw.tt2tdb,
+            ut1_minus_utc: self.raw.dut1,
+            tt_minus_ut1: self.raw.ut1_to_tt,
+            tdb_from_ephemeris: false,
+        }
+    }
+}
+
+// Conversions from calendar types: UTC instants, with the leap seconds of their date from the
+// table of `eop::set_leap_seconds` and UT1 = UTC. They fail before 1972, when UTC had no leap
+// seconds.
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl Time {
+    fn from_utc_unix(seconds: i64, nanos: i32) -> Result<Self> {
+        let mjd = UNIX_EPOCH_MJD + seconds.div_euclid(86400) as f64;
+        let leap_seconds = eop::leap_seconds().at(mjd).ok_or(Error::InvalidArgument("date precedes the leap-second table"))?;
+        Time::from_unix(seconds, nanos, leap_seconds, 0.0)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::DateTime<chrono::Utc>> for Time {
+    type Error = Error;
+
+    fn try_from(date: chrono::DateTime<chrono::Utc>) -> Result<Self> {
+        Time::from_utc_unix(date.timestamp(), date.timestamp_subsec_nanos() as i32)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<Time> for chrono::DateTime<chrono::Utc> {
+    type Error = Error;
+
+    fn try_from(time: Time) -> Result<Self> {
+        let (seconds, nanos) = time.unix();
+        chrono::DateTime::from_timestamp(seconds, nanos as u32).ok_or(Error::InvalidArgument("time out of the range of chrono"))
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<::time::OffsetDateTime> for Time {
+    type Error = Error;
+
+    fn try_from(date: ::time::OffsetDateTime) -> Result<Self> {
+        Time::from_utc_unix(date.unix_timestamp(), date.nanosecond() as i32)
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Time> for ::time::OffsetDateTime {
+    type Error = Error;
+
+    fn try_from(time: Time) -> Result<Self> {
+        let (seconds, nanos) = time.unix();
+        ::time::OffsetDateTime::from_unix_timestamp_nanos(seconds as i128 * 1_000_000_000 + nanos as i128)
+            .map_err(|_| Error::InvalidArgument("time out of the range of the time crate"))
+    }
+}
+
+// hifitime epochs are durations of TAI since 1900-01-01T00:00:00 TAI, converted to the split TT
+// Julian date of the timespec to the nanosecond.
+#[cfg(feature = "hifitime")]
+impl TryFrom<hifitime::Epoch> for Time {
+    type Error = Error;
+
+    /// The epoch, with the leap seconds of its date from hifitime and UT1 = UTC. Fails before 1972.
+    fn try_from(epoch: hifitime::Epoch) -> Result<Self> {
+        let leap_seconds = epoch.leap_seconds(true).ok_or(Error::InvalidArgument("date precedes the leap-second table"))?;
+        let tt = epoch.to_tai_duration().total_nanoseconds() + (TT_MINUS_TAI * 1e9) as i128;
+        let (days, nanos) = (tt.div_euclid(NANOS_PER_DAY), tt.rem_euclid(NANOS_PER_DAY));
+        let mut raw = sn::novas_timespec::default();
+        check("novas_set_split_time", || unsafe {
+            sn::novas_set_split_time(
+                sn::novas_timescale_NOVAS_TT,
+                (JD_1900 + days as i64) as _,
+                0.5 + nanos as f64 / NANOS_PER_DAY as f64,
+                leap_seconds as i32,
+                0.0,
+                &mut raw,
+            )
+        })?;
+        Ok(Time { raw })
+    }
+}
+
+#[cfg(feature = "hifitime")]
+impl From<Time> for hifitime::Epoch {
+    fn from(time: Time) -> Self {
+        let days = time.raw.ijd_tt as i128 - JD_1900 as i128;
+        let nanos = ((time.raw.fjd_tt - 0.5) * NANOS_PER_DAY as f64).round() as i128;
+        let tai = days * NANOS_PER_DAY + nanos - (TT_MINUS_TAI * 1e9) as i128;
+        hifitime::Epoch::from_tai_duration(hifitime::Duration::from_total_nanoseconds(tai))
+    }
+}
+
+/// [day] Integer part of the Julian date of 1900-01-01T00:00, the reference of hifitime.
+#[cfg(feature = "hifitime")]
+const JD_1900: i64 = 2415020;
+
+#[cfg(feature = "hifitime")]
+const NANOS_PER_DAY: i128 = 86_400_000_000_000;
+
+/// [day] Modified Julian date of the UNIX epoch.
+#[cfg(any(feature = "chrono", feature = "time"))]
+const UNIX_EPOCH_MJD: f64 = 40587.0;
+
+/// [day] Integer part of the Julian date of the GPS epoch, 1980-01-06T00:00 UTC.
+const JD_GPS_EPOCH: i64 = 2444244;
+
+/// [s] TAI - GPS, fixed since the GPS epoch.
+const GPS_TO_TAI: f64 = 19.0;
+
+const SECONDS_PER_WEEK: f64 = 7.0 * 86400.0;
+
+// TAI - UTC [s] at a TAI MJD, from the table of `eop::set_leap_seconds`.
+fn leap_seconds_at_tai(mjd_tai: f64) -> Result<i32> {
+    let table = eop::leap_seconds();
+    // TAI runs ahead of UTC, so the leap seconds at the TAI date are an upper bound
+    let mut leap = table.at(mjd_tai).ok_or(Error::InvalidArgument("date precedes the leap-second table"))?;
+    for _ in 0..2 {
+        leap = table.at(mjd_tai - leap as f64 / 86400.0).unwrap_or(leap);
+    }
+    Ok(leap)
+}
+
+/// [s] TT - TAI, fixed by definition.
+pub(crate) const TT_MINUS_TAI: f64 = 32.184;
+
+/// Offsets [s] between timescales at an instant, for auditing the conversions of a [`Time`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeOffsets {
+    /// TAI - UTC, the accumulated leap seconds
+    pub tai_minus_utc: f64,
+    /// TT - TAI, 32.184 s
+    pub tt_minus_tai: f64,
+    /// TT - TDB, periodic with an amplitude of about 1.7 ms
+    pub tt_minus_tdb: f64,
+    /// UT1 - UTC
+    pub ut1_minus_utc: f64,
+    /// TT - UT1
+    pub tt_minus_ut1: f64,
+    /// Whether TT - TDB was read from an ephemeris rather than computed from a series
+    pub tdb_from_ephemeris: bool,
+}
+
+impl TimeOffsets {
+    /// TT - UTC.
+    pub fn tt_minus_utc(&self) -> f64 {
+        self.tt_minus_tai + self.tai_minus_utc
+    }
+}
+
+/// Evenly spaced instants from a start time up to a stop time, for ephemeris tables and other
+/// batch calculations.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeRange {
+    start: Time,
+    /// [s] TT between samples
+    step: f64,
+    len: usize,
+}
+
+impl TimeRange {
+    /// Samples every `step` seconds of `scale` from `start`, up to and including `stop` (to
+    /// within a millisecond).
+    pub fn with_step(start: &Time, stop: &Time, step: f64, scale: Timescale) -> Result<Self> {
+        let span = stop.diff(start);
+        if !step.is_finite() || step <= 0.0 {
+            return Err(Error::InvalidArgument("step must be positive"));
+        }
+        if span.is_nan() || span < 0.0 {
+            return Err(Error::InvalidArgument("stop time before start time"));
+        }
+        let step = step * scale.tt_rate();
+        // Allow for rounding in the time difference, so that the stop time itself is included
+        let len = ((span + 1e-3) / step).floor() as usize + 1;
+        Ok(TimeRange { start: *start, step, len })
+    }
+
+    /// `count` samples, the first at `start` and the last at `stop`.
+    pub fn with_count(start: &Time, stop: &Time, count: usize) -> Result<Self> {
+        let span = stop.diff(start);
+        if count == 0 {
+            return Err(Error::InvalidArgument("no samples"));
+        }
+        if span.is_nan() || span < 0.0 {
+            return Err(Error::InvalidArgument("stop time before start time"));
+        }
+        let step = if count > 1 { span / (count - 1) as f64 } else { 0.0 };
+        Ok(TimeRange { start: *start, step, len: count })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// [s] Interval between samples, in TT.
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    /// The `i`-th sample, if in range.
+    pub fn get(&self, i: usize) -> Option<Result<Time>> {
+        (i < self.len).then(|| self.start.offset(i as f64 * self.step))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Result<Time>> + '_ {
+        (0..self.len).map(|i| self.start.offset(i as f64 * self.step))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2025-06-24T12:29:36Z
+    const UNIX: i64 = 1750768176;
+
+    fn time() -> Time {
+        Time::parse("2025-06-24T12:29:36Z", Timescale::Utc, 37, 0.035).unwrap()
+    }
+
+    #[test]
+    fn timescales_by_name() {
+        for scale in [Timescale::Tcb, Timescale::Tdb, Timescale::Tcg, Timescale::Tt, Timescale::Tai, Timescale::Gps, Timescale::Utc, Timescale::Ut1] {
+            assert_eq!(scale.to_string().parse::<Timescale>().unwrap(), scale);
+        }
+        assert_eq!(" tdt ".parse::<Timescale>().unwrap(), Timescale::Tt);
+        assert!("GMT".parse::<Timescale>().is_err());
+    }
+
+    #[test]
+    fn offsets_between_timescales() {
+        let time = time();
+        let seconds = |scale| (time.jd(scale) - time.jd(Timescale::Utc)) * 86400.0;
+        assert!((seconds(Timescale::Tai) - 37.0).abs() < 1e-4);
+        assert!((seconds(Timescale::Tt) - 69.184).abs() < 1e-4);
+        assert!((seconds(Timescale::Gps) - 18.0).abs() < 1e-4);
+        assert!((seconds(Timescale::Ut1) - 0.035).abs() < 1e-4);
+        assert!(seconds(Timescale::Tdb).abs() - 69.184 < 2e-3);
+        let offsets = time.offsets();
+        assert_eq!(offsets.tai_minus_utc, 37.0);
+        assert!((offsets.tt_minus_utc() - 69.184).abs() < 1e-12);
+        assert!((offsets.tt_minus_ut1 - 69.149).abs() < 1e-9);
+        assert_eq!((time.leap_seconds(), time.dut1()), (37, 0.035));
+    }
+
+    #[test]
+    fn unix_and_iso_timestamps() {
+        let time = time();
+        // To the precision of the Julian date that the date parses to
+        let (seconds, nanos) = time.unix();
+        assert!(((seconds - UNIX) as f64 + nanos as f64 * 1e-9).abs() < 1e-5);
+        assert_eq!(time.iso_timestamp(), "2025-06-24T12:29:36.000Z");
+        assert_eq!(time.timestamp(Timescale::Tt), "2025-06-24T12:30:45.184 TT");
+        let time = Time::from_unix(UNIX, 123_456_789, 37, 0.0).unwrap();
+        assert_eq!(time.iso_timestamp_with_decimals(6), "2025-06-24T12:29:36.123457Z");
+        let (seconds, nanos) = time.unix();
+        assert_eq!(seconds, UNIX);
+        assert!((nanos - 123_456_789).abs() < 1000);
+        assert!(Time::parse("tomorrow", Timescale::Utc, 37, 0.0).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_date_times() {
+        let date = chrono::DateTime::from_timestamp(UNIX, 250_000_000).unwrap();
+        let time = Time::try_from(date).unwrap();
+        assert_eq!(time.leap_seconds(), 37);
+        assert_eq!(time.iso_timestamp(), "2025-06-24T12:29:36.250Z");
+        let back = chrono::DateTime::<chrono::Utc>::try_from(time).unwrap();
+        assert_eq!(back.timestamp(), UNIX);
+        assert!((back.timestamp_subsec_nanos() as i64 - 250_000_000).abs() < 1000);
+        // 1970, before leap seconds
+        assert!(Time::try_from(chrono::DateTime::from_timestamp(0, 0).unwrap()).is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_crate_date_times() {
+        let date = ::time::OffsetDateTime::from_unix_timestamp_nanos(UNIX as i128 * 1_000_000_000 + 250_000_000).unwrap();
+        let time = Time::try_from(date).unwrap();
+        assert_eq!(time.iso_timestamp(), "2025-06-24T12:29:36.250Z");
+        let back = ::time::OffsetDateTime::try_from(time).unwrap();
+        assert_eq!(back.unix_timestamp(), UNIX);
+        assert!((back.nanosecond() as i64 - 250_000_000).abs() < 1000);
+        assert!(Time::try_from(::time::OffsetDateTime::UNIX_EPOCH).is_err());
+    }
+
+    #[cfg(feature = "hifitime")]
+    #[test]
+    fn hifitime_epochs_to_the_nanosecond() {
+        // TAI since 1900-01-01T00:00 TAI of 2025-06-24T12:29:36.000000001 UTC
+        let tai = (UNIX as i128 + 2_208_988_800 + 37) * 1_000_000_000 + 1;
+        let epoch = hifitime::Epoch::from_tai_duration(hifitime::Duration::from_total_nanoseconds(tai));
+        let time = Time::try_from(epoch).unwrap();
+        assert_eq!((time.leap_seconds(), time.dut1()), (37, 0.0));
+        assert!(time.diff(&Time::from_unix(UNIX, 1, 37, 0.0).unwrap()).abs() < 1e-6);
+        assert_eq!(hifitime::Epoch::from(time), epoch);
+    }
+
+    #[test]
+    fn gps_weeks() {
+        let (week, seconds) = time().gps_week_seconds().unwrap();
+        assert_eq!(week, 2372);
+        assert!((seconds - 217794.0).abs() < 1e-4);
+        let back = Time::from_gps_week_seconds(week, seconds, 0.035).unwrap();
+        assert!(back.diff(&time()).abs() < 1e-4);
+        assert!(Time::from_gps_week_seconds(week, 604800.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn offsets_and_differences() {
+        let time = time();
+        let later = time.offset(3600.5).unwrap();
+        assert!((later.diff(&time) - 3600.5).abs() < 1e-6);
+        assert_eq!(later.iso_timestamp(), "2025-06-24T13:29:36.500Z");
+        // Same instant, UTC stepped by a leap second
+        let stepped = time.with_earth_orientation(38, 0.0).unwrap();
+        assert_eq!(stepped.diff(&time), 0.0);
+        assert_eq!(stepped.iso_timestamp(), "2025-06-24T12:29:35.000Z");
+    }
+
+    #[test]
+    fn delta_t_sets_ut1_of_historical_dates() {
+        // 1000-01-01, before UTC
+        let time = Time::from_delta_t(Timescale::Tt, 2086308.0).unwrap();
+        let delta_t = (time.jd(Timescale::Tt) - time.jd(Timescale::Ut1)) * 86400.0;
+        assert!((delta_t - super::delta_t(Epoch::from_jd(2086308.0))).abs() < 1e-3);
+        assert!((time.jd(Timescale::Utc) - time.jd(Timescale::Ut1)).abs() * 86400.0 <= 0.5);
+    }
+
+    #[test]
+    fn ranges() {
+        let start = time();
+        let stop = start.offset(3600.0).unwrap();
+        let range = TimeRange::with_step(&start, &stop, 600.0, Timescale::Tt).unwrap();
+        assert_eq!(range.len(), 7);
+        assert!((range.get(6).unwrap().unwrap().diff(&stop)).abs() < 1e-6);
+        assert!(range.get(7).is_none());
+        let range = TimeRange::with_count(&start, &stop, 5).unwrap();
+        assert!((range.step() - 900.0).abs() < 1e-6);
+        assert_eq!(range.iter().count(), 5);
+        assert!(TimeRange::with_step(&stop, &start, 600.0, Timescale::Tt).is_err());
+        assert!(TimeRange::with_step(&start, &stop, 0.0, Timescale::Tt).is_err());
+        assert!(TimeRange::with_count(&start, &stop, 0).is_err());
+    }
+}
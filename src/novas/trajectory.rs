@@ -0,0 +1,109 @@
+use super::error::Result;
+use super::frame::{Accuracy, ObservingFrame, ReferenceSystem, SkyPos};
+use super::observer::Observer;
+use super::source::Source;
+use super::time::{Time, TimeRange};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// One time-tagged observer state along a trajectory.
+#[derive(Debug, Clone, Copy)]
+pub struct TrajectoryPoint {
+    pub time: Time,
+    pub observer: Observer,
+}
+
+/// A time-tagged observer trajectory, e.g. an aircraft flight path or a spacecraft orbit.
+///
+/// Each point carries its own observer state, so airborne (surface-relative), Earth-orbiting,
+/// and Solar-system observers can all be described.
+#[derive(Debug, Clone, Default)]
+pub struct Trajectory {
+    points: Vec<TrajectoryPoint>,
+    accuracy: Accuracy,
+    dx: f64,
+    dy: f64,
+}
+
+impl Trajectory {
+    pub fn new(points: Vec<TrajectoryPoint>) -> Self {
+        Trajectory {
+            points,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the accuracy used for the frames along the trajectory.
+    pub fn with_accuracy(mut self, accuracy: Accuracy) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    /// Sets the polar offsets [mas] used for the frames along the trajectory.
+    pub fn with_polar_offsets(mut self, dx: f64, dy: f64) -> Self {
+        self.dx = dx;
+        self.dy = dy;
+        self
+    }
+
+    /// A fixed observer at the times of `range`.
+    pub fn sampled(range: &TimeRange, observer: Observer) -> Result<Self> {
+        let points = range
+            .iter()
+            .map(|time| Ok(TrajectoryPoint { time: time?, observer }))
+            .collect::<Result<_>>()?;
+        Ok(Trajectory::new(points))
+    }
+
+    pub fn push(&mut self, time: Time, observer: Observer) {
+        self.points.push(TrajectoryPoint { time, observer });
+    }
+
+    pub fn points(&self) -> &[TrajectoryPoint] {
+        &self.points
+    }
+
+    /// Calculates the apparent position of `source` at every point of the trajectory.
+    ///
+    /// The returned positions are in the same order as the trajectory points. With the `rayon`
+    /// feature, the points are computed in parallel.
+    pub fn sky_positions(&self, source: &Source, system: ReferenceSystem) -> Result<Vec<SkyPos>> {
+        #[cfg(not(feature = "rayon"))]
+        let points = self.points.iter();
+        #[cfg(feature = "rayon")]
+        let points = self.points.par_iter();
+        points
+            .map(|p| {
+                ObservingFrame::new(self.accuracy, &p.observer, &p.time, self.dx, self.dy)?
+                    .sky_pos(source, system)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::time::Timescale;
+
+    #[test]
+    fn positions_along_the_trajectory() {
+        let start = Time::new(Timescale::Utc, 2460850.5, 37, 0.035).unwrap();
+        let range = TimeRange::with_count(&start, &start.offset(7200.0).unwrap(), 3).unwrap();
+        let site = Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap();
+        let mut trajectory = Trajectory::sampled(&range, site).unwrap().with_accuracy(Accuracy::Reduced);
+        let flight = Observer::airborne(43.0, 87.0, 10000.0, [0.2, 0.1, 0.0]).unwrap();
+        trajectory.push(start.offset(10800.0).unwrap(), flight);
+        assert_eq!(trajectory.points().len(), 4);
+
+        let vega = Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap();
+        let positions = trajectory.sky_positions(&vega, ReferenceSystem::Tod).unwrap();
+        assert_eq!(positions.len(), 4);
+        for (point, pos) in trajectory.points().iter().zip(&positions) {
+            let frame = ObservingFrame::new(Accuracy::Reduced, &point.observer, &point.time, 0.0, 0.0).unwrap();
+            assert_eq!(*pos, frame.sky_pos(&vega, ReferenceSystem::Tod).unwrap());
+        }
+        assert!(Trajectory::default().sky_positions(&vega, ReferenceSystem::Tod).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use super::error::{Error, Result};
+use super::frame::{ObservingFrame, ReferenceSystem, SkyPos};
+use super::source::{Source, SourceKind};
+use supernovas_sys as sn;
+
+/// [mas] Milliarcseconds per degree.
+const MAS: f64 = 3.6e6;
+
+/// Independent 1-sigma uncertainties of catalog astrometry.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct AstrometricErrors {
+    /// [mas] uncertainty of the right ascension, including the cos(dec) factor
+    pub ra: f64,
+    /// [mas] uncertainty of the declination
+    pub dec: f64,
+    /// [mas/yr] uncertainty of the proper motion in right ascension
+    pub pm_ra: f64,
+    /// [mas/yr] uncertainty of the proper motion in declination
+    pub pm_dec: f64,
+    /// [mas] uncertainty of the parallax
+    pub parallax: f64,
+}
+
+/// 1-sigma error ellipse of a position on the sky.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErrorEllipse {
+    /// [mas] semi-major axis
+    pub semi_major: f64,
+    /// [mas] semi-minor axis
+    pub semi_minor: f64,
+    /// [deg] position angle of the major axis, from North through East
+    pub position_angle: f64,
+}
+
+impl ErrorEllipse {
+    // Principal axes of the covariance [mas^2] of East and North offsets.
+    fn from_covariance(ee: f64, nn: f64, en: f64) -> Self {
+        let mean = 0.5 * (ee + nn);
+        let radius = (0.25 * (ee - nn).powi(2) + en * en).sqrt();
+        ErrorEllipse {
+            semi_major: (mean + radius).sqrt(),
+            semi_minor: (mean - radius).max(0.0).sqrt(),
+            position_angle: (0.5 * (2.0 * en).atan2(nn - ee).to_degrees()).rem_euclid(180.0),
+        }
+    }
+}
+
+impl ObservingFrame {
+    /// Apparent position of a catalog source, with the error ellipse propagated from its catalog
+    /// uncertainties. The propagation is linearized, by offsetting the catalog data of the source
+    /// by each uncertainty in turn.
+    pub fn sky_pos_with_errors(
+        &self,
+        source: &Source,
+        errors: &AstrometricErrors,
+        system: ReferenceSystem,
+    ) -> Result<(SkyPos, ErrorEllipse)> {
+        if source.kind() != SourceKind::Catalog {
+            return Err(Error::InvalidArgument("error propagation requires a catalog source"));
+        }
+        let pos = self.sky_pos(source, system)?;
+        let cos_dec = source.as_raw().star.dec.to_radians().cos();
+
+        type Offset = fn(&mut sn::cat_entry, f64);
+        let perturbations: [(f64, Offset); 5] = [
+            (errors.ra / cos_dec / MAS / 15.0, |star, d| star.ra += d),
+            (errors.dec / MAS, |star, d| star.dec += d),
+            (errors.pm_ra, |star, d| star.promora += d),
+            (errors.pm_dec, |star, d| star.promodec += d),
+            (errors.parallax, |star, d| star.parallax += d),
+        ];
+        let (mut ee, mut nn, mut en) = (0.0, 0.0, 0.0);
+        for (delta, apply) in perturbations {
+            if delta == 0.0 {
+                continue;
+            }
+            let mut raw = *source.as_raw();
+            apply(&mut raw.star, delta);
+            let moved = self.sky_pos(&Source::from_raw(raw), system)?;
+            // [mas] offsets of the apparent position, East and North
+            let dra = (moved.ra - pos.ra + 12.0).rem_euclid(24.0) - 12.0;
+            let east = dra * 15.0 * pos.dec.to_radians().cos() * MAS;
+            let north = (moved.dec - pos.dec) * MAS;
+            ee += east * east;
+            nn += north * north;
+            en += east * north;
+        }
+        Ok((pos, ErrorEllipse::from_covariance(ee, nn, en)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::frame::Accuracy;
+    use crate::novas::observer::Observer;
+    use crate::novas::source::Planet;
+    use crate::novas::time::{Time, Timescale};
+
+    fn frame() -> ObservingFrame {
+        let observer = Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap();
+        let time = Time::new(Timescale::Utc, 2460850.5, 37, 0.035).unwrap();
+        ObservingFrame::new(Accuracy::Reduced, &observer, &time, 0.0, 0.0).unwrap()
+    }
+
+    fn vega() -> Source {
+        Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap()
+    }
+
+    #[test]
+    fn ellipses_of_covariances() {
+        let circle = ErrorEllipse::from_covariance(4.0, 4.0, 0.0);
+        assert_eq!((circle.semi_major, circle.semi_minor), (2.0, 2.0));
+        let tilted = ErrorEllipse::from_covariance(1.0, 1.0, 0.5);
+        assert!((tilted.semi_major - 1.5f64.sqrt()).abs() < 1e-12);
+        assert!((tilted.semi_minor - 0.5f64.sqrt()).abs() < 1e-12);
+        assert!((tilted.position_angle - 45.0).abs() < 1e-12);
+        assert!((ErrorEllipse::from_covariance(9.0, 1.0, 0.0).position_angle - 90.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn errors_are_propagated() {
+        let frame = frame();
+        let ra = AstrometricErrors { ra: 10.0, ..Default::default() };
+        let (pos, ellipse) = frame.sky_pos_with_errors(&vega(), &ra, ReferenceSystem::Icrs).unwrap();
+        assert_eq!(pos, frame.sky_pos(&vega(), ReferenceSystem::Icrs).unwrap());
+        assert!((ellipse.semi_major - 10.0).abs() < 0.01, "{ellipse:?}");
+        assert!(ellipse.semi_minor < 0.01);
+        assert!((ellipse.position_angle - 90.0).abs() < 0.1);
+        let dec = AstrometricErrors { dec: 5.0, ..Default::default() };
+        let (_, ellipse) = frame.sky_pos_with_errors(&vega(), &dec, ReferenceSystem::Icrs).unwrap();
+        assert!((ellipse.semi_major - 5.0).abs() < 0.01);
+        assert!(ellipse.position_angle < 0.1 || ellipse.position_angle > 179.9);
+        let none = frame.sky_pos_with_errors(&vega(), &AstrometricErrors::default(), ReferenceSystem::Icrs).unwrap().1;
+        assert_eq!((none.semi_major, none.semi_minor), (0.0, 0.0));
+        assert!(frame.sky_pos_with_errors(&Source::planet(Planet::Sun).unwrap(), &ra, ReferenceSystem::Icrs).is_err());
+    }
+}
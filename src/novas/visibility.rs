@@ -0,0 +1,167 @@
+use super::error::Result;
+use super::frame::{Accuracy, ObservingFrame, ReferenceSystem};
+use super::observer::Observer;
+use super::refraction::Refraction;
+use super::source::Source;
+use super::time::Time;
+
+/// [s] Searches that find no crossing resume a day later.
+const DAY: f64 = 86400.0;
+
+/// Finds when sources rise above, set below and transit at an elevation limit, as seen by an
+/// observer in a time window. Times outside the window are reported as `None`.
+///
+/// ```no_run
+/// # use astrokits::novas::*;
+/// # fn main() -> Result<()> {
+/// let observer = Observer::on_surface(43.4712, 87.1781, 2080.0)?;
+/// let start = Time::from_utc(2460850.5, 0.0)?;
+/// let planner = VisibilityPlanner::new(&observer, 30.0, Refraction::Standard, &start, &start.offset(7.0 * 86400.0)?);
+/// let vega = Source::star("Vega", 18.6156, 38.7836, "ICRS")?;
+/// if let Some(time) = planner.next_visible(&vega)? {
+///     println!("Vega is above 30° from {}", time.iso_timestamp());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct VisibilityPlanner {
+    observer: Observer,
+    accuracy: Accuracy,
+    dx: f64,
+    dy: f64,
+    elevation: f64,
+    refraction: Refraction,
+    start: Time,
+    end: Time,
+}
+
+impl VisibilityPlanner {
+    /// A planner for `observer`, from `start` to `end`, with a limit at `elevation` degrees of
+    /// elevation after `refraction`.
+    pub fn new(observer: &Observer, elevation: f64, refraction: impl Into<Refraction>, start: &Time, end: &Time) -> Self {
+        VisibilityPlanner {
+            observer: *observer,
+            accuracy: Accuracy::default(),
+            dx: 0.0,
+            dy: 0.0,
+            elevation,
+            refraction: refraction.into(),
+            start: *start,
+            end: *end,
+        }
+    }
+
+    /// Sets the accuracy of the position calculations.
+    pub fn with_accuracy(mut self, accuracy: Accuracy) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    /// Sets the polar offsets [mas] used for the frames.
+    pub fn with_polar_offsets(mut self, dx: f64, dy: f64) -> Self {
+        self.dx = dx;
+        self.dy = dy;
+        self
+    }
+
+    /// [deg] The elevation limit.
+    pub fn elevation(&self) -> f64 {
+        self.elevation
+    }
+
+    /// First time in the window at which the source rises above the limit.
+    pub fn next_rise(&self, source: &Source) -> Result<Option<Time>> {
+        self.search(|frame| Ok(frame.rises_above(self.elevation, source, self.refraction.clone())))
+    }
+
+    /// First time in the window at which the source sets below the limit.
+    pub fn next_set(&self, source: &Source) -> Result<Option<Time>> {
+        self.search(|frame| Ok(frame.sets_below(self.elevation, source, self.refraction.clone())))
+    }
+
+    /// First time in the window at which the source transits the local meridian, whatever its
+    /// elevation.
+    pub fn next_transit(&self, source: &Source) -> Result<Option<Time>> {
+        self.search(|frame| Ok(frame.transit_time(source)))
+    }
+
+    /// First time in the window at which the source is above the limit: the start of the window
+    /// if it is already up, or else its next rise.
+    pub fn next_visible(&self, source: &Source) -> Result<Option<Time>> {
+        if self.is_visible(source, &self.start)? {
+            return Ok(Some(self.start));
+        }
+        self.next_rise(source)
+    }
+
+    /// Whether the source is above the limit at `time`.
+    pub fn is_visible(&self, source: &Source, time: &Time) -> Result<bool> {
+        let frame = self.frame(time)?;
+        let pos = frame.sky_pos(source, ReferenceSystem::Tod)?;
+        Ok(frame.to_horizontal(&pos, ReferenceSystem::Tod, self.refraction.clone())?.el > self.elevation)
+    }
+
+    fn frame(&self, time: &Time) -> Result<ObservingFrame> {
+        ObservingFrame::new(self.accuracy, &self.observer, time, self.dx, self.dy)
+    }
+
+    // Runs a search for the next crossing, within a day of the frame time, one day after the other
+    // until one is found or the window ends.
+    fn search(&self, find: impl Fn(&ObservingFrame) -> Result<Option<Time>>) -> Result<Option<Time>> {
+        let mut from = self.start;
+        while from.diff(&self.end) <= 0.0 {
+            if let Some(time) = find(&self.frame(&from)?)? {
+                return Ok((time.diff(&self.end) <= 0.0).then_some(time));
+            }
+            from = from.offset(DAY)?;
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::novas::Timescale;
+
+    fn vega() -> Source {
+        Source::star("Vega", 18.6156, 38.7836, "ICRS").unwrap()
+    }
+
+    fn planner(elevation: f64, days: f64) -> VisibilityPlanner {
+        let observer = Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap();
+        // 2025-06-24 00:00 UTC, when Vega is 32 deg up in the west
+        let start = Time::from_utc(2460850.5, 0.0).unwrap();
+        VisibilityPlanner::new(&observer, elevation, Refraction::None, &start, &start.offset(days * DAY).unwrap())
+    }
+
+    #[test]
+    fn crossings_are_at_the_limit_and_in_order() {
+        let planner = planner(20.0, 2.0);
+        let source = vega();
+        let rise = planner.next_rise(&source).unwrap().unwrap();
+        let set = planner.next_set(&source).unwrap().unwrap();
+        let transit = planner.next_transit(&source).unwrap().unwrap();
+        for time in [rise, set, transit] {
+            assert!(time.diff(&planner.start) >= 0.0 && time.diff(&planner.end) <= 0.0);
+        }
+        // Up after its transit at the start, so it sets, rises again and then transits
+        assert!(planner.is_visible(&source, &planner.start).unwrap());
+        assert!(set.diff(&rise) < 0.0 && rise.diff(&transit) < 0.0);
+        assert!(!planner.is_visible(&source, &set.offset(60.0).unwrap()).unwrap());
+        assert!(planner.is_visible(&source, &rise.offset(60.0).unwrap()).unwrap());
+        assert_eq!(planner.next_visible(&source).unwrap().map(|time| time.jd(Timescale::Utc)), Some(2460850.5));
+    }
+
+    #[test]
+    fn nothing_outside_the_window() {
+        // Vega culminates near 85 deg here, so never reaches 89 deg
+        let planner = planner(89.0, 3.0);
+        assert!(planner.next_rise(&vega()).unwrap().is_none());
+        assert!(planner.next_visible(&vega()).unwrap().is_none());
+        // A window too short for the next set
+        let planner = self::planner(20.0, 60.0 / DAY);
+        assert!(planner.next_set(&vega()).unwrap().is_none());
+    }
+}
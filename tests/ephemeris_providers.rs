@@ -0,0 +1,52 @@
+//! Ephemeris providers registered from Rust. Providers are global to the process, so these tests
+//! run in a test binary of their own.
+#![cfg(feature = "novas")]
+
+use astrokits::novas::ephemeris::{set_ephem_provider, set_planet_provider, EphemProvider, Origin, PlanetProvider};
+use astrokits::novas::{Accuracy, Observer, ObservingFrame, Planet, ReferenceSystem, Source, Time, Timescale};
+
+// Circular heliocentric orbits, enough to place the Earth and Mars.
+struct Circles;
+
+impl PlanetProvider for Circles {
+    fn state(&self, _jd_tdb: [f64; 2], body: Planet, _origin: Origin) -> Option<([f64; 3], [f64; 3])> {
+        match body {
+            Planet::Mars => Some(([1.5, 0.0, 0.0], [0.0, 0.01, 0.0])),
+            Planet::Earth => Some(([0.0, 1.0, 0.0], [-0.017, 0.0, 0.0])),
+            Planet::Sun | Planet::Ssb => Some(([0.0; 3], [0.0; 3])),
+            Planet::Venus => panic!("no Venus in this ephemeris"),
+            _ => None,
+        }
+    }
+}
+
+struct Ceres;
+
+impl EphemProvider for Ceres {
+    fn state(&self, name: &str, id: i64, jd_tdb: [f64; 2]) -> Option<(Origin, [f64; 3], [f64; 3])> {
+        if name == "VESTA" {
+            panic!("no Vesta in this ephemeris");
+        }
+        let days = jd_tdb[0] - 2460000.5 + jd_tdb[1];
+        (name == "CERES" && id == 1).then_some((Origin::Barycenter, [2.7, 0.01 * days, 0.0], [0.0, 0.01, 0.0]))
+    }
+}
+
+#[test]
+fn providers_feed_sky_positions_and_report_panics_as_missing_data() {
+    set_planet_provider(Circles).unwrap();
+    set_ephem_provider(Ceres).unwrap();
+    let site = Observer::on_surface(30.0, 100.0, 1000.0).unwrap();
+    let time = Time::new(Timescale::Tt, 2460000.5, 37, 0.1).unwrap();
+
+    let frame = ObservingFrame::new(Accuracy::Full, &site, &time, 0.0, 0.0).unwrap();
+    let mars = frame.sky_pos(&Source::planet(Planet::Mars).unwrap(), ReferenceSystem::Icrs).unwrap();
+    assert!((mars.dist - 1.8).abs() < 0.01, "Mars at {} AU", mars.dist);
+    assert!(frame.sky_pos(&Source::planet(Planet::Jupiter).unwrap(), ReferenceSystem::Icrs).is_err());
+    assert!(frame.sky_pos(&Source::planet(Planet::Venus).unwrap(), ReferenceSystem::Icrs).is_err());
+
+    let frame = ObservingFrame::new(Accuracy::Reduced, &site, &time, 0.0, 0.0).unwrap();
+    let ceres = frame.sky_pos(&Source::ephemeris("Ceres", 1).unwrap(), ReferenceSystem::Icrs).unwrap();
+    assert!(ceres.dist > 1.5 && ceres.dist < 4.0, "Ceres at {} AU", ceres.dist);
+    assert!(frame.sky_pos(&Source::ephemeris("Vesta", 4).unwrap(), ReferenceSystem::Icrs).is_err());
+}
@@ -0,0 +1,139 @@
+//! JSON round trips of the types that implement `Serialize` and `Deserialize`.
+#![cfg(all(feature = "novas", feature = "serde"))]
+
+use astrokits::novas::geodesy::Geodetic;
+#[cfg(supernovas_1_4)]
+use astrokits::novas::interferometry::Uvw;
+use astrokits::novas::jd::{CalendarDate, JulianDate};
+use astrokits::novas::{
+    Astrometry, CatalogEntry, CometElements, CometMagnitude, Constraints, Degrees, Epoch, Hours, NonGravitational, Observer, Orbit, Planet,
+    Redshift, SkyPos, Source, SourceKind, Time, Timescale,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+// Round trips types without `PartialEq` by comparing their JSON.
+fn assert_same_json<T: Serialize + DeserializeOwned>(value: &T) -> serde_json::Value {
+    let json = serde_json::to_value(value).unwrap();
+    assert_eq!(serde_json::to_value(round_trip(value)).unwrap(), json);
+    json
+}
+
+fn vega() -> Astrometry {
+    Astrometry { ra: 18.6156, dec: 38.7836, pm_ra: 200.94, pm_dec: 286.23, parallax: 130.23, rv: -13.9 }
+}
+
+#[test]
+fn plain_types() {
+    let pos = SkyPos { ra: 18.6156, dec: 38.7836, dist: 1.0e9, rv: -13.9, r_hat: [0.1, -0.7, 0.7] };
+    assert_eq!(round_trip(&pos), pos);
+    for epoch in [Epoch::J2000, Epoch::B1950, Epoch::Julian(1991.25), Epoch::Besselian(1875.0)] {
+        assert_eq!(round_trip(&epoch), epoch);
+    }
+    assert_eq!(round_trip(&vega()), vega());
+    assert_eq!(round_trip(&Degrees(-12.5)), Degrees(-12.5));
+    assert_eq!(round_trip(&Hours(18.6)), Hours(18.6));
+    assert_eq!(round_trip(&Planet::PlutoBarycenter), Planet::PlutoBarycenter);
+    assert_eq!(serde_json::to_string(&Planet::PlutoBarycenter).unwrap(), "\"pluto_barycenter\"");
+    assert_eq!(round_trip(&Geodetic::new(43.4712, 87.1781, 2080.0)), Geodetic::new(43.4712, 87.1781, 2080.0));
+    #[cfg(supernovas_1_4)]
+    assert_eq!(round_trip(&Uvw { u: 1.0, v: -2.0, w: 3.5 }), Uvw { u: 1.0, v: -2.0, w: 3.5 });
+    assert_eq!(round_trip(&CalendarDate::new(-44, 3, 15, 11.5)), CalendarDate::new(-44, 3, 15, 11.5));
+    assert_eq!(round_trip(&JulianDate(2460850.5)), JulianDate(2460850.5));
+}
+
+#[test]
+fn catalog_entries() {
+    let mut entry = CatalogEntry::new("Vega", vega());
+    entry.catalog = "HIP".to_string();
+    entry.number = 91262;
+    entry.epoch = Epoch::Julian(1991.25);
+    assert_eq!(round_trip(&entry), entry);
+}
+
+#[test]
+fn orbits() {
+    let orbit = Orbit::heliocentric(2460800.5, 2.77, 0.0789, 73.4, 80.3, 10.6, 291.4);
+    assert_eq!(round_trip(&orbit), orbit);
+    let comet = CometElements {
+        perihelion_time: 2460800.5,
+        q: 1.35,
+        e: 0.64,
+        arg_perihelion: 12.8,
+        node: 50.1,
+        inclination: 7.0,
+        nongrav: Some(NonGravitational { a1: 1.0e-8, a2: -2.0e-9, a3: 0.0 }),
+        magnitude: None,
+    };
+    assert_eq!(round_trip(&comet), comet);
+    let comet = CometElements { nongrav: None, magnitude: Some(CometMagnitude { g: 11.5, k: 10.0 }), ..comet };
+    assert_eq!(round_trip(&comet), comet);
+}
+
+#[test]
+fn constraints() {
+    let constraints = Constraints {
+        min_elevation: 30.0,
+        max_airmass: Some(2.0),
+        min_moon_separation: None,
+        max_sun_elevation: Some(-18.0),
+        duration: 2700.0,
+    };
+    assert_eq!(round_trip(&constraints), constraints);
+    assert_eq!(round_trip(&Constraints::default()), Constraints::default());
+}
+
+#[test]
+fn times_are_exact() {
+    let time = Time::new(Timescale::Tt, 2460850.123456789, 37, 0.035).unwrap();
+    let back = round_trip(&time);
+    for scale in [Timescale::Tt, Timescale::Tdb, Timescale::Ut1, Timescale::Utc] {
+        assert_eq!(back.jd(scale), time.jd(scale));
+    }
+    assert_same_json(&time);
+}
+
+#[test]
+fn observers_by_place() {
+    let observers = [
+        Observer::at_geocenter().unwrap(),
+        Observer::on_surface(43.4712, 87.1781, 2080.0).unwrap(),
+        Observer::airborne(43.4712, 87.1781, 10000.0, [0.2, 0.1, 0.0]).unwrap(),
+        Observer::in_space([7000.0, 0.0, 0.0], [0.0, 7.5, 0.0]).unwrap(),
+        Observer::in_solar_system([1.0, 0.0, 0.0], [0.0, 0.017, 0.0]).unwrap(),
+    ];
+    let places: Vec<serde_json::Value> = observers.iter().map(|observer| assert_same_json(observer)["place"].clone()).collect();
+    assert_eq!(places, ["geocenter", "on_earth", "airborne", "in_earth_orbit", "solar_system"]);
+    let site = round_trip(&observers[1]);
+    assert_eq!(site.geodetic(), observers[1].geodetic());
+}
+
+#[test]
+fn sources_by_kind() {
+    let orbit = Orbit::heliocentric(2460800.5, 2.77, 0.0789, 73.4, 80.3, 10.6, 291.4);
+    let sources = [
+        Source::planet(Planet::Mars).unwrap(),
+        Source::ephemeris("Ceres", 2000001).unwrap(),
+        Source::catalog("Vega", &vega(), "ICRS").unwrap(),
+        Source::redshifted("3c273", 12.4852, 2.0524, "ICRS", Redshift::new(0.158339).unwrap()).unwrap(),
+        Source::orbital("Ceres", &orbit).unwrap(),
+    ];
+    for source in &sources {
+        let json = assert_same_json(source);
+        let back = round_trip(source);
+        assert_eq!(back.kind(), source.kind());
+        assert_eq!(back.name(), source.name());
+        assert!(json["kind"].is_string());
+    }
+    assert_eq!(round_trip(&sources[4]).orbit(), Some(orbit));
+    assert_eq!(round_trip(&sources[2]).kind(), SourceKind::Catalog);
+    // Catalog sources are written in ICRS, after the conversion from their catalog system
+    let fk5 = Source::catalog("Vega", &vega(), "FK5").unwrap();
+    let back = round_trip(&fk5);
+    assert_eq!(back.as_raw().star.ra, fk5.as_raw().star.ra);
+    assert_eq!(back.as_raw().star.dec, fk5.as_raw().star.dec);
+}